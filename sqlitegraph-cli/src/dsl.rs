@@ -80,7 +80,11 @@ fn parse_repetitive_pattern(segment: &str) -> PatternQuery {
             });
         }
     }
-    PatternQuery { root: None, legs }
+    PatternQuery {
+        root: None,
+        legs,
+        ..PatternQuery::default()
+    }
 }
 
 fn parse_hop_command(input: &str) -> Option<DslResult> {
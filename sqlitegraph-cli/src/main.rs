@@ -241,6 +241,7 @@ fn create_reindex_config(args: &[String]) -> Result<ReindexConfig, SqliteGraphEr
         validate,
         batch_size,
         progress_callback,
+        cancel: None,
     })
 }
 
@@ -0,0 +1,70 @@
+//! Label and property assignment benchmarks.
+//!
+//! Tags every entity of a fresh in-memory graph with a label and a property,
+//! once through per-call `add_label`/`add_property` and once through the
+//! single-transaction bulk variants.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use sqlitegraph::{
+    graph::{GraphEntity, SqliteGraph},
+    index::{add_label, add_labels_bulk, add_properties_bulk, add_property},
+};
+
+mod bench_utils;
+use bench_utils::{MEASURE, WARM_UP};
+
+const SIZES: &[usize] = &[100, 1_000];
+
+/// In-memory graph holding `size` entities, with their ids
+fn graph_with_entities(size: usize) -> (SqliteGraph, Vec<i64>) {
+    let graph = SqliteGraph::open_in_memory().expect("Failed to create graph");
+    let ids = (0..size)
+        .map(|i| {
+            graph
+                .insert_entity(&GraphEntity {
+                    id: 0,
+                    kind: "Node".to_string(),
+                    name: format!("node_{}", i),
+                    file_path: None,
+                    data: serde_json::json!({}),
+                })
+                .expect("Failed to insert entity")
+        })
+        .collect();
+    (graph, ids)
+}
+
+/// Benchmark looped against bulk label and property assignment
+fn assign_labels_and_properties(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("index_assign");
+    group.measurement_time(MEASURE);
+    group.warm_up_time(WARM_UP);
+
+    for &size in SIZES {
+        group.bench_with_input(BenchmarkId::new("looped", size), &size, |b, &size| {
+            b.iter(|| {
+                let (graph, ids) = graph_with_entities(size);
+                for &id in &ids {
+                    add_label(&graph, id, "Tagged").expect("Failed to add label");
+                    add_property(&graph, id, "tier", "1").expect("Failed to add property");
+                }
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("bulk", size), &size, |b, &size| {
+            b.iter(|| {
+                let (graph, ids) = graph_with_entities(size);
+                let pairs: Vec<(i64, &str)> = ids.iter().map(|&id| (id, "Tagged")).collect();
+                let triples: Vec<(i64, &str, &str)> =
+                    ids.iter().map(|&id| (id, "tier", "1")).collect();
+                add_labels_bulk(&graph, &pairs).expect("Failed to add labels");
+                add_properties_bulk(&graph, &triples).expect("Failed to add properties");
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, assign_labels_and_properties);
+criterion_main!(benches);
@@ -7,7 +7,9 @@ use std::time::Duration;
 
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use rand::{RngCore, SeedableRng};
-use sqlitegraph::{BackendDirection, EdgeSpec, NodeSpec, open_graph};
+use sqlitegraph::{
+    BackendDirection, EdgeSpec, GraphBackend, NativeGraphBackend, NodeSpec, open_graph,
+};
 
 mod bench_utils;
 use bench_utils::{BENCHMARK_SIZES, MEASURE, WARM_UP, create_benchmark_temp_dir};
@@ -456,11 +458,73 @@ fn insert_incremental(criterion: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark native per-record inserts against the batched bulk insert path
+fn insert_native_bulk(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("insert_native_bulk");
+    group.measurement_time(MEASURE);
+    group.warm_up_time(WARM_UP);
+
+    for &size in &[100usize, 1_000] {
+        let nodes: Vec<NodeSpec> = (0..size)
+            .map(|i| NodeSpec {
+                kind: "Node".to_string(),
+                name: format!("node_{}", i),
+                file_path: None,
+                data: serde_json::json!({"id": i}),
+            })
+            .collect();
+        // Chain edges between consecutive node ids
+        let edges: Vec<EdgeSpec> = (1..size as i64)
+            .map(|i| EdgeSpec {
+                from: i,
+                to: i + 1,
+                edge_type: "chain".to_string(),
+                data: serde_json::json!({"order": i}),
+            })
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("per_record", size), &size, |b, _| {
+            b.iter(|| {
+                let temp_dir = create_benchmark_temp_dir();
+                let graph = NativeGraphBackend::new(temp_dir.path().join("benchmark.db"))
+                    .expect("Failed to create graph");
+                for node in &nodes {
+                    graph
+                        .insert_node(node.clone())
+                        .expect("Failed to insert node");
+                }
+                for edge in &edges {
+                    graph
+                        .insert_edge(edge.clone())
+                        .expect("Failed to insert edge");
+                }
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("bulk", size), &size, |b, _| {
+            b.iter(|| {
+                let temp_dir = create_benchmark_temp_dir();
+                let graph = NativeGraphBackend::new(temp_dir.path().join("benchmark.db"))
+                    .expect("Failed to create graph");
+                graph
+                    .bulk_insert_nodes(&nodes)
+                    .expect("Failed to bulk insert nodes");
+                graph
+                    .bulk_insert_edges(&edges)
+                    .expect("Failed to bulk insert edges");
+            });
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     insert_nodes,
     insert_edges,
     insert_mixed,
-    insert_incremental
+    insert_incremental,
+    insert_native_bulk
 );
 criterion_main!(benches);
@@ -0,0 +1,78 @@
+//! Native backend read-path benchmarks.
+//!
+//! Measures neighbor iteration over a reopened native graph file. Run once with
+//! and once without `--features mmap` to compare memory-mapped reads against
+//! positional reads on the file handle.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use sqlitegraph::{EdgeSpec, GraphBackend, NativeGraphBackend, NeighborQuery, NodeSpec};
+
+mod bench_utils;
+use bench_utils::{MEASURE, WARM_UP, create_benchmark_temp_dir};
+
+/// Every neighbor lookup re-resolves node offsets from the start of the file,
+/// so a full sweep is quadratic; larger sizes take minutes per sample.
+const SIZES: &[usize] = &[100, 1_000];
+
+/// Label distinguishing the two read paths in criterion reports
+const READ_PATH: &str = if cfg!(feature = "mmap") {
+    "mmap"
+} else {
+    "pread"
+};
+
+/// Benchmark outgoing neighbor lookups for every node of a fan-out graph
+fn native_neighbor_iteration(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("native_neighbor_iteration");
+    group.measurement_time(MEASURE);
+    group.warm_up_time(WARM_UP);
+
+    for &size in SIZES {
+        let temp_dir = create_benchmark_temp_dir();
+        let db_path = temp_dir.path().join("benchmark.db");
+        {
+            let graph = NativeGraphBackend::new(&db_path).expect("Failed to create graph");
+            for i in 0..size {
+                graph
+                    .insert_node(NodeSpec {
+                        kind: "Node".to_string(),
+                        name: format!("node_{}", i),
+                        file_path: None,
+                        data: serde_json::json!({"id": i}),
+                    })
+                    .expect("Failed to insert node");
+            }
+            // Each node links to the next three, wrapping around
+            for i in 0..size as i64 {
+                for step in 1..=3 {
+                    graph
+                        .insert_edge(EdgeSpec {
+                            from: i + 1,
+                            to: (i + step) % size as i64 + 1,
+                            edge_type: "link".to_string(),
+                            data: serde_json::json!({}),
+                        })
+                        .expect("Failed to insert edge");
+                }
+            }
+            // Lay records out contiguously with exact adjacency ranges
+            graph.compact().expect("Failed to compact graph");
+        }
+
+        let graph = NativeGraphBackend::open(&db_path).expect("Failed to open graph");
+        group.bench_with_input(BenchmarkId::new(READ_PATH, size), &size, |b, &size| {
+            b.iter(|| {
+                for node in 1..=size as i64 {
+                    let _neighbors = graph
+                        .neighbors(node, NeighborQuery::default())
+                        .expect("Failed to read neighbors");
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, native_neighbor_iteration);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+//! Native backend file pre-allocation benchmarks.
+//!
+//! Loads the same graph record by record into a fresh native file, once as is
+//! and once after [`NativeGraphBackend::reserve`]. Before either is measured,
+//! the reserved load is checked to resize the file less often.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use sqlitegraph::{EdgeSpec, GraphBackend, NativeGraphBackend, NodeSpec};
+
+mod bench_utils;
+use bench_utils::{MEASURE, WARM_UP, create_benchmark_temp_dir};
+
+const SIZES: &[usize] = &[100, 1_000];
+
+/// Number of outgoing edges per node
+const FAN_OUT: usize = 2;
+
+/// Insert `size` nodes, then `FAN_OUT` edges per node, returning the file growth count
+fn load(size: usize, reserve: bool) -> u64 {
+    let temp_dir = create_benchmark_temp_dir();
+    let graph = NativeGraphBackend::new(temp_dir.path().join("benchmark.db"))
+        .expect("Failed to create graph");
+    if reserve {
+        graph
+            .reserve(size, size * FAN_OUT)
+            .expect("Failed to reserve space");
+    }
+    for i in 0..size {
+        graph
+            .insert_node(NodeSpec {
+                kind: "Node".to_string(),
+                name: format!("node_{}", i),
+                file_path: None,
+                data: serde_json::json!({"id": i}),
+            })
+            .expect("Failed to insert node");
+    }
+    for i in 0..size as i64 {
+        for step in 1..=FAN_OUT as i64 {
+            graph
+                .insert_edge(EdgeSpec {
+                    from: i + 1,
+                    to: (i + step) % size as i64 + 1,
+                    edge_type: "link".to_string(),
+                    data: serde_json::json!({}),
+                })
+                .expect("Failed to insert edge");
+        }
+    }
+    graph.file_growth_count()
+}
+
+/// Benchmark a record-by-record load with and without reserved file space
+fn native_reserved_load(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("native_reserved_load");
+    group.measurement_time(MEASURE);
+    group.warm_up_time(WARM_UP);
+
+    for &size in SIZES {
+        assert!(load(size, true) < load(size, false));
+        for (label, reserve) in [("unreserved", false), ("reserved", true)] {
+            group.bench_with_input(BenchmarkId::new(label, size), &size, |b, &size| {
+                b.iter(|| load(size, reserve));
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, native_reserved_load);
+criterion_main!(benches);
@@ -1,37 +1,622 @@
-use std::collections::VecDeque;
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+};
 
-use ahash::AHashSet;
+use ahash::{AHashMap, AHashSet};
 
-use crate::{errors::SqliteGraphError, graph::SqliteGraph};
+use crate::{
+    backend::BackendDirection,
+    errors::SqliteGraphError,
+    graph::{GraphEdge, SqliteGraph},
+};
 
+/// Weakly connected components, treating every edge as undirected.
+///
+/// Each component is sorted ascending and components are ordered by their
+/// smallest node id; isolated nodes form singleton components.
 pub fn connected_components(graph: &SqliteGraph) -> Result<Vec<Vec<i64>>, SqliteGraphError> {
-    let mut components = Vec::new();
-    let mut visited = AHashSet::new();
-    for id in graph.all_entity_ids()? {
-        if !visited.insert(id) {
+    let nodes = graph.all_entity_ids()?;
+    Ok(components_from_edges(&nodes, graph.edge_endpoints()?))
+}
+
+/// Union-find over `edges`, grouping `nodes` into weakly connected components.
+///
+/// Edges with an endpoint outside `nodes` are ignored. The output order matches
+/// [`connected_components`] regardless of the order of the inputs.
+pub(crate) fn components_from_edges<I>(nodes: &[i64], edges: I) -> Vec<Vec<i64>>
+where
+    I: IntoIterator<Item = (i64, i64)>,
+{
+    let mut sorted = nodes.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let index: AHashMap<i64, usize> = sorted.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let mut parent: Vec<usize> = (0..sorted.len()).collect();
+    for (from, to) in edges {
+        let (Some(&a), Some(&b)) = (index.get(&from), index.get(&to)) else {
             continue;
+        };
+        let (root_a, root_b) = (find_root(&mut parent, a), find_root(&mut parent, b));
+        // The smaller index becomes the root, so a root is always its component's minimum.
+        if root_a < root_b {
+            parent[root_b] = root_a;
+        } else {
+            parent[root_a] = root_b;
+        }
+    }
+    let mut slots: AHashMap<usize, usize> = AHashMap::new();
+    let mut components: Vec<Vec<i64>> = Vec::new();
+    for (i, &id) in sorted.iter().enumerate() {
+        let root = find_root(&mut parent, i);
+        let slot = *slots.entry(root).or_insert_with(|| {
+            components.push(Vec::new());
+            components.len() - 1
+        });
+        components[slot].push(id);
+    }
+    components
+}
+
+/// Shortest outgoing path from `start` to `end`, searching from both ends.
+///
+/// Alternately expands a whole BFS layer forward from `start` (outgoing edges)
+/// or backward from `end` (incoming edges), always the side with the smaller
+/// frontier (taking turns on ties), and stops once the two searches meet. The result is the same as
+/// the forward BFS of [`crate::bfs::shortest_path_directed`]: among paths of
+/// minimal length the lexicographically smallest node-id sequence is returned.
+/// Endpoints are not checked for existence.
+pub fn bidirectional_shortest_path(
+    graph: &SqliteGraph,
+    start: i64,
+    end: i64,
+) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+    if start == end {
+        return Ok(Some(vec![start]));
+    }
+    let mut forward = SearchSide::new(start);
+    let mut backward = SearchSide::new(end);
+    let mut forward_last = false;
+    let meeting = loop {
+        let (Some(forward_frontier), Some(backward_frontier)) =
+            (forward.layers.last(), backward.layers.last())
+        else {
+            unreachable!("search sides always hold their start layer");
+        };
+        if forward_frontier.is_empty() || backward_frontier.is_empty() {
+            return Ok(None);
+        }
+        // Equal frontiers take turns, so neither side can starve the other.
+        forward_last = match forward_frontier.len().cmp(&backward_frontier.len()) {
+            std::cmp::Ordering::Less => true,
+            std::cmp::Ordering::Greater => false,
+            std::cmp::Ordering::Equal => !forward_last,
+        };
+        let meeting = if forward_last {
+            forward.expand(&backward, |node| {
+                graph.fetch_neighbors(node, BackendDirection::Outgoing)
+            })?
+        } else {
+            backward.expand(&forward, |node| {
+                graph.fetch_neighbors(node, BackendDirection::Incoming)
+            })?
+        };
+        if !meeting.is_empty() {
+            break meeting;
+        }
+    };
+
+    // Nodes that lie on some shortest path, per position along the path.
+    let forward_depth = forward.layers.len() - 1;
+    let length = forward_depth + backward.layers.len() - 1;
+    let mut on_path: Vec<AHashSet<i64>> = vec![AHashSet::new(); length + 1];
+    on_path[forward_depth] = meeting.into_iter().collect();
+    for position in (0..forward_depth).rev() {
+        let next: Vec<i64> = on_path[position + 1].iter().copied().collect();
+        for node in next {
+            on_path[position].extend(forward.links[&node].iter().copied());
+        }
+    }
+    for position in forward_depth + 1..=length {
+        let previous: Vec<i64> = on_path[position - 1].iter().copied().collect();
+        for node in previous {
+            on_path[position].extend(backward.links[&node].iter().copied());
         }
-        let mut queue = VecDeque::new();
-        queue.push_back(id);
-        let mut component = Vec::new();
+    }
+
+    // Walk from `start`, always taking the smallest id that stays on a shortest path.
+    let mut path = vec![start];
+    let mut current = start;
+    for (position, candidates) in on_path.iter().enumerate().skip(1) {
+        let next = if position <= forward_depth {
+            candidates
+                .iter()
+                .copied()
+                .filter(|node| forward.links[node].contains(&current))
+                .min()
+        } else {
+            backward.links[&current]
+                .iter()
+                .copied()
+                .filter(|node| candidates.contains(node))
+                .min()
+        };
+        let Some(next) = next else {
+            unreachable!("every node on a shortest path has a successor on it");
+        };
+        path.push(next);
+        current = next;
+    }
+    Ok(Some(path))
+}
+
+/// One direction of [`bidirectional_shortest_path`].
+struct SearchSide {
+    /// Complete BFS layers; `layers[d]` holds every node at distance `d`.
+    layers: Vec<Vec<i64>>,
+    /// For every reached node, its neighbors one layer closer to this side's origin.
+    links: AHashMap<i64, Vec<i64>>,
+}
+
+impl SearchSide {
+    fn new(origin: i64) -> Self {
+        Self {
+            layers: vec![vec![origin]],
+            links: AHashMap::from_iter([(origin, Vec::new())]),
+        }
+    }
+
+    /// Expand the frontier by one full layer; returns the new nodes `other` has reached.
+    fn expand<F>(
+        &mut self,
+        other: &SearchSide,
+        mut neighbors: F,
+    ) -> Result<Vec<i64>, SqliteGraphError>
+    where
+        F: FnMut(i64) -> Result<Vec<i64>, SqliteGraphError>,
+    {
+        let frontier = self.layers.last().cloned().unwrap_or_default();
+        let mut next_layer = Vec::new();
+        let mut added = AHashSet::new();
+        for node in frontier {
+            for next in neighbors(node)? {
+                if added.contains(&next) {
+                    let links = self.links.entry(next).or_default();
+                    if !links.contains(&node) {
+                        links.push(node);
+                    }
+                } else if !self.links.contains_key(&next) {
+                    added.insert(next);
+                    self.links.insert(next, vec![node]);
+                    next_layer.push(next);
+                }
+            }
+        }
+        next_layer.sort_unstable();
+        let meeting = next_layer
+            .iter()
+            .copied()
+            .filter(|node| other.links.contains_key(node))
+            .collect();
+        self.layers.push(next_layer);
+        Ok(meeting)
+    }
+}
+
+/// PageRank scores of every entity, computed with the power method.
+///
+/// `damping` must lie strictly between 0 and 1. Rank held by nodes without
+/// outgoing edges is spread uniformly over all nodes, so the scores sum to 1.
+pub fn pagerank(
+    graph: &SqliteGraph,
+    damping: f64,
+    iterations: usize,
+) -> Result<HashMap<i64, f64>, SqliteGraphError> {
+    let nodes = graph.all_entity_ids()?;
+    pagerank_from_edges(&nodes, graph.edge_endpoints()?, damping, iterations)
+}
+
+/// Power-method PageRank over `edges`; edges leaving `nodes` are ignored.
+///
+/// Parallel edges each carry their share of the source's rank.
+pub(crate) fn pagerank_from_edges<I>(
+    nodes: &[i64],
+    edges: I,
+    damping: f64,
+    iterations: usize,
+) -> Result<HashMap<i64, f64>, SqliteGraphError>
+where
+    I: IntoIterator<Item = (i64, i64)>,
+{
+    if !(damping > 0.0 && damping < 1.0) {
+        return Err(SqliteGraphError::invalid_input(format!(
+            "pagerank damping must be in (0, 1), got {damping}"
+        )));
+    }
+    let mut sorted = nodes.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    if sorted.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let index: AHashMap<i64, usize> = sorted.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let mut links = Vec::new();
+    let mut out_degree = vec![0usize; sorted.len()];
+    for (from, to) in edges {
+        if let (Some(&a), Some(&b)) = (index.get(&from), index.get(&to)) {
+            links.push((a, b));
+            out_degree[a] += 1;
+        }
+    }
+    // Summation order only depends on the sorted ids and edge order.
+    links.sort_unstable();
+
+    let n = sorted.len() as f64;
+    let mut rank = vec![1.0 / n; sorted.len()];
+    for _ in 0..iterations {
+        let dangling: f64 = rank
+            .iter()
+            .zip(&out_degree)
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(score, _)| score)
+            .sum();
+        let base = (1.0 - damping) / n + damping * dangling / n;
+        let mut next = vec![base; sorted.len()];
+        for &(from, to) in &links {
+            next[to] += damping * rank[from] / out_degree[from] as f64;
+        }
+        rank = next;
+    }
+    Ok(sorted.into_iter().zip(rank).collect())
+}
+
+/// Outgoing hop distance between every ordered pair of distinct entities
+/// where the second is reachable from the first.
+///
+/// Runs one BFS per entity, so cost grows quadratically with the graph; fails
+/// with [`SqliteGraphError::InvalidInput`] when there are more than `max_nodes`
+/// entities.
+pub fn all_pairs_shortest_paths(
+    graph: &SqliteGraph,
+    max_nodes: usize,
+) -> Result<HashMap<(i64, i64), u32>, SqliteGraphError> {
+    let nodes = graph.all_entity_ids()?;
+    check_node_limit(nodes.len(), max_nodes)?;
+    all_pairs_shortest_paths_from_edges(&nodes, graph.edge_endpoints()?, max_nodes)
+}
+
+/// Repeated-BFS all-pairs distances over `edges`; edges leaving `nodes` are ignored.
+pub(crate) fn all_pairs_shortest_paths_from_edges<I>(
+    nodes: &[i64],
+    edges: I,
+    max_nodes: usize,
+) -> Result<HashMap<(i64, i64), u32>, SqliteGraphError>
+where
+    I: IntoIterator<Item = (i64, i64)>,
+{
+    let mut sorted = nodes.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    check_node_limit(sorted.len(), max_nodes)?;
+    let index: AHashMap<i64, usize> = sorted.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let mut adjacency = vec![Vec::new(); sorted.len()];
+    for (from, to) in edges {
+        if let (Some(&a), Some(&b)) = (index.get(&from), index.get(&to)) {
+            adjacency[a].push(b);
+        }
+    }
+
+    let mut distances = HashMap::new();
+    let mut depth = vec![u32::MAX; sorted.len()];
+    let mut queue = std::collections::VecDeque::new();
+    for source in 0..sorted.len() {
+        depth.fill(u32::MAX);
+        depth[source] = 0;
+        queue.push_back(source);
         while let Some(node) = queue.pop_front() {
-            component.push(node);
-            for next in graph.fetch_outgoing(node)? {
-                if visited.insert(next) {
+            for &next in &adjacency[node] {
+                if depth[next] == u32::MAX {
+                    depth[next] = depth[node] + 1;
+                    distances.insert((sorted[source], sorted[next]), depth[next]);
                     queue.push_back(next);
                 }
             }
-            for prev in graph.fetch_incoming(node)? {
-                if visited.insert(prev) {
-                    queue.push_back(prev);
+        }
+    }
+    Ok(distances)
+}
+
+/// Betweenness centrality of every entity by Brandes' algorithm.
+///
+/// Paths follow outgoing edges and every edge counts as one hop. A node's score
+/// sums, over ordered pairs of other nodes, the share of shortest paths between
+/// them that pass through it. With `normalized` the scores are divided by
+/// `(n - 1)(n - 2)`, the number of such pairs. Fails with
+/// [`SqliteGraphError::InvalidInput`] when there are more than `max_nodes` entities.
+pub fn betweenness_centrality(
+    graph: &SqliteGraph,
+    normalized: bool,
+    max_nodes: usize,
+) -> Result<HashMap<i64, f64>, SqliteGraphError> {
+    let nodes = graph.all_entity_ids()?;
+    check_node_limit(nodes.len(), max_nodes)?;
+    betweenness_from_edges(&nodes, graph.edge_endpoints()?, normalized, max_nodes)
+}
+
+/// Brandes betweenness over `edges`; edges leaving `nodes` are ignored.
+///
+/// Parallel edges count once, so they do not multiply shortest paths.
+pub(crate) fn betweenness_from_edges<I>(
+    nodes: &[i64],
+    edges: I,
+    normalized: bool,
+    max_nodes: usize,
+) -> Result<HashMap<i64, f64>, SqliteGraphError>
+where
+    I: IntoIterator<Item = (i64, i64)>,
+{
+    let mut sorted = nodes.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    check_node_limit(sorted.len(), max_nodes)?;
+    let n = sorted.len();
+    let index: AHashMap<i64, usize> = sorted.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let mut adjacency = vec![Vec::new(); n];
+    for (from, to) in edges {
+        if let (Some(&a), Some(&b)) = (index.get(&from), index.get(&to)) {
+            if a != b {
+                adjacency[a].push(b);
+            }
+        }
+    }
+    for neighbors in &mut adjacency {
+        neighbors.sort_unstable();
+        neighbors.dedup();
+    }
+
+    let mut score = vec![0.0; n];
+    let mut order = Vec::with_capacity(n);
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut paths = vec![0.0f64; n];
+    let mut depth = vec![usize::MAX; n];
+    let mut dependency = vec![0.0; n];
+    let mut queue = std::collections::VecDeque::new();
+    for source in 0..n {
+        order.clear();
+        predecessors.iter_mut().for_each(Vec::clear);
+        paths.fill(0.0);
+        depth.fill(usize::MAX);
+        dependency.fill(0.0);
+        paths[source] = 1.0;
+        depth[source] = 0;
+        queue.push_back(source);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &next in &adjacency[node] {
+                if depth[next] == usize::MAX {
+                    depth[next] = depth[node] + 1;
+                    queue.push_back(next);
+                }
+                if depth[next] == depth[node] + 1 {
+                    paths[next] += paths[node];
+                    predecessors[next].push(node);
                 }
             }
         }
-        component.sort();
-        components.push(component);
+        // Accumulate dependencies from the farthest nodes back to the source
+        for &node in order.iter().rev() {
+            for &previous in &predecessors[node] {
+                dependency[previous] += paths[previous] / paths[node] * (1.0 + dependency[node]);
+            }
+            if node != source {
+                score[node] += dependency[node];
+            }
+        }
+    }
+
+    if normalized && n > 2 {
+        let pairs = ((n - 1) * (n - 2)) as f64;
+        score.iter_mut().for_each(|value| *value /= pairs);
+    }
+    Ok(sorted.into_iter().zip(score).collect())
+}
+
+fn check_node_limit(node_count: usize, max_nodes: usize) -> Result<(), SqliteGraphError> {
+    if node_count > max_nodes {
+        return Err(SqliteGraphError::invalid_input(format!(
+            "graph has {node_count} nodes, more than the limit of {max_nodes}"
+        )));
+    }
+    Ok(())
+}
+
+/// Cheapest outgoing path from `from` to `to`, found by A* search.
+///
+/// Each edge costs the number stored under `weight_key` in its `data`, or 1.0
+/// when the key is absent; negative or non-numeric weights are rejected.
+/// `heuristic(node)` estimates the remaining cost from `node` to `to` and must
+/// be non-negative. The result is only guaranteed optimal when the heuristic is
+/// admissible, i.e. never overestimates the true remaining cost. A heuristic
+/// that always returns 0 turns the search into Dijkstra's algorithm.
+///
+/// `out_edges(node)` must return the outgoing edges of `node`. Returns the node
+/// path with its total cost, or `None` if `to` cannot be reached. Among equally
+/// promising nodes the smallest id is expanded first, so results are
+/// deterministic.
+pub fn astar<H, F>(
+    from: i64,
+    to: i64,
+    weight_key: &str,
+    heuristic: H,
+    mut out_edges: F,
+) -> Result<Option<(Vec<i64>, f64)>, SqliteGraphError>
+where
+    H: Fn(i64) -> f64,
+    F: FnMut(i64) -> Result<Vec<GraphEdge>, SqliteGraphError>,
+{
+    let estimate = |node: i64| {
+        let h = heuristic(node);
+        if h.is_nan() || h < 0.0 {
+            return Err(SqliteGraphError::invalid_input(format!(
+                "heuristic for node {node} must be non-negative, got {h}"
+            )));
+        }
+        Ok(h)
+    };
+    let mut best: AHashMap<i64, f64> = AHashMap::new();
+    let mut parent: AHashMap<i64, i64> = AHashMap::new();
+    let mut open = BinaryHeap::new();
+    best.insert(from, 0.0);
+    open.push(OpenNode {
+        f: estimate(from)?,
+        g: 0.0,
+        node: from,
+    });
+    while let Some(OpenNode { g, node, .. }) = open.pop() {
+        // Entries superseded by a cheaper route are skipped, which also lets
+        // nodes be reopened when an inconsistent heuristic settles them early.
+        if best.get(&node).is_some_and(|&known| g > known) {
+            continue;
+        }
+        if node == to {
+            let mut path = vec![to];
+            let mut current = to;
+            while let Some(&previous) = parent.get(&current) {
+                path.push(previous);
+                current = previous;
+            }
+            path.reverse();
+            return Ok(Some((path, g)));
+        }
+        for edge in out_edges(node)? {
+            let next_g = g + edge_weight(&edge, weight_key)?;
+            if best.get(&edge.to_id).map_or(true, |&known| next_g < known) {
+                best.insert(edge.to_id, next_g);
+                parent.insert(edge.to_id, node);
+                open.push(OpenNode {
+                    f: next_g + estimate(edge.to_id)?,
+                    g: next_g,
+                    node: edge.to_id,
+                });
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Traversal cost of `edge`: its numeric `weight_key` property, or 1.0 if absent.
+fn edge_weight(edge: &GraphEdge, weight_key: &str) -> Result<f64, SqliteGraphError> {
+    let Some(value) = edge.data.get(weight_key) else {
+        return Ok(1.0);
+    };
+    match value.as_f64() {
+        Some(weight) if weight.is_finite() && weight >= 0.0 => Ok(weight),
+        _ => Err(SqliteGraphError::invalid_input(format!(
+            "edge {} has invalid '{weight_key}' weight {value}; expected a non-negative number",
+            edge.id
+        ))),
+    }
+}
+
+/// Open-set entry for [`astar`], popped in order of `f = g + h` then node id.
+struct OpenNode {
+    f: f64,
+    g: f64,
+    node: i64,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+/// Every entity in dependency order: each edge's source precedes its target.
+///
+/// Kahn's algorithm over in-degrees from a single edge scan; among the nodes
+/// ready at any step the smallest id is emitted first, so the order is
+/// deterministic. Fails with [`SqliteGraphError::CycleDetected`] on a cycle.
+pub fn topological_sort(graph: &SqliteGraph) -> Result<Vec<i64>, SqliteGraphError> {
+    let nodes = graph.all_entity_ids()?;
+    topological_sort_from_edges(&nodes, graph.edge_endpoints()?)
+}
+
+/// Kahn's algorithm over `edges`; edges leaving `nodes` are ignored.
+pub(crate) fn topological_sort_from_edges<I>(
+    nodes: &[i64],
+    edges: I,
+) -> Result<Vec<i64>, SqliteGraphError>
+where
+    I: IntoIterator<Item = (i64, i64)>,
+{
+    let mut in_degree: AHashMap<i64, usize> = nodes.iter().map(|&id| (id, 0)).collect();
+    let mut successors: AHashMap<i64, Vec<i64>> = AHashMap::new();
+    for (from, to) in edges {
+        if !in_degree.contains_key(&from) {
+            continue;
+        }
+        if let Some(degree) = in_degree.get_mut(&to) {
+            *degree += 1;
+            successors.entry(from).or_default().push(to);
+        }
+    }
+    let mut ready: BinaryHeap<Reverse<i64>> = in_degree
+        .iter()
+        .filter(|(_, degree)| **degree == 0)
+        .map(|(&id, _)| Reverse(id))
+        .collect();
+    let mut order = Vec::with_capacity(in_degree.len());
+    while let Some(Reverse(node)) = ready.pop() {
+        order.push(node);
+        for next in successors.remove(&node).unwrap_or_default() {
+            let degree = in_degree
+                .get_mut(&next)
+                .expect("successors are known nodes");
+            *degree -= 1;
+            if *degree == 0 {
+                ready.push(Reverse(next));
+            }
+        }
+    }
+    if order.len() < in_degree.len() {
+        let stuck = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree > 0)
+            .map(|(&id, _)| id)
+            .min()
+            .unwrap_or_default();
+        return Err(SqliteGraphError::cycle_detected(format!(
+            "{} of {} nodes lie on or behind a cycle (smallest: {stuck})",
+            in_degree.len() - order.len(),
+            in_degree.len()
+        )));
     }
-    components.sort_by(|a, b| a[0].cmp(&b[0]));
-    Ok(components)
+    Ok(order)
+}
+
+fn find_root(parent: &mut [usize], mut node: usize) -> usize {
+    while parent[node] != node {
+        parent[node] = parent[parent[node]];
+        node = parent[node];
+    }
+    node
 }
 
 pub fn find_cycles_limited(
@@ -94,6 +679,91 @@ pub fn nodes_by_degree(
     Ok(degrees)
 }
 
+/// The `k` live nodes with the most live edges in `direction`, by degree
+/// descending then id ascending.
+///
+/// `Both` adds incoming and outgoing edges, so a self-loop counts twice. Nodes
+/// without edges rank last with degree 0. Degrees are counted in one grouped
+/// query.
+pub fn top_nodes_by_degree(
+    graph: &SqliteGraph,
+    k: usize,
+    direction: BackendDirection,
+) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
+    if k == 0 {
+        return Ok(Vec::new());
+    }
+    let ends = match direction {
+        BackendDirection::Outgoing => "SELECT from_id AS id FROM live",
+        BackendDirection::Incoming => "SELECT to_id AS id FROM live",
+        BackendDirection::Both => {
+            "SELECT from_id AS id FROM live UNION ALL SELECT to_id AS id FROM live"
+        }
+    };
+    let sql = format!(
+        "WITH live AS (SELECT from_id, to_id FROM graph_edges e \
+         WHERE NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+         WHERE t.entity_id IN (e.from_id, e.to_id))), \
+         ends AS ({ends}), \
+         degrees AS (SELECT id, COUNT(*) AS degree FROM ends GROUP BY id) \
+         SELECT n.id, COALESCE(d.degree, 0) FROM graph_entities n \
+         LEFT JOIN degrees d ON d.id = n.id \
+         WHERE NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id = n.id) \
+         ORDER BY 2 DESC, n.id LIMIT ?1"
+    );
+    let conn = graph.connection();
+    let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([k as i64], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(SqliteGraphError::query)?;
+    rows.collect::<Result<_, _>>()
+        .map_err(SqliteGraphError::query)
+}
+
+/// [`top_nodes_by_degree`] over `nodes` and `edges`, keeping only the best `k`
+/// in a bounded heap.
+///
+/// Edges with an endpoint outside `nodes` are ignored.
+pub(crate) fn top_by_degree_from_edges<I>(
+    nodes: &[i64],
+    edges: I,
+    k: usize,
+    direction: BackendDirection,
+) -> Vec<(i64, u32)>
+where
+    I: IntoIterator<Item = (i64, i64)>,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut degrees: AHashMap<i64, u32> = nodes.iter().map(|&id| (id, 0)).collect();
+    for (from, to) in edges {
+        if !degrees.contains_key(&from) || !degrees.contains_key(&to) {
+            continue;
+        }
+        let ends = match direction {
+            BackendDirection::Outgoing => [Some(from), None],
+            BackendDirection::Incoming => [Some(to), None],
+            BackendDirection::Both => [Some(from), Some(to)],
+        };
+        for end in ends.into_iter().flatten() {
+            *degrees.entry(end).or_default() += 1;
+        }
+    }
+    // Min-heap on (degree, Reverse(id)), so the root is the weakest candidate kept
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for (id, degree) in degrees {
+        heap.push(Reverse((degree, Reverse(id))));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((degree, Reverse(id)))| (id, degree))
+        .collect()
+}
+
 fn normalize_cycles(cycles: &mut [Vec<i64>]) {
     for cycle in cycles.iter_mut() {
         // rotate so smallest node first for determinism
@@ -12,8 +12,82 @@ pub struct Label(pub String);
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct PropertyKey(pub String);
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub struct PropertyValue(pub String);
+/// A property value that keeps its type across storage.
+///
+/// Values are stored as text next to a type tag, so numeric values still take
+/// part in the numeric comparisons of [`crate::index::PropertyFilter`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue {
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+}
+
+impl PropertyValue {
+    /// Tag stored in `graph_properties.value_type`.
+    pub(crate) fn type_tag(&self) -> &'static str {
+        match self {
+            PropertyValue::Integer(_) => "integer",
+            PropertyValue::Float(_) => "float",
+            PropertyValue::Bool(_) => "bool",
+            PropertyValue::String(_) => "string",
+        }
+    }
+
+    /// Rebuild a value from its stored text and type tag; unknown tags and
+    /// text that does not parse as the tagged type read back as `String`.
+    pub(crate) fn from_stored(tag: &str, text: String) -> Self {
+        let parsed = match tag {
+            "integer" => text.parse().ok().map(PropertyValue::Integer),
+            "float" => text.parse().ok().map(PropertyValue::Float),
+            "bool" => text.parse().ok().map(PropertyValue::Bool),
+            _ => None,
+        };
+        parsed.unwrap_or(PropertyValue::String(text))
+    }
+}
+
+impl fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyValue::Integer(value) => write!(f, "{value}"),
+            PropertyValue::Float(value) => write!(f, "{value}"),
+            PropertyValue::Bool(value) => write!(f, "{value}"),
+            PropertyValue::String(value) => f.write_str(value),
+        }
+    }
+}
+
+impl From<i64> for PropertyValue {
+    fn from(value: i64) -> Self {
+        PropertyValue::Integer(value)
+    }
+}
+
+impl From<f64> for PropertyValue {
+    fn from(value: f64) -> Self {
+        PropertyValue::Float(value)
+    }
+}
+
+impl From<bool> for PropertyValue {
+    fn from(value: bool) -> Self {
+        PropertyValue::Bool(value)
+    }
+}
+
+impl From<String> for PropertyValue {
+    fn from(value: String) -> Self {
+        PropertyValue::String(value)
+    }
+}
+
+impl From<&str> for PropertyValue {
+    fn from(value: &str) -> Self {
+        PropertyValue::String(value.to_string())
+    }
+}
 
 impl fmt::Display for NodeId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -17,12 +17,17 @@ pub use native::NativeGraphBackend;
 
 // Re-export types for external users
 pub use crate::multi_hop::ChainStep;
-pub use sqlite::types::{BackendDirection, EdgeSpec, NeighborQuery, NodeSpec};
+pub(crate) use sqlite::types::merge_undirected;
+pub use sqlite::types::{BackendDirection, EdgeSpec, NeighborQuery, NodeEdges, NodeSpec};
+
+use std::collections::HashMap;
 
 use crate::{
     SqliteGraphError,
-    graph::GraphEntity,
+    cache::CacheStats,
+    graph::{GraphEdge, GraphEntity, GraphStatistics},
     pattern::{PatternMatch, PatternQuery},
+    safety::SafetyReport,
 };
 
 /// Backend trait defining the interface for graph database backends.
@@ -33,16 +38,119 @@ pub trait GraphBackend {
     fn insert_node(&self, node: NodeSpec) -> Result<i64, SqliteGraphError>;
     fn get_node(&self, id: i64) -> Result<GraphEntity, SqliteGraphError>;
     fn insert_edge(&self, edge: EdgeSpec) -> Result<i64, SqliteGraphError>;
+    /// Insert `edge` unless one with the same `(from, to, edge_type)` exists.
+    ///
+    /// Duplicates are defined by that tuple alone, ignoring `data`. An existing
+    /// match has its `data` replaced by `edge.data` and its id is returned;
+    /// otherwise this behaves like [`insert_edge`](Self::insert_edge).
+    fn upsert_edge(&self, edge: EdgeSpec) -> Result<i64, SqliteGraphError>;
+    /// Fetch a single edge; `NotFound` if it is missing or deleted.
+    fn get_edge(&self, id: i64) -> Result<GraphEdge, SqliteGraphError>;
+    /// Ids of every live edge in ascending order.
+    fn list_edge_ids(&self) -> Result<Vec<i64>, SqliteGraphError>;
+    /// Ids of live nodes whose property `key` equals `value`, ascending.
+    fn find_nodes_by_property(&self, key: &str, value: &str) -> Result<Vec<i64>, SqliteGraphError>;
+    /// Ids of live nodes carrying `label`, ascending.
+    fn find_nodes_by_label(&self, label: &str) -> Result<Vec<i64>, SqliteGraphError>;
+    /// Delete a node together with every edge touching it.
+    ///
+    /// Afterwards the node reads as not found and no traversal reaches it.
+    fn delete_node(&self, id: i64) -> Result<(), SqliteGraphError>;
+    /// Delete a single edge; errors if it does not exist.
+    fn delete_edge(&self, id: i64) -> Result<(), SqliteGraphError>;
+    /// Replace a node's `data` in place, keeping its id and edges.
+    ///
+    /// The native backend rewrites the stored record, moving it behind the other
+    /// node records when the new payload does not fit its space.
+    fn update_node_data(&self, id: i64, data: serde_json::Value) -> Result<(), SqliteGraphError>;
+    /// Replace an edge's `data` in place, keeping its id and endpoints.
+    fn update_edge_data(&self, id: i64, data: serde_json::Value) -> Result<(), SqliteGraphError>;
     fn neighbors(&self, node: i64, query: NeighborQuery) -> Result<Vec<i64>, SqliteGraphError>;
+    /// `neighbors` read straight from storage, bypassing and not populating
+    /// any adjacency cache.
+    ///
+    /// Use after modifying the database behind the backend's back, or to check
+    /// whether a cached answer has gone stale.
+    fn neighbors_uncached(
+        &self,
+        node: i64,
+        query: NeighborQuery,
+    ) -> Result<Vec<i64>, SqliteGraphError>;
+    /// `neighbors` for many nodes at once, keyed by node id.
+    ///
+    /// Every id in `ids` gets an entry, ordered exactly as `neighbors` would
+    /// order it.
+    fn neighbors_batch(
+        &self,
+        ids: &[i64],
+        query: NeighborQuery,
+    ) -> Result<HashMap<i64, Vec<i64>>, SqliteGraphError>;
     fn bfs(&self, start: i64, depth: u32) -> Result<Vec<i64>, SqliteGraphError>;
+    fn bfs_directed(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<i64>, SqliteGraphError>;
+    /// Outgoing BFS from `start`, listing `start` first, that only follows
+    /// edges whose type is in `edge_types`; an empty list returns just `start`.
+    fn bfs_filtered(
+        &self,
+        start: i64,
+        max_depth: u32,
+        edge_types: &[&str],
+    ) -> Result<Vec<i64>, SqliteGraphError>;
+    /// Shortest outgoing path from `start` to `end`.
+    ///
+    /// `start == end` yields `Some(vec![start])`; among equal-length paths the
+    /// lexicographically smallest node-id sequence is returned.
     fn shortest_path(&self, start: i64, end: i64) -> Result<Option<Vec<i64>>, SqliteGraphError>;
+    fn shortest_path_directed(
+        &self,
+        start: i64,
+        end: i64,
+        direction: BackendDirection,
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError>;
+    /// Shortest outgoing path using only edges whose type is in `edge_types`;
+    /// `None` when no such path exists, even if one over other types does.
+    fn shortest_path_filtered(
+        &self,
+        start: i64,
+        end: i64,
+        edge_types: &[&str],
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError>;
     fn node_degree(&self, node: i64) -> Result<(usize, usize), SqliteGraphError>;
+    /// Number of ids `neighbors(node, query)` would return, counted without
+    /// building the list.
+    fn neighbor_count(&self, node: i64, query: NeighborQuery) -> Result<usize, SqliteGraphError>;
+    /// Outgoing and incoming edges of `node` in one call; errors if the node is missing.
+    fn node_edges(&self, node: i64) -> Result<NodeEdges, SqliteGraphError>;
+    /// The `k` nodes with the most edges in `direction`, as `(id, degree)` by
+    /// degree descending then id ascending.
+    ///
+    /// `Both` adds incoming and outgoing edges. Nodes without edges rank last
+    /// with degree 0.
+    fn top_nodes_by_degree(
+        &self,
+        k: usize,
+        direction: BackendDirection,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError>;
     fn k_hop(
         &self,
         start: i64,
         depth: u32,
         direction: BackendDirection,
     ) -> Result<Vec<i64>, SqliteGraphError>;
+    /// Nodes within `depth` hops of `start` paired with their minimum hop distance.
+    ///
+    /// Ordered like [`k_hop`](Self::k_hop): by distance, then node id; `start`
+    /// itself is not included.
+    fn k_hop_with_distance(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError>;
     fn k_hop_filtered(
         &self,
         start: i64,
@@ -50,16 +158,96 @@ pub trait GraphBackend {
         direction: BackendDirection,
         allowed_edge_types: &[&str],
     ) -> Result<Vec<i64>, SqliteGraphError>;
+    /// Cost-bounded expansion where each hop consumes its edge type's cost.
+    ///
+    /// Returns nodes reached within `budget` with their cheapest accumulated cost,
+    /// ordered by cost then id. Edge types absent from `type_costs` are not followed.
+    fn k_hop_weighted(
+        &self,
+        start: i64,
+        budget: f64,
+        direction: BackendDirection,
+        type_costs: &HashMap<String, f64>,
+    ) -> Result<Vec<(i64, f64)>, SqliteGraphError>;
+    /// Outgoing hop distance for every ordered pair of distinct nodes where
+    /// the second is reachable from the first.
+    ///
+    /// Unreachable pairs are absent. Fails with `InvalidInput` when the graph
+    /// has more than `max_nodes` nodes, since the work is quadratic.
+    fn all_pairs_shortest_paths(
+        &self,
+        max_nodes: usize,
+    ) -> Result<HashMap<(i64, i64), u32>, SqliteGraphError>;
+    /// Brandes betweenness centrality of every node over outgoing edges.
+    ///
+    /// With `normalized` the scores are divided by `(n - 1)(n - 2)`. Fails with
+    /// `InvalidInput` when the graph has more than `max_nodes` nodes.
+    fn betweenness_centrality(
+        &self,
+        normalized: bool,
+        max_nodes: usize,
+    ) -> Result<HashMap<i64, f64>, SqliteGraphError>;
+    /// Cheapest outgoing path from `from` to `to` by A* search.
+    ///
+    /// Edge costs come from the numeric `weight_key` property of edge data,
+    /// defaulting to 1.0. `heuristic` must never overestimate the remaining cost
+    /// to `to` for the result to be optimal; see [`crate::algo::astar`].
+    fn astar(
+        &self,
+        from: i64,
+        to: i64,
+        weight_key: &str,
+        heuristic: &dyn Fn(i64) -> f64,
+    ) -> Result<Option<(Vec<i64>, f64)>, SqliteGraphError>;
     fn chain_query(
         &self,
         start: i64,
         chain: &[crate::multi_hop::ChainStep],
     ) -> Result<Vec<i64>, SqliteGraphError>;
+    /// Weakly connected components, treating edges as undirected.
+    ///
+    /// Each component is sorted ascending and components are ordered by their
+    /// smallest node id; isolated nodes form singleton components.
+    fn connected_components(&self) -> Result<Vec<Vec<i64>>, SqliteGraphError>;
+    /// PageRank of every node after `iterations` power-method steps.
+    ///
+    /// Rank of nodes without outgoing edges is spread uniformly, so scores sum
+    /// to 1. `damping` outside `(0, 1)` is rejected as invalid input.
+    fn pagerank(
+        &self,
+        damping: f64,
+        iterations: usize,
+    ) -> Result<HashMap<i64, f64>, SqliteGraphError>;
+    /// Every node in dependency order, so each edge's source precedes its target.
+    ///
+    /// Ties are broken by smallest node id. Fails with
+    /// [`SqliteGraphError::CycleDetected`] if the graph contains a cycle.
+    fn topological_sort(&self) -> Result<Vec<i64>, SqliteGraphError>;
+    /// Matches of `pattern` from `start`, ordered by node sequence and paged by
+    /// `pattern.offset` / `pattern.limit`.
     fn pattern_search(
         &self,
         start: i64,
         pattern: &PatternQuery,
     ) -> Result<Vec<PatternMatch>, SqliteGraphError>;
+    /// Node and edge counts, per-kind and per-type breakdowns and degree
+    /// summary of the live graph.
+    fn statistics(&self) -> Result<GraphStatistics, SqliteGraphError>;
+    /// Combined hit, miss and entry counts of the backend's adjacency caches.
+    ///
+    /// Backends without a cache report all zeros.
+    fn cache_stats(&self) -> CacheStats;
+    /// Zero the hit and miss counters, keeping cached entries warm.
+    fn reset_cache_stats(&self);
+    /// Report every edge whose `from` or `to` node is missing or deleted.
+    ///
+    /// Only [`SafetyReport::dangling_edges`] is filled in.
+    fn validate_integrity(&self) -> Result<SafetyReport, SqliteGraphError>;
+    /// Schema version recorded in the underlying file or database.
+    fn schema_version(&self) -> Result<u32, SqliteGraphError>;
+    /// Fail with [`SqliteGraphError::SchemaError`] unless this build can use the
+    /// stored schema as it is.
+    fn check_compatibility(&self) -> Result<(), SqliteGraphError>;
 }
 
 /// Reference implementation for GraphBackend trait that works with references.
@@ -79,22 +267,126 @@ where
         (*self).insert_edge(edge)
     }
 
+    fn upsert_edge(&self, edge: EdgeSpec) -> Result<i64, SqliteGraphError> {
+        (*self).upsert_edge(edge)
+    }
+
+    fn get_edge(&self, id: i64) -> Result<GraphEdge, SqliteGraphError> {
+        (*self).get_edge(id)
+    }
+
+    fn list_edge_ids(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        (*self).list_edge_ids()
+    }
+
+    fn find_nodes_by_property(&self, key: &str, value: &str) -> Result<Vec<i64>, SqliteGraphError> {
+        (*self).find_nodes_by_property(key, value)
+    }
+
+    fn find_nodes_by_label(&self, label: &str) -> Result<Vec<i64>, SqliteGraphError> {
+        (*self).find_nodes_by_label(label)
+    }
+
+    fn delete_node(&self, id: i64) -> Result<(), SqliteGraphError> {
+        (*self).delete_node(id)
+    }
+
+    fn delete_edge(&self, id: i64) -> Result<(), SqliteGraphError> {
+        (*self).delete_edge(id)
+    }
+
+    fn update_node_data(&self, id: i64, data: serde_json::Value) -> Result<(), SqliteGraphError> {
+        (*self).update_node_data(id, data)
+    }
+
+    fn update_edge_data(&self, id: i64, data: serde_json::Value) -> Result<(), SqliteGraphError> {
+        (*self).update_edge_data(id, data)
+    }
+
     fn neighbors(&self, node: i64, query: NeighborQuery) -> Result<Vec<i64>, SqliteGraphError> {
         (*self).neighbors(node, query)
     }
 
+    fn neighbors_uncached(
+        &self,
+        node: i64,
+        query: NeighborQuery,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        (*self).neighbors_uncached(node, query)
+    }
+
+    fn neighbors_batch(
+        &self,
+        ids: &[i64],
+        query: NeighborQuery,
+    ) -> Result<HashMap<i64, Vec<i64>>, SqliteGraphError> {
+        (*self).neighbors_batch(ids, query)
+    }
+
     fn bfs(&self, start: i64, depth: u32) -> Result<Vec<i64>, SqliteGraphError> {
         (*self).bfs(start, depth)
     }
 
+    fn bfs_filtered(
+        &self,
+        start: i64,
+        max_depth: u32,
+        edge_types: &[&str],
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        (*self).bfs_filtered(start, max_depth, edge_types)
+    }
+
+    fn bfs_directed(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        (*self).bfs_directed(start, depth, direction)
+    }
+
     fn shortest_path(&self, start: i64, end: i64) -> Result<Option<Vec<i64>>, SqliteGraphError> {
         (*self).shortest_path(start, end)
     }
 
+    fn shortest_path_directed(
+        &self,
+        start: i64,
+        end: i64,
+        direction: BackendDirection,
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+        (*self).shortest_path_directed(start, end, direction)
+    }
+
+    fn shortest_path_filtered(
+        &self,
+        start: i64,
+        end: i64,
+        edge_types: &[&str],
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+        (*self).shortest_path_filtered(start, end, edge_types)
+    }
+
     fn node_degree(&self, node: i64) -> Result<(usize, usize), SqliteGraphError> {
         (*self).node_degree(node)
     }
 
+    fn neighbor_count(&self, node: i64, query: NeighborQuery) -> Result<usize, SqliteGraphError> {
+        (*self).neighbor_count(node, query)
+    }
+
+    fn node_edges(&self, node: i64) -> Result<NodeEdges, SqliteGraphError> {
+        (*self).node_edges(node)
+    }
+
+    fn top_nodes_by_degree(
+        &self,
+        k: usize,
+        direction: BackendDirection,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
+        (*self).top_nodes_by_degree(k, direction)
+    }
+
     fn k_hop(
         &self,
         start: i64,
@@ -104,6 +396,15 @@ where
         (*self).k_hop(start, depth, direction)
     }
 
+    fn k_hop_with_distance(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
+        (*self).k_hop_with_distance(start, depth, direction)
+    }
+
     fn k_hop_filtered(
         &self,
         start: i64,
@@ -114,6 +415,41 @@ where
         (*self).k_hop_filtered(start, depth, direction, allowed_edge_types)
     }
 
+    fn k_hop_weighted(
+        &self,
+        start: i64,
+        budget: f64,
+        direction: BackendDirection,
+        type_costs: &HashMap<String, f64>,
+    ) -> Result<Vec<(i64, f64)>, SqliteGraphError> {
+        (*self).k_hop_weighted(start, budget, direction, type_costs)
+    }
+
+    fn all_pairs_shortest_paths(
+        &self,
+        max_nodes: usize,
+    ) -> Result<HashMap<(i64, i64), u32>, SqliteGraphError> {
+        (*self).all_pairs_shortest_paths(max_nodes)
+    }
+
+    fn betweenness_centrality(
+        &self,
+        normalized: bool,
+        max_nodes: usize,
+    ) -> Result<HashMap<i64, f64>, SqliteGraphError> {
+        (*self).betweenness_centrality(normalized, max_nodes)
+    }
+
+    fn astar(
+        &self,
+        from: i64,
+        to: i64,
+        weight_key: &str,
+        heuristic: &dyn Fn(i64) -> f64,
+    ) -> Result<Option<(Vec<i64>, f64)>, SqliteGraphError> {
+        (*self).astar(from, to, weight_key, heuristic)
+    }
+
     fn chain_query(
         &self,
         start: i64,
@@ -122,6 +458,22 @@ where
         (*self).chain_query(start, chain)
     }
 
+    fn connected_components(&self) -> Result<Vec<Vec<i64>>, SqliteGraphError> {
+        (*self).connected_components()
+    }
+
+    fn pagerank(
+        &self,
+        damping: f64,
+        iterations: usize,
+    ) -> Result<HashMap<i64, f64>, SqliteGraphError> {
+        (*self).pagerank(damping, iterations)
+    }
+
+    fn topological_sort(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        (*self).topological_sort()
+    }
+
     fn pattern_search(
         &self,
         start: i64,
@@ -129,4 +481,28 @@ where
     ) -> Result<Vec<PatternMatch>, SqliteGraphError> {
         (*self).pattern_search(start, pattern)
     }
+
+    fn statistics(&self) -> Result<GraphStatistics, SqliteGraphError> {
+        (*self).statistics()
+    }
+
+    fn cache_stats(&self) -> CacheStats {
+        (*self).cache_stats()
+    }
+
+    fn reset_cache_stats(&self) {
+        (*self).reset_cache_stats()
+    }
+
+    fn validate_integrity(&self) -> Result<SafetyReport, SqliteGraphError> {
+        (*self).validate_integrity()
+    }
+
+    fn schema_version(&self) -> Result<u32, SqliteGraphError> {
+        (*self).schema_version()
+    }
+
+    fn check_compatibility(&self) -> Result<(), SqliteGraphError> {
+        (*self).check_compatibility()
+    }
 }
@@ -3,6 +3,7 @@
 //! This module provides efficient adjacency iteration using the node and edge
 //! records stored in the native graph file format.
 
+use crate::backend::merge_undirected;
 use crate::backend::native::edge_store::EdgeStore;
 use crate::backend::native::graph_file::GraphFile;
 use crate::backend::native::node_store::NodeStore;
@@ -140,10 +141,19 @@ impl<'a> AdjacencyIterator<'a> {
 
             // Read the edge record using local edge store
             let mut edge_store = EdgeStore::new(self.graph_file);
-            let edge = edge_store.read_edge(current_edge_id)?;
+            let edge = edge_store.read_edge_including_deleted(current_edge_id)?;
 
             // Apply direction filtering and return appropriate neighbor
             let neighbor_id = match self.direction {
+                // Deleted edges are skipped like edges of other nodes
+                _ if edge.flags.contains(EdgeFlags::DELETED) => None,
+                _ if self
+                    .edge_filter
+                    .as_ref()
+                    .is_some_and(|types| !types.contains(&edge.edge_type)) =>
+                {
+                    None
+                }
                 Direction::Outgoing => {
                     // For outgoing edges, neighbor is the target node
                     if edge.from_id == self.node_id {
@@ -198,6 +208,20 @@ impl<'a> AdjacencyIterator<'a> {
         Ok(neighbors)
     }
 
+    /// Count the remaining neighbors without collecting them
+    pub fn count(mut self) -> NativeResult<usize> {
+        let mut count = 0;
+
+        while !self.is_complete() {
+            if self.get_current_neighbor()?.is_some() {
+                count += 1;
+            }
+            self.current_index += 1;
+        }
+
+        Ok(count)
+    }
+
     /// Check if a specific neighbor exists
     pub fn contains(&mut self, target_id: NativeNodeId) -> NativeResult<bool> {
         // Store original position
@@ -303,6 +327,58 @@ impl AdjacencyHelpers {
         iterator.collect()
     }
 
+    /// Get the sorted, deduplicated union of outgoing and incoming neighbors
+    ///
+    /// Runs the outgoing pass followed by the incoming pass, so a reciprocal
+    /// pair of edges contributes its neighbor only once.
+    pub fn get_undirected_neighbors(
+        graph_file: &mut GraphFile,
+        node_id: NativeNodeId,
+        edge_types: Option<&[&str]>,
+    ) -> NativeResult<Vec<NativeNodeId>> {
+        let (outgoing, incoming) = match edge_types {
+            Some(types) => (
+                Self::get_outgoing_neighbors_filtered(graph_file, node_id, types)?,
+                Self::get_incoming_neighbors_filtered(graph_file, node_id, types)?,
+            ),
+            None => (
+                Self::get_outgoing_neighbors(graph_file, node_id)?,
+                Self::get_incoming_neighbors(graph_file, node_id)?,
+            ),
+        };
+        Ok(merge_undirected(outgoing, incoming))
+    }
+
+    /// Live edges in the outgoing or incoming adjacency range of a node, in id order
+    ///
+    /// Only the slots inside the node's range are read; deleted edges and edges
+    /// of other nodes that fall inside it are skipped.
+    pub fn range_edges(
+        graph_file: &mut GraphFile,
+        node_id: NativeNodeId,
+        direction: Direction,
+    ) -> NativeResult<Vec<EdgeRecord>> {
+        let node = NodeStore::new(graph_file).read_node(node_id)?;
+        let (first, count) = match direction {
+            Direction::Outgoing => (node.outgoing_offset, node.outgoing_count),
+            Direction::Incoming => (node.incoming_offset, node.incoming_count),
+        };
+        let first = first as NativeEdgeId;
+        let mut edges = Vec::new();
+        let mut edge_store = EdgeStore::new(graph_file);
+        for edge_id in first..first + count as NativeEdgeId {
+            let edge = edge_store.read_edge_including_deleted(edge_id)?;
+            let endpoint = match direction {
+                Direction::Outgoing => edge.from_id,
+                Direction::Incoming => edge.to_id,
+            };
+            if endpoint == node_id && !edge.flags.contains(EdgeFlags::DELETED) {
+                edges.push(edge);
+            }
+        }
+        Ok(edges)
+    }
+
     /// Check if there's a path from source to target (direct edge)
     pub fn has_direct_edge(
         graph_file: &mut GraphFile,
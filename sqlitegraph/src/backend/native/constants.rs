@@ -64,6 +64,9 @@ pub mod node {
     /// Maximum allowed string lengths as u32 for compatibility with error types
     pub const MAX_STRING_LENGTH_U32: u32 = 65535;
     pub const MAX_DATA_LENGTH: u32 = 1_000_000; // 1MB per node max
+
+    /// Typical serialized node size, used to size file reservations
+    pub const ESTIMATED_RECORD_SIZE: u64 = 128;
 }
 
 /// Edge record constants
@@ -84,13 +87,35 @@ pub mod edge {
     /// Maximum allowed string lengths as u32 for compatibility with error types
     pub const MAX_STRING_LENGTH_U32: u32 = 65535;
     pub const MAX_DATA_LENGTH: u32 = 1_000_000; // 1MB per edge max
+
+    /// Size of the fixed slot each edge record occupies
+    pub const SLOT_SIZE: u64 = 256;
 }
 
 /// Default feature flags (currently none defined)
 pub const DEFAULT_FEATURE_FLAGS: u32 = 0;
 
+/// Feature flag: node and edge data payloads are MessagePack instead of JSON
+pub const FEATURE_MSGPACK_DATA: u32 = 1 << 0;
+
 /// Default schema version
-pub const DEFAULT_SCHEMA_VERSION: u64 = 1;
+pub const DEFAULT_SCHEMA_VERSION: u64 = 2;
+
+/// First schema version whose files may end with a persisted property index section.
+/// Older files have no section and get their index rebuilt on first use.
+pub const PROPERTY_INDEX_SCHEMA_VERSION: u64 = 2;
+
+/// Property index section constants
+pub mod property_index {
+    /// Magic number closing the property index footer
+    pub const FOOTER_MAGIC: [u8; 8] = [b'S', b'Q', b'L', b'T', b'G', b'P', b'I', b'X'];
+
+    /// Footer size in bytes: section offset + checksum + magic
+    pub const FOOTER_SIZE: u64 = 24;
+
+    /// Fixed size of the section header: indexed-through node id + entry count
+    pub const SECTION_HEADER_SIZE: usize = 16;
+}
 
 /// Checksum calculation parameters
 pub mod checksum {
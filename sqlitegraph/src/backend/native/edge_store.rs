@@ -24,12 +24,61 @@ impl<'a> EdgeStore<'a> {
         // Validate edge record - check node references against current node count
         self.validate_edge_fields(edge)?;
 
+        self.write_edge_slot(edge)?;
+
+        // Update node adjacency metadata
+        self.update_node_adjacency(&edge)?;
+
+        // Update header if this is a new edge
+        if edge.id as u64 > self.graph_file.header().edge_count {
+            self.graph_file.header_mut().edge_count = edge.id as u64;
+            // Persist header changes to disk
+            self.graph_file.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Write an edge record into its slot without touching node adjacency metadata
+    ///
+    /// Used when rewriting a file whose node records already carry their
+    /// adjacency ranges.
+    pub fn write_edge_slot_only(&mut self, edge: &EdgeRecord) -> NativeResult<()> {
+        self.validate_edge_fields(edge)?;
+        self.write_edge_slot(edge)?;
+        if edge.id as u64 > self.graph_file.header().edge_count {
+            self.graph_file.header_mut().edge_count = edge.id as u64;
+        }
+        Ok(())
+    }
+
+    /// Replace the data of an existing edge, rewriting its slot in place
+    ///
+    /// Endpoints, type and node adjacency metadata are left untouched.
+    pub fn update_edge_data(
+        &mut self,
+        edge_id: NativeEdgeId,
+        data: serde_json::Value,
+    ) -> NativeResult<()> {
+        let mut edge = self.read_edge(edge_id)?;
+        edge.data = data;
+        self.write_edge_slot(&edge)
+    }
+
+    /// Serialize an edge into its fixed-size slot
+    fn write_edge_slot(&mut self, edge: &EdgeRecord) -> NativeResult<()> {
         // Serialize edge record
         let serialized = self.serialize_edge(edge)?;
 
         // Calculate offset where this edge should be written (fixed-size slot)
         let offset = self.edge_offset(edge.id);
         let fixed_slot_size = 256u64;
+        if serialized.len() as u64 > fixed_slot_size {
+            return Err(NativeBackendError::RecordTooLarge {
+                size: serialized.len() as u32,
+                max_size: fixed_slot_size as u32,
+            });
+        }
 
         // Ensure file is large enough for the fixed-size edge slot
         let edge_end = offset + fixed_slot_size;
@@ -44,39 +93,34 @@ impl<'a> EdgeStore<'a> {
 
         // Write to file
         self.graph_file.write_bytes(offset, &buffer)?;
-
-        // Update node adjacency metadata
-        self.update_node_adjacency(&edge)?;
-
-        // Update header if this is a new edge
-        if edge.id as u64 > self.graph_file.header().edge_count {
-            self.graph_file.header_mut().edge_count = edge.id as u64;
-            // Persist header changes to disk
-            self.graph_file.flush()?;
-        }
-
         Ok(())
     }
 
     /// Update node adjacency metadata when an edge is written
+    ///
+    /// Each range runs from the node's first edge id to its last, as
+    /// [`native_compact`](super::graph_ops::native_compact) lays them out;
+    /// edges of other nodes inside the range are skipped when iterating.
     fn update_node_adjacency(&mut self, edge: &EdgeRecord) -> NativeResult<()> {
         let mut node_store = NodeStore::new(self.graph_file);
 
         // Update source node (outgoing)
         let mut source_node = node_store.read_node(edge.from_id)?;
-        if source_node.outgoing_count == 0 {
-            source_node.outgoing_offset = edge.id as FileOffset;
-        }
-        source_node.outgoing_count += 1;
-        node_store.write_node(&source_node)?;
+        (source_node.outgoing_offset, source_node.outgoing_count) = extend_range(
+            source_node.outgoing_offset,
+            source_node.outgoing_count,
+            edge.id,
+        );
+        node_store.rewrite_node(&source_node)?;
 
         // Update target node (incoming)
         let mut target_node = node_store.read_node(edge.to_id)?;
-        if target_node.incoming_count == 0 {
-            target_node.incoming_offset = edge.id as FileOffset;
-        }
-        target_node.incoming_count += 1;
-        node_store.write_node(&target_node)?;
+        (target_node.incoming_offset, target_node.incoming_count) = extend_range(
+            target_node.incoming_offset,
+            target_node.incoming_count,
+            edge.id,
+        );
+        node_store.rewrite_node(&target_node)?;
 
         Ok(())
     }
@@ -118,7 +162,31 @@ impl<'a> EdgeStore<'a> {
     }
 
     /// Read an edge record from the file
+    ///
+    /// Deleted edges are reported as [`NativeBackendError::DeletedEdge`].
     pub fn read_edge(&mut self, edge_id: NativeEdgeId) -> NativeResult<EdgeRecord> {
+        let edge = self.read_edge_including_deleted(edge_id)?;
+        if edge.flags.contains(EdgeFlags::DELETED) {
+            return Err(NativeBackendError::DeletedEdge { id: edge_id });
+        }
+        Ok(edge)
+    }
+
+    /// Mark an edge as deleted by rewriting the flags of its slot in place
+    pub fn mark_deleted(&mut self, edge_id: NativeEdgeId) -> NativeResult<()> {
+        let edge = self.read_edge_including_deleted(edge_id)?;
+        let flags = edge.flags.set(EdgeFlags::DELETED);
+        let offset = self.edge_offset(edge_id);
+        self.graph_file
+            .write_bytes(offset + 1, &flags.0.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// Read an edge record whether or not it has been deleted
+    pub fn read_edge_including_deleted(
+        &mut self,
+        edge_id: NativeEdgeId,
+    ) -> NativeResult<EdgeRecord> {
         let header = self.graph_file.header();
 
         if edge_id <= 0 || edge_id > header.edge_count as NativeEdgeId {
@@ -211,7 +279,11 @@ impl<'a> EdgeStore<'a> {
         buffer.extend_from_slice(&(edge_type_bytes.len() as u16).to_be_bytes());
 
         // Data length (big-endian)
-        let data_bytes = serde_json::to_vec(&edge.data)?;
+        let data_bytes = self
+            .graph_file
+            .data_format()
+            .encode(&edge.data)
+            .map_err(NativeBackendError::InvalidData)?;
         if data_bytes.len() > edge::MAX_DATA_LENGTH as usize {
             return Err(NativeBackendError::RecordTooLarge {
                 size: data_bytes.len() as u32,
@@ -325,7 +397,14 @@ impl<'a> EdgeStore<'a> {
 
         // Read data
         let data_bytes = &buffer[offset..offset + data_len];
-        let data = serde_json::from_slice(data_bytes)?;
+        let data = self
+            .graph_file
+            .data_format()
+            .decode(data_bytes)
+            .map_err(|e| NativeBackendError::CorruptEdgeRecord {
+                edge_id,
+                reason: e.to_string(),
+            })?;
 
         Ok(EdgeRecord {
             id,
@@ -423,7 +502,7 @@ impl<'a> EdgeStore<'a> {
         let max_node_id = self.graph_file.header().node_count as NativeNodeId;
 
         for edge_id in 1..=max_id {
-            match self.read_edge(edge_id) {
+            match self.read_edge_including_deleted(edge_id) {
                 Ok(edge) => {
                     // Validate node references
                     if edge.from_id <= 0 || edge.from_id > max_node_id {
@@ -453,6 +532,20 @@ impl<'a> EdgeStore<'a> {
     }
 }
 
+/// Adjacency range `(first edge id, span)` widened to cover `edge_id`
+pub(crate) fn extend_range(
+    offset: FileOffset,
+    count: u32,
+    edge_id: NativeEdgeId,
+) -> (FileOffset, u32) {
+    if count == 0 {
+        return (edge_id as FileOffset, 1);
+    }
+    let first = (offset as NativeEdgeId).min(edge_id);
+    let last = (offset as NativeEdgeId + count as NativeEdgeId - 1).max(edge_id);
+    (first as FileOffset, (last - first + 1) as u32)
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::node_store::NodeStore;
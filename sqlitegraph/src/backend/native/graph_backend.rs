@@ -1,23 +1,31 @@
 //! Native GraphBackend implementation with interior mutability.
 
-use super::adjacency::{AdjacencyHelpers, Direction};
+use std::collections::{HashMap, hash_map::Entry};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::constants::DEFAULT_SCHEMA_VERSION;
 use super::edge_store::EdgeStore;
 use super::graph_file::GraphFile;
 use super::graph_ops::*;
 use super::graph_validation::*;
 use super::node_store::NodeStore;
 use super::types::*;
-use crate::SqliteGraphError;
 use crate::backend::{
-    BackendDirection, ChainStep, EdgeSpec, GraphBackend, NeighborQuery, NodeSpec, PatternMatch,
-    PatternQuery,
+    BackendDirection, ChainStep, EdgeSpec, GraphBackend, NeighborQuery, NodeEdges, NodeSpec,
+    PatternMatch, PatternQuery,
 };
-use crate::graph::GraphEntity;
+use crate::cache::CacheStats;
+use crate::data_format::DataFormat;
+use crate::graph::{GraphEdge, GraphEntity, GraphStatistics};
+use crate::multi_hop;
+use crate::safety::SafetyReport;
+use crate::{EntityKind, SqliteGraphError};
 use parking_lot::RwLock;
 
 /// Native backend implementation using interior mutability
 pub struct NativeGraphBackend {
     graph_file: RwLock<GraphFile>,
+    reject_self_loops: AtomicBool,
 }
 
 impl NativeGraphBackend {
@@ -25,13 +33,13 @@ impl NativeGraphBackend {
     #[cfg(test)]
     pub fn new_temp() -> Result<Self, SqliteGraphError> {
         use tempfile::NamedTempFile;
-        let temp_file =
-            NamedTempFile::new().map_err(|e| SqliteGraphError::connection(e.to_string()))?;
+        let temp_file = NamedTempFile::new().map_err(SqliteGraphError::connection)?;
         let path = temp_file.path();
         let graph_file = GraphFile::create(path).map_err(map_to_graph_error)?;
 
         Ok(Self {
             graph_file: RwLock::new(graph_file),
+            reject_self_loops: AtomicBool::new(false),
         })
     }
 
@@ -41,6 +49,21 @@ impl NativeGraphBackend {
 
         Ok(Self {
             graph_file: RwLock::new(graph_file),
+            reject_self_loops: AtomicBool::new(false),
+        })
+    }
+
+    /// Create a native backend held entirely in memory
+    ///
+    /// It supports every operation of a file-backed backend, compaction
+    /// included, without touching the file system; the graph is lost when the
+    /// backend is dropped.
+    pub fn in_memory() -> Result<Self, SqliteGraphError> {
+        let graph_file = GraphFile::create_in_memory().map_err(map_to_graph_error)?;
+
+        Ok(Self {
+            graph_file: RwLock::new(graph_file),
+            reject_self_loops: AtomicBool::new(false),
         })
     }
 
@@ -50,9 +73,150 @@ impl NativeGraphBackend {
 
         Ok(Self {
             graph_file: RwLock::new(graph_file),
+            reject_self_loops: AtomicBool::new(false),
         })
     }
 
+    /// Current header flush policy.
+    pub fn flush_policy(&self) -> FlushPolicy {
+        self.graph_file.read().flush_policy()
+    }
+
+    /// Change how often the file header is persisted after inserts.
+    pub fn set_flush_policy(&self, policy: FlushPolicy) {
+        self.graph_file.write().set_flush_policy(policy);
+    }
+
+    /// Encoding of node and edge data payloads in this file.
+    pub fn data_format(&self) -> DataFormat {
+        self.graph_file.read().data_format()
+    }
+
+    /// Choose the data payload encoding, recorded in the file header.
+    ///
+    /// A file uses a single format, so this fails once any node or edge has
+    /// been written.
+    pub fn set_data_format(&self, format: DataFormat) -> Result<(), SqliteGraphError> {
+        self.with_graph_file(|graph_file| graph_file.set_data_format(format))
+    }
+
+    /// Whether self-loop edges are rejected on insert.
+    pub fn reject_self_loops(&self) -> bool {
+        self.reject_self_loops.load(Ordering::Relaxed)
+    }
+
+    /// Reject edges whose `from` equals their `to` with
+    /// [`SqliteGraphError::InvalidInput`]. The setting is not stored in the file.
+    pub fn set_reject_self_loops(&self, reject: bool) {
+        self.reject_self_loops.store(reject, Ordering::Relaxed);
+    }
+
+    /// Fail if self-loops are rejected and any of `specs` is one
+    fn check_self_loops<'a, I>(&self, specs: I) -> Result<(), SqliteGraphError>
+    where
+        I: IntoIterator<Item = &'a EdgeSpec>,
+    {
+        if !self.reject_self_loops() {
+            return Ok(());
+        }
+        match specs.into_iter().find(|spec| spec.from == spec.to) {
+            Some(spec) => Err(SqliteGraphError::invalid_input(format!(
+                "self-loop edge on node {} rejected",
+                spec.from
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Persist the file header and sync the file to disk.
+    ///
+    /// Required to make inserts survive a process crash under
+    /// [`FlushPolicy::Manual`], and under every policy to make them survive a
+    /// power loss or OS crash, since the policies write the header without
+    /// syncing.
+    pub fn flush(&self) -> Result<(), SqliteGraphError> {
+        self.with_graph_file(|graph_file| graph_file.persist_header())
+    }
+
+    /// Reserve file space for about `nodes` more nodes and `edges` more edges.
+    ///
+    /// A hint for bulk loads: inserts that fit in the reservation advance the
+    /// end of the data instead of resizing the file each time. The unused part
+    /// is released by the next [`flush`](Self::flush) or when the graph is
+    /// closed, so it never ends up in the file on a clean shutdown. Space left
+    /// behind by a crash is trimmed when the file is next opened.
+    pub fn reserve(&self, nodes: usize, edges: usize) -> Result<(), SqliteGraphError> {
+        self.with_graph_file(|graph_file| graph_file.reserve(nodes as u64, edges as u64))
+    }
+
+    /// Number of times the file has been resized to make room for records
+    /// since the graph was opened.
+    pub fn file_growth_count(&self) -> u64 {
+        self.graph_file.read().growth_count()
+    }
+
+    /// Insert a batch of nodes, returning their ids in input order.
+    ///
+    /// Records are written in one pass and the header is persisted once at the
+    /// end, regardless of the flush policy. A failure rolls the whole batch back.
+    pub fn bulk_insert_nodes(&self, specs: &[NodeSpec]) -> Result<Vec<i64>, SqliteGraphError> {
+        self.with_graph_file(|graph_file| native_bulk_insert_nodes(graph_file, specs))
+    }
+
+    /// Insert a batch of edges, returning their ids in input order.
+    ///
+    /// Adjacency metadata is updated once per touched node rather than per
+    /// edge, and the header is persisted once at the end. A failure, such as a
+    /// missing endpoint or a rejected self-loop, rolls the whole batch back.
+    pub fn bulk_insert_edges(&self, specs: &[EdgeSpec]) -> Result<Vec<i64>, SqliteGraphError> {
+        self.check_self_loops(specs)?;
+        self.with_graph_file(|graph_file| native_bulk_insert_edges(graph_file, specs))
+    }
+
+    /// Rewrite the graph file without deleted records and stale node copies.
+    ///
+    /// Node and edge ids are preserved. The compacted file replaces the old one
+    /// by an atomic rename, so a crash mid-compaction leaves the old file intact.
+    pub fn compact(&self) -> Result<CompactionStats, SqliteGraphError> {
+        self.with_graph_file(native_compact)
+    }
+
+    /// Check the header and every node and edge record without stopping at the
+    /// first failure.
+    ///
+    /// Returns the ids of the records that cannot be read back; see
+    /// [`VerifyReport`] for what counts as corrupt.
+    pub fn verify(&self) -> Result<VerifyReport, SqliteGraphError> {
+        self.with_graph_file(native_verify)
+    }
+
+    /// Drop the records [`verify`](Self::verify) flags and rebuild the header
+    /// counts and adjacency.
+    ///
+    /// Corrupt nodes become deleted tombstones and edges that are corrupt or
+    /// touch such a node are deleted, so surviving ids are unchanged. The file
+    /// is rewritten and renamed into place like [`compact`](Self::compact).
+    /// Returns the report taken before repairing; a clean file is left as is.
+    pub fn repair(&self) -> Result<VerifyReport, SqliteGraphError> {
+        self.with_graph_file(native_repair)
+    }
+
+    /// Replace the whole file with `nodes` and `edges`, keeping their ids; see
+    /// [`native_load`].
+    pub(crate) fn load_records(
+        &self,
+        nodes: &[NodeRecord],
+        edges: &[EdgeRecord],
+    ) -> Result<CompactionStats, SqliteGraphError> {
+        self.with_graph_file(|graph_file| native_load(graph_file, nodes, edges))
+    }
+
+    /// Every node and edge record in id order, deleted ones included; see
+    /// [`native_records`].
+    pub(crate) fn records(&self) -> Result<(Vec<NodeRecord>, Vec<EdgeRecord>), SqliteGraphError> {
+        self.with_graph_file(native_records)
+    }
+
     /// Get mutable access to the underlying graph file for internal operations
     fn with_graph_file<R, F>(&self, f: F) -> Result<R, SqliteGraphError>
     where
@@ -61,6 +225,24 @@ impl NativeGraphBackend {
         let mut graph_file = self.graph_file.write();
         f(&mut *graph_file).map_err(map_to_graph_error)
     }
+
+    /// Like `with_graph_file`, but out-of-range ids are reported as not found,
+    /// matching the SQLite backend for lookups and mutations of existing records.
+    fn with_existing<R, F>(&self, f: F) -> Result<R, SqliteGraphError>
+    where
+        F: FnOnce(&mut GraphFile) -> Result<R, NativeBackendError>,
+    {
+        let mut graph_file = self.graph_file.write();
+        f(&mut graph_file).map_err(|err| match err {
+            NativeBackendError::InvalidNodeId { id, .. } => {
+                SqliteGraphError::not_found(EntityKind::Node, id)
+            }
+            NativeBackendError::InvalidEdgeId { id, .. } => {
+                SqliteGraphError::not_found(EntityKind::Edge, id)
+            }
+            other => map_to_graph_error(other),
+        })
+    }
 }
 
 impl GraphBackend for NativeGraphBackend {
@@ -70,12 +252,13 @@ impl GraphBackend for NativeGraphBackend {
             let node_id = node_store.allocate_node_id();
             let record = node_spec_to_record(node, node_id);
             node_store.write_node(&record)?;
+            graph_file.record_write()?;
             Ok(node_id as i64)
         })
     }
 
     fn get_node(&self, id: i64) -> Result<GraphEntity, SqliteGraphError> {
-        self.with_graph_file(|graph_file| {
+        self.with_existing(|graph_file| {
             let mut node_store = NodeStore::new(graph_file);
             let record = node_store.read_node(id as NativeNodeId)?;
             Ok(node_record_to_entity(record))
@@ -83,12 +266,69 @@ impl GraphBackend for NativeGraphBackend {
     }
 
     fn insert_edge(&self, edge: EdgeSpec) -> Result<i64, SqliteGraphError> {
+        self.check_self_loops([&edge])?;
+        self.with_existing(|graph_file| native_insert_edge(graph_file, edge))
+    }
+
+    fn upsert_edge(&self, edge: EdgeSpec) -> Result<i64, SqliteGraphError> {
+        self.check_self_loops([&edge])?;
+        self.with_existing(|graph_file| {
+            let (from, to) = (edge.from as NativeNodeId, edge.to as NativeNodeId);
+            match native_find_edge(graph_file, from, to, &edge.edge_type)? {
+                Some(edge_id) => {
+                    EdgeStore::new(graph_file).update_edge_data(edge_id, edge.data)?;
+                    Ok(edge_id)
+                }
+                None => native_insert_edge(graph_file, edge),
+            }
+        })
+    }
+
+    fn get_edge(&self, id: i64) -> Result<GraphEdge, SqliteGraphError> {
+        self.with_existing(|graph_file| {
+            let record = EdgeStore::new(graph_file).read_edge(id as NativeEdgeId)?;
+            Ok(edge_record_to_edge(record))
+        })
+    }
+
+    fn list_edge_ids(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        self.with_graph_file(native_live_edge_ids)
+    }
+
+    /// Native nodes have no property table; `key` is a top-level property of the
+    /// node's `data`, looked up in the file's sorted property index.
+    fn find_nodes_by_property(&self, key: &str, value: &str) -> Result<Vec<i64>, SqliteGraphError> {
+        self.with_graph_file(|graph_file| native_find_nodes_by_property(graph_file, key, value))
+    }
+
+    /// Native nodes have no label table; labels are read from a `labels` array in `data`.
+    fn find_nodes_by_label(&self, label: &str) -> Result<Vec<i64>, SqliteGraphError> {
         self.with_graph_file(|graph_file| {
+            native_scan_nodes(graph_file, |data| data_has_label(data, label))
+        })
+    }
+
+    fn delete_node(&self, id: i64) -> Result<(), SqliteGraphError> {
+        self.with_existing(|graph_file| native_delete_node(graph_file, id as NativeNodeId))
+    }
+
+    fn delete_edge(&self, id: i64) -> Result<(), SqliteGraphError> {
+        self.with_existing(|graph_file| {
             let mut edge_store = EdgeStore::new(graph_file);
-            let edge_id = edge_store.allocate_edge_id();
-            let record = edge_spec_to_record(edge, edge_id);
-            edge_store.write_edge(&record)?;
-            Ok(edge_id as i64)
+            edge_store.read_edge(id as NativeEdgeId)?;
+            edge_store.mark_deleted(id as NativeEdgeId)
+        })
+    }
+
+    fn update_node_data(&self, id: i64, data: serde_json::Value) -> Result<(), SqliteGraphError> {
+        self.with_existing(|graph_file| {
+            NodeStore::new(graph_file).update_node_data(id as NativeNodeId, data)
+        })
+    }
+
+    fn update_edge_data(&self, id: i64, data: serde_json::Value) -> Result<(), SqliteGraphError> {
+        self.with_existing(|graph_file| {
+            EdgeStore::new(graph_file).update_edge_data(id as NativeEdgeId, data)
         })
     }
 
@@ -96,39 +336,50 @@ impl GraphBackend for NativeGraphBackend {
         self.with_graph_file(|graph_file| {
             let node_id = node as NativeNodeId;
 
-            let neighbors = if let Some(edge_type) = &query.edge_type {
-                let edge_type_ref = edge_type.as_str();
-                match query.direction {
-                    BackendDirection::Outgoing => {
-                        AdjacencyHelpers::get_outgoing_neighbors_filtered(
-                            graph_file,
-                            node_id,
-                            &[edge_type_ref],
-                        )
-                    }
-                    BackendDirection::Incoming => {
-                        AdjacencyHelpers::get_incoming_neighbors_filtered(
-                            graph_file,
-                            node_id,
-                            &[edge_type_ref],
-                        )
-                    }
-                }
-            } else {
-                match query.direction {
-                    BackendDirection::Outgoing => {
-                        AdjacencyHelpers::get_outgoing_neighbors(graph_file, node_id)
-                    }
-                    BackendDirection::Incoming => {
-                        AdjacencyHelpers::get_incoming_neighbors(graph_file, node_id)
-                    }
-                }
-            }?;
+            let edge_type = query.edge_type.as_deref();
+            let neighbors = native_neighbors(
+                graph_file,
+                node_id,
+                query.direction,
+                edge_type.as_ref().map(std::slice::from_ref),
+            )?;
 
             Ok(neighbors.into_iter().map(|id| id as i64).collect())
         })
     }
 
+    fn neighbors_uncached(
+        &self,
+        node: i64,
+        query: NeighborQuery,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        // Adjacency is read from the node and edge records on every lookup
+        self.neighbors(node, query)
+    }
+
+    fn neighbors_batch(
+        &self,
+        ids: &[i64],
+        query: NeighborQuery,
+    ) -> Result<HashMap<i64, Vec<i64>>, SqliteGraphError> {
+        self.with_graph_file(|graph_file| {
+            let edge_type = query.edge_type.as_deref();
+            let edge_types = edge_type.as_ref().map(std::slice::from_ref);
+            let mut result = HashMap::with_capacity(ids.len());
+            for &id in ids {
+                if let Entry::Vacant(entry) = result.entry(id) {
+                    entry.insert(native_neighbors(
+                        graph_file,
+                        id as NativeNodeId,
+                        query.direction,
+                        edge_types,
+                    )?);
+                }
+            }
+            Ok(result)
+        })
+    }
+
     fn bfs(&self, start: i64, depth: u32) -> Result<Vec<i64>, SqliteGraphError> {
         self.with_graph_file(|graph_file| {
             let result = native_bfs(graph_file, start as NativeNodeId, depth)?;
@@ -136,6 +387,28 @@ impl GraphBackend for NativeGraphBackend {
         })
     }
 
+    fn bfs_directed(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        self.with_graph_file(|graph_file| {
+            native_bfs_directed(graph_file, start as NativeNodeId, depth, direction)
+        })
+    }
+
+    fn bfs_filtered(
+        &self,
+        start: i64,
+        max_depth: u32,
+        edge_types: &[&str],
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        self.with_graph_file(|graph_file| {
+            native_bfs_filtered(graph_file, start as NativeNodeId, max_depth, edge_types)
+        })
+    }
+
     fn shortest_path(&self, start: i64, end: i64) -> Result<Option<Vec<i64>>, SqliteGraphError> {
         self.with_graph_file(|graph_file| {
             let result =
@@ -144,12 +417,78 @@ impl GraphBackend for NativeGraphBackend {
         })
     }
 
+    fn shortest_path_directed(
+        &self,
+        start: i64,
+        end: i64,
+        direction: BackendDirection,
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+        self.with_graph_file(|graph_file| {
+            native_shortest_path_directed(
+                graph_file,
+                start as NativeNodeId,
+                end as NativeNodeId,
+                direction,
+            )
+        })
+    }
+
+    fn shortest_path_filtered(
+        &self,
+        start: i64,
+        end: i64,
+        edge_types: &[&str],
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+        self.with_graph_file(|graph_file| {
+            native_shortest_path_filtered(
+                graph_file,
+                start as NativeNodeId,
+                end as NativeNodeId,
+                edge_types,
+            )
+        })
+    }
+
     fn node_degree(&self, node: i64) -> Result<(usize, usize), SqliteGraphError> {
         self.with_graph_file(|graph_file| {
-            let node_id = node as NativeNodeId;
-            let outgoing = AdjacencyHelpers::outgoing_degree(graph_file, node_id)?;
-            let incoming = AdjacencyHelpers::incoming_degree(graph_file, node_id)?;
-            Ok((outgoing as usize, incoming as usize))
+            // Counted from the edge slots so deleted edges drop out.
+            let (outgoing, incoming) = native_node_edges(graph_file, node as NativeNodeId)?;
+            Ok((outgoing.len(), incoming.len()))
+        })
+    }
+
+    fn neighbor_count(&self, node: i64, query: NeighborQuery) -> Result<usize, SqliteGraphError> {
+        self.with_graph_file(|graph_file| {
+            let edge_type = query.edge_type.as_deref();
+            native_neighbor_count(
+                graph_file,
+                node as NativeNodeId,
+                query.direction,
+                edge_type.as_ref().map(std::slice::from_ref),
+            )
+        })
+    }
+
+    fn node_edges(&self, node: i64) -> Result<NodeEdges, SqliteGraphError> {
+        self.with_graph_file(|graph_file| {
+            let (outgoing, incoming) = native_node_edges(graph_file, node as NativeNodeId)?;
+            Ok(NodeEdges {
+                outgoing: outgoing.into_iter().map(edge_record_to_edge).collect(),
+                incoming: incoming.into_iter().map(edge_record_to_edge).collect(),
+            })
+        })
+    }
+
+    fn top_nodes_by_degree(
+        &self,
+        k: usize,
+        direction: BackendDirection,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
+        self.with_graph_file(|graph_file| {
+            let (nodes, edges) = native_live_graph(graph_file)?;
+            Ok(crate::algo::top_by_degree_from_edges(
+                &nodes, edges, k, direction,
+            ))
         })
     }
 
@@ -160,19 +499,22 @@ impl GraphBackend for NativeGraphBackend {
         direction: BackendDirection,
     ) -> Result<Vec<i64>, SqliteGraphError> {
         self.with_graph_file(|graph_file| {
-            let result = native_k_hop(
-                graph_file,
-                start as NativeNodeId,
-                depth,
-                match direction {
-                    BackendDirection::Outgoing => Direction::Outgoing,
-                    BackendDirection::Incoming => Direction::Incoming,
-                },
-            )?;
+            let result = native_k_hop(graph_file, start as NativeNodeId, depth, direction)?;
             Ok(result.into_iter().map(|id| id as i64).collect())
         })
     }
 
+    fn k_hop_with_distance(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
+        self.with_graph_file(|graph_file| {
+            native_k_hop_with_distance(graph_file, start as NativeNodeId, depth, direction)
+        })
+    }
+
     fn k_hop_filtered(
         &self,
         start: i64,
@@ -185,16 +527,59 @@ impl GraphBackend for NativeGraphBackend {
                 graph_file,
                 start as NativeNodeId,
                 depth,
-                match direction {
-                    BackendDirection::Outgoing => Direction::Outgoing,
-                    BackendDirection::Incoming => Direction::Incoming,
-                },
+                direction,
                 allowed_edge_types,
             )?;
             Ok(result.into_iter().map(|id| id as i64).collect())
         })
     }
 
+    fn k_hop_weighted(
+        &self,
+        start: i64,
+        budget: f64,
+        direction: BackendDirection,
+        type_costs: &HashMap<String, f64>,
+    ) -> Result<Vec<(i64, f64)>, SqliteGraphError> {
+        let mut graph_file = self.graph_file.write();
+        multi_hop::cost_bounded_expand(start, budget, type_costs, |node, edge_type| {
+            native_neighbors(&mut graph_file, node, direction, Some(&[edge_type]))
+                .map_err(map_to_graph_error)
+        })
+    }
+
+    fn all_pairs_shortest_paths(
+        &self,
+        max_nodes: usize,
+    ) -> Result<HashMap<(i64, i64), u32>, SqliteGraphError> {
+        let (nodes, edges) = self.with_graph_file(native_live_graph)?;
+        crate::algo::all_pairs_shortest_paths_from_edges(&nodes, edges, max_nodes)
+    }
+
+    fn betweenness_centrality(
+        &self,
+        normalized: bool,
+        max_nodes: usize,
+    ) -> Result<HashMap<i64, f64>, SqliteGraphError> {
+        let (nodes, edges) = self.with_graph_file(native_live_graph)?;
+        crate::algo::betweenness_from_edges(&nodes, edges, normalized, max_nodes)
+    }
+
+    fn astar(
+        &self,
+        from: i64,
+        to: i64,
+        weight_key: &str,
+        heuristic: &dyn Fn(i64) -> f64,
+    ) -> Result<Option<(Vec<i64>, f64)>, SqliteGraphError> {
+        let mut graph_file = self.graph_file.write();
+        crate::algo::astar(from, to, weight_key, heuristic, |node| {
+            let (outgoing, _) = native_node_edges(&mut graph_file, node as NativeNodeId)
+                .map_err(map_to_graph_error)?;
+            Ok(outgoing.into_iter().map(edge_record_to_edge).collect())
+        })
+    }
+
     fn chain_query(&self, start: i64, chain: &[ChainStep]) -> Result<Vec<i64>, SqliteGraphError> {
         self.with_graph_file(|graph_file| {
             let result = native_chain_query(graph_file, start as NativeNodeId, chain)?;
@@ -202,15 +587,70 @@ impl GraphBackend for NativeGraphBackend {
         })
     }
 
+    fn connected_components(&self) -> Result<Vec<Vec<i64>>, SqliteGraphError> {
+        self.with_graph_file(native_connected_components)
+    }
+
+    fn pagerank(
+        &self,
+        damping: f64,
+        iterations: usize,
+    ) -> Result<HashMap<i64, f64>, SqliteGraphError> {
+        let (nodes, edges) = self.with_graph_file(native_live_graph)?;
+        crate::algo::pagerank_from_edges(&nodes, edges, damping, iterations)
+    }
+
+    fn topological_sort(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        let (nodes, edges) = self.with_graph_file(native_live_graph)?;
+        crate::algo::topological_sort_from_edges(&nodes, edges)
+    }
+
     fn pattern_search(
         &self,
         start: i64,
         pattern: &PatternQuery,
     ) -> Result<Vec<PatternMatch>, SqliteGraphError> {
-        self.with_graph_file(|graph_file| {
+        let matches = self.with_graph_file(|graph_file| {
             native_pattern_search(graph_file, start as NativeNodeId, pattern)
+        })?;
+        Ok(pattern.paginate(matches))
+    }
+
+    fn statistics(&self) -> Result<GraphStatistics, SqliteGraphError> {
+        self.with_graph_file(native_statistics)
+    }
+
+    fn cache_stats(&self) -> CacheStats {
+        // Adjacency is read from the records on every lookup; nothing is cached
+        CacheStats::default()
+    }
+
+    fn reset_cache_stats(&self) {}
+
+    fn validate_integrity(&self) -> Result<SafetyReport, SqliteGraphError> {
+        Ok(SafetyReport {
+            dangling_edges: self.with_graph_file(native_dangling_edges)?,
+            ..SafetyReport::default()
         })
     }
+
+    fn schema_version(&self) -> Result<u32, SqliteGraphError> {
+        let version = self.graph_file.read().header().schema_version;
+        u32::try_from(version)
+            .map_err(|_| SqliteGraphError::schema(format!("invalid schema version {version}")))
+    }
+
+    fn check_compatibility(&self) -> Result<(), SqliteGraphError> {
+        // Older files are read as they are; what they lack, such as the
+        // persisted property index, is rebuilt on first use
+        let version = self.graph_file.read().header().schema_version;
+        if version > DEFAULT_SCHEMA_VERSION {
+            return Err(SqliteGraphError::schema(format!(
+                "native schema version {version} is newer than supported {DEFAULT_SCHEMA_VERSION}"
+            )));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -242,4 +682,38 @@ mod tests {
         assert_eq!(node.name, "node1");
         assert_eq!(node.kind, "Test");
     }
+
+    #[test]
+    fn test_flush_policy_controls_header_persistence() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+        let persisted_nodes = || {
+            GraphFile::open(temp_file.path())
+                .unwrap()
+                .header()
+                .node_count
+        };
+        let node = || NodeSpec {
+            kind: "Test".to_string(),
+            name: "node".to_string(),
+            file_path: None,
+            data: serde_json::json!({}),
+        };
+
+        backend.insert_node(node()).unwrap();
+        assert_eq!(persisted_nodes(), 1);
+
+        backend.set_flush_policy(FlushPolicy::EveryN(2));
+        backend.insert_node(node()).unwrap();
+        assert_eq!(persisted_nodes(), 1);
+        backend.insert_node(node()).unwrap();
+        assert_eq!(persisted_nodes(), 3);
+
+        backend.set_flush_policy(FlushPolicy::Manual);
+        backend.insert_node(node()).unwrap();
+        backend.insert_node(node()).unwrap();
+        assert_eq!(persisted_nodes(), 3);
+        backend.flush().unwrap();
+        assert_eq!(persisted_nodes(), 5);
+    }
 }
@@ -4,18 +4,104 @@
 //! and basic file I/O operations for the native graph database format.
 
 use std::fs::{File, OpenOptions};
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 use std::os::unix::fs::OpenOptionsExt;
 use std::path::Path;
 
 use crate::backend::native::constants::*;
+use crate::backend::native::property_index::{PropertyIndex, decode_footer};
 use crate::backend::native::types::*;
+use crate::data_format::DataFormat;
+
+/// Bytes behind a [`GraphFile`]: a file on disk or a buffer in memory
+enum Storage {
+    File(File),
+    Memory(Cursor<Vec<u8>>),
+}
+
+impl Storage {
+    /// Current length in bytes
+    fn len(&self) -> std::io::Result<u64> {
+        match self {
+            Storage::File(file) => Ok(file.metadata()?.len()),
+            Storage::Memory(buffer) => Ok(buffer.get_ref().len() as u64),
+        }
+    }
+
+    /// Truncate or zero-extend to `len` bytes
+    fn set_len(&mut self, len: u64) -> std::io::Result<()> {
+        match self {
+            Storage::File(file) => file.set_len(len),
+            Storage::Memory(buffer) => {
+                buffer.get_mut().resize(len as usize, 0);
+                Ok(())
+            }
+        }
+    }
+
+    fn sync_all(&self) -> std::io::Result<()> {
+        match self {
+            Storage::File(file) => file.sync_all(),
+            Storage::Memory(_) => Ok(()),
+        }
+    }
+}
+
+impl Read for Storage {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Storage::File(file) => file.read(buf),
+            Storage::Memory(buffer) => buffer.read(buf),
+        }
+    }
+}
+
+impl Write for Storage {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Storage::File(file) => file.write(buf),
+            Storage::Memory(buffer) => buffer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Storage::File(file) => file.flush(),
+            Storage::Memory(_) => Ok(()),
+        }
+    }
+}
+
+impl Seek for Storage {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            Storage::File(file) => file.seek(pos),
+            Storage::Memory(buffer) => buffer.seek(pos),
+        }
+    }
+}
 
 /// Graph file wrapper that manages file handle and header operations
 pub struct GraphFile {
-    file: File,
+    file: Storage,
     header: FileHeader,
     file_path: std::path::PathBuf,
+    flush_policy: FlushPolicy,
+    unflushed_writes: usize,
+    /// Property index, or `None` until it is rebuilt from the node records
+    property_index: Option<PropertyIndex>,
+    /// Offset of the persisted property index section, while it is still on disk
+    index_section_offset: Option<u64>,
+    /// End of the written data while the file extends past it into reserved space
+    reserved_from: Option<u64>,
+    /// Number of times the file has been physically extended since it was opened
+    growths: u64,
+    /// Whether reads are served from a memory map of the file
+    #[cfg(feature = "mmap")]
+    use_mmap: bool,
+    /// Read-only mapping, dropped whenever the file is resized and remapped on the next read
+    #[cfg(feature = "mmap")]
+    map: Option<memmap2::Mmap>,
 }
 
 impl GraphFile {
@@ -33,11 +119,7 @@ impl GraphFile {
             .mode(FILE_PERMISSIONS)
             .open(path)?;
 
-        let mut graph_file = Self {
-            file,
-            header: FileHeader::new(),
-            file_path,
-        };
+        let mut graph_file = Self::with_storage(Storage::File(file), file_path);
 
         // Write initial header
         graph_file.write_header()?;
@@ -45,7 +127,43 @@ impl GraphFile {
         Ok(graph_file)
     }
 
+    /// Create an empty graph held in a buffer in memory
+    ///
+    /// Behaves like a file made with [`create`](Self::create) but never
+    /// touches the file system, and its contents are gone once dropped. It has
+    /// an empty [`path`](Self::path).
+    pub fn create_in_memory() -> NativeResult<Self> {
+        let mut graph_file = Self::with_storage(
+            Storage::Memory(Cursor::new(Vec::new())),
+            std::path::PathBuf::new(),
+        );
+        graph_file.write_header()?;
+        Ok(graph_file)
+    }
+
+    /// A fresh graph file over `file` with a new header that is not written yet
+    fn with_storage(file: Storage, file_path: std::path::PathBuf) -> Self {
+        Self {
+            file,
+            header: FileHeader::new(),
+            file_path,
+            flush_policy: FlushPolicy::default(),
+            unflushed_writes: 0,
+            property_index: Some(PropertyIndex::new()),
+            index_section_offset: None,
+            reserved_from: None,
+            growths: 0,
+            #[cfg(feature = "mmap")]
+            use_mmap: false,
+            #[cfg(feature = "mmap")]
+            map: None,
+        }
+    }
+
     /// Open an existing graph file
+    ///
+    /// With the `mmap` feature, reads of the opened file are served from a
+    /// read-only memory map; see [`set_mmap`](Self::set_mmap).
     pub fn open<P: AsRef<Path>>(path: P) -> NativeResult<Self> {
         let path = path.as_ref();
         let file_path = path.to_path_buf();
@@ -53,14 +171,26 @@ impl GraphFile {
         let file = OpenOptions::new().read(true).write(true).open(path)?;
 
         let mut graph_file = Self {
-            file,
+            file: Storage::File(file),
             header: FileHeader::new(), // Will be overwritten by read_header
             file_path,
+            flush_policy: FlushPolicy::default(),
+            unflushed_writes: 0,
+            property_index: None,
+            index_section_offset: None,
+            reserved_from: None,
+            growths: 0,
+            #[cfg(feature = "mmap")]
+            use_mmap: true,
+            #[cfg(feature = "mmap")]
+            map: None,
         };
 
         // Read and validate existing header
         graph_file.read_header()?;
         graph_file.header.validate()?;
+        graph_file.load_property_index()?;
+        graph_file.trim_unreleased_reservation()?;
 
         Ok(graph_file)
     }
@@ -88,6 +218,234 @@ impl GraphFile {
         Ok(())
     }
 
+    /// Load the property index section left at the end of the file, if it is intact
+    ///
+    /// A missing, stale or corrupt section is not an error: the index stays
+    /// unloaded and is rebuilt from the node records on first use.
+    fn load_property_index(&mut self) -> NativeResult<()> {
+        if self.header.schema_version < PROPERTY_INDEX_SCHEMA_VERSION {
+            return Ok(());
+        }
+        let file_size = self.file.len()?;
+        let min_size = HEADER_SIZE + property_index::FOOTER_SIZE;
+        if file_size < min_size {
+            return Ok(());
+        }
+        let footer_offset = file_size - property_index::FOOTER_SIZE;
+        let mut footer = vec![0u8; property_index::FOOTER_SIZE as usize];
+        self.read_bytes(footer_offset, &mut footer)?;
+        let Some((section_offset, checksum)) = decode_footer(&footer) else {
+            return Ok(());
+        };
+        if section_offset < HEADER_SIZE || section_offset > footer_offset {
+            return Ok(());
+        }
+        let mut section = vec![0u8; (footer_offset - section_offset) as usize];
+        self.read_bytes(section_offset, &mut section)?;
+        if let Some(index) = PropertyIndex::decode(&section, checksum)
+            .filter(|index| index.indexed_through() as u64 == self.header.node_count)
+        {
+            self.property_index = Some(index);
+            self.index_section_offset = Some(section_offset);
+        }
+        Ok(())
+    }
+
+    /// Cut off a zero-filled tail left by a reservation that was never released
+    ///
+    /// A process that dies during a reserved load leaves the reserved space in
+    /// the file, and records are appended at the physical end of an opened
+    /// file, so the tail is trimmed back to the end of the last record.
+    fn trim_unreleased_reservation(&mut self) -> NativeResult<()> {
+        if self.index_section_offset.is_some() {
+            return Ok(());
+        }
+        let len = self.file.len()?;
+        let data_end = self.records_end(len)?;
+        if data_end < len {
+            self.invalidate_map();
+            self.file.set_len(data_end)?;
+        }
+        Ok(())
+    }
+
+    /// End of the last node record or edge slot in the first `len` bytes
+    fn records_end(&mut self, len: u64) -> NativeResult<u64> {
+        // Everything up to the last non-zero byte is data
+        let mut end = len;
+        let mut chunk = vec![0u8; 64 * 1024];
+        while end > HEADER_SIZE {
+            let start = end.saturating_sub(chunk.len() as u64).max(HEADER_SIZE);
+            let buffer = &mut chunk[..(end - start) as usize];
+            self.read_bytes(start, buffer)?;
+            if let Some(last) = buffer.iter().rposition(|&byte| byte != 0) {
+                end = start + last as u64 + 1;
+                break;
+            }
+            end = start;
+        }
+        if end == len {
+            return Ok(len);
+        }
+
+        // Node records may end in zero bytes, so walk them
+        let has_edges = self.header.edge_count > 0;
+        let limit = if has_edges {
+            self.header.edge_data_offset.min(len)
+        } else {
+            len
+        };
+        let mut offset = self.header.node_data_offset;
+        let mut fixed = [0u8; node::FIXED_HEADER_SIZE];
+        while offset + fixed.len() as u64 <= limit {
+            self.read_bytes(offset, &mut fixed)?;
+            if fixed[0] != 1 {
+                break;
+            }
+            let kind_len = u16::from_be_bytes([fixed[13], fixed[14]]) as u64;
+            let name_len = u16::from_be_bytes([fixed[15], fixed[16]]) as u64;
+            let data_len = u32::from_be_bytes([fixed[17], fixed[18], fixed[19], fixed[20]]) as u64;
+            offset += fixed.len() as u64
+                + kind_len
+                + name_len
+                + data_len
+                + node::ADJACENCY_METADATA_SIZE as u64;
+        }
+        end = end.max(offset.min(len));
+
+        // Edge slots are padded with zeros to their full size
+        let edge_start = self.header.edge_data_offset;
+        if has_edges && end > edge_start {
+            let slots = (end - edge_start + edge::SLOT_SIZE - 1) / edge::SLOT_SIZE;
+            end = (edge_start + slots * edge::SLOT_SIZE).min(len);
+        }
+        Ok(end)
+    }
+
+    /// Write the property index section at the end of the file
+    ///
+    /// Nothing is written while the index is unloaded or the section on disk is
+    /// still current.
+    fn persist_property_index(&mut self) -> NativeResult<()> {
+        // The section has to follow the data directly
+        self.release_reservation()?;
+        if self.index_section_offset.is_some() {
+            return Ok(());
+        }
+        let Some(index) = &self.property_index else {
+            return Ok(());
+        };
+        let section_offset = self.file.len()?;
+        let bytes = index.encode(section_offset);
+        self.file.seek(SeekFrom::Start(section_offset))?;
+        self.file.write_all(&bytes)?;
+        self.index_section_offset = Some(section_offset);
+        self.header.schema_version = self
+            .header
+            .schema_version
+            .max(PROPERTY_INDEX_SCHEMA_VERSION);
+        Ok(())
+    }
+
+    /// Cut the persisted property index section off the end of the file
+    ///
+    /// Called before every record write, so the section never sits between
+    /// records; the in-memory index is written back on the next persist.
+    fn discard_index_section(&mut self) -> NativeResult<()> {
+        if let Some(section_offset) = self.index_section_offset.take() {
+            // Accessing a mapping past the end of a shrunk file faults
+            self.invalidate_map();
+            self.file.set_len(section_offset)?;
+        }
+        Ok(())
+    }
+
+    /// Drop the in-memory property index; it is rebuilt from the node records on next use
+    pub fn clear_property_index(&mut self) {
+        self.property_index = None;
+    }
+
+    /// Get the property index, if it has been loaded or rebuilt
+    pub fn property_index(&self) -> Option<&PropertyIndex> {
+        self.property_index.as_ref()
+    }
+
+    /// Get mutable access to the property index, if it has been loaded or rebuilt
+    pub fn property_index_mut(&mut self) -> Option<&mut PropertyIndex> {
+        self.property_index.as_mut()
+    }
+
+    /// Install a property index rebuilt from the node records
+    pub fn set_property_index(&mut self, index: PropertyIndex) {
+        self.property_index = Some(index);
+    }
+
+    /// Persist the property index and header and sync the file to disk
+    pub fn persist_header(&mut self) -> NativeResult<()> {
+        self.persist_property_index()?;
+        self.write_header()?;
+        self.sync()?;
+        self.unflushed_writes = 0;
+        Ok(())
+    }
+
+    /// Record a completed insert, persisting the header if the flush policy requires it
+    pub fn record_write(&mut self) -> NativeResult<()> {
+        self.unflushed_writes += 1;
+        let due = match self.flush_policy {
+            FlushPolicy::EveryWrite => true,
+            FlushPolicy::EveryN(n) => self.unflushed_writes >= n,
+            FlushPolicy::Manual => false,
+        };
+        if due {
+            self.write_header()?;
+            self.unflushed_writes = 0;
+        }
+        Ok(())
+    }
+
+    /// Get the current flush policy
+    pub fn flush_policy(&self) -> FlushPolicy {
+        self.flush_policy
+    }
+
+    /// Set the flush policy used by `record_write`
+    pub fn set_flush_policy(&mut self, policy: FlushPolicy) {
+        self.flush_policy = policy;
+    }
+
+    /// Number of inserts recorded since the header was last persisted
+    pub fn unflushed_writes(&self) -> usize {
+        self.unflushed_writes
+    }
+
+    /// Get the encoding of node and edge data payloads, from the header flags
+    pub fn data_format(&self) -> DataFormat {
+        if self.header.flags & FEATURE_MSGPACK_DATA != 0 {
+            DataFormat::MessagePack
+        } else {
+            DataFormat::Json
+        }
+    }
+
+    /// Set the data payload encoding; only allowed while the file holds no records
+    pub fn set_data_format(&mut self, format: DataFormat) -> NativeResult<()> {
+        if format == self.data_format() {
+            return Ok(());
+        }
+        if self.header.node_count > 0 || self.header.edge_count > 0 {
+            return Err(NativeBackendError::InvalidHeader {
+                field: "flags".to_string(),
+                reason: "data format can only be changed on an empty file".to_string(),
+            });
+        }
+        match format {
+            DataFormat::Json => self.header.flags &= !FEATURE_MSGPACK_DATA,
+            DataFormat::MessagePack => self.header.flags |= FEATURE_MSGPACK_DATA,
+        }
+        self.persist_header()
+    }
+
     /// Get the current header
     pub fn header(&self) -> &FileHeader {
         &self.header
@@ -103,10 +461,29 @@ impl GraphFile {
         &self.file_path
     }
 
+    /// Whether the graph is held in memory rather than in a file
+    pub fn is_in_memory(&self) -> bool {
+        matches!(self.file, Storage::Memory(_))
+    }
+
+    /// Bytes currently taken by the storage, including any property index
+    /// section and reserved space
+    pub fn stored_len(&self) -> NativeResult<u64> {
+        Ok(self.file.len()?)
+    }
+
     /// Get file size
+    ///
+    /// A persisted property index section and reserved space are not counted:
+    /// records are appended where they start.
     pub fn file_size(&self) -> NativeResult<u64> {
-        let metadata = self.file.metadata()?;
-        Ok(metadata.len())
+        if let Some(section_offset) = self.index_section_offset {
+            return Ok(section_offset);
+        }
+        if let Some(data_end) = self.reserved_from {
+            return Ok(data_end);
+        }
+        Ok(self.file.len()?)
     }
 
     /// Validate file size against header information
@@ -147,12 +524,79 @@ impl GraphFile {
             return Ok(());
         }
 
+        self.discard_index_section()?;
         let current_size = self.file_size()?;
-        self.file
-            .seek(SeekFrom::Start(current_size + additional_bytes - 1))?;
+        let new_size = current_size + additional_bytes;
+        if self.reserved_from.is_some() {
+            let physical = self.file.len()?;
+            if new_size <= physical {
+                self.reserved_from = Some(new_size).filter(|&end| end < physical);
+                return Ok(());
+            }
+            self.reserved_from = None;
+        }
+        self.file.seek(SeekFrom::Start(new_size - 1))?;
         self.file.write_all(&[0])?;
         self.file.flush()?;
+        self.invalidate_map();
+        self.growths += 1;
+
+        Ok(())
+    }
 
+    /// Extend the file to at least `len` bytes without moving the end of the data
+    ///
+    /// Later growth up to `len` only advances the end of the data, so loads
+    /// that append record by record resize the file once. The space is handed
+    /// back by the next [`persist_header`](Self::persist_header) or on drop; if
+    /// neither runs, [`open`](Self::open) trims it.
+    pub fn reserve_until(&mut self, len: u64) -> NativeResult<()> {
+        self.discard_index_section()?;
+        let data_end = self.file_size()?;
+        if len <= self.file.len()? {
+            return Ok(());
+        }
+        self.file.set_len(len)?;
+        self.invalidate_map();
+        self.growths += 1;
+        self.reserved_from = Some(data_end);
+        Ok(())
+    }
+
+    /// Reserve room for roughly `nodes` more nodes and `edges` more edges
+    ///
+    /// Sized from [`node::ESTIMATED_RECORD_SIZE`] and [`edge::SLOT_SIZE`].
+    pub fn reserve(&mut self, nodes: u64, edges: u64) -> NativeResult<()> {
+        let mut end = self.file_size()? + nodes * node::ESTIMATED_RECORD_SIZE;
+        if edges > 0 {
+            // The first edge slot lies at the start of the edge section
+            end = end.max(self.header.edge_data_offset) + edges * edge::SLOT_SIZE;
+        }
+        self.reserve_until(end)
+    }
+
+    /// Give reserved space past the end of the data back to the file system
+    fn release_reservation(&mut self) -> NativeResult<()> {
+        if let Some(data_end) = self.reserved_from.take() {
+            self.invalidate_map();
+            self.file.set_len(data_end)?;
+        }
+        Ok(())
+    }
+
+    /// Number of times the file has been physically extended since it was opened
+    pub fn growth_count(&self) -> u64 {
+        self.growths
+    }
+
+    /// Cut the file back to `len` bytes, dropping anything written past it
+    pub fn truncate(&mut self, len: u64) -> NativeResult<()> {
+        self.discard_index_section()?;
+        if len < self.file_size()? {
+            self.invalidate_map();
+            self.file.set_len(len)?;
+            self.reserved_from = None;
+        }
         Ok(())
     }
 
@@ -164,13 +608,90 @@ impl GraphFile {
 
     /// Read bytes from file at specific offset
     pub fn read_bytes(&mut self, offset: u64, buffer: &mut [u8]) -> NativeResult<()> {
+        #[cfg(feature = "mmap")]
+        if let Some(bytes) = self.mapped_range(offset, buffer.len())? {
+            buffer.copy_from_slice(bytes);
+            return Ok(());
+        }
         self.file.seek(SeekFrom::Start(offset))?;
         self.file.read_exact(buffer)?;
         Ok(())
     }
 
+    /// Whether reads are served from a memory map of the file
+    pub fn is_mmap_enabled(&self) -> bool {
+        #[cfg(feature = "mmap")]
+        {
+            self.use_mmap
+        }
+        #[cfg(not(feature = "mmap"))]
+        {
+            false
+        }
+    }
+
+    /// Switch reads between the memory map and positional reads on the file handle
+    ///
+    /// Files are mapped by default when opened and read through the handle when
+    /// created. Writes always go through the file handle.
+    #[cfg(feature = "mmap")]
+    pub fn set_mmap(&mut self, enabled: bool) {
+        self.use_mmap = enabled;
+        if !enabled {
+            self.invalidate_map();
+        }
+    }
+
+    /// Slice of the mapping covering `len` bytes at `offset`, mapping the file if needed
+    ///
+    /// Returns `None` when mapping is disabled or the range lies past the mapped
+    /// length, in which case the caller falls back to reading the file handle.
+    #[cfg(feature = "mmap")]
+    fn mapped_range(&mut self, offset: u64, len: usize) -> NativeResult<Option<&[u8]>> {
+        if !self.use_mmap {
+            return Ok(None);
+        }
+        let end = offset.checked_add(len as u64);
+        let covered = |map: &memmap2::Mmap| end.is_some_and(|end| end <= map.len() as u64);
+        if !self.map.as_ref().is_some_and(covered) {
+            // Writes past the end extend the file without going through `grow`
+            self.invalidate_map();
+            if self.file.len()? == 0 {
+                return Ok(None);
+            }
+            // SAFETY: the mapping is read-only and private to this `GraphFile`,
+            // which drops it before shrinking the file. Writes through the file
+            // handle stay visible through the shared page cache. Another process
+            // truncating the file while it is open is not supported, as with the
+            // rest of the native backend.
+            let Storage::File(file) = &self.file else {
+                return Ok(None);
+            };
+            self.map = Some(unsafe { memmap2::Mmap::map(file)? });
+        }
+        Ok(self
+            .map
+            .as_ref()
+            .filter(|map| covered(map))
+            .map(|map| &map[offset as usize..offset as usize + len]))
+    }
+
+    /// Drop the memory map so the next read remaps the file at its current size
+    fn invalidate_map(&mut self) {
+        #[cfg(feature = "mmap")]
+        {
+            self.map = None;
+        }
+    }
+
     /// Write bytes to file at specific offset
     pub fn write_bytes(&mut self, offset: u64, data: &[u8]) -> NativeResult<()> {
+        self.discard_index_section()?;
+        let end = offset + data.len() as u64;
+        if self.reserved_from.is_some_and(|data_end| end > data_end) {
+            let physical = self.file.len()?;
+            self.reserved_from = Some(end).filter(|&end| end < physical);
+        }
         self.file.seek(SeekFrom::Start(offset))?;
         self.file.write_all(data)?;
         Ok(())
@@ -185,7 +706,8 @@ impl GraphFile {
 
 impl Drop for GraphFile {
     fn drop(&mut self) {
-        // Ensure header is written before closing
+        // Ensure property index and header are written before closing
+        let _ = self.persist_property_index();
         let _ = self.write_header();
         let _ = self.sync();
     }
@@ -395,4 +917,73 @@ mod tests {
 
         assert!(header.verify_checksum().is_err());
     }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mmap_reads_match_file_reads() {
+        use crate::backend::native::{EdgeStore, NodeStore};
+        use crate::backend::{EdgeSpec, GraphBackend, NativeGraphBackend, NodeSpec};
+
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        {
+            let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+            for i in 0..20 {
+                backend
+                    .insert_node(NodeSpec {
+                        kind: "Test".to_string(),
+                        name: format!("node{i}"),
+                        file_path: None,
+                        data: serde_json::json!({"i": i}),
+                    })
+                    .unwrap();
+            }
+            for i in 1..20 {
+                backend
+                    .insert_edge(EdgeSpec {
+                        from: i,
+                        to: i + 1,
+                        edge_type: "next".to_string(),
+                        data: serde_json::json!({"i": i}),
+                    })
+                    .unwrap();
+            }
+        }
+
+        let mut mapped = GraphFile::open(temp_file.path()).unwrap();
+        let mut plain = GraphFile::open(temp_file.path()).unwrap();
+        plain.set_mmap(false);
+        assert!(mapped.is_mmap_enabled());
+        assert!(!plain.is_mmap_enabled());
+
+        let size = mapped.file_size().unwrap();
+        let (mut a, mut b) = (vec![0u8; size as usize], vec![0u8; size as usize]);
+        mapped.read_bytes(0, &mut a).unwrap();
+        plain.read_bytes(0, &mut b).unwrap();
+        assert!(a == b, "whole-file reads differ");
+        for id in 1..=20 {
+            assert_eq!(
+                NodeStore::new(&mut mapped).read_node(id).unwrap(),
+                NodeStore::new(&mut plain).read_node(id).unwrap()
+            );
+        }
+        for id in 1..20 {
+            assert_eq!(
+                EdgeStore::new(&mut mapped).read_edge(id).unwrap(),
+                EdgeStore::new(&mut plain).read_edge(id).unwrap()
+            );
+        }
+        drop(plain);
+
+        // In-place writes are visible through the mapping, and growth is remapped
+        mapped.write_bytes(HEADER_SIZE, &[1]).unwrap();
+        let mut byte = [0u8];
+        mapped.read_bytes(HEADER_SIZE, &mut byte).unwrap();
+        assert_eq!(byte, [1]);
+        let end = mapped.file_size().unwrap();
+        mapped.grow(16).unwrap();
+        mapped.write_bytes(end + 8, &[7; 8]).unwrap();
+        let mut tail = [0u8; 16];
+        mapped.read_bytes(end, &mut tail).unwrap();
+        assert_eq!(tail, [0, 0, 0, 0, 0, 0, 0, 0, 7, 7, 7, 7, 7, 7, 7, 7]);
+    }
 }
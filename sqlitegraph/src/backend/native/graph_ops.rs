@@ -1,9 +1,69 @@
 //! Core operations and algorithms for native graph backend.
 
-use super::adjacency::{AdjacencyHelpers, Direction};
-use super::graph_file::GraphFile;
+use super::adjacency::{AdjacencyHelpers, AdjacencyIterator, Direction};
+use super::constants::HEADER_SIZE;
+use super::edge_store::{EdgeStore, extend_range};
+use super::graph_file::{GraphFile, decode_header};
+use super::graph_validation::{edge_spec_to_record, node_record_to_entity, node_spec_to_record};
+use super::node_store::NodeStore;
+use super::property_index::PropertyIndex;
 use super::types::*;
-use crate::backend::{BackendDirection, ChainStep, PatternMatch, PatternQuery};
+use crate::backend::{BackendDirection, ChainStep, EdgeSpec, NodeSpec, PatternMatch, PatternQuery};
+use crate::graph::{DanglingEdge, GraphStatistics};
+use crate::pattern::PatternLeg;
+
+/// Neighbors of `node` in `direction`, optionally restricted to `edge_types`.
+///
+/// `BackendDirection::Both` merges outgoing and incoming neighbors into a sorted,
+/// deduplicated list.
+pub fn native_neighbors(
+    graph_file: &mut GraphFile,
+    node: NativeNodeId,
+    direction: BackendDirection,
+    edge_types: Option<&[&str]>,
+) -> Result<Vec<NativeNodeId>, NativeBackendError> {
+    match (direction, edge_types) {
+        (BackendDirection::Outgoing, None) => {
+            AdjacencyHelpers::get_outgoing_neighbors(graph_file, node)
+        }
+        (BackendDirection::Incoming, None) => {
+            AdjacencyHelpers::get_incoming_neighbors(graph_file, node)
+        }
+        (BackendDirection::Outgoing, Some(types)) => {
+            AdjacencyHelpers::get_outgoing_neighbors_filtered(graph_file, node, types)
+        }
+        (BackendDirection::Incoming, Some(types)) => {
+            AdjacencyHelpers::get_incoming_neighbors_filtered(graph_file, node, types)
+        }
+        (BackendDirection::Both, _) => {
+            AdjacencyHelpers::get_undirected_neighbors(graph_file, node, edge_types)
+        }
+    }
+}
+
+/// Number of ids `native_neighbors` would return for the same arguments.
+///
+/// A single direction is counted while iterating; `Both` still has to collect
+/// both sides to drop neighbors linked in both orientations.
+pub fn native_neighbor_count(
+    graph_file: &mut GraphFile,
+    node: NativeNodeId,
+    direction: BackendDirection,
+    edge_types: Option<&[&str]>,
+) -> Result<usize, NativeBackendError> {
+    let mut iterator = match direction {
+        BackendDirection::Outgoing => AdjacencyIterator::new_outgoing(graph_file, node)?,
+        BackendDirection::Incoming => AdjacencyIterator::new_incoming(graph_file, node)?,
+        BackendDirection::Both => {
+            return AdjacencyHelpers::get_undirected_neighbors(graph_file, node, edge_types)
+                .map(|neighbors| neighbors.len());
+        }
+    };
+    if let Some(types) = edge_types {
+        iterator = iterator.with_edge_filter(types);
+    }
+    iterator.count()
+}
 
 /// Native BFS implementation using adjacency helpers
 pub fn native_bfs(
@@ -11,13 +71,22 @@ pub fn native_bfs(
     start: NativeNodeId,
     depth: u32,
 ) -> Result<Vec<NativeNodeId>, NativeBackendError> {
-    if depth == 0 {
-        return Ok(vec![start]);
-    }
+    native_bfs_directed(graph_file, start, depth, BackendDirection::Outgoing)
+}
 
+/// Native BFS following edges in `direction`
+///
+/// The result starts with `start`, which must exist, like the SQLite backend's `bfs`.
+pub fn native_bfs_directed(
+    graph_file: &mut GraphFile,
+    start: NativeNodeId,
+    depth: u32,
+    direction: BackendDirection,
+) -> Result<Vec<NativeNodeId>, NativeBackendError> {
+    NodeStore::new(graph_file).read_node(start)?;
     let mut visited = std::collections::HashSet::new();
     let mut queue = std::collections::VecDeque::new();
-    let mut result = Vec::new();
+    let mut result = vec![start];
 
     visited.insert(start);
     queue.push_back((start, 0));
@@ -27,7 +96,7 @@ pub fn native_bfs(
             continue;
         }
 
-        let neighbors = AdjacencyHelpers::get_outgoing_neighbors(graph_file, current_node)?;
+        let neighbors = native_neighbors(graph_file, current_node, direction, None)?;
         for neighbor in neighbors {
             if !visited.contains(&neighbor) {
                 visited.insert(neighbor);
@@ -40,11 +109,90 @@ pub fn native_bfs(
     Ok(result)
 }
 
+/// Outgoing native BFS over edges whose type is in `edge_types`.
+///
+/// Like [`native_bfs_directed`] the result starts with `start`, which must
+/// exist, matching the SQLite backend's `bfs_filtered`.
+pub fn native_bfs_filtered(
+    graph_file: &mut GraphFile,
+    start: NativeNodeId,
+    depth: u32,
+    edge_types: &[&str],
+) -> Result<Vec<NativeNodeId>, NativeBackendError> {
+    NodeStore::new(graph_file).read_node(start)?;
+    let mut visited = std::collections::HashSet::new();
+    let mut queue = std::collections::VecDeque::new();
+    let mut result = Vec::new();
+
+    visited.insert(start);
+    queue.push_back((start, 0));
+
+    while let Some((current_node, current_depth)) = queue.pop_front() {
+        result.push(current_node);
+        if current_depth >= depth || edge_types.is_empty() {
+            continue;
+        }
+
+        let neighbors = native_neighbors(
+            graph_file,
+            current_node,
+            BackendDirection::Outgoing,
+            Some(edge_types),
+        )?;
+        for neighbor in neighbors {
+            if visited.insert(neighbor) {
+                queue.push_back((neighbor, current_depth + 1));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
 /// Native shortest path implementation using BFS
 pub fn native_shortest_path(
     graph_file: &mut GraphFile,
     start: NativeNodeId,
     end: NativeNodeId,
+) -> Result<Option<Vec<NativeNodeId>>, NativeBackendError> {
+    native_shortest_path_directed(graph_file, start, end, BackendDirection::Outgoing)
+}
+
+/// Native shortest path following edges in `direction`
+///
+/// Neighbors are expanded in ascending id order, so among equal-length paths the
+/// lexicographically smallest node sequence is returned, matching the SQLite backend.
+pub fn native_shortest_path_directed(
+    graph_file: &mut GraphFile,
+    start: NativeNodeId,
+    end: NativeNodeId,
+    direction: BackendDirection,
+) -> Result<Option<Vec<NativeNodeId>>, NativeBackendError> {
+    native_shortest_path_with_types(graph_file, start, end, direction, None)
+}
+
+/// Native outgoing shortest path over edges whose type is in `edge_types`
+pub fn native_shortest_path_filtered(
+    graph_file: &mut GraphFile,
+    start: NativeNodeId,
+    end: NativeNodeId,
+    edge_types: &[&str],
+) -> Result<Option<Vec<NativeNodeId>>, NativeBackendError> {
+    native_shortest_path_with_types(
+        graph_file,
+        start,
+        end,
+        BackendDirection::Outgoing,
+        Some(edge_types),
+    )
+}
+
+fn native_shortest_path_with_types(
+    graph_file: &mut GraphFile,
+    start: NativeNodeId,
+    end: NativeNodeId,
+    direction: BackendDirection,
+    edge_types: Option<&[&str]>,
 ) -> Result<Option<Vec<NativeNodeId>>, NativeBackendError> {
     if start == end {
         return Ok(Some(vec![start]));
@@ -73,7 +221,8 @@ pub fn native_shortest_path(
             return Ok(Some(path));
         }
 
-        let neighbors = AdjacencyHelpers::get_outgoing_neighbors(graph_file, current_node)?;
+        let mut neighbors = native_neighbors(graph_file, current_node, direction, edge_types)?;
+        neighbors.sort_unstable();
         for neighbor in neighbors {
             if !visited.contains(&neighbor) {
                 visited.insert(neighbor);
@@ -91,7 +240,7 @@ pub fn native_k_hop(
     graph_file: &mut GraphFile,
     start: NativeNodeId,
     depth: u32,
-    direction: Direction,
+    direction: BackendDirection,
 ) -> Result<Vec<NativeNodeId>, NativeBackendError> {
     if depth == 0 {
         return Ok(vec![start]);
@@ -106,10 +255,7 @@ pub fn native_k_hop(
         let mut next_level = Vec::new();
 
         for node in current_level {
-            let neighbors = match direction {
-                Direction::Outgoing => AdjacencyHelpers::get_outgoing_neighbors(graph_file, node)?,
-                Direction::Incoming => AdjacencyHelpers::get_incoming_neighbors(graph_file, node)?,
-            };
+            let neighbors = native_neighbors(graph_file, node, direction, None)?;
 
             for neighbor in neighbors {
                 if !visited.contains(&neighbor) {
@@ -129,12 +275,42 @@ pub fn native_k_hop(
     Ok(result)
 }
 
+/// Nodes within `depth` hops of `start` with their hop distance, ordered by
+/// distance and then node id; `start` is not included.
+pub fn native_k_hop_with_distance(
+    graph_file: &mut GraphFile,
+    start: NativeNodeId,
+    depth: u32,
+    direction: BackendDirection,
+) -> Result<Vec<(NativeNodeId, u32)>, NativeBackendError> {
+    let mut visited = std::collections::HashSet::from([start]);
+    let mut current_level = vec![start];
+    let mut result = Vec::new();
+    for distance in 1..=depth {
+        let mut next_level = Vec::new();
+        for node in current_level {
+            for neighbor in native_neighbors(graph_file, node, direction, None)? {
+                if visited.insert(neighbor) {
+                    next_level.push(neighbor);
+                }
+            }
+        }
+        if next_level.is_empty() {
+            break;
+        }
+        next_level.sort_unstable();
+        result.extend(next_level.iter().map(|&node| (node, distance)));
+        current_level = next_level;
+    }
+    Ok(result)
+}
+
 /// Native k-hop implementation with edge type filtering
 pub fn native_k_hop_filtered(
     graph_file: &mut GraphFile,
     start: NativeNodeId,
     depth: u32,
-    direction: Direction,
+    direction: BackendDirection,
     allowed_edge_types: &[&str],
 ) -> Result<Vec<NativeNodeId>, NativeBackendError> {
     if depth == 0 {
@@ -150,18 +326,8 @@ pub fn native_k_hop_filtered(
         let mut next_level = Vec::new();
 
         for node in current_level {
-            let neighbors = match direction {
-                Direction::Outgoing => AdjacencyHelpers::get_outgoing_neighbors_filtered(
-                    graph_file,
-                    node,
-                    allowed_edge_types,
-                )?,
-                Direction::Incoming => AdjacencyHelpers::get_incoming_neighbors_filtered(
-                    graph_file,
-                    node,
-                    allowed_edge_types,
-                )?,
-            };
+            let neighbors =
+                native_neighbors(graph_file, node, direction, Some(allowed_edge_types))?;
 
             for neighbor in neighbors {
                 if !visited.contains(&neighbor) {
@@ -192,37 +358,11 @@ pub fn native_chain_query(
 
     for step in chain {
         let mut next_nodes = Vec::new();
-        let direction = match step.direction {
-            BackendDirection::Outgoing => Direction::Outgoing,
-            BackendDirection::Incoming => Direction::Incoming,
-        };
+        let edge_type = step.edge_type.as_deref();
+        let edge_types = edge_type.as_ref().map(std::slice::from_ref);
 
         for &node in &current_nodes {
-            let neighbors = if let Some(edge_type) = &step.edge_type {
-                let edge_type_ref = edge_type.as_str();
-                match direction {
-                    Direction::Outgoing => AdjacencyHelpers::get_outgoing_neighbors_filtered(
-                        graph_file,
-                        node,
-                        &[edge_type_ref],
-                    )?,
-                    Direction::Incoming => AdjacencyHelpers::get_incoming_neighbors_filtered(
-                        graph_file,
-                        node,
-                        &[edge_type_ref],
-                    )?,
-                }
-            } else {
-                match direction {
-                    Direction::Outgoing => {
-                        AdjacencyHelpers::get_outgoing_neighbors(graph_file, node)?
-                    }
-                    Direction::Incoming => {
-                        AdjacencyHelpers::get_incoming_neighbors(graph_file, node)?
-                    }
-                }
-            };
-
+            let neighbors = native_neighbors(graph_file, node, step.direction, edge_types)?;
             next_nodes.extend(neighbors);
         }
 
@@ -237,16 +377,733 @@ pub fn native_chain_query(
     Ok(result)
 }
 
-/// Native pattern search implementation (basic version)
+/// Edges touching `node` as `(outgoing, incoming)`, ordered by the other endpoint then edge id
+///
+/// Walks the node's two adjacency ranges, so only edge slots between its first
+/// and last edge are read. Deleted edges are skipped.
+pub fn native_node_edges(
+    graph_file: &mut GraphFile,
+    node: NativeNodeId,
+) -> Result<(Vec<EdgeRecord>, Vec<EdgeRecord>), NativeBackendError> {
+    let mut outgoing = AdjacencyHelpers::range_edges(graph_file, node, Direction::Outgoing)?;
+    let mut incoming = AdjacencyHelpers::range_edges(graph_file, node, Direction::Incoming)?;
+    outgoing.sort_by_key(|edge| (edge.to_id, edge.id));
+    incoming.sort_by_key(|edge| (edge.from_id, edge.id));
+    Ok((outgoing, incoming))
+}
+
+/// Allocate an id for `edge` and write it, updating both endpoints' adjacency
+pub fn native_insert_edge(
+    graph_file: &mut GraphFile,
+    edge: EdgeSpec,
+) -> Result<i64, NativeBackendError> {
+    // Checked before the slot is written, so a missing or deleted endpoint
+    // leaves nothing behind
+    let mut node_store = NodeStore::new(graph_file);
+    for id in [edge.from, edge.to] {
+        node_store.read_node(id as NativeNodeId)?;
+    }
+    let mut edge_store = EdgeStore::new(graph_file);
+    let edge_id = edge_store.allocate_edge_id();
+    let record = edge_spec_to_record(edge, edge_id);
+    edge_store.write_edge(&record)?;
+    graph_file.record_write()?;
+    Ok(edge_id)
+}
+
+/// Lowest id of a live edge matching `(from, to, edge_type)`
+///
+/// Walks the outgoing adjacency range of `from`, which must exist.
+pub fn native_find_edge(
+    graph_file: &mut GraphFile,
+    from: NativeNodeId,
+    to: NativeNodeId,
+    edge_type: &str,
+) -> Result<Option<NativeEdgeId>, NativeBackendError> {
+    let outgoing = AdjacencyHelpers::range_edges(graph_file, from, Direction::Outgoing)?;
+    Ok(outgoing
+        .into_iter()
+        .find(|edge| edge.to_id == to && edge.edge_type == edge_type)
+        .map(|edge| edge.id))
+}
+
+/// Delete `node` and every edge touching it
+///
+/// Records are flagged as deleted in place, so node offsets and edge slots stay
+/// where they are. The edges are found through the node's adjacency ranges.
+pub fn native_delete_node(
+    graph_file: &mut GraphFile,
+    node: NativeNodeId,
+) -> Result<(), NativeBackendError> {
+    let (outgoing, incoming) = native_node_edges(graph_file, node)?;
+    let mut edge_store = EdgeStore::new(graph_file);
+    // Self-loops are listed in both directions; take them from the outgoing side
+    let incoming = incoming.iter().filter(|edge| edge.from_id != node);
+    for edge in outgoing.iter().chain(incoming) {
+        edge_store.mark_deleted(edge.id)?;
+    }
+    NodeStore::new(graph_file).mark_deleted(node)
+}
+
+/// Ids of the live (non-deleted) nodes and endpoints of the live edges
+///
+/// Reads every node record and edge slot once, for whole-graph algorithms.
+pub fn native_live_graph(
+    graph_file: &mut GraphFile,
+) -> Result<(Vec<NativeNodeId>, Vec<(NativeNodeId, NativeNodeId)>), NativeBackendError> {
+    let node_count = graph_file.header().node_count as NativeNodeId;
+    let mut nodes = Vec::new();
+    let mut node_store = NodeStore::new(graph_file);
+    for node_id in 1..=node_count {
+        let node = node_store.read_node_including_deleted(node_id)?;
+        if !node.flags.contains(NodeFlags::DELETED) {
+            nodes.push(node_id);
+        }
+    }
+    let edge_count = graph_file.header().edge_count as NativeEdgeId;
+    let mut edges = Vec::new();
+    let mut edge_store = EdgeStore::new(graph_file);
+    for edge_id in 1..=edge_count {
+        let edge = edge_store.read_edge_including_deleted(edge_id)?;
+        if !edge.flags.contains(EdgeFlags::DELETED) {
+            edges.push((edge.from_id, edge.to_id));
+        }
+    }
+    Ok((nodes, edges))
+}
+
+/// Live edges with an endpoint that is out of range or deleted, by edge id
+pub fn native_dangling_edges(
+    graph_file: &mut GraphFile,
+) -> Result<Vec<DanglingEdge>, NativeBackendError> {
+    let (nodes, _) = native_live_graph(graph_file)?;
+    let live: std::collections::HashSet<NativeNodeId> = nodes.into_iter().collect();
+    let edge_count = graph_file.header().edge_count as NativeEdgeId;
+    let mut dangling = Vec::new();
+    let mut edge_store = EdgeStore::new(graph_file);
+    for edge_id in 1..=edge_count {
+        let edge = edge_store.read_edge_including_deleted(edge_id)?;
+        if edge.flags.contains(EdgeFlags::DELETED) {
+            continue;
+        }
+        let missing_from = !live.contains(&edge.from_id);
+        let missing_to = !live.contains(&edge.to_id);
+        if missing_from || missing_to {
+            dangling.push(DanglingEdge {
+                edge_id,
+                from_id: edge.from_id,
+                to_id: edge.to_id,
+                missing_from,
+                missing_to,
+            });
+        }
+    }
+    Ok(dangling)
+}
+
+/// Counts and degree summary of the live nodes and edges, from one pass over
+/// every record
+pub fn native_statistics(
+    graph_file: &mut GraphFile,
+) -> Result<GraphStatistics, NativeBackendError> {
+    let node_count = graph_file.header().node_count as NativeNodeId;
+    let mut nodes = Vec::new();
+    let mut node_store = NodeStore::new(graph_file);
+    for node_id in 1..=node_count {
+        let node = node_store.read_node_including_deleted(node_id)?;
+        if !node.flags.contains(NodeFlags::DELETED) {
+            nodes.push((node_id, node.kind));
+        }
+    }
+    let edge_count = graph_file.header().edge_count as NativeEdgeId;
+    let mut edges = Vec::new();
+    let mut edge_store = EdgeStore::new(graph_file);
+    for edge_id in 1..=edge_count {
+        let edge = edge_store.read_edge_including_deleted(edge_id)?;
+        if !edge.flags.contains(EdgeFlags::DELETED) {
+            edges.push((edge.from_id, edge.to_id, edge.edge_type));
+        }
+    }
+    Ok(GraphStatistics::from_records(nodes, edges))
+}
+
+/// Ids of live nodes whose decoded `data` satisfies `predicate`, in ascending order
+///
+/// Reads every node record; property equality lookups should use
+/// [`native_find_nodes_by_property`] instead.
+pub fn native_scan_nodes<F>(
+    graph_file: &mut GraphFile,
+    mut predicate: F,
+) -> Result<Vec<i64>, NativeBackendError>
+where
+    F: FnMut(&serde_json::Value) -> bool,
+{
+    let node_count = graph_file.header().node_count as NativeNodeId;
+    let mut ids = Vec::new();
+    let mut node_store = NodeStore::new(graph_file);
+    for node_id in 1..=node_count {
+        let node = node_store.read_node_including_deleted(node_id)?;
+        if !node.flags.contains(NodeFlags::DELETED) && predicate(&node.data) {
+            ids.push(node_id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Ids of live nodes whose property `key` is indexed as `value`, in ascending order
+///
+/// Uses the sorted property index, rebuilding it from the node records first if
+/// the file was opened without an intact index section.
+pub fn native_find_nodes_by_property(
+    graph_file: &mut GraphFile,
+    key: &str,
+    value: &str,
+) -> Result<Vec<i64>, NativeBackendError> {
+    if graph_file.property_index().is_none() {
+        let index = native_rebuild_property_index(graph_file)?;
+        graph_file.set_property_index(index);
+    }
+    Ok(graph_file
+        .property_index()
+        .map(|index| index.lookup(key, value))
+        .unwrap_or_default())
+}
+
+/// Build a property index from every live node record
+pub fn native_rebuild_property_index(
+    graph_file: &mut GraphFile,
+) -> Result<PropertyIndex, NativeBackendError> {
+    let node_count = graph_file.header().node_count as NativeNodeId;
+    let mut nodes = Vec::new();
+    let mut node_store = NodeStore::new(graph_file);
+    for node_id in 1..=node_count {
+        let node = node_store.read_node_including_deleted(node_id)?;
+        if !node.flags.contains(NodeFlags::DELETED) {
+            nodes.push((node_id, node.data));
+        }
+    }
+    Ok(PropertyIndex::rebuild(
+        node_count,
+        nodes.iter().map(|(id, data)| (*id, data)),
+    ))
+}
+
+/// Whether top-level property `key` of `data` renders as `value`
+///
+/// Strings compare verbatim; numbers and booleans compare by their JSON text,
+/// matching how the SQLite backend stores property values as strings.
+pub fn data_property_matches(data: &serde_json::Value, key: &str, value: &str) -> bool {
+    match data.get(key) {
+        Some(serde_json::Value::String(s)) => s == value,
+        Some(serde_json::Value::Number(n)) => n.to_string() == value,
+        Some(serde_json::Value::Bool(b)) => b.to_string() == value,
+        _ => false,
+    }
+}
+
+/// Whether the `labels` array of `data` contains `label`
+pub fn data_has_label(data: &serde_json::Value, label: &str) -> bool {
+    data.get("labels")
+        .and_then(serde_json::Value::as_array)
+        .is_some_and(|labels| labels.iter().any(|l| l.as_str() == Some(label)))
+}
+
+/// Ids of every edge that has not been deleted, in ascending order
+pub fn native_live_edge_ids(graph_file: &mut GraphFile) -> Result<Vec<i64>, NativeBackendError> {
+    let edge_count = graph_file.header().edge_count as NativeEdgeId;
+    let mut ids = Vec::new();
+    let mut edge_store = EdgeStore::new(graph_file);
+    for edge_id in 1..=edge_count {
+        let edge = edge_store.read_edge_including_deleted(edge_id)?;
+        if !edge.flags.contains(EdgeFlags::DELETED) {
+            ids.push(edge_id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Header counts and file length before a batch, restored if the batch fails
+struct BatchCheckpoint {
+    node_count: u64,
+    edge_count: u64,
+    file_size: u64,
+}
+
+impl BatchCheckpoint {
+    fn take(graph_file: &GraphFile) -> Result<Self, NativeBackendError> {
+        Ok(Self {
+            node_count: graph_file.header().node_count,
+            edge_count: graph_file.header().edge_count,
+            file_size: graph_file.file_size()?,
+        })
+    }
+
+    /// Roll the file back to the checkpoint
+    ///
+    /// Appended bytes are truncated away. Edge slots and node copies written
+    /// inside the old length stay behind, but past the restored header counts
+    /// they are never read.
+    fn restore(self, graph_file: &mut GraphFile) -> Result<(), NativeBackendError> {
+        let header = graph_file.header_mut();
+        header.node_count = self.node_count;
+        header.edge_count = self.edge_count;
+        graph_file.clear_property_index();
+        graph_file.truncate(self.file_size)?;
+        graph_file.persist_header()
+    }
+}
+
+/// Run a batch, persisting the header once on success and rolling back on failure
+fn native_batch<R, F>(graph_file: &mut GraphFile, batch: F) -> Result<R, NativeBackendError>
+where
+    F: FnOnce(&mut GraphFile) -> Result<R, NativeBackendError>,
+{
+    let checkpoint = BatchCheckpoint::take(graph_file)?;
+    match batch(graph_file) {
+        Ok(result) => {
+            // Records must be durable before the header that makes them reachable
+            graph_file.sync()?;
+            graph_file.persist_header()?;
+            Ok(result)
+        }
+        Err(err) => {
+            checkpoint.restore(graph_file)?;
+            Err(err)
+        }
+    }
+}
+
+/// Insert `specs` as new nodes with one record write and one header flush
+///
+/// Ids are assigned consecutively in input order. On failure nothing is
+/// inserted: the header counts and file length are rolled back. A crash
+/// mid-batch leaves the previously persisted header, whose `node_count` does
+/// not cover the partially written records.
+pub fn native_bulk_insert_nodes(
+    graph_file: &mut GraphFile,
+    specs: &[NodeSpec],
+) -> Result<Vec<i64>, NativeBackendError> {
+    native_batch(graph_file, |graph_file| {
+        let first_id = graph_file.header().node_count as NativeNodeId + 1;
+        let records: Vec<NodeRecord> = specs
+            .iter()
+            .zip(first_id..)
+            .map(|(spec, id)| node_spec_to_record(spec.clone(), id))
+            .collect();
+        NodeStore::new(graph_file).write_nodes(&records)?;
+        Ok(records.iter().map(|record| record.id).collect())
+    })
+}
+
+/// Insert `specs` as new edges, flushing the header once at the end
+///
+/// Endpoints are checked before anything is written. Edge slots are written
+/// first; node adjacency metadata is then accumulated and each touched node
+/// rewritten once, instead of twice per edge. Failure and crash behave as in
+/// [`native_bulk_insert_nodes`], with `edge_count` guarding the edge slots.
+pub fn native_bulk_insert_edges(
+    graph_file: &mut GraphFile,
+    specs: &[EdgeSpec],
+) -> Result<Vec<i64>, NativeBackendError> {
+    native_batch(graph_file, |graph_file| {
+        let mut node_store = NodeStore::new(graph_file);
+        for spec in specs {
+            node_store.read_node(spec.from as NativeNodeId)?;
+            node_store.read_node(spec.to as NativeNodeId)?;
+        }
+
+        let first_id = graph_file.header().edge_count as NativeEdgeId + 1;
+        // node id -> (outgoing, incoming) ranges of the new edges as (first, last) ids
+        type NewRange = Option<(NativeEdgeId, NativeEdgeId)>;
+        let mut adjacency: std::collections::BTreeMap<NativeNodeId, (NewRange, NewRange)> =
+            std::collections::BTreeMap::new();
+        let widen = |range: &mut NewRange, id| {
+            *range = Some(range.map_or((id, id), |(first, _)| (first, id)));
+        };
+        let mut ids = Vec::with_capacity(specs.len());
+        let mut edge_store = EdgeStore::new(graph_file);
+        for (spec, id) in specs.iter().zip(first_id..) {
+            let record = edge_spec_to_record(spec.clone(), id);
+            edge_store.write_edge_slot_only(&record)?;
+            widen(&mut adjacency.entry(record.from_id).or_default().0, id);
+            widen(&mut adjacency.entry(record.to_id).or_default().1, id);
+            ids.push(id);
+        }
+
+        let mut node_store = NodeStore::new(graph_file);
+        for (node_id, (outgoing, incoming)) in adjacency {
+            let mut node = node_store.read_node(node_id)?;
+            if let Some((first, last)) = outgoing {
+                let (offset, count) =
+                    extend_range(node.outgoing_offset, node.outgoing_count, first);
+                (node.outgoing_offset, node.outgoing_count) = extend_range(offset, count, last);
+            }
+            if let Some((first, last)) = incoming {
+                let (offset, count) =
+                    extend_range(node.incoming_offset, node.incoming_count, first);
+                (node.incoming_offset, node.incoming_count) = extend_range(offset, count, last);
+            }
+            node_store.rewrite_node(&node)?;
+        }
+        Ok(ids)
+    })
+}
+
+/// Rewrite the graph file without dead bytes, keeping node and edge ids
+///
+/// Node records are written back contiguously in id order: live nodes with
+/// adjacency ranges recomputed from the live edges, deleted nodes as empty
+/// tombstones so later ids keep their position. Records moved by data updates
+/// go back to their id's position, dropping the old copy. Edge slots are copied as they are. The new file is
+/// built next to the old one, synced, and renamed over it, so a crash leaves
+/// either the old or the new file in place. `graph_file` is reopened on the
+/// result.
+pub fn native_compact(graph_file: &mut GraphFile) -> Result<CompactionStats, NativeBackendError> {
+    let (nodes, edges) = native_records(graph_file)?;
+    rewrite_graph_file(graph_file, &nodes, &edges)
+}
+
+/// Read every node and edge record in id order, deleted ones included
+///
+/// This is the input [`native_load`] expects, so the two round-trip a file.
+pub fn native_records(
+    graph_file: &mut GraphFile,
+) -> Result<(Vec<NodeRecord>, Vec<EdgeRecord>), NativeBackendError> {
+    graph_file.persist_header()?;
+    let node_count = graph_file.header().node_count as NativeNodeId;
+    let edge_count = graph_file.header().edge_count as NativeEdgeId;
+    let mut nodes = Vec::with_capacity(node_count as usize);
+    let mut node_store = NodeStore::new(graph_file);
+    for node_id in 1..=node_count {
+        nodes.push(node_store.read_node_including_deleted(node_id)?);
+    }
+    let mut edges = Vec::with_capacity(edge_count as usize);
+    let mut edge_store = EdgeStore::new(graph_file);
+    for edge_id in 1..=edge_count {
+        edges.push(edge_store.read_edge_including_deleted(edge_id)?);
+    }
+    Ok((nodes, edges))
+}
+
+/// Replace the contents of `graph_file` with `nodes` and `edges`, keeping ids
+///
+/// Each slice must hold ids 1, 2, ... in order, with deleted records standing
+/// in for ids that carry nothing. Adjacency is computed from the live edges
+/// and the file is written in one pass and renamed into place, as in
+/// [`native_compact`].
+pub fn native_load(
+    graph_file: &mut GraphFile,
+    nodes: &[NodeRecord],
+    edges: &[EdgeRecord],
+) -> Result<CompactionStats, NativeBackendError> {
+    let node_count = nodes.len() as NativeNodeId;
+    if let Some((_, node)) = (1..)
+        .zip(nodes)
+        .find(|(position, node)| node.id != *position)
+    {
+        return Err(NativeBackendError::InvalidNodeId {
+            id: node.id,
+            max_id: node_count,
+        });
+    }
+    let edge_count = edges.len() as NativeEdgeId;
+    if let Some((_, edge)) = (1..)
+        .zip(edges)
+        .find(|(position, edge)| edge.id != *position)
+    {
+        return Err(NativeBackendError::InvalidEdgeId {
+            id: edge.id,
+            max_id: edge_count,
+        });
+    }
+    let in_range = |id| id > 0 && id <= node_count;
+    if let Some(edge) = edges
+        .iter()
+        .find(|edge| !in_range(edge.from_id) || !in_range(edge.to_id))
+    {
+        let id = if in_range(edge.from_id) {
+            edge.to_id
+        } else {
+            edge.from_id
+        };
+        return Err(NativeBackendError::InvalidNodeId {
+            id,
+            max_id: node_count,
+        });
+    }
+    rewrite_graph_file(graph_file, nodes, edges)
+}
+
+/// Check the header and every node and edge record, collecting failures
+///
+/// Node offsets are found by walking the records in order, so a node whose
+/// length fields are damaged also hides every node stored after it; those are
+/// reported as corrupt too. Edge slots are fixed-size and checked
+/// independently.
+pub fn native_verify(graph_file: &mut GraphFile) -> Result<VerifyReport, NativeBackendError> {
+    let mut report = VerifyReport::default();
+    let mut header_bytes = [0u8; HEADER_SIZE as usize];
+    graph_file.read_bytes(0, &mut header_bytes)?;
+    let header_check = decode_header(&header_bytes)
+        .and_then(|on_disk| on_disk.verify_checksum())
+        .and_then(|()| graph_file.header().validate());
+    report.header_error = header_check.err().map(|err| err.to_string());
+
+    let node_count = graph_file.header().node_count as NativeNodeId;
+    let edge_count = graph_file.header().edge_count as NativeEdgeId;
+    let mut node_store = NodeStore::new(graph_file);
+    for node_id in 1..=node_count {
+        if node_store.read_node_including_deleted(node_id).is_err() {
+            report.corrupt_nodes.push(node_id);
+        }
+    }
+    let mut edge_store = EdgeStore::new(graph_file);
+    for edge_id in 1..=edge_count {
+        let in_range = |id| id > 0 && id <= node_count;
+        match edge_store.read_edge_including_deleted(edge_id) {
+            Ok(edge) if in_range(edge.from_id) && in_range(edge.to_id) => {}
+            _ => report.corrupt_edges.push(edge_id),
+        }
+    }
+    report.nodes_checked = node_count as u64;
+    report.edges_checked = edge_count as u64;
+    Ok(report)
+}
+
+/// Rewrite the graph file without its unreadable records
+///
+/// Corrupt nodes are replaced by deleted tombstones, and corrupt edges as well
+/// as edges touching a replaced node become deleted, so every id keeps its
+/// position. Header counts and adjacency ranges are rebuilt from what
+/// survives, the same way [`native_compact`] does. Returns the report taken
+/// before repairing.
+pub fn native_repair(graph_file: &mut GraphFile) -> Result<VerifyReport, NativeBackendError> {
+    let report = native_verify(graph_file)?;
+    if report.is_clean() {
+        return Ok(report);
+    }
+
+    let node_count = graph_file.header().node_count as NativeNodeId;
+    let edge_count = graph_file.header().edge_count as NativeEdgeId;
+    let dropped_nodes: std::collections::HashSet<NativeNodeId> =
+        report.corrupt_nodes.iter().copied().collect();
+    let mut nodes = Vec::with_capacity(node_count as usize);
+    let mut node_store = NodeStore::new(graph_file);
+    for node_id in 1..=node_count {
+        let node = match node_store.read_node_including_deleted(node_id) {
+            Ok(node) => node,
+            Err(_) => {
+                let mut tombstone = NodeRecord::new(
+                    node_id,
+                    String::new(),
+                    String::new(),
+                    serde_json::Value::Null,
+                );
+                tombstone.flags = tombstone.flags.set(NodeFlags::DELETED);
+                tombstone
+            }
+        };
+        nodes.push(node);
+    }
+
+    let mut edges = Vec::with_capacity(edge_count as usize);
+    // Without any node there is no valid endpoint for a placeholder edge
+    if node_count > 0 {
+        let corrupt_edges: std::collections::HashSet<NativeEdgeId> =
+            report.corrupt_edges.iter().copied().collect();
+        let mut edge_store = EdgeStore::new(graph_file);
+        for edge_id in 1..=edge_count {
+            let mut edge = match edge_store.read_edge_including_deleted(edge_id) {
+                Ok(edge) if !corrupt_edges.contains(&edge_id) => edge,
+                _ => EdgeRecord::new(edge_id, 1, 1, String::new(), serde_json::Value::Null),
+            };
+            if corrupt_edges.contains(&edge_id)
+                || dropped_nodes.contains(&edge.from_id)
+                || dropped_nodes.contains(&edge.to_id)
+            {
+                edge.flags = edge.flags.set(EdgeFlags::DELETED);
+            }
+            edges.push(edge);
+        }
+    }
+    rewrite_graph_file(graph_file, &nodes, &edges)?;
+    Ok(report)
+}
+
+/// Write `nodes` and `edges` to a fresh file that atomically replaces the
+/// one behind `graph_file`, then reopen it
+///
+/// An in-memory graph is rebuilt in a new buffer that takes its place.
+fn rewrite_graph_file(
+    graph_file: &mut GraphFile,
+    nodes: &[NodeRecord],
+    edges: &[EdgeRecord],
+) -> Result<CompactionStats, NativeBackendError> {
+    let path = graph_file.path().to_path_buf();
+    let in_memory = graph_file.is_in_memory();
+    let bytes_before = graph_file.stored_len()?;
+
+    // Adjacency is read as a run of edge ids starting at the offset, skipping
+    // edges of other nodes, so each range spans the node's first to last edge.
+    let mut outgoing = std::collections::HashMap::new();
+    let mut incoming = std::collections::HashMap::new();
+    for edge in edges
+        .iter()
+        .filter(|e| !e.flags.contains(EdgeFlags::DELETED))
+    {
+        let widen = |range: &mut (NativeEdgeId, NativeEdgeId)| {
+            *range = (range.0.min(edge.id), range.1.max(edge.id));
+        };
+        widen(outgoing.entry(edge.from_id).or_insert((edge.id, edge.id)));
+        widen(incoming.entry(edge.to_id).or_insert((edge.id, edge.id)));
+    }
+    let adjacency =
+        |ranges: &std::collections::HashMap<NativeNodeId, (NativeEdgeId, NativeEdgeId)>,
+         node_id| {
+            ranges.get(&node_id).map_or((0, 0), |&(first, last)| {
+                (first as FileOffset, (last - first + 1) as u32)
+            })
+        };
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".compact");
+    let temp_path = path.with_file_name(temp_name);
+    let build = |tombstoned: &mut u64| -> Result<GraphFile, NativeBackendError> {
+        let mut compacted = if in_memory {
+            GraphFile::create_in_memory()?
+        } else {
+            GraphFile::create(&temp_path)?
+        };
+        compacted.set_flush_policy(FlushPolicy::Manual);
+        compacted.set_data_format(graph_file.data_format())?;
+        let mut node_store = NodeStore::new(&mut compacted);
+        for node in nodes {
+            let record = if node.flags.contains(NodeFlags::DELETED) {
+                *tombstoned += 1;
+                let mut tombstone = NodeRecord::new(
+                    node.id,
+                    String::new(),
+                    String::new(),
+                    serde_json::Value::Null,
+                );
+                tombstone.flags = node.flags;
+                tombstone
+            } else {
+                let (outgoing_offset, outgoing_count) = adjacency(&outgoing, node.id);
+                let (incoming_offset, incoming_count) = adjacency(&incoming, node.id);
+                NodeRecord {
+                    outgoing_offset,
+                    outgoing_count,
+                    incoming_offset,
+                    incoming_count,
+                    ..node.clone()
+                }
+            };
+            node_store.write_node(&record)?;
+        }
+        let nodes_end = compacted.file_size()?;
+        let header = compacted.header_mut();
+        header.edge_data_offset = header.edge_data_offset.max(nodes_end);
+        let mut edge_store = EdgeStore::new(&mut compacted);
+        for edge in edges {
+            edge_store.write_edge_slot_only(edge)?;
+        }
+        compacted.persist_header()?;
+        Ok(compacted)
+    };
+
+    let mut tombstoned_nodes = 0;
+    let flush_policy = graph_file.flush_policy();
+    match build(&mut tombstoned_nodes) {
+        Ok(compacted) if in_memory => *graph_file = compacted,
+        Ok(compacted) => {
+            drop(compacted);
+            std::fs::rename(&temp_path, &path)?;
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                std::fs::File::open(dir)?.sync_all()?;
+            }
+            *graph_file = GraphFile::open(&path)?;
+        }
+        Err(err) => {
+            if !in_memory {
+                let _ = std::fs::remove_file(&temp_path);
+            }
+            return Err(err);
+        }
+    }
+    graph_file.set_flush_policy(flush_policy);
+    let bytes_after = graph_file.stored_len()?;
+    Ok(CompactionStats {
+        bytes_before,
+        bytes_after,
+        bytes_reclaimed: bytes_before.saturating_sub(bytes_after),
+        tombstoned_nodes,
+    })
+}
+
+/// Weakly connected components of the live nodes, grouped with union-find
+pub fn native_connected_components(
+    graph_file: &mut GraphFile,
+) -> Result<Vec<Vec<NativeNodeId>>, NativeBackendError> {
+    let (nodes, edges) = native_live_graph(graph_file)?;
+    Ok(crate::algo::components_from_edges(&nodes, edges))
+}
+
+/// Every match of `pattern` from `start`, ordered by node sequence
+///
+/// Legs are expanded depth-first in ascending neighbor order, as the SQLite
+/// backend does; `offset` and `limit` are left to the caller.
 pub fn native_pattern_search(
     graph_file: &mut GraphFile,
     start: NativeNodeId,
     pattern: &PatternQuery,
 ) -> Result<Vec<PatternMatch>, NativeBackendError> {
-    // This is a simplified implementation
-    // In a full implementation, this would use the pattern engine
-    // For now, return empty matches as the pattern engine is complex
-    Ok(vec![])
+    if let Some(root) = &pattern.root {
+        let record = NodeStore::new(graph_file).read_node(start)?;
+        if !root.matches(&node_record_to_entity(record)) {
+            return Ok(Vec::new());
+        }
+    }
+    let mut matches = Vec::new();
+    let mut path = vec![start];
+    collect_pattern_matches(graph_file, &pattern.legs, &mut path, &mut matches)?;
+    Ok(matches)
+}
+
+fn collect_pattern_matches(
+    graph_file: &mut GraphFile,
+    legs: &[PatternLeg],
+    path: &mut Vec<NativeNodeId>,
+    matches: &mut Vec<PatternMatch>,
+) -> Result<(), NativeBackendError> {
+    let Some((leg, rest)) = legs.split_first() else {
+        matches.push(PatternMatch {
+            nodes: path.clone(),
+        });
+        return Ok(());
+    };
+    let current = *path.last().expect("path non-empty");
+    let edge_type = leg.edge_type.as_deref();
+    let mut neighbors = native_neighbors(
+        graph_file,
+        current,
+        leg.direction,
+        edge_type.as_ref().map(std::slice::from_ref),
+    )?;
+    neighbors.sort_unstable();
+    neighbors.dedup();
+    for neighbor in neighbors {
+        if let Some(constraint) = &leg.constraint {
+            let record = NodeStore::new(graph_file).read_node(neighbor)?;
+            if !constraint.matches(&node_record_to_entity(record)) {
+                continue;
+            }
+        }
+        path.push(neighbor);
+        collect_pattern_matches(graph_file, rest, path, matches)?;
+        path.pop();
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -1,20 +1,22 @@
 //! Validation and error mapping utilities for native graph backend.
 
 use super::types::*;
-use crate::SqliteGraphError;
 use crate::backend::{EdgeSpec, NodeSpec};
-use crate::graph::GraphEntity;
+use crate::graph::{GraphEdge, GraphEntity};
+use crate::{EntityKind, SqliteGraphError};
 
 /// Error mapping from NativeBackendError to SqliteGraphError
 pub fn map_to_graph_error(err: NativeBackendError) -> SqliteGraphError {
     match err {
-        NativeBackendError::Io(e) => SqliteGraphError::connection(e.to_string()),
+        NativeBackendError::Io(e) => SqliteGraphError::connection(e),
         NativeBackendError::InvalidNodeId { id, max_id } => {
             SqliteGraphError::query(format!("Invalid node ID: {} (max: {})", id, max_id))
         }
         NativeBackendError::InvalidEdgeId { id, max_id } => {
             SqliteGraphError::query(format!("Invalid edge ID: {} (max: {})", id, max_id))
         }
+        NativeBackendError::DeletedNode { id } => SqliteGraphError::not_found(EntityKind::Node, id),
+        NativeBackendError::DeletedEdge { id } => SqliteGraphError::not_found(EntityKind::Edge, id),
         NativeBackendError::CorruptNodeRecord { node_id, reason } => {
             SqliteGraphError::connection(format!("Corrupt node record {}: {}", node_id, reason))
         }
@@ -54,12 +56,13 @@ pub fn map_to_graph_error(err: NativeBackendError) -> SqliteGraphError {
                 expected, found
             ))
         }
-        NativeBackendError::Utf8Error(e) => SqliteGraphError::connection(e.to_string()),
-        NativeBackendError::JsonError(e) => SqliteGraphError::connection(e.to_string()),
-        NativeBackendError::InvalidUtf8(e) => SqliteGraphError::connection(e.to_string()),
+        NativeBackendError::Utf8Error(e) => SqliteGraphError::connection(e),
+        NativeBackendError::JsonError(e) => SqliteGraphError::connection(e),
+        NativeBackendError::InvalidUtf8(e) => SqliteGraphError::connection(e),
         NativeBackendError::BufferTooSmall { size, min_size } => {
             SqliteGraphError::connection(format!("Buffer too small: {} < {}", size, min_size))
         }
+        NativeBackendError::InvalidData(e) => e,
     }
 }
 
@@ -90,6 +93,17 @@ pub fn edge_spec_to_record(spec: EdgeSpec, edge_id: NativeEdgeId) -> EdgeRecord
     )
 }
 
+/// Convert EdgeRecord from storage to GraphEdge
+pub fn edge_record_to_edge(record: EdgeRecord) -> GraphEdge {
+    GraphEdge {
+        id: record.id,
+        from_id: record.from_id,
+        to_id: record.to_id,
+        edge_type: record.edge_type,
+        data: record.data,
+    }
+}
+
 /// Validate node exists and is accessible
 pub fn validate_node_exists(
     graph_file: &mut super::graph_file::GraphFile,
@@ -198,10 +212,10 @@ mod tests {
         let mapped = map_to_graph_error(node_error);
 
         match mapped {
-            SqliteGraphError::QueryError(msg) => {
-                assert!(msg.contains("Invalid node ID"));
-                assert!(msg.contains("0"));
-                assert!(msg.contains("10"));
+            SqliteGraphError::QueryError { message, .. } => {
+                assert!(message.contains("Invalid node ID"));
+                assert!(message.contains("0"));
+                assert!(message.contains("10"));
             }
             _ => panic!("Expected QueryError"),
         }
@@ -10,6 +10,7 @@ pub mod constants;
 pub mod edge_store;
 pub mod graph_file;
 pub mod node_store;
+pub mod property_index;
 pub mod types;
 
 // Include GraphBackend implementation
@@ -19,8 +20,9 @@ pub mod graph_validation;
 
 // Re-export public types for external use
 pub use types::{
-    EdgeFlags, EdgeRecord, FileHeader, FileOffset, NativeBackendError, NativeEdgeId, NativeNodeId,
-    NativeResult, NodeFlags, NodeRecord, RecordSize,
+    CompactionStats, EdgeFlags, EdgeRecord, FileHeader, FileOffset, FlushPolicy,
+    NativeBackendError, NativeEdgeId, NativeNodeId, NativeResult, NodeFlags, NodeRecord,
+    RecordSize, VerifyReport,
 };
 
 pub use adjacency::{AdjacencyHelpers, AdjacencyIterator, Direction};
@@ -28,3 +30,4 @@ pub use edge_store::EdgeStore;
 pub use graph_backend::NativeGraphBackend;
 pub use graph_file::{GraphFile, decode_header, encode_header};
 pub use node_store::NodeStore;
+pub use property_index::PropertyIndex;
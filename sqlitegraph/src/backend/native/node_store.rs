@@ -8,20 +8,25 @@ use std::io::Read;
 use crate::backend::native::constants::*;
 use crate::backend::native::graph_file::GraphFile;
 use crate::backend::native::types::*;
+use crate::data_format::DataFormat;
 
 /// Node store manages node records in the graph file
 pub struct NodeStore<'a> {
     graph_file: &'a mut GraphFile,
     /// In-memory index mapping node_id -> actual file offset
     node_index: std::collections::HashMap<NativeNodeId, FileOffset>,
+    /// Next node id of the sequential index scan, and the offset it starts at
+    scan_cursor: (NativeNodeId, FileOffset),
 }
 
 impl<'a> NodeStore<'a> {
     /// Create a new node store from a graph file
     pub fn new(graph_file: &'a mut GraphFile) -> Self {
+        let node_data_offset = graph_file.header().node_data_offset;
         Self {
             graph_file,
             node_index: std::collections::HashMap::new(),
+            scan_cursor: (1, node_data_offset),
         }
     }
 
@@ -47,6 +52,14 @@ impl<'a> NodeStore<'a> {
         // Store the actual offset in our index
         self.node_index.insert(node.id, offset);
 
+        // Index the properties of a new node; rewrites of existing records only
+        // carry adjacency updates
+        if let Some(index) = self.graph_file.property_index_mut() {
+            if node.id > index.indexed_through() {
+                index.insert_node(node.id, &node.data);
+            }
+        }
+
         // Update header if this is a new node
         if node.id as u64 > self.graph_file.header().node_count {
             self.graph_file.header_mut().node_count = node.id as u64;
@@ -57,6 +70,61 @@ impl<'a> NodeStore<'a> {
         Ok(())
     }
 
+    /// Append a batch of new node records with a single write
+    ///
+    /// Every record is validated and serialized before anything touches the
+    /// file, so a rejected record leaves the file unchanged. The header node
+    /// count is raised in memory only; persisting it is left to the caller.
+    pub fn write_nodes(&mut self, nodes: &[NodeRecord]) -> NativeResult<()> {
+        let mut buffer = Vec::new();
+        let mut starts = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            self.validate_node_fields(node)?;
+            starts.push(buffer.len() as u64);
+            buffer.extend_from_slice(&self.serialize_node(node)?);
+        }
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let offset = self.graph_file.file_size()?;
+        self.graph_file.grow(buffer.len() as u64)?;
+        self.graph_file.write_bytes(offset, &buffer)?;
+
+        let max_id = nodes.iter().map(|node| node.id).max().unwrap_or(0);
+        if max_id as u64 > self.graph_file.header().node_count {
+            self.graph_file.header_mut().node_count = max_id as u64;
+        }
+        for (node, start) in nodes.iter().zip(starts) {
+            self.node_index.insert(node.id, offset + start);
+            if let Some(index) = self.graph_file.property_index_mut() {
+                if node.id > index.indexed_through() {
+                    index.insert_node(node.id, &node.data);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rewrite the record of an existing node in place
+    ///
+    /// Used for adjacency updates, which change fixed-size fields only; a
+    /// record whose encoding changed size is rejected with
+    /// [`NativeBackendError::RecordTooLarge`].
+    pub fn rewrite_node(&mut self, node: &NodeRecord) -> NativeResult<()> {
+        self.validate_node_fields(node)?;
+        let offset = self.node_offset(node.id)?;
+        let old_size = self.record_size(node.id, offset)?;
+        let serialized = self.serialize_node(node)?;
+        if serialized.len() != old_size {
+            return Err(NativeBackendError::RecordTooLarge {
+                size: serialized.len() as u32,
+                max_size: old_size as u32,
+            });
+        }
+        self.graph_file.write_bytes(offset, &serialized)
+    }
+
     /// Validate node record fields except for ID range (used when writing)
     fn validate_node_fields(&self, node: &NodeRecord) -> NativeResult<()> {
         if node.id <= 0 {
@@ -84,7 +152,179 @@ impl<'a> NodeStore<'a> {
     }
 
     /// Read a node record from the file
+    ///
+    /// Deleted nodes are reported as [`NativeBackendError::DeletedNode`].
     pub fn read_node(&mut self, node_id: NativeNodeId) -> NativeResult<NodeRecord> {
+        let node = self.read_node_including_deleted(node_id)?;
+        if node.flags.contains(NodeFlags::DELETED) {
+            return Err(NativeBackendError::DeletedNode { id: node_id });
+        }
+        Ok(node)
+    }
+
+    /// Read a node record whether or not it has been deleted
+    pub fn read_node_including_deleted(
+        &mut self,
+        node_id: NativeNodeId,
+    ) -> NativeResult<NodeRecord> {
+        let offset = self.node_offset(node_id)?;
+        self.read_node_internal(node_id, offset)
+    }
+
+    /// Mark a node as deleted by rewriting the flags of its record in place
+    pub fn mark_deleted(&mut self, node_id: NativeNodeId) -> NativeResult<()> {
+        let offset = self.node_offset(node_id)?;
+        let node = self.read_node_internal(node_id, offset)?;
+        let flags = node.flags.set(NodeFlags::DELETED);
+        self.graph_file
+            .write_bytes(offset + 1, &flags.0.to_be_bytes())?;
+        if let Some(index) = self.graph_file.property_index_mut() {
+            index.remove_node(node_id, &node.data);
+        }
+        Ok(())
+    }
+
+    /// Replace the data of an existing node
+    ///
+    /// The record is rewritten in place when the new encoding fits the space of
+    /// the old one; JSON payloads are padded with trailing whitespace to fill it
+    /// exactly. Otherwise the record is moved, see [`relocate`](Self::relocate).
+    /// Adjacency metadata is carried over unchanged.
+    pub fn update_node_data(
+        &mut self,
+        node_id: NativeNodeId,
+        data: serde_json::Value,
+    ) -> NativeResult<()> {
+        let offset = self.node_offset(node_id)?;
+        let mut node = self.read_node_internal(node_id, offset)?;
+        if node.flags.contains(NodeFlags::DELETED) {
+            return Err(NativeBackendError::DeletedNode { id: node_id });
+        }
+        let old_size = self.record_size(node_id, offset)?;
+        let old_data = std::mem::replace(&mut node.data, data);
+
+        let format = self.graph_file.data_format();
+        let mut data_bytes = format
+            .encode(&node.data)
+            .map_err(NativeBackendError::InvalidData)?;
+        let mut serialized = self.serialize_node_with_data(&node, &data_bytes)?;
+        if serialized.len() < old_size && format == DataFormat::Json {
+            data_bytes.resize(data_bytes.len() + old_size - serialized.len(), b' ');
+            serialized = self.serialize_node_with_data(&node, &data_bytes)?;
+        }
+        if serialized.len() == old_size {
+            self.graph_file.write_bytes(offset, &serialized)?;
+        } else {
+            self.relocate(node_id, offset, old_size, &serialized)?;
+        }
+        if let Some(index) = self.graph_file.property_index_mut() {
+            index.remove_node(node_id, &old_data);
+            index.insert_node(node_id, &node.data);
+        }
+        Ok(())
+    }
+
+    /// Move a node record that no longer fits its space behind the last node record
+    ///
+    /// The copy is written first, then the old record is flagged
+    /// [`NodeFlags::MOVED`] and pointed at it in a single write, so a crash in
+    /// between leaves the old record in effect. Reads follow the pointer, and
+    /// index scans skip the copy, which keeps working after the file is
+    /// reopened; compaction writes the copy back in id order. Once edges exist
+    /// the copy has to fit in front of the edge section, or the update is
+    /// rejected with [`NativeBackendError::RecordTooLarge`].
+    fn relocate(
+        &mut self,
+        node_id: NativeNodeId,
+        offset: FileOffset,
+        old_size: usize,
+        serialized: &[u8],
+    ) -> NativeResult<()> {
+        let target = self.node_records_end()?;
+        let end = target + serialized.len() as u64;
+        let edge_data_offset = self.graph_file.header().edge_data_offset;
+        if self.graph_file.header().edge_count > 0 && end > edge_data_offset {
+            return Err(NativeBackendError::RecordTooLarge {
+                size: serialized.len() as u32,
+                max_size: edge_data_offset.saturating_sub(target) as u32,
+            });
+        }
+        let file_size = self.graph_file.file_size()?;
+        if end > file_size {
+            self.graph_file.grow(end - file_size)?;
+        }
+        self.graph_file.write_bytes(target, serialized)?;
+
+        let mut old = vec![0u8; old_size];
+        self.graph_file.read_bytes(offset, &mut old)?;
+        let flags = u32::from_be_bytes([old[1], old[2], old[3], old[4]]);
+        old[1..5].copy_from_slice(&NodeFlags(flags).set(NodeFlags::MOVED).0.to_be_bytes());
+        let pointer_at = old_size - node::ADJACENCY_METADATA_SIZE;
+        old[pointer_at..pointer_at + 8].copy_from_slice(&target.to_be_bytes());
+        self.graph_file.write_bytes(offset, &old)?;
+        self.node_index.insert(node_id, target);
+        Ok(())
+    }
+
+    /// File offset just past the last node record, relocated copies included
+    ///
+    /// Without edges the node records run to the end of the data; otherwise
+    /// they are walked up to the gap in front of the edge section.
+    fn node_records_end(&mut self) -> NativeResult<FileOffset> {
+        if self.graph_file.header().edge_count == 0 {
+            return self.graph_file.file_size();
+        }
+        let node_count = self.graph_file.header().node_count as NativeNodeId;
+        if self.scan_cursor.0 <= node_count {
+            self.rebuild_index_for_node(node_count)?;
+        }
+        let mut offset = self.scan_cursor.1;
+        let limit = self
+            .graph_file
+            .header()
+            .edge_data_offset
+            .min(self.graph_file.file_size()?);
+        while offset + node::FIXED_HEADER_SIZE as u64 <= limit {
+            let mut version = [0u8; 1];
+            self.graph_file.read_bytes(offset, &mut version)?;
+            if version[0] != 1 {
+                break;
+            }
+            offset += self.record_size(node_count, offset)? as u64;
+        }
+        Ok(offset)
+    }
+
+    /// Follow the pointers of moved records to the live copy of a node
+    fn follow_moves(
+        &mut self,
+        node_id: NativeNodeId,
+        mut offset: FileOffset,
+    ) -> NativeResult<FileOffset> {
+        loop {
+            let mut flags = [0u8; 4];
+            self.graph_file.read_bytes(offset + 1, &mut flags)?;
+            if !NodeFlags(u32::from_be_bytes(flags)).contains(NodeFlags::MOVED) {
+                return Ok(offset);
+            }
+            let pointer_at = offset + self.record_size(node_id, offset)? as u64
+                - node::ADJACENCY_METADATA_SIZE as u64;
+            let mut target = [0u8; 8];
+            self.graph_file.read_bytes(pointer_at, &mut target)?;
+            let target = u64::from_be_bytes(target);
+            // Copies always lie behind the record they replace
+            if target <= offset {
+                return Err(NativeBackendError::CorruptNodeRecord {
+                    node_id,
+                    reason: format!("Moved record points back to offset {}", target),
+                });
+            }
+            offset = target;
+        }
+    }
+
+    /// Resolve the file offset of a node record
+    fn node_offset(&mut self, node_id: NativeNodeId) -> NativeResult<FileOffset> {
         let header = self.graph_file.header();
 
         if node_id <= 0 || node_id > header.node_count as NativeNodeId {
@@ -102,8 +342,10 @@ impl<'a> NodeStore<'a> {
             // Fall back to sequential search from the beginning
             self.rebuild_index_for_node(node_id)?
         };
+        let offset = self.follow_moves(node_id, offset)?;
+        self.node_index.insert(node_id, offset);
 
-        self.read_node_internal(node_id, offset)
+        Ok(offset)
     }
 
     /// Internal method to read a node record from a specific offset
@@ -112,7 +354,29 @@ impl<'a> NodeStore<'a> {
         node_id: NativeNodeId,
         offset: FileOffset,
     ) -> NativeResult<NodeRecord> {
-        // First read the node header to get the record size
+        let total_size = self.record_size(node_id, offset)?;
+
+        // Read the complete node record
+        let mut buffer = vec![0u8; total_size];
+        self.graph_file.read_bytes(offset, &mut buffer)?;
+        if buffer.len() != total_size {
+            return Err(NativeBackendError::CorruptNodeRecord {
+                node_id,
+                reason: format!(
+                    "Buffer size mismatch: expected {}, got {}",
+                    total_size,
+                    buffer.len()
+                ),
+            });
+        }
+
+        // Deserialize node record
+        self.deserialize_node(node_id, &buffer)
+    }
+
+    /// Size in bytes of the node record stored at `offset`
+    fn record_size(&mut self, node_id: NativeNodeId, offset: FileOffset) -> NativeResult<usize> {
+        // Read the node header to get the record size
         let mut header_buffer = vec![0u8; 32]; // Enough for version + flags + id + length fields
         self.graph_file.read_bytes(offset, &mut header_buffer)?;
 
@@ -141,34 +405,32 @@ impl<'a> NodeStore<'a> {
             header_buffer[19],
             header_buffer[20],
         ]) as usize;
-
-        // Calculate total record size exactly as serialize_node writes it
-        let total_size = 1 + 4 + 8 + 2 + 2 + 4 + kind_len + name_len + data_len + 8 + 4 + 8 + 4; // version + flags + id + kind_len + name_len + data_len + strings + adjacency
-
-        // Read the complete node record
-        let mut buffer = vec![0u8; total_size];
-        self.graph_file.read_bytes(offset, &mut buffer)?;
-        if buffer.len() != total_size {
+        if data_len > node::MAX_DATA_LENGTH as usize {
             return Err(NativeBackendError::CorruptNodeRecord {
                 node_id,
-                reason: format!(
-                    "Buffer size mismatch: expected {}, got {}",
-                    total_size,
-                    buffer.len()
-                ),
+                reason: format!("Node data length {} exceeds maximum", data_len),
             });
         }
 
-        // Deserialize node record
-        self.deserialize_node(node_id, &buffer)
+        // Calculate total record size exactly as serialize_node writes it
+        Ok(1 + 4 + 8 + 2 + 2 + 4 + kind_len + name_len + data_len + 8 + 4 + 8 + 4) // version + flags + id + kind_len + name_len + data_len + strings + adjacency
     }
 
-    /// Rebuild index up to the target node by scanning from the beginning
+    /// Rebuild index up to the target node by scanning sequentially
+    ///
+    /// The scan resumes where the previous one stopped, so reading ascending
+    /// ids through one store walks the node records once. Copies of moved
+    /// records carry the id of an earlier node and are stepped over; the
+    /// index keeps the original record, whose pointer leads to the copy.
     fn rebuild_index_for_node(&mut self, target_id: NativeNodeId) -> NativeResult<FileOffset> {
-        let mut current_offset = self.graph_file.header().node_data_offset;
+        let (mut id, mut current_offset) = self.scan_cursor;
+        if id > target_id {
+            id = 1;
+            current_offset = self.graph_file.header().node_data_offset;
+        }
         let file_size = self.graph_file.file_size()?;
 
-        for id in 1..=target_id {
+        while id <= target_id {
             // Stop if we've reached the end of the file
             if current_offset >= file_size {
                 return Err(NativeBackendError::InvalidNodeId {
@@ -177,9 +439,6 @@ impl<'a> NodeStore<'a> {
                 });
             }
 
-            // Store offset for this node
-            self.node_index.insert(id, current_offset);
-
             // Read the node header to get its size
             if current_offset + 32 > file_size {
                 return Err(NativeBackendError::CorruptNodeRecord {
@@ -211,8 +470,17 @@ impl<'a> NodeStore<'a> {
 
             // Calculate total record size
             let total_size = 1 + 4 + 8 + 2 + 2 + 4 + kind_len + name_len + data_len + 8 + 4 + 8 + 4;
+            let mut stored_id = [0u8; 8];
+            stored_id.copy_from_slice(&header_buffer[5..13]);
+            let stored_id = i64::from_be_bytes(stored_id);
+            if !(1..id).contains(&stored_id) {
+                // Store offset for this node
+                self.node_index.insert(id, current_offset);
+                id += 1;
+            }
             current_offset += total_size as u64;
         }
+        self.scan_cursor = (target_id + 1, current_offset);
 
         // Return the offset for the target node
         Ok(self.node_index[&target_id])
@@ -220,6 +488,20 @@ impl<'a> NodeStore<'a> {
 
     /// Serialize a node record to bytes
     fn serialize_node(&self, node: &NodeRecord) -> NativeResult<Vec<u8>> {
+        let data_bytes = self
+            .graph_file
+            .data_format()
+            .encode(&node.data)
+            .map_err(NativeBackendError::InvalidData)?;
+        self.serialize_node_with_data(node, &data_bytes)
+    }
+
+    /// Serialize a node record around an already encoded data payload
+    fn serialize_node_with_data(
+        &self,
+        node: &NodeRecord,
+        data_bytes: &[u8],
+    ) -> NativeResult<Vec<u8>> {
         let mut buffer = Vec::new();
 
         // Record header (version + flags)
@@ -250,7 +532,6 @@ impl<'a> NodeStore<'a> {
         buffer.extend_from_slice(&(name_bytes.len() as u16).to_be_bytes());
 
         // Data length (big-endian)
-        let data_bytes = serde_json::to_vec(&node.data)?;
         if data_bytes.len() > node::MAX_DATA_LENGTH as usize {
             return Err(NativeBackendError::RecordTooLarge {
                 size: data_bytes.len() as u32,
@@ -262,7 +543,7 @@ impl<'a> NodeStore<'a> {
         // Variable-length fields
         buffer.extend_from_slice(kind_bytes);
         buffer.extend_from_slice(name_bytes);
-        buffer.extend_from_slice(&data_bytes);
+        buffer.extend_from_slice(data_bytes);
 
         // Adjacency metadata
         buffer.extend_from_slice(&node.outgoing_offset.to_be_bytes());
@@ -359,7 +640,14 @@ impl<'a> NodeStore<'a> {
 
         // Read data
         let data_bytes = &buffer[offset..offset + data_len];
-        let data = serde_json::from_slice(data_bytes)?;
+        let data = self
+            .graph_file
+            .data_format()
+            .decode(data_bytes)
+            .map_err(|e| NativeBackendError::CorruptNodeRecord {
+                node_id,
+                reason: e.to_string(),
+            })?;
         offset += data_len;
 
         // Read adjacency metadata
@@ -437,7 +725,7 @@ impl<'a> NodeStore<'a> {
         let max_id = self.max_node_id();
 
         for node_id in 1..=max_id {
-            match self.read_node(node_id) {
+            match self.read_node_including_deleted(node_id) {
                 Ok(node) => {
                     // Validate adjacency metadata consistency
                     if node.outgoing_count > 0 && node.outgoing_offset == 0 {
@@ -0,0 +1,271 @@
+//! Sorted property index for the native backend.
+//!
+//! The index maps `(key, value)` pairs taken from the top-level properties of
+//! node `data` to node ids. Entries are kept sorted so lookups are a binary
+//! search. The index is persisted as a section at the end of the graph file,
+//! closed by a fixed-size footer:
+//!
+//! ```text
+//! section: indexed_through (i64) | entry_count (u64) | entries...
+//! entry:   key_len (u32) | key | value_len (u32) | value | node_id (i64)
+//! footer:  section_offset (u64) | checksum (u64) | FOOTER_MAGIC
+//! ```
+
+use crate::backend::native::constants::property_index::*;
+use crate::backend::native::types::NativeNodeId;
+
+/// Text a top-level property value is indexed under
+///
+/// Strings are used verbatim; numbers and booleans by their JSON text, matching
+/// how the SQLite backend stores property values. Other values are not indexed.
+pub fn property_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct IndexEntry {
+    key: String,
+    value: String,
+    node_id: NativeNodeId,
+}
+
+/// In-memory `(key, value) -> node_id` index, sorted by key, value and node id
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertyIndex {
+    entries: Vec<IndexEntry>,
+    /// Highest node id whose first write has been indexed
+    indexed_through: NativeNodeId,
+}
+
+impl PropertyIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build an index over the given live nodes of a file holding `indexed_through` node ids
+    pub fn rebuild<'a, I>(indexed_through: NativeNodeId, nodes: I) -> Self
+    where
+        I: IntoIterator<Item = (NativeNodeId, &'a serde_json::Value)>,
+    {
+        let mut entries: Vec<IndexEntry> = nodes
+            .into_iter()
+            .flat_map(|(node_id, data)| Self::entries_for(node_id, data))
+            .collect();
+        entries.sort_unstable();
+        entries.dedup();
+        Self {
+            entries,
+            indexed_through,
+        }
+    }
+
+    /// Number of `(key, value, node_id)` entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the index holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Highest node id the index has seen written
+    ///
+    /// Node records are rewritten in place for adjacency updates; only writes
+    /// of ids above this mark are new nodes that need indexing.
+    pub fn indexed_through(&self) -> NativeNodeId {
+        self.indexed_through
+    }
+
+    /// Index the properties of a newly written node
+    pub fn insert_node(&mut self, node_id: NativeNodeId, data: &serde_json::Value) {
+        self.indexed_through = self.indexed_through.max(node_id);
+        for entry in Self::entries_for(node_id, data) {
+            if let Err(pos) = self.entries.binary_search(&entry) {
+                self.entries.insert(pos, entry);
+            }
+        }
+    }
+
+    /// Drop the entries previously indexed for `node_id` from `data`
+    pub fn remove_node(&mut self, node_id: NativeNodeId, data: &serde_json::Value) {
+        for entry in Self::entries_for(node_id, data) {
+            if let Ok(pos) = self.entries.binary_search(&entry) {
+                self.entries.remove(pos);
+            }
+        }
+    }
+
+    /// Ids of nodes whose property `key` is indexed as `value`, in ascending order
+    pub fn lookup(&self, key: &str, value: &str) -> Vec<NativeNodeId> {
+        let start = self
+            .entries
+            .partition_point(|e| (e.key.as_str(), e.value.as_str()) < (key, value));
+        self.entries[start..]
+            .iter()
+            .take_while(|e| e.key == key && e.value == value)
+            .map(|e| e.node_id)
+            .collect()
+    }
+
+    fn entries_for(
+        node_id: NativeNodeId,
+        data: &serde_json::Value,
+    ) -> impl Iterator<Item = IndexEntry> + '_ {
+        data.as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(move |(key, value)| {
+                property_text(value).map(|value| IndexEntry {
+                    key: key.clone(),
+                    value,
+                    node_id,
+                })
+            })
+    }
+
+    /// Encode the index as a section followed by its footer
+    ///
+    /// `section_offset` is the file offset the section will be written at.
+    pub fn encode(&self, section_offset: u64) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(SECTION_HEADER_SIZE + FOOTER_SIZE as usize);
+        buffer.extend_from_slice(&self.indexed_through.to_be_bytes());
+        buffer.extend_from_slice(&(self.entries.len() as u64).to_be_bytes());
+        for entry in &self.entries {
+            buffer.extend_from_slice(&(entry.key.len() as u32).to_be_bytes());
+            buffer.extend_from_slice(entry.key.as_bytes());
+            buffer.extend_from_slice(&(entry.value.len() as u32).to_be_bytes());
+            buffer.extend_from_slice(entry.value.as_bytes());
+            buffer.extend_from_slice(&entry.node_id.to_be_bytes());
+        }
+        let checksum = section_checksum(&buffer);
+        buffer.extend_from_slice(&section_offset.to_be_bytes());
+        buffer.extend_from_slice(&checksum.to_be_bytes());
+        buffer.extend_from_slice(&FOOTER_MAGIC);
+        buffer
+    }
+
+    /// Decode a section previously produced by [`encode`](Self::encode)
+    ///
+    /// Returns `None` if the bytes are truncated, out of order or fail the
+    /// checksum; callers then rebuild the index from the node records.
+    pub fn decode(section: &[u8], checksum: u64) -> Option<Self> {
+        if section_checksum(section) != checksum {
+            return None;
+        }
+        let mut reader = SectionReader { bytes: section };
+        let indexed_through = reader.i64()?;
+        let count = reader.u64()?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let key = reader.string()?;
+            let value = reader.string()?;
+            let node_id = reader.i64()?;
+            entries.push(IndexEntry {
+                key,
+                value,
+                node_id,
+            });
+        }
+        if !reader.bytes.is_empty() || entries.windows(2).any(|pair| pair[0] > pair[1]) {
+            return None;
+        }
+        Some(Self {
+            entries,
+            indexed_through,
+        })
+    }
+}
+
+/// Decode the fixed-size footer into `(section_offset, checksum)`
+pub fn decode_footer(footer: &[u8]) -> Option<(u64, u64)> {
+    if footer.len() != FOOTER_SIZE as usize || footer[16..] != FOOTER_MAGIC {
+        return None;
+    }
+    let section_offset = u64::from_be_bytes(footer[0..8].try_into().ok()?);
+    let checksum = u64::from_be_bytes(footer[8..16].try_into().ok()?);
+    Some((section_offset, checksum))
+}
+
+/// FNV-1a hash of the section bytes
+fn section_checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+struct SectionReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl SectionReader<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(head)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_be_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        Some(i64::from_be_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = u32::from_be_bytes(self.take(4)?.try_into().ok()?) as usize;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_lookup_returns_sorted_ids_and_tracks_removal() {
+        let mut index = PropertyIndex::new();
+        index.insert_node(3, &json!({"lang": "rust", "loc": 10}));
+        index.insert_node(1, &json!({"lang": "rust", "public": true}));
+        index.insert_node(2, &json!({"lang": "go", "tags": ["a"]}));
+
+        assert_eq!(index.lookup("lang", "rust"), vec![1, 3]);
+        assert_eq!(index.lookup("loc", "10"), vec![3]);
+        assert_eq!(index.lookup("public", "true"), vec![1]);
+        assert!(index.lookup("tags", "a").is_empty());
+        assert_eq!(index.indexed_through(), 3);
+
+        index.remove_node(3, &json!({"lang": "rust", "loc": 10}));
+        assert_eq!(index.lookup("lang", "rust"), vec![1]);
+        assert!(index.lookup("loc", "10").is_empty());
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip_and_corruption() {
+        let mut index = PropertyIndex::new();
+        index.insert_node(1, &json!({"a": "x", "b": 2}));
+        index.insert_node(2, &json!({"a": "y"}));
+
+        let encoded = index.encode(4096);
+        let (section, footer) = encoded.split_at(encoded.len() - FOOTER_SIZE as usize);
+        let (offset, checksum) = decode_footer(footer).unwrap();
+        assert_eq!(offset, 4096);
+        assert_eq!(PropertyIndex::decode(section, checksum), Some(index));
+
+        let mut corrupt = section.to_vec();
+        corrupt[SECTION_HEADER_SIZE] ^= 0xFF;
+        assert_eq!(PropertyIndex::decode(&corrupt, checksum), None);
+        assert_eq!(decode_footer(&footer[..8]), None);
+    }
+}
@@ -45,6 +45,13 @@ impl NodeFlags {
 
     /// No flags set
     pub const NONE: Self = Self(0);
+
+    /// Node has been deleted; its record stays in place so offsets remain stable
+    pub const DELETED: Self = Self(1 << 0);
+
+    /// Record was replaced by a copy that did not fit its space; the outgoing
+    /// offset field holds the file offset of the copy
+    pub const MOVED: Self = Self(1 << 1);
 }
 
 impl fmt::LowerHex for NodeFlags {
@@ -80,6 +87,9 @@ impl EdgeFlags {
 
     /// No flags set
     pub const NONE: Self = Self(0);
+
+    /// Edge has been deleted; its slot is kept so edge ids remain stable
+    pub const DELETED: Self = Self(1 << 0);
 }
 
 impl fmt::LowerHex for EdgeFlags {
@@ -88,6 +98,75 @@ impl fmt::LowerHex for EdgeFlags {
     }
 }
 
+/// How often the native backend persists its file header after writes.
+///
+/// The header carries the node and edge counts, so a crash before it is
+/// persisted loses every record written since the last header write even though
+/// the record bytes themselves reached the file.
+///
+/// "Crash" here means the process dying. Header writes are not synced, so
+/// after a power loss or OS crash only what was persisted by the last
+/// `flush()` is guaranteed to survive, whatever the policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlushPolicy {
+    /// Persist the header after every insert. Nothing acknowledged is lost if
+    /// the process crashes, at the cost of an extra header write per record.
+    #[default]
+    EveryWrite,
+    /// Persist the header after every `n` inserts. At most `n - 1` records are
+    /// lost on crash; `EveryN(0)` and `EveryN(1)` behave like `EveryWrite`.
+    EveryN(usize),
+    /// Only persist the header on an explicit `flush()` or when the graph is
+    /// dropped. Fastest for bulk loads; everything since the last flush is lost
+    /// on crash.
+    Manual,
+}
+
+/// Outcome of compacting a native graph file.
+///
+/// Node and edge ids survive compaction, so deleted nodes keep a minimal
+/// tombstone record and deleted edges keep their fixed-size slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompactionStats {
+    /// File size before compaction
+    pub bytes_before: u64,
+    /// File size after compaction
+    pub bytes_after: u64,
+    /// `bytes_before - bytes_after`, or zero if the file did not shrink
+    pub bytes_reclaimed: u64,
+    /// Deleted nodes whose records were reduced to tombstones
+    pub tombstoned_nodes: u64,
+}
+
+/// Outcome of checking every record of a native graph file.
+///
+/// Records carry no checksums of their own, so a record counts as corrupt when
+/// it cannot be read back: a bad version byte, length fields that overrun the
+/// record, an id that does not match its position, undecodable strings or
+/// data, or an edge endpoint outside the node id range.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VerifyReport {
+    /// Node records visited, `1..=node_count`
+    pub nodes_checked: u64,
+    /// Edge slots visited, `1..=edge_count`
+    pub edges_checked: u64,
+    /// Ids of unreadable node records, in ascending order
+    pub corrupt_nodes: Vec<NativeNodeId>,
+    /// Ids of unreadable or dangling edge records, in ascending order
+    pub corrupt_edges: Vec<NativeEdgeId>,
+    /// Why the header failed validation, if it did
+    pub header_error: Option<String>,
+}
+
+impl VerifyReport {
+    /// Whether the header and every record passed.
+    pub fn is_clean(&self) -> bool {
+        self.header_error.is_none()
+            && self.corrupt_nodes.is_empty()
+            && self.corrupt_edges.is_empty()
+    }
+}
+
 /// File header structure for native graph database
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileHeader {
@@ -374,6 +453,12 @@ pub enum NativeBackendError {
         max_id: NativeEdgeId,
     },
 
+    #[error("Node {id} has been deleted")]
+    DeletedNode { id: NativeNodeId },
+
+    #[error("Edge {id} has been deleted")]
+    DeletedEdge { id: NativeEdgeId },
+
     #[error("Corrupt node record at node {node_id}: {reason}")]
     CorruptNodeRecord {
         node_id: NativeNodeId,
@@ -413,6 +498,9 @@ pub enum NativeBackendError {
 
     #[error("Buffer too small: {size} bytes (need at least {min_size} bytes)")]
     BufferTooSmall { size: usize, min_size: usize },
+
+    #[error("Invalid data payload: {0}")]
+    InvalidData(#[source] crate::errors::SqliteGraphError),
 }
 
 /// Result type alias for native backend operations
@@ -38,15 +38,17 @@ impl SqliteGraphBackendHelpers for crate::backend::sqlite::impl_::SqliteGraphBac
                 let conn = graph.connection();
                 let mut stmt = conn
                     .prepare_cached(
-                        "SELECT to_id FROM graph_edges WHERE from_id=?1 AND edge_type=?2 ORDER BY to_id, id",
+                        "SELECT to_id FROM graph_edges WHERE from_id=?1 AND edge_type=?2 \
+                         AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=to_id) \
+                         ORDER BY to_id, id",
                     )
-                    .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                    .map_err(SqliteGraphError::query)?;
                 let rows = stmt
                     .query_map(params![node, edge_type], |row| row.get(0))
-                    .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                    .map_err(SqliteGraphError::query)?;
                 let mut values = Vec::new();
                 for value in rows {
-                    values.push(value.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+                    values.push(value.map_err(SqliteGraphError::query)?);
                 }
                 Ok(values)
             }
@@ -54,15 +56,17 @@ impl SqliteGraphBackendHelpers for crate::backend::sqlite::impl_::SqliteGraphBac
                 let conn = graph.connection();
                 let mut stmt = conn
                     .prepare_cached(
-                        "SELECT from_id FROM graph_edges WHERE to_id=?1 AND edge_type=?2 ORDER BY from_id, id",
+                        "SELECT from_id FROM graph_edges WHERE to_id=?1 AND edge_type=?2 \
+                         AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=from_id) \
+                         ORDER BY from_id, id",
                     )
-                    .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                    .map_err(SqliteGraphError::query)?;
                 let rows = stmt
                     .query_map(params![node, edge_type], |row| row.get(0))
-                    .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                    .map_err(SqliteGraphError::query)?;
                 let mut values = Vec::new();
                 for value in rows {
-                    values.push(value.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+                    values.push(value.map_err(SqliteGraphError::query)?);
                 }
                 Ok(values)
             }
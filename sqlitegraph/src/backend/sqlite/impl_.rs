@@ -3,15 +3,24 @@
 //! This module contains the SqliteGraphBackend implementation that wraps
 //! SqliteGraph and provides the concrete implementation of all GraphBackend methods.
 
+use std::collections::HashMap;
+
 use rusqlite::params;
 
 use crate::{
-    SqliteGraphError,
-    backend::sqlite::types::{BackendDirection, EdgeSpec, NeighborQuery, NodeSpec},
-    bfs::{bfs_neighbors, shortest_path},
-    graph::{GraphEdge, GraphEntity, SqliteGraph},
-    multi_hop,
+    SqliteGraphError, algo,
+    backend::sqlite::types::{
+        BackendDirection, EdgeSpec, NeighborQuery, NodeEdges, NodeSpec, merge_undirected,
+    },
+    bfs::{
+        BfsIterator, bfs_iter, bfs_neighbors, bfs_neighbors_directed, bfs_neighbors_filtered,
+        shortest_path, shortest_path_directed, shortest_path_filtered,
+    },
+    cache::CacheStats,
+    graph::{GraphEdge, GraphEntity, GraphStatistics, MAX_SQL_VARIABLES, SqliteGraph},
+    index, multi_hop,
     pattern::{self, PatternMatch, PatternQuery},
+    safety::SafetyReport,
 };
 
 /// SQLite-backed implementation of the GraphBackend trait.
@@ -45,6 +54,18 @@ impl SqliteGraphBackend {
         self.graph.all_entity_ids()
     }
 
+    /// Lazily yield the outgoing BFS from `start` in the same order as `bfs`.
+    ///
+    /// Neighbor lists are fetched on demand, so stopping early avoids querying
+    /// the part of the graph that was never reached.
+    pub fn bfs_iter(
+        &self,
+        start: i64,
+        max_depth: u32,
+    ) -> Result<BfsIterator<'_>, SqliteGraphError> {
+        bfs_iter(&self.graph, start, max_depth)
+    }
+
     /// Execute optimized neighbor queries based on direction and edge type filtering.
     fn query_neighbors(
         &self,
@@ -53,21 +74,26 @@ impl SqliteGraphBackend {
         edge_type: &Option<String>,
     ) -> Result<Vec<i64>, SqliteGraphError> {
         match (direction, edge_type) {
-            (BackendDirection::Outgoing, None) => self.graph.fetch_outgoing(node),
-            (BackendDirection::Incoming, None) => self.graph.fetch_incoming(node),
+            (direction, None) => self.graph.fetch_neighbors(node, direction),
+            (BackendDirection::Both, Some(_)) => Ok(merge_undirected(
+                self.query_neighbors(node, BackendDirection::Outgoing, edge_type)?,
+                self.query_neighbors(node, BackendDirection::Incoming, edge_type)?,
+            )),
             (BackendDirection::Outgoing, Some(edge_type)) => {
                 let conn = self.graph.connection();
                 let mut stmt = conn
                     .prepare_cached(
-                        "SELECT to_id FROM graph_edges WHERE from_id=?1 AND edge_type=?2 ORDER BY to_id, id",
+                        "SELECT to_id FROM graph_edges WHERE from_id=?1 AND edge_type=?2 \
+                         AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=to_id) \
+                         ORDER BY to_id, id",
                     )
-                    .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                    .map_err(SqliteGraphError::query)?;
                 let rows = stmt
                     .query_map(params![node, edge_type], |row| row.get(0))
-                    .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                    .map_err(SqliteGraphError::query)?;
                 let mut values = Vec::new();
                 for value in rows {
-                    values.push(value.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+                    values.push(value.map_err(SqliteGraphError::query)?);
                 }
                 Ok(values)
             }
@@ -75,20 +101,86 @@ impl SqliteGraphBackend {
                 let conn = self.graph.connection();
                 let mut stmt = conn
                     .prepare_cached(
-                        "SELECT from_id FROM graph_edges WHERE to_id=?1 AND edge_type=?2 ORDER BY from_id, id",
+                        "SELECT from_id FROM graph_edges WHERE to_id=?1 AND edge_type=?2 \
+                         AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=from_id) \
+                         ORDER BY from_id, id",
                     )
-                    .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                    .map_err(SqliteGraphError::query)?;
                 let rows = stmt
                     .query_map(params![node, edge_type], |row| row.get(0))
-                    .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                    .map_err(SqliteGraphError::query)?;
                 let mut values = Vec::new();
                 for value in rows {
-                    values.push(value.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+                    values.push(value.map_err(SqliteGraphError::query)?);
                 }
                 Ok(values)
             }
         }
     }
+
+    /// Neighbor lists for many nodes, one `IN (...)` query per chunk of ids.
+    fn query_neighbors_batch(
+        &self,
+        ids: &[i64],
+        direction: BackendDirection,
+        edge_type: Option<&str>,
+    ) -> Result<HashMap<i64, Vec<i64>>, SqliteGraphError> {
+        let (anchor, other) = match direction {
+            BackendDirection::Outgoing => ("from_id", "to_id"),
+            BackendDirection::Incoming => ("to_id", "from_id"),
+            BackendDirection::Both => {
+                let mut outgoing =
+                    self.query_neighbors_batch(ids, BackendDirection::Outgoing, edge_type)?;
+                let mut incoming =
+                    self.query_neighbors_batch(ids, BackendDirection::Incoming, edge_type)?;
+                return Ok(ids
+                    .iter()
+                    .map(|&id| {
+                        let merged = merge_undirected(
+                            outgoing.remove(&id).unwrap_or_default(),
+                            incoming.remove(&id).unwrap_or_default(),
+                        );
+                        (id, merged)
+                    })
+                    .collect());
+            }
+        };
+        let mut result: HashMap<i64, Vec<i64>> = ids.iter().map(|&id| (id, Vec::new())).collect();
+        let mut unique: Vec<i64> = result.keys().copied().collect();
+        unique.sort_unstable();
+        // Leave room for the edge type parameter within SQLite's variable limit.
+        let chunk_size = MAX_SQL_VARIABLES - usize::from(edge_type.is_some());
+        let conn = self.graph.connection();
+        for chunk in unique.chunks(chunk_size) {
+            let placeholders = vec!["?"; chunk.len()].join(",");
+            let type_filter = if edge_type.is_some() {
+                "AND edge_type=? "
+            } else {
+                ""
+            };
+            let sql = format!(
+                "SELECT {anchor}, {other} FROM graph_edges WHERE {anchor} IN ({placeholders}) \
+                 {type_filter}AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id={other}) \
+                 ORDER BY {anchor}, {other}, id"
+            );
+            let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+            let mut values: Vec<rusqlite::types::Value> =
+                chunk.iter().map(|&id| id.into()).collect();
+            if let Some(edge_type) = edge_type {
+                values.push(edge_type.to_string().into());
+            }
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(values), |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+                })
+                .map_err(SqliteGraphError::query)?;
+            for row in rows {
+                let (node, neighbor) = row.map_err(SqliteGraphError::query)?;
+                result.entry(node).or_default().push(neighbor);
+            }
+        }
+        Ok(result)
+    }
 }
 
 impl crate::backend::GraphBackend for SqliteGraphBackend {
@@ -116,24 +208,139 @@ impl crate::backend::GraphBackend for SqliteGraphBackend {
         })
     }
 
+    fn upsert_edge(&self, edge: EdgeSpec) -> Result<i64, SqliteGraphError> {
+        self.graph.upsert_edge(&GraphEdge {
+            id: 0,
+            from_id: edge.from,
+            to_id: edge.to,
+            edge_type: edge.edge_type,
+            data: edge.data,
+        })
+    }
+
+    fn get_edge(&self, id: i64) -> Result<GraphEdge, SqliteGraphError> {
+        self.graph.get_live_edge(id)
+    }
+
+    fn list_edge_ids(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        self.graph.live_edge_ids()
+    }
+
+    fn find_nodes_by_property(&self, key: &str, value: &str) -> Result<Vec<i64>, SqliteGraphError> {
+        index::entity_ids_by_property(&self.graph, key, value)
+    }
+
+    fn find_nodes_by_label(&self, label: &str) -> Result<Vec<i64>, SqliteGraphError> {
+        index::entity_ids_by_label(&self.graph, label)
+    }
+
+    fn delete_node(&self, id: i64) -> Result<(), SqliteGraphError> {
+        self.graph.delete_entity(id).map(|_| ())
+    }
+
+    fn delete_edge(&self, id: i64) -> Result<(), SqliteGraphError> {
+        self.graph.delete_edge(id).map(|_| ())
+    }
+
+    fn update_node_data(&self, id: i64, data: serde_json::Value) -> Result<(), SqliteGraphError> {
+        self.graph.update_entity_data(id, &data).map(|_| ())
+    }
+
+    fn update_edge_data(&self, id: i64, data: serde_json::Value) -> Result<(), SqliteGraphError> {
+        self.graph.update_edge_data(id, &data).map(|_| ())
+    }
+
     fn neighbors(&self, node: i64, query: NeighborQuery) -> Result<Vec<i64>, SqliteGraphError> {
         self.query_neighbors(node, query.direction, &query.edge_type)
     }
 
+    fn neighbors_uncached(
+        &self,
+        node: i64,
+        query: NeighborQuery,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        match query.edge_type {
+            None => self.graph.load_neighbors(node, query.direction),
+            // Typed lookups never go through the cache
+            Some(_) => self.query_neighbors(node, query.direction, &query.edge_type),
+        }
+    }
+
+    fn neighbors_batch(
+        &self,
+        ids: &[i64],
+        query: NeighborQuery,
+    ) -> Result<HashMap<i64, Vec<i64>>, SqliteGraphError> {
+        self.query_neighbors_batch(ids, query.direction, query.edge_type.as_deref())
+    }
+
     fn bfs(&self, start: i64, depth: u32) -> Result<Vec<i64>, SqliteGraphError> {
         bfs_neighbors(&self.graph, start, depth)
     }
 
+    fn bfs_directed(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        bfs_neighbors_directed(&self.graph, start, depth, direction)
+    }
+
+    fn bfs_filtered(
+        &self,
+        start: i64,
+        max_depth: u32,
+        edge_types: &[&str],
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        bfs_neighbors_filtered(&self.graph, start, max_depth, edge_types)
+    }
+
     fn shortest_path(&self, start: i64, end: i64) -> Result<Option<Vec<i64>>, SqliteGraphError> {
         shortest_path(&self.graph, start, end)
     }
 
+    fn shortest_path_directed(
+        &self,
+        start: i64,
+        end: i64,
+        direction: BackendDirection,
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+        shortest_path_directed(&self.graph, start, end, direction)
+    }
+
+    fn shortest_path_filtered(
+        &self,
+        start: i64,
+        end: i64,
+        edge_types: &[&str],
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+        shortest_path_filtered(&self.graph, start, end, edge_types)
+    }
+
     fn node_degree(&self, node: i64) -> Result<(usize, usize), SqliteGraphError> {
         let out = self.graph.fetch_outgoing(node)?.len();
         let incoming = self.graph.fetch_incoming(node)?.len();
         Ok((out, incoming))
     }
 
+    fn neighbor_count(&self, node: i64, query: NeighborQuery) -> Result<usize, SqliteGraphError> {
+        self.graph
+            .count_neighbors(node, query.direction, query.edge_type.as_deref())
+    }
+
+    fn node_edges(&self, node: i64) -> Result<NodeEdges, SqliteGraphError> {
+        self.graph.fetch_node_edges(node, None)
+    }
+
+    fn top_nodes_by_degree(
+        &self,
+        k: usize,
+        direction: BackendDirection,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
+        algo::top_nodes_by_degree(&self.graph, k, direction)
+    }
+
     fn k_hop(
         &self,
         start: i64,
@@ -143,6 +350,15 @@ impl crate::backend::GraphBackend for SqliteGraphBackend {
         multi_hop::k_hop(&self.graph, start, depth, direction)
     }
 
+    fn k_hop_with_distance(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
+        multi_hop::k_hop_with_distance(&self.graph, start, depth, direction)
+    }
+
     fn k_hop_filtered(
         &self,
         start: i64,
@@ -153,6 +369,43 @@ impl crate::backend::GraphBackend for SqliteGraphBackend {
         multi_hop::k_hop_filtered(&self.graph, start, depth, direction, allowed_edge_types)
     }
 
+    fn k_hop_weighted(
+        &self,
+        start: i64,
+        budget: f64,
+        direction: BackendDirection,
+        type_costs: &HashMap<String, f64>,
+    ) -> Result<Vec<(i64, f64)>, SqliteGraphError> {
+        multi_hop::k_hop_weighted(&self.graph, start, budget, direction, type_costs)
+    }
+
+    fn all_pairs_shortest_paths(
+        &self,
+        max_nodes: usize,
+    ) -> Result<HashMap<(i64, i64), u32>, SqliteGraphError> {
+        algo::all_pairs_shortest_paths(&self.graph, max_nodes)
+    }
+
+    fn betweenness_centrality(
+        &self,
+        normalized: bool,
+        max_nodes: usize,
+    ) -> Result<HashMap<i64, f64>, SqliteGraphError> {
+        algo::betweenness_centrality(&self.graph, normalized, max_nodes)
+    }
+
+    fn astar(
+        &self,
+        from: i64,
+        to: i64,
+        weight_key: &str,
+        heuristic: &dyn Fn(i64) -> f64,
+    ) -> Result<Option<(Vec<i64>, f64)>, SqliteGraphError> {
+        algo::astar(from, to, weight_key, heuristic, |node| {
+            Ok(self.graph.fetch_node_edges(node, None)?.outgoing)
+        })
+    }
+
     fn chain_query(
         &self,
         start: i64,
@@ -161,6 +414,22 @@ impl crate::backend::GraphBackend for SqliteGraphBackend {
         multi_hop::chain_query(&self.graph, start, chain)
     }
 
+    fn connected_components(&self) -> Result<Vec<Vec<i64>>, SqliteGraphError> {
+        algo::connected_components(&self.graph)
+    }
+
+    fn pagerank(
+        &self,
+        damping: f64,
+        iterations: usize,
+    ) -> Result<HashMap<i64, f64>, SqliteGraphError> {
+        algo::pagerank(&self.graph, damping, iterations)
+    }
+
+    fn topological_sort(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        algo::topological_sort(&self.graph)
+    }
+
     fn pattern_search(
         &self,
         start: i64,
@@ -168,4 +437,32 @@ impl crate::backend::GraphBackend for SqliteGraphBackend {
     ) -> Result<Vec<PatternMatch>, SqliteGraphError> {
         pattern::execute_pattern(&self.graph, start, pattern)
     }
+
+    fn statistics(&self) -> Result<GraphStatistics, SqliteGraphError> {
+        self.graph.statistics()
+    }
+
+    fn cache_stats(&self) -> CacheStats {
+        crate::graph_opt::cache_stats(&self.graph)
+    }
+
+    fn reset_cache_stats(&self) {
+        self.graph.outgoing_cache_ref().reset_stats();
+        self.graph.incoming_cache_ref().reset_stats();
+    }
+
+    fn validate_integrity(&self) -> Result<SafetyReport, SqliteGraphError> {
+        Ok(SafetyReport {
+            dangling_edges: self.graph.live_dangling_edges()?,
+            ..SafetyReport::default()
+        })
+    }
+
+    fn schema_version(&self) -> Result<u32, SqliteGraphError> {
+        self.graph.backend_schema_version()
+    }
+
+    fn check_compatibility(&self) -> Result<(), SqliteGraphError> {
+        self.graph.check_compatibility()
+    }
 }
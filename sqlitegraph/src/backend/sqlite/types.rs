@@ -5,11 +5,26 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::graph::GraphEdge;
+
 /// Direction specification for graph traversal operations.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BackendDirection {
     Outgoing,
     Incoming,
+    /// Follow edges regardless of their stored orientation (undirected traversal).
+    Both,
+}
+
+/// Merge outgoing and incoming neighbor lists for [`BackendDirection::Both`].
+///
+/// The result is sorted and deduplicated, so a node linked in both orientations
+/// (or by parallel edges) appears once.
+pub(crate) fn merge_undirected(mut outgoing: Vec<i64>, incoming: Vec<i64>) -> Vec<i64> {
+    outgoing.extend(incoming);
+    outgoing.sort_unstable();
+    outgoing.dedup();
+    outgoing
 }
 
 /// Query configuration for neighbor lookups with optional filtering.
@@ -45,3 +60,19 @@ pub struct EdgeSpec {
     pub edge_type: String,
     pub data: serde_json::Value,
 }
+
+/// All edges touching a node, as returned by `GraphBackend::node_edges`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeEdges {
+    /// Edges whose source is the node, ordered by target then edge id.
+    pub outgoing: Vec<GraphEdge>,
+    /// Edges whose target is the node, ordered by source then edge id.
+    pub incoming: Vec<GraphEdge>,
+}
+
+impl NodeEdges {
+    /// `(outgoing, incoming)` degree, matching `GraphBackend::node_degree`.
+    pub fn degree(&self) -> (usize, usize) {
+        (self.outgoing.len(), self.incoming.len())
+    }
+}
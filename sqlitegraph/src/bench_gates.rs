@@ -56,9 +56,8 @@ pub fn record_bench_run(name: &str, metric: BenchMetric) -> Result<(), SqliteGra
     runs.retain(|m| m.name != name);
     runs.push(metric);
     runs.sort_by(|a, b| a.name.cmp(&b.name));
-    let data = serde_json::to_vec_pretty(&runs)
-        .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
-    fs::write(path, data).map_err(|e| SqliteGraphError::query(e.to_string()))
+    let data = serde_json::to_vec_pretty(&runs).map_err(SqliteGraphError::invalid_input)?;
+    fs::write(path, data).map_err(SqliteGraphError::query)
 }
 
 pub fn check_thresholds(
@@ -70,7 +69,7 @@ pub fn check_thresholds(
     let metric = runs
         .into_iter()
         .find(|m| m.name == name)
-        .ok_or_else(|| SqliteGraphError::not_found(format!("bench metric {name}")))?;
+        .ok_or_else(|| SqliteGraphError::invalid_input(format!("no bench metric {name}")))?;
     if metric.ops_per_sec < threshold.min_ops_per_sec {
         return Ok(BenchGateResult::Fail {
             reason: format!(
@@ -106,7 +105,7 @@ pub fn compare_to_baseline(
     let baseline = load_runs_from(&path)?
         .into_iter()
         .find(|m| m.name == name)
-        .ok_or_else(|| SqliteGraphError::not_found(format!("baseline {name}")))?;
+        .ok_or_else(|| SqliteGraphError::invalid_input(format!("no baseline {name}")))?;
     let delta = current.ops_per_sec - baseline.ops_per_sec;
     Ok(BenchComparison {
         name: name.to_string(),
@@ -131,9 +130,9 @@ fn load_runs_from(path: &Path) -> Result<Vec<BenchMetric>, SqliteGraphError> {
     if !path.exists() {
         return Ok(Vec::new());
     }
-    let data = fs::read(path).map_err(|e| SqliteGraphError::query(e.to_string()))?;
+    let data = fs::read(path).map_err(SqliteGraphError::query)?;
     if data.is_empty() {
         return Ok(Vec::new());
     }
-    serde_json::from_slice(&data).map_err(|e| SqliteGraphError::invalid_input(e.to_string()))
+    serde_json::from_slice(&data).map_err(SqliteGraphError::invalid_input)
 }
@@ -2,13 +2,90 @@ use std::collections::VecDeque;
 
 use ahash::{AHashMap, AHashSet};
 
-use crate::{errors::SqliteGraphError, graph::SqliteGraph};
+use crate::{backend::BackendDirection, errors::SqliteGraphError, graph::SqliteGraph};
 
 pub fn bfs_neighbors(
     graph: &SqliteGraph,
     start: i64,
     max_depth: u32,
 ) -> Result<Vec<i64>, SqliteGraphError> {
+    bfs_neighbors_directed(graph, start, max_depth, BackendDirection::Outgoing)
+}
+
+/// Breadth-first traversal following edges in `direction`.
+///
+/// With `BackendDirection::Both` edges are followed regardless of their stored
+/// orientation, which treats the graph as undirected.
+pub fn bfs_neighbors_directed(
+    graph: &SqliteGraph,
+    start: i64,
+    max_depth: u32,
+    direction: BackendDirection,
+) -> Result<Vec<i64>, SqliteGraphError> {
+    bfs_with(graph, start, max_depth, |node| {
+        graph.fetch_neighbors(node, direction)
+    })
+}
+
+/// Outgoing breadth-first traversal that only follows edges whose type is in
+/// `edge_types`, visiting nodes in the same order as [`bfs_neighbors`].
+///
+/// An empty `edge_types` follows no edge, so the result is just `start`.
+pub fn bfs_neighbors_filtered(
+    graph: &SqliteGraph,
+    start: i64,
+    max_depth: u32,
+    edge_types: &[&str],
+) -> Result<Vec<i64>, SqliteGraphError> {
+    let allowed: AHashSet<&str> = edge_types.iter().copied().collect();
+    bfs_with(graph, start, max_depth, |node| {
+        typed_outgoing(graph, node, &allowed)
+    })
+}
+
+/// Live outgoing neighbors of `node` over edges whose type is in `allowed`,
+/// ordered like [`SqliteGraph::fetch_outgoing`].
+fn typed_outgoing(
+    graph: &SqliteGraph,
+    node: i64,
+    allowed: &AHashSet<&str>,
+) -> Result<Vec<i64>, SqliteGraphError> {
+    if allowed.is_empty() {
+        return Ok(Vec::new());
+    }
+    let conn = graph.connection();
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT to_id, edge_type FROM graph_edges WHERE from_id=?1 \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=to_id) \
+             ORDER BY to_id, edge_type, id",
+        )
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([node], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(SqliteGraphError::query)?;
+    let mut neighbors = Vec::new();
+    for row in rows {
+        let (neighbor, edge_type) = row.map_err(SqliteGraphError::query)?;
+        if allowed.contains(edge_type.as_str()) {
+            neighbors.push(neighbor);
+        }
+    }
+    Ok(neighbors)
+}
+
+/// Breadth-first traversal from `start` expanding each node through `neighbors`.
+fn bfs_with<F>(
+    graph: &SqliteGraph,
+    start: i64,
+    max_depth: u32,
+    mut neighbors: F,
+) -> Result<Vec<i64>, SqliteGraphError>
+where
+    F: FnMut(i64) -> Result<Vec<i64>, SqliteGraphError>,
+{
     graph.get_entity(start)?;
     let mut visited = Vec::new();
     let mut seen = AHashSet::new();
@@ -20,7 +97,7 @@ pub fn bfs_neighbors(
         if depth >= max_depth {
             continue;
         }
-        for next in graph.fetch_outgoing(node)? {
+        for next in neighbors(node)? {
             if seen.insert(next) {
                 queue.push_back((next, depth + 1));
             }
@@ -29,11 +106,133 @@ pub fn bfs_neighbors(
     Ok(visited)
 }
 
+/// Lazy outgoing breadth-first traversal from `start`.
+///
+/// Yields the same nodes in the same order as [`bfs_neighbors`], but neighbors
+/// are only fetched when the next node is needed, so dropping the iterator early
+/// skips the adjacency queries for the rest of the graph.
+pub fn bfs_iter(
+    graph: &SqliteGraph,
+    start: i64,
+    max_depth: u32,
+) -> Result<BfsIterator<'_>, SqliteGraphError> {
+    graph.get_entity(start)?;
+    let mut seen = AHashSet::new();
+    seen.insert(start);
+    Ok(BfsIterator {
+        graph,
+        max_depth,
+        direction: BackendDirection::Outgoing,
+        queue: VecDeque::from([(start, 0)]),
+        unexpanded: VecDeque::new(),
+        seen,
+        failed: false,
+    })
+}
+
+/// Iterator returned by [`bfs_iter`].
+///
+/// Yielded nodes are expanded only once the queue of discovered nodes runs dry,
+/// which preserves BFS order while deferring each layer's neighbor fetches.
+pub struct BfsIterator<'g> {
+    graph: &'g SqliteGraph,
+    max_depth: u32,
+    direction: BackendDirection,
+    queue: VecDeque<(i64, u32)>,
+    unexpanded: VecDeque<(i64, u32)>,
+    seen: AHashSet<i64>,
+    failed: bool,
+}
+
+impl Iterator for BfsIterator<'_> {
+    type Item = Result<i64, SqliteGraphError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        while self.queue.is_empty() {
+            let (node, depth) = self.unexpanded.pop_front()?;
+            let neighbors = match self.graph.fetch_neighbors(node, self.direction) {
+                Ok(neighbors) => neighbors,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            };
+            for next in neighbors {
+                if self.seen.insert(next) {
+                    self.queue.push_back((next, depth + 1));
+                }
+            }
+        }
+        let (node, depth) = self.queue.pop_front()?;
+        if depth < self.max_depth {
+            self.unexpanded.push_back((node, depth));
+        }
+        Some(Ok(node))
+    }
+}
+
+/// Shortest outgoing path from `start` to `end`.
+///
+/// The search runs from both ends through
+/// [`crate::algo::bidirectional_shortest_path`] and returns the same result as
+/// [`shortest_path_directed`] with [`BackendDirection::Outgoing`], including a
+/// [`SqliteGraphError::NotFound`] error when either endpoint is missing.
 pub fn shortest_path(
     graph: &SqliteGraph,
     start: i64,
     end: i64,
 ) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+    graph.get_entity(start)?;
+    graph.get_entity(end)?;
+    crate::algo::bidirectional_shortest_path(graph, start, end)
+}
+
+/// Shortest path from `start` to `end` following edges in `direction`.
+///
+/// A node's path to itself is `Some(vec![start])`. When several paths share the
+/// minimum length, the lexicographically smallest node-id sequence wins: the BFS
+/// expands neighbors in ascending id order and keeps the first parent found.
+pub fn shortest_path_directed(
+    graph: &SqliteGraph,
+    start: i64,
+    end: i64,
+    direction: BackendDirection,
+) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+    shortest_path_with(graph, start, end, |node| {
+        graph.fetch_neighbors(node, direction)
+    })
+}
+
+/// Shortest outgoing path from `start` to `end` using only edges whose type is
+/// in `edge_types`, with the tie-break rule of [`shortest_path_directed`].
+///
+/// Returns `None` when no such path exists, even if one over other edge types
+/// does. An empty `edge_types` only finds the path from a node to itself.
+pub fn shortest_path_filtered(
+    graph: &SqliteGraph,
+    start: i64,
+    end: i64,
+    edge_types: &[&str],
+) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+    let allowed: AHashSet<&str> = edge_types.iter().copied().collect();
+    shortest_path_with(graph, start, end, |node| {
+        typed_outgoing(graph, node, &allowed)
+    })
+}
+
+/// BFS shortest path from `start` to `end` expanding each node through `neighbors`.
+fn shortest_path_with<F>(
+    graph: &SqliteGraph,
+    start: i64,
+    end: i64,
+    mut neighbors: F,
+) -> Result<Option<Vec<i64>>, SqliteGraphError>
+where
+    F: FnMut(i64) -> Result<Vec<i64>, SqliteGraphError>,
+{
     graph.get_entity(start)?;
     graph.get_entity(end)?;
     if start == end {
@@ -46,7 +245,8 @@ pub fn shortest_path(
     seen.insert(start);
     let mut found = false;
     while let Some(node) = queue.pop_front() {
-        for next in graph.fetch_outgoing(node)? {
+        // Adjacency lists are already sorted by neighbor id; see the tie-break rule above.
+        for next in neighbors(node)? {
             if seen.insert(next) {
                 parents.insert(next, node);
                 if next == end {
@@ -40,16 +40,38 @@ impl AdjacencyCache {
         self.inner.write().insert(key, value);
     }
 
+    /// Insert `neighbor` into the cached list for `key`, keeping it sorted.
+    ///
+    /// Keys that are not cached are left untouched so the next read still goes
+    /// to the database; hit and miss counters are not affected.
+    pub fn insert_neighbor(&self, key: i64, neighbor: i64) {
+        if let Some(neighbors) = self.inner.write().get_mut(&key) {
+            let pos = neighbors.partition_point(|&existing| existing <= neighbor);
+            neighbors.insert(pos, neighbor);
+        }
+    }
+
     pub fn clear(&self) {
         self.inner.write().clear();
         self.hits.store(0, Ordering::Relaxed);
         self.misses.store(0, Ordering::Relaxed);
     }
 
+    /// Zero the hit and miss counters without evicting entries.
+    pub fn reset_stats(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
     pub fn remove(&self, key: i64) {
         self.inner.write().remove(&key);
     }
 
+    /// Remove and return the cached list for `key` without touching hit and miss counters.
+    pub fn take(&self, key: i64) -> Option<Vec<i64>> {
+        self.inner.write().remove(&key)
+    }
+
     pub fn stats(&self) -> CacheStats {
         let entries = self.inner.read().len();
         CacheStats {
@@ -7,8 +7,12 @@
 use std::collections::HashMap;
 use std::path::Path;
 
+use rusqlite::OptionalExtension;
+
 use crate::SqliteGraphError;
+use crate::backend::native::FlushPolicy;
 use crate::backend::{GraphBackend, NativeGraphBackend, SqliteGraphBackend};
+use crate::data_format::DataFormat;
 
 /// Backend selection enum for choosing between storage implementations.
 ///
@@ -102,8 +106,8 @@ pub struct NativeConfig {
     /// **Default:** `None`
     ///
     /// When set to `Some(capacity)`, this provides a hint to the native backend
-    /// about the expected number of nodes. This can improve performance by reducing
-    /// the number of memory reallocations during bulk insertions.
+    /// about the expected number of nodes. The file is extended up front, see
+    /// [`NativeGraphBackend::reserve`], so bulk insertions resize it less often.
     ///
     /// **Note:** This is a performance hint and not a hard limit. The backend will
     /// automatically grow beyond this capacity if needed.
@@ -124,8 +128,8 @@ pub struct NativeConfig {
     /// **Default:** `None`
     ///
     /// When set to `Some(capacity)`, this provides a hint to the native backend
-    /// about the expected number of edges. This can improve performance by reducing
-    /// the number of memory reallocations during bulk insertions.
+    /// about the expected number of edges. The file is extended up front, see
+    /// [`NativeGraphBackend::reserve`], so bulk insertions resize it less often.
     ///
     /// **Note:** This is a performance hint and not a hard limit. The backend will
     /// automatically grow beyond this capacity if needed.
@@ -140,6 +144,55 @@ pub struct NativeConfig {
     /// let graph = open_graph("dense_graph.db", &cfg)?;
     /// ```
     pub reserve_edge_capacity: Option<usize>,
+
+    /// How often the file header is persisted after inserts
+    ///
+    /// **Default:** [`FlushPolicy::EveryWrite`]
+    ///
+    /// The header records how many nodes and edges the file holds, so inserts made
+    /// after the last header write are not visible after the process crashes:
+    ///
+    /// - `EveryWrite` persists after each insert: no acknowledged insert is lost,
+    ///   but every insert pays for a header write.
+    /// - `EveryN(n)` persists after every `n` inserts: bulk loads get much faster
+    ///   and a crash loses at most `n - 1` inserts.
+    /// - `Manual` persists only on [`NativeGraphBackend::flush`] or drop: fastest,
+    ///   but everything since the last flush is lost on crash.
+    ///
+    /// None of the policies sync the file, so surviving a power loss or OS crash
+    /// still takes an explicit [`NativeGraphBackend::flush`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sqlitegraph::{FlushPolicy, GraphConfig, open_graph};
+    ///
+    /// let mut cfg = GraphConfig::native();
+    /// cfg.native.flush_policy = FlushPolicy::EveryN(1000);
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let graph = open_graph(dir.path().join("bulk_graph.db"), &cfg).unwrap();
+    /// ```
+    pub flush_policy: FlushPolicy,
+
+    /// Encoding of node and edge `data` payloads for newly created files
+    ///
+    /// **Default:** `None` (JSON for new files, whatever the header records for existing ones)
+    ///
+    /// The format is stored in the file header and a file uses a single format,
+    /// so `Some(format)` must match the header of an existing non-empty file or
+    /// [`open_graph`] fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sqlitegraph::{DataFormat, GraphConfig, open_graph};
+    ///
+    /// let mut cfg = GraphConfig::native();
+    /// cfg.native.data_format = Some(DataFormat::MessagePack);
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let graph = open_graph(dir.path().join("compact.db"), &cfg).unwrap();
+    /// ```
+    pub data_format: Option<DataFormat>,
 }
 
 impl Default for NativeConfig {
@@ -148,6 +201,8 @@ impl Default for NativeConfig {
             create_if_missing: true, // Default: create files if they don't exist
             reserve_node_capacity: None,
             reserve_edge_capacity: None,
+            flush_policy: FlushPolicy::default(),
+            data_format: None,
         }
     }
 }
@@ -193,13 +248,16 @@ pub struct SqliteConfig {
 
     /// Optional cache size for prepared statements
     ///
-    /// **Default:** `None`
+    /// **Default:** `None`, which keeps up to 128 statements
     ///
     /// When set to `Some(size)`, configures the SQLite prepared statement cache to
-    /// hold the specified number of cached statements. This can improve performance for
-    /// repetitive queries by avoiding SQL statement recompilation.
+    /// hold the specified number of cached statements, evicting the least recently
+    /// used beyond that. This can improve performance for repetitive queries by
+    /// avoiding SQL statement recompilation; `Some(0)` disables the cache.
+    /// [`SqliteGraph::statement_cache_len`](crate::SqliteGraph::statement_cache_len)
+    /// reports how many statements are cached.
     ///
-    /// **Note:** SQLite's default cache size is typically sufficient for most workloads.
+    /// **Note:** The default cache size is typically sufficient for most workloads.
     /// Only modify this if you have evidence of statement compilation overhead.
     ///
     /// # Examples
@@ -226,6 +284,10 @@ pub struct SqliteConfig {
     /// - `cache_size`: Configure SQLite page cache size
     /// - `temp_store`: Set temp storage location ("MEMORY", "FILE")
     ///
+    /// Each PRAGMA is read back after it is set. Unknown PRAGMA names and values
+    /// SQLite silently ignored make [`open_graph`] fail with
+    /// [`SqliteGraphError::InvalidInput`] naming the PRAGMA.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -244,8 +306,182 @@ pub struct SqliteConfig {
     /// let graph = open_graph("optimized.db", &cfg)?;
     /// ```
     pub pragma_settings: HashMap<String, String>,
+
+    /// Optional upper bound on the serialized size of `data` payloads
+    ///
+    /// **Default:** `None` (unlimited)
+    ///
+    /// When set to `Some(bytes)`, node and edge inserts and updates whose JSON
+    /// `data` serializes to more than `bytes` bytes are rejected with
+    /// [`SqliteGraphError::InvalidInput`] reporting the actual size. This mirrors
+    /// the per-record `MAX_DATA_LENGTH` guard of the native backend.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sqlitegraph::{GraphConfig, NodeSpec, open_graph};
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let mut cfg = GraphConfig::sqlite();
+    /// cfg.sqlite.max_data_bytes = Some(64);
+    /// let graph = open_graph(dir.path().join("capped.db"), &cfg).unwrap();
+    ///
+    /// let oversized = NodeSpec {
+    ///     kind: "Blob".into(),
+    ///     name: "big".into(),
+    ///     file_path: None,
+    ///     data: serde_json::json!({ "payload": "x".repeat(128) }),
+    /// };
+    /// assert!(graph.insert_node(oversized).is_err());
+    /// ```
+    pub max_data_bytes: Option<usize>,
+
+    /// Defer edge endpoint validation until [`SqliteGraph::check_integrity`]
+    ///
+    /// **Default:** `false`
+    ///
+    /// When set to `true`, `insert_edge` accepts edges whose `from`/`to` entities
+    /// do not exist yet, so edges may be streamed in before their nodes. Call
+    /// `check_integrity()` on the underlying graph once loading finishes to list
+    /// any edges that are still dangling.
+    ///
+    /// **Note:** Only the SQLite backend supports deferral; the native backend
+    /// always validates endpoints because its adjacency metadata lives on the
+    /// node records.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sqlitegraph::{GraphEdge, GraphEntity, SqliteGraph};
+    ///
+    /// let mut graph = SqliteGraph::open_in_memory().unwrap();
+    /// graph.set_deferred_integrity(true);
+    /// graph
+    ///     .insert_edge(&GraphEdge {
+    ///         id: 0,
+    ///         from_id: 1,
+    ///         to_id: 2,
+    ///         edge_type: "LINK".into(),
+    ///         data: serde_json::json!({}),
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(graph.check_integrity().unwrap().dangling_edges.len(), 1);
+    /// ```
+    ///
+    /// [`SqliteGraph::check_integrity`]: crate::SqliteGraph::check_integrity
+    pub deferred_integrity: bool,
+
+    /// Encoding of `data` payloads written through this graph
+    ///
+    /// **Default:** `None` (keep the format persisted in the database, JSON if unset)
+    ///
+    /// `Some(format)` switches the format and persists the choice, so later opens
+    /// keep it. JSON payloads are stored as TEXT and MessagePack payloads as BLOB,
+    /// which lets rows written before a switch keep decoding. SQL-level JSON
+    /// functions such as `json_extract` only see JSON rows.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sqlitegraph::{DataFormat, GraphConfig, NodeSpec, open_graph};
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let mut cfg = GraphConfig::sqlite();
+    /// cfg.sqlite.data_format = Some(DataFormat::MessagePack);
+    /// let graph = open_graph(dir.path().join("compact.db"), &cfg).unwrap();
+    ///
+    /// let id = graph
+    ///     .insert_node(NodeSpec {
+    ///         kind: "Doc".into(),
+    ///         name: "readme".into(),
+    ///         file_path: None,
+    ///         data: serde_json::json!({ "words": 120 }),
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(graph.get_node(id).unwrap().data["words"], 120);
+    /// ```
+    pub data_format: Option<DataFormat>,
+
+    /// Open an existing database without write access
+    ///
+    /// **Default:** `false`
+    ///
+    /// When set to `true`, the connection is opened with `SQLITE_OPEN_READONLY`,
+    /// which makes it safe to attach to a file another process is writing.
+    /// Migrations are skipped, as are PRAGMAs that would have to write to the
+    /// file (such as `journal_mode`), and every mutating call fails with
+    /// [`SqliteGraphError::ReadOnly`]. The database must already exist.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sqlitegraph::{GraphConfig, NodeSpec, SqliteGraphError, open_graph};
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("shared.db");
+    /// open_graph(&path, &GraphConfig::sqlite()).unwrap();
+    ///
+    /// let mut cfg = GraphConfig::sqlite();
+    /// cfg.sqlite.read_only = true;
+    /// let graph = open_graph(&path, &cfg).unwrap();
+    /// let node = NodeSpec {
+    ///     kind: "Doc".into(),
+    ///     name: "readme".into(),
+    ///     file_path: None,
+    ///     data: serde_json::json!({}),
+    /// };
+    /// assert!(matches!(graph.insert_node(node), Err(SqliteGraphError::ReadOnly(_))));
+    /// ```
+    pub read_only: bool,
+
+    /// How long a statement waits for a lock held by another connection
+    ///
+    /// **Default:** `None` (5000 ms when the database ends up in WAL mode, as
+    /// file databases do unless `pragma_settings` picks another journal mode)
+    ///
+    /// While another connection holds the write lock, statements retry for up
+    /// to this many milliseconds before failing with `SQLITE_BUSY`.
+    /// `Some(0)` disables waiting. Applied after `pragma_settings`, so it takes
+    /// precedence over a `busy_timeout` entry there; with `None`, such an entry
+    /// is kept instead of the WAL default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sqlitegraph::{GraphConfig, open_graph};
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let mut cfg = GraphConfig::sqlite();
+    /// cfg.sqlite.busy_timeout_ms = Some(10_000);
+    /// let graph = open_graph(dir.path().join("shared.db"), &cfg).unwrap();
+    /// ```
+    pub busy_timeout_ms: Option<u64>,
+
+    /// Number of WAL pages after which a commit checkpoints automatically
+    ///
+    /// **Default:** `None` (SQLite's default of 1000 pages)
+    ///
+    /// Larger values make write bursts cheaper at the cost of a bigger `-wal`
+    /// file; `Some(0)` turns automatic checkpoints off. Applied after
+    /// `pragma_settings`, so it takes precedence over a `wal_autocheckpoint`
+    /// entry there.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sqlitegraph::{GraphConfig, open_graph};
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let mut cfg = GraphConfig::sqlite();
+    /// cfg.sqlite.wal_autocheckpoint = Some(4000);
+    /// let graph = open_graph(dir.path().join("bursty.db"), &cfg).unwrap();
+    /// ```
+    pub wal_autocheckpoint: Option<u32>,
 }
 
+/// Busy timeout applied to WAL databases when [`SqliteConfig::busy_timeout_ms`] is unset.
+const DEFAULT_WAL_BUSY_TIMEOUT_MS: u64 = 5000;
+
 /// Complete configuration for graph construction.
 ///
 /// This structure combines backend selection with backend-specific
@@ -312,6 +548,56 @@ pub struct GraphConfig {
     /// When using the SQLite backend, these settings are ignored but still
     /// available for configuration consistency when switching backends.
     pub native: NativeConfig,
+
+    /// Reject edges whose source and target are the same node
+    ///
+    /// **Default:** `false`
+    ///
+    /// Applies to both backends. When enabled, `insert_edge` fails with
+    /// [`SqliteGraphError::InvalidInput`] for an edge with `from == to`.
+    /// Edges already stored are not checked; audit them with
+    /// [`crate::safety::validate_no_self_loops`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sqlitegraph::{EdgeSpec, GraphConfig, NodeSpec, open_graph};
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let mut cfg = GraphConfig::sqlite();
+    /// cfg.reject_self_loops = true;
+    /// let graph = open_graph(dir.path().join("acyclic.db"), &cfg).unwrap();
+    ///
+    /// let id = graph
+    ///     .insert_node(NodeSpec {
+    ///         kind: "Task".into(),
+    ///         name: "build".into(),
+    ///         file_path: None,
+    ///         data: serde_json::json!({}),
+    ///     })
+    ///     .unwrap();
+    /// let self_loop = EdgeSpec {
+    ///     from: id,
+    ///     to: id,
+    ///     edge_type: "depends_on".into(),
+    ///     data: serde_json::json!({}),
+    /// };
+    /// assert!(graph.insert_edge(self_loop).is_err());
+    /// ```
+    pub reject_self_loops: bool,
+
+    /// Reject edges whose `from` or `to` node does not exist
+    ///
+    /// **Default:** `true`
+    ///
+    /// When enabled, `insert_edge` and `upsert_edge` fail with
+    /// [`SqliteGraphError::NotFound`] for the first missing or deleted endpoint
+    /// on both backends. Turning it off on the SQLite backend has the same
+    /// effect as [`SqliteConfig::deferred_integrity`]. The native backend keeps
+    /// adjacency on the node records and cannot store such edges, so
+    /// [`open_graph`] fails with [`SqliteGraphError::InvalidInput`] when this is
+    /// turned off for it.
+    pub enforce_referential_integrity: bool,
 }
 
 impl GraphConfig {
@@ -336,6 +622,8 @@ impl GraphConfig {
             backend,
             sqlite: sqlite_config,
             native: native_config,
+            reject_self_loops: false,
+            enforce_referential_integrity: true,
         }
     }
 
@@ -392,55 +680,289 @@ pub fn open_graph<P: AsRef<Path>>(
     cfg: &GraphConfig,
 ) -> Result<Box<dyn GraphBackend>, SqliteGraphError> {
     match cfg.backend {
-        BackendKind::SQLite => {
-            // Construct SQLite backend with configuration
-            let sqlite_graph = if cfg.sqlite.without_migrations {
-                crate::graph::SqliteGraph::open_without_migrations(&path)?
-            } else {
-                crate::graph::SqliteGraph::open(&path)?
-            };
-
-            // Apply PRAGMA settings if provided
-            for (key, value) in &cfg.sqlite.pragma_settings {
-                let pragma_sql = format!("PRAGMA {} = {}", key, value);
-                match sqlite_graph.conn.execute(&pragma_sql, []) {
-                    Ok(_) => {} // PRAGMA executed successfully
-                    Err(rusqlite::Error::ExecuteReturnedResults) => {
-                        // Some PRAGMAs return results - that's fine, just ignore them
-                    }
-                    Err(e) => {
-                        return Err(SqliteGraphError::connection(format!(
-                            "PRAGMA {} = {}: {}",
-                            key, value, e
-                        )));
-                    }
-                }
-            }
-
-            Ok(Box::new(SqliteGraphBackend::from_graph(sqlite_graph)))
-        }
+        BackendKind::SQLite => Ok(Box::new(SqliteGraphBackend::from_graph(open_sqlite_graph(
+            path, cfg,
+        )?))),
         BackendKind::Native => {
+            check_native_config(cfg)?;
             // Construct Native backend with configuration
-            let mut native_graph = if cfg.native.create_if_missing {
+            let native_graph = if cfg.native.create_if_missing {
                 crate::backend::NativeGraphBackend::new(&path)?
             } else {
                 crate::backend::NativeGraphBackend::open(&path)?
             };
+            Ok(Box::new(configure_native_graph(native_graph, cfg)?))
+        }
+    }
+}
 
-            // Apply capacity pre-allocation if requested
-            if let Some(node_capacity) = cfg.native.reserve_node_capacity {
-                // Note: Native backend doesn't currently expose capacity pre-allocation
-                // This would require extending the NativeGraphBackend API
-                // For now, we store this for potential future optimization
+/// Open an empty in-memory graph of the backend selected by `cfg.backend`.
+///
+/// The same options as [`open_graph`] are applied, except the ones that only
+/// make sense for a file: `native.create_if_missing` is ignored, and
+/// `sqlite.read_only` is rejected since an empty read-only graph is useless.
+/// Nothing is persisted once the returned backend is dropped.
+///
+/// # Examples
+/// ```rust
+/// use sqlitegraph::{open_graph_in_memory, GraphConfig};
+///
+/// for cfg in [GraphConfig::sqlite(), GraphConfig::native()] {
+///     let graph = open_graph_in_memory(&cfg)?;
+///     assert!(graph.list_edge_ids()?.is_empty());
+/// }
+/// # Ok::<(), sqlitegraph::SqliteGraphError>(())
+/// ```
+pub fn open_graph_in_memory(cfg: &GraphConfig) -> Result<Box<dyn GraphBackend>, SqliteGraphError> {
+    match cfg.backend {
+        BackendKind::SQLite => {
+            if cfg.sqlite.read_only {
+                return Err(SqliteGraphError::invalid_input(
+                    "read_only is not supported for an in-memory graph",
+                ));
             }
+            let sqlite_graph = if cfg.sqlite.without_migrations {
+                crate::graph::SqliteGraph::open_in_memory_without_migrations()?
+            } else {
+                crate::graph::SqliteGraph::open_in_memory()?
+            };
+            Ok(Box::new(SqliteGraphBackend::from_graph(
+                configure_sqlite_graph(sqlite_graph, cfg)?,
+            )))
+        }
+        BackendKind::Native => {
+            check_native_config(cfg)?;
+            Ok(Box::new(configure_native_graph(
+                crate::backend::NativeGraphBackend::in_memory()?,
+                cfg,
+            )?))
+        }
+    }
+}
 
-            if let Some(edge_capacity) = cfg.native.reserve_edge_capacity {
-                // Note: Same as above - future optimization opportunity
-            }
+/// Reject options the native backend cannot honor, before anything is created.
+fn check_native_config(cfg: &GraphConfig) -> Result<(), SqliteGraphError> {
+    if !cfg.enforce_referential_integrity {
+        return Err(SqliteGraphError::invalid_input(
+            "the native backend always enforces referential integrity",
+        ));
+    }
+    Ok(())
+}
 
-            Ok(Box::new(native_graph))
+fn configure_native_graph(
+    native_graph: crate::backend::NativeGraphBackend,
+    cfg: &GraphConfig,
+) -> Result<crate::backend::NativeGraphBackend, SqliteGraphError> {
+    // Pre-allocate file space for the expected load
+    if cfg.native.reserve_node_capacity.is_some() || cfg.native.reserve_edge_capacity.is_some() {
+        native_graph.reserve(
+            cfg.native.reserve_node_capacity.unwrap_or(0),
+            cfg.native.reserve_edge_capacity.unwrap_or(0),
+        )?;
+    }
+
+    native_graph.set_flush_policy(cfg.native.flush_policy);
+    native_graph.set_reject_self_loops(cfg.reject_self_loops);
+    if let Some(format) = cfg.native.data_format {
+        native_graph.set_data_format(format)?;
+    }
+    Ok(native_graph)
+}
+
+/// PRAGMAs that perform an action instead of holding a value, so they cannot be read back.
+const UNVERIFIABLE_PRAGMAS: &[&str] = &[
+    "case_sensitive_like",
+    "incremental_vacuum",
+    "optimize",
+    "shrink_memory",
+    "wal_checkpoint",
+];
+
+/// Apply the busy timeout and WAL autocheckpoint of `cfg`.
+///
+/// Runs after `pragma_settings` so these fields win over equivalent entries
+/// there. The default busy timeout is only applied when those entries neither
+/// set one nor leave the database outside WAL mode.
+fn apply_lock_settings(
+    conn: &rusqlite::Connection,
+    cfg: &SqliteConfig,
+) -> Result<(), SqliteGraphError> {
+    let timeout_ms = match cfg.busy_timeout_ms {
+        Some(ms) => Some(ms),
+        None if cfg
+            .pragma_settings
+            .keys()
+            .any(|key| key.trim().eq_ignore_ascii_case("busy_timeout")) =>
+        {
+            None
+        }
+        None => {
+            let mode: String = conn
+                .pragma_query_value(None, "journal_mode", |row| row.get(0))
+                .map_err(SqliteGraphError::connection)?;
+            mode.eq_ignore_ascii_case("wal")
+                .then_some(DEFAULT_WAL_BUSY_TIMEOUT_MS)
         }
+    };
+    if let Some(ms) = timeout_ms {
+        conn.busy_timeout(std::time::Duration::from_millis(ms))
+            .map_err(SqliteGraphError::connection)?;
     }
+    if let Some(pages) = cfg.wal_autocheckpoint {
+        apply_pragma(conn, "wal_autocheckpoint", &pages.to_string())?;
+    }
+    Ok(())
+}
+
+/// PRAGMAs whose setting is stored in the database file or that modify it.
+const WRITING_PRAGMAS: &[&str] = &[
+    "application_id",
+    "auto_vacuum",
+    "incremental_vacuum",
+    "journal_mode",
+    "optimize",
+    "page_size",
+    "user_version",
+    "wal_checkpoint",
+];
+
+/// Whether applying PRAGMA `key` needs write access to the database.
+fn writes_to_file(key: &str) -> bool {
+    WRITING_PRAGMAS.contains(&key.trim().to_ascii_lowercase().as_str())
+}
+
+/// Symbolic PRAGMA values and the integers SQLite reports when they are read back.
+const PRAGMA_VALUE_CODES: &[(&str, &str, &str)] = &[
+    ("synchronous", "off", "0"),
+    ("synchronous", "normal", "1"),
+    ("synchronous", "full", "2"),
+    ("synchronous", "extra", "3"),
+    ("temp_store", "default", "0"),
+    ("temp_store", "file", "1"),
+    ("temp_store", "memory", "2"),
+    ("auto_vacuum", "none", "0"),
+    ("auto_vacuum", "full", "1"),
+    ("auto_vacuum", "incremental", "2"),
+];
+
+/// Execute `PRAGMA key = value` and read it back to confirm SQLite applied it.
+///
+/// SQLite ignores unknown PRAGMAs and some values (e.g. `journal_mode=WAL` on an
+/// in-memory database) without reporting an error, so a setting that cannot be
+/// read back or reads back differently is rejected with
+/// [`SqliteGraphError::InvalidInput`] naming the PRAGMA.
+fn apply_pragma(
+    conn: &rusqlite::Connection,
+    key: &str,
+    value: &str,
+) -> Result<(), SqliteGraphError> {
+    let name = key.trim().to_ascii_lowercase();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(SqliteGraphError::invalid_input(format!(
+            "PRAGMA name '{key}' is not a valid identifier"
+        )));
+    }
+    match conn.execute(&format!("PRAGMA {name} = {value}"), []) {
+        // Some PRAGMAs return the new value as a result row
+        Ok(_) | Err(rusqlite::Error::ExecuteReturnedResults) => {}
+        Err(e) => {
+            return Err(SqliteGraphError::invalid_input(format!(
+                "PRAGMA {key} = {value}: {e}"
+            )));
+        }
+    }
+    if UNVERIFIABLE_PRAGMAS.contains(&name.as_str()) {
+        return Ok(());
+    }
+    let actual: Option<String> = conn
+        .query_row(&format!("PRAGMA {name}"), [], |row| {
+            Ok(match row.get_ref(0)? {
+                rusqlite::types::ValueRef::Integer(i) => i.to_string(),
+                rusqlite::types::ValueRef::Real(f) => f.to_string(),
+                rusqlite::types::ValueRef::Text(t) => {
+                    String::from_utf8_lossy(t).to_ascii_lowercase()
+                }
+                _ => String::new(),
+            })
+        })
+        .optional()
+        .map_err(|e| SqliteGraphError::invalid_input(format!("PRAGMA {key}: {e}")))?;
+    let Some(actual) = actual else {
+        return Err(SqliteGraphError::invalid_input(format!(
+            "PRAGMA {key} is not recognized by SQLite"
+        )));
+    };
+    if pragma_value_matches(&name, value, &actual) {
+        Ok(())
+    } else {
+        Err(SqliteGraphError::invalid_input(format!(
+            "PRAGMA {key} = {value} did not take effect (reads back as '{actual}')"
+        )))
+    }
+}
+
+/// Open the SQLite graph at `path` with every SQLite setting of `cfg` applied.
+fn open_sqlite_graph<P: AsRef<Path>>(
+    path: P,
+    cfg: &GraphConfig,
+) -> Result<crate::graph::SqliteGraph, SqliteGraphError> {
+    let sqlite_graph = if cfg.sqlite.read_only {
+        crate::graph::SqliteGraph::open_read_only(&path)?
+    } else if cfg.sqlite.without_migrations {
+        crate::graph::SqliteGraph::open_without_migrations(&path)?
+    } else {
+        crate::graph::SqliteGraph::open(&path)?
+    };
+    configure_sqlite_graph(sqlite_graph, cfg)
+}
+
+fn configure_sqlite_graph(
+    mut sqlite_graph: crate::graph::SqliteGraph,
+    cfg: &GraphConfig,
+) -> Result<crate::graph::SqliteGraph, SqliteGraphError> {
+    sqlite_graph.set_max_data_bytes(cfg.sqlite.max_data_bytes);
+    sqlite_graph.set_deferred_integrity(
+        cfg.sqlite.deferred_integrity || !cfg.enforce_referential_integrity,
+    );
+    sqlite_graph.set_reject_self_loops(cfg.reject_self_loops);
+    if let Some(format) = cfg.sqlite.data_format {
+        sqlite_graph.set_data_format(format)?;
+    }
+    if let Some(capacity) = cfg.sqlite.cache_size {
+        sqlite_graph.set_statement_cache_capacity(capacity);
+    }
+
+    // Apply PRAGMA settings if provided, verifying each one took effect
+    for (key, value) in &cfg.sqlite.pragma_settings {
+        if cfg.sqlite.read_only && writes_to_file(key) {
+            continue;
+        }
+        apply_pragma(&sqlite_graph.conn, key, value)?;
+    }
+    apply_lock_settings(&sqlite_graph.conn, &cfg.sqlite)?;
+    Ok(sqlite_graph)
+}
+
+fn pragma_value_matches(name: &str, requested: &str, actual: &str) -> bool {
+    let requested = requested
+        .trim()
+        .trim_matches(|c| c == '\'' || c == '"')
+        .to_ascii_lowercase();
+    if requested == actual {
+        return true;
+    }
+    let code = match requested.as_str() {
+        "on" | "true" | "yes" => Some("1"),
+        "off" | "false" | "no" => Some("0"),
+        _ => None,
+    };
+    let code = PRAGMA_VALUE_CODES
+        .iter()
+        .find(|(pragma, symbol, _)| *pragma == name && *symbol == requested)
+        .map(|(_, _, code)| *code)
+        .or(code);
+    code == Some(actual)
 }
 
 #[cfg(test)]
@@ -455,9 +977,17 @@ mod tests {
         assert!(!cfg.sqlite.without_migrations);
         assert!(cfg.sqlite.cache_size.is_none());
         assert!(cfg.sqlite.pragma_settings.is_empty());
+        assert!(cfg.sqlite.max_data_bytes.is_none());
+        assert!(!cfg.sqlite.deferred_integrity);
+        assert!(cfg.sqlite.data_format.is_none());
+        assert!(!cfg.sqlite.read_only);
+        assert!(cfg.sqlite.busy_timeout_ms.is_none());
+        assert!(cfg.sqlite.wal_autocheckpoint.is_none());
         assert!(cfg.native.create_if_missing);
         assert!(cfg.native.reserve_node_capacity.is_none());
         assert!(cfg.native.reserve_edge_capacity.is_none());
+        assert_eq!(cfg.native.flush_policy, FlushPolicy::EveryWrite);
+        assert!(cfg.native.data_format.is_none());
     }
 
     #[test]
@@ -520,4 +1050,312 @@ mod tests {
         assert!(result.is_ok());
         assert!(db_path.exists());
     }
+
+    #[test]
+    fn test_sqlite_config_rejects_pragmas_that_do_not_apply() {
+        let temp_dir = tempdir().unwrap();
+        let cases = [
+            ("journal_mod", "WAL", "not recognized"),
+            ("synchronous", "SOMETIMES", "did not take effect"),
+            (
+                "cache_size; DROP TABLE graph_entities",
+                "1",
+                "not a valid identifier",
+            ),
+        ];
+        for (i, (key, value, message)) in cases.into_iter().enumerate() {
+            let mut cfg = GraphConfig::sqlite();
+            cfg.sqlite
+                .pragma_settings
+                .insert(key.to_string(), value.to_string());
+            let err = open_graph(temp_dir.path().join(format!("bad_{i}.db")), &cfg)
+                .err()
+                .expect("pragma should be rejected");
+            assert!(matches!(err, SqliteGraphError::InvalidInput { .. }));
+            let text = err.to_string();
+            assert!(text.contains(key) && text.contains(message), "{text}");
+        }
+
+        let mut cfg = GraphConfig::sqlite();
+        cfg.sqlite
+            .pragma_settings
+            .insert("temp_store".to_string(), "MEMORY".to_string());
+        cfg.sqlite
+            .pragma_settings
+            .insert("foreign_keys".to_string(), "ON".to_string());
+        assert!(open_graph(temp_dir.path().join("good.db"), &cfg).is_ok());
+    }
+
+    #[test]
+    fn test_sqlite_config_max_data_bytes() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test_max_data.db");
+
+        let mut cfg = GraphConfig::sqlite();
+        cfg.sqlite.max_data_bytes = Some(32);
+        let graph = open_graph(&db_path, &cfg).unwrap();
+
+        let small = crate::backend::NodeSpec {
+            kind: "Node".to_string(),
+            name: "small".to_string(),
+            file_path: None,
+            data: serde_json::json!({"ok": true}),
+        };
+        assert!(graph.insert_node(small).is_ok());
+
+        let large = crate::backend::NodeSpec {
+            kind: "Node".to_string(),
+            name: "large".to_string(),
+            file_path: None,
+            data: serde_json::json!({"blob": "x".repeat(64)}),
+        };
+        match graph.insert_node(large) {
+            Err(SqliteGraphError::InvalidInput { message, .. }) => {
+                assert!(
+                    message.contains("75 bytes"),
+                    "unexpected message: {message}"
+                );
+                assert!(
+                    message.contains("32 bytes"),
+                    "unexpected message: {message}"
+                );
+            }
+            other => panic!("expected InvalidInput, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_sqlite_config_deferred_integrity() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test_deferred.db");
+
+        let edge = || crate::backend::EdgeSpec {
+            from: 1,
+            to: 2,
+            edge_type: "LINK".to_string(),
+            data: serde_json::json!({}),
+        };
+
+        let strict = open_graph(
+            temp_dir.path().join("test_strict.db"),
+            &GraphConfig::sqlite(),
+        )
+        .unwrap();
+        assert!(strict.insert_edge(edge()).is_err());
+
+        let mut cfg = GraphConfig::sqlite();
+        cfg.sqlite.deferred_integrity = true;
+        let graph = open_graph(&db_path, &cfg).unwrap();
+        assert!(graph.insert_edge(edge()).is_ok());
+    }
+
+    #[test]
+    fn test_reject_self_loops_on_both_backends() {
+        let temp_dir = tempdir().unwrap();
+        for (name, mut cfg) in [
+            ("sqlite.db", GraphConfig::sqlite()),
+            ("native.db", GraphConfig::native()),
+        ] {
+            cfg.reject_self_loops = true;
+            let graph = open_graph(temp_dir.path().join(name), &cfg).unwrap();
+            let node = || crate::backend::NodeSpec {
+                kind: "Node".to_string(),
+                name: "n".to_string(),
+                file_path: None,
+                data: serde_json::json!({}),
+            };
+            let a = graph.insert_node(node()).unwrap();
+            let b = graph.insert_node(node()).unwrap();
+            let edge = |from, to| crate::backend::EdgeSpec {
+                from,
+                to,
+                edge_type: "LINK".to_string(),
+                data: serde_json::json!({}),
+            };
+
+            let err = graph.insert_edge(edge(a, a)).unwrap_err();
+            assert!(
+                matches!(err, SqliteGraphError::InvalidInput { .. }),
+                "{name}"
+            );
+            assert!(graph.list_edge_ids().unwrap().is_empty(), "{name}");
+            assert!(graph.insert_edge(edge(a, b)).is_ok(), "{name}");
+        }
+
+        assert!(!GraphConfig::default().reject_self_loops);
+    }
+
+    #[test]
+    fn test_referential_integrity_on_both_backends() {
+        let temp_dir = tempdir().unwrap();
+        let edge = |from, to| crate::backend::EdgeSpec {
+            from,
+            to,
+            edge_type: "LINK".to_string(),
+            data: serde_json::json!({}),
+        };
+        for (name, cfg) in [
+            ("sqlite.db", GraphConfig::sqlite()),
+            ("native.db", GraphConfig::native()),
+        ] {
+            let graph = open_graph(temp_dir.path().join(name), &cfg).unwrap();
+            let node = graph
+                .insert_node(crate::backend::NodeSpec {
+                    kind: "Node".to_string(),
+                    name: "n".to_string(),
+                    file_path: None,
+                    data: serde_json::json!({}),
+                })
+                .unwrap();
+
+            for (from, to) in [(node, 42), (42, node)] {
+                match graph.insert_edge(edge(from, to)) {
+                    Err(SqliteGraphError::NotFound { kind, id }) => {
+                        assert_eq!((kind, id), (crate::EntityKind::Node, 42), "{name}")
+                    }
+                    other => panic!("{name}: expected NotFound, got {other:?}"),
+                }
+            }
+            assert!(matches!(
+                graph.upsert_edge(edge(node, 42)),
+                Err(SqliteGraphError::NotFound { id: 42, .. })
+            ));
+            assert!(graph.list_edge_ids().unwrap().is_empty(), "{name}");
+        }
+
+        let mut cfg = GraphConfig::sqlite();
+        cfg.enforce_referential_integrity = false;
+        let graph = open_graph(temp_dir.path().join("lenient.db"), &cfg).unwrap();
+        assert!(graph.insert_edge(edge(1, 2)).is_ok());
+        assert!(GraphConfig::default().enforce_referential_integrity);
+
+        let mut cfg = GraphConfig::native();
+        cfg.enforce_referential_integrity = false;
+        let path = temp_dir.path().join("lenient.graph");
+        assert!(matches!(
+            open_graph(&path, &cfg),
+            Err(SqliteGraphError::InvalidInput { .. })
+        ));
+        assert!(!path.exists());
+        assert!(matches!(
+            open_graph_in_memory(&cfg),
+            Err(SqliteGraphError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sqlite_config_cache_size_limits_statement_cache() {
+        let temp_dir = tempdir().unwrap();
+        let run_distinct_queries = |graph: &crate::graph::SqliteGraph| {
+            for i in 0..20 {
+                let sql = format!("SELECT {i}");
+                let conn = graph.connection();
+                let mut stmt = conn.prepare_cached(&sql).unwrap();
+                let value: i64 = stmt.query_row([], |row| row.get(0)).unwrap();
+                assert_eq!(value, i);
+            }
+        };
+
+        let mut cfg = GraphConfig::sqlite();
+        cfg.sqlite.cache_size = Some(4);
+        let graph = open_sqlite_graph(temp_dir.path().join("small.db"), &cfg).unwrap();
+        run_distinct_queries(&graph);
+        assert_eq!(graph.statement_cache_len(), 4);
+        // Cycling through more statements than fit evicts each one before reuse
+        graph.reset_metrics();
+        run_distinct_queries(&graph);
+        let metrics = graph.metrics_snapshot();
+        assert_eq!(metrics.prepare_cache_hits, 0);
+        assert_eq!(metrics.prepare_cache_misses, 20);
+
+        let graph =
+            open_sqlite_graph(temp_dir.path().join("default.db"), &GraphConfig::sqlite()).unwrap();
+        run_distinct_queries(&graph);
+        assert!(graph.statement_cache_len() >= 20);
+        graph.reset_metrics();
+        run_distinct_queries(&graph);
+        assert_eq!(graph.metrics_snapshot().prepare_cache_hits, 20);
+
+        cfg.sqlite.cache_size = Some(0);
+        let graph = open_sqlite_graph(temp_dir.path().join("none.db"), &cfg).unwrap();
+        run_distinct_queries(&graph);
+        assert_eq!(graph.statement_cache_len(), 0);
+    }
+
+    #[test]
+    fn test_sqlite_config_read_only() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test_read_only.db");
+        let node = || crate::backend::NodeSpec {
+            kind: "Node".to_string(),
+            name: "n".to_string(),
+            file_path: None,
+            data: serde_json::json!({}),
+        };
+        let id = open_graph(&db_path, &GraphConfig::sqlite())
+            .unwrap()
+            .insert_node(node())
+            .unwrap();
+
+        let mut cfg = GraphConfig::sqlite();
+        cfg.sqlite.read_only = true;
+        // Would need to write to the file, so it is skipped rather than rejected
+        cfg.sqlite
+            .pragma_settings
+            .insert("journal_mode".to_string(), "DELETE".to_string());
+        let graph = open_graph(&db_path, &cfg).unwrap();
+        assert_eq!(graph.get_node(id).unwrap().name, "n");
+
+        let err = graph.insert_node(node()).unwrap_err();
+        assert!(matches!(err, SqliteGraphError::ReadOnly(_)), "{err:?}");
+        let err = graph.delete_node(id).unwrap_err();
+        assert!(matches!(err, SqliteGraphError::ReadOnly(_)), "{err:?}");
+        assert!(graph.get_node(id).is_ok());
+
+        let missing = temp_dir.path().join("missing.db");
+        assert!(open_graph(&missing, &cfg).is_err());
+        assert!(!missing.exists());
+    }
+
+    #[test]
+    fn test_lock_settings_defaults_and_precedence() {
+        let temp_dir = tempdir().unwrap();
+        let busy_timeout = |conn: &rusqlite::Connection| -> i64 {
+            conn.pragma_query_value(None, "busy_timeout", |row| row.get(0))
+                .unwrap()
+        };
+
+        // WAL databases get the default timeout unless a PRAGMA entry set one
+        let conn = rusqlite::Connection::open(temp_dir.path().join("wal.db")).unwrap();
+        conn.pragma_update(None, "journal_mode", "WAL").unwrap();
+        apply_pragma(&conn, "busy_timeout", "0").unwrap();
+        apply_lock_settings(&conn, &SqliteConfig::default()).unwrap();
+        assert_eq!(busy_timeout(&conn), 5000);
+        let mut from_pragma = SqliteConfig::default();
+        from_pragma
+            .pragma_settings
+            .insert("busy_timeout".to_string(), "100".to_string());
+        apply_pragma(&conn, "busy_timeout", "100").unwrap();
+        apply_lock_settings(&conn, &from_pragma).unwrap();
+        assert_eq!(busy_timeout(&conn), 100);
+
+        let journal = rusqlite::Connection::open(temp_dir.path().join("delete.db")).unwrap();
+        apply_pragma(&journal, "busy_timeout", "0").unwrap();
+        apply_lock_settings(&journal, &SqliteConfig::default()).unwrap();
+        assert_eq!(busy_timeout(&journal), 0);
+
+        // Explicit fields override the same settings given as PRAGMAs
+        apply_pragma(&conn, "wal_autocheckpoint", "10").unwrap();
+        let cfg = SqliteConfig {
+            busy_timeout_ms: Some(250),
+            wal_autocheckpoint: Some(2000),
+            ..from_pragma
+        };
+        apply_lock_settings(&conn, &cfg).unwrap();
+        assert_eq!(busy_timeout(&conn), 250);
+        let pages: i64 = conn
+            .pragma_query_value(None, "wal_autocheckpoint", |row| row.get(0))
+            .unwrap();
+        assert_eq!(pages, 2000);
+    }
 }
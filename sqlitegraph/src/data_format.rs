@@ -0,0 +1,345 @@
+//! Serialization formats for entity and edge `data` payloads.
+//!
+//! JSON is the default and stays human readable inside the database. MessagePack
+//! is a compact binary alternative for data-heavy graphs. The SQLite backend
+//! stores JSON payloads as TEXT and MessagePack payloads as BLOB, so a database
+//! may hold rows written in both formats and every row decodes by its storage
+//! type. The native backend records the format in its file header and uses a
+//! single format per file.
+//!
+//! Only the subset of MessagePack that maps onto [`serde_json::Value`] is
+//! supported: nil, booleans, integers, floats, strings, arrays and maps with
+//! string keys. Binary and extension types are rejected on decode.
+
+use serde_json::{Map, Number, Value};
+
+use crate::errors::SqliteGraphError;
+
+/// Deepest nesting of arrays and maps accepted in a `data` payload.
+///
+/// Matches the recursion limit of `serde_json`'s parser, so anything either
+/// format writes can be read back.
+const MAX_NESTING_DEPTH: usize = 127;
+
+/// Encoding used for entity and edge `data` payloads.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DataFormat {
+    /// UTF-8 JSON text.
+    #[default]
+    Json,
+    /// MessagePack binary encoding.
+    MessagePack,
+}
+
+impl DataFormat {
+    /// Stable name used when the format is persisted.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DataFormat::Json => "json",
+            DataFormat::MessagePack => "msgpack",
+        }
+    }
+
+    /// Parse a name produced by [`as_str`](Self::as_str).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(DataFormat::Json),
+            "msgpack" => Some(DataFormat::MessagePack),
+            _ => None,
+        }
+    }
+
+    /// Encode `value` in this format.
+    ///
+    /// Fails with [`SqliteGraphError::InvalidInput`] when arrays and maps are
+    /// nested more than 127 levels deep, since such a value could not be
+    /// decoded again.
+    pub fn encode(self, value: &Value) -> Result<Vec<u8>, SqliteGraphError> {
+        match self {
+            DataFormat::Json => {
+                check_nesting(value, 0)?;
+                Ok(value.to_string().into_bytes())
+            }
+            DataFormat::MessagePack => encode_msgpack(value),
+        }
+    }
+
+    /// Decode bytes previously produced by [`encode`](Self::encode).
+    pub fn decode(self, bytes: &[u8]) -> Result<Value, SqliteGraphError> {
+        match self {
+            DataFormat::Json => {
+                serde_json::from_slice(bytes).map_err(SqliteGraphError::invalid_input)
+            }
+            DataFormat::MessagePack => decode_msgpack(bytes),
+        }
+    }
+}
+
+/// Encode a JSON value as MessagePack.
+///
+/// Fails when arrays and maps are nested more than 127 levels deep, which
+/// [`decode_msgpack`] would refuse.
+pub fn encode_msgpack(value: &Value) -> Result<Vec<u8>, SqliteGraphError> {
+    let mut out = Vec::new();
+    write_value(&mut out, value, 0)?;
+    Ok(out)
+}
+
+/// Decode a MessagePack document into a JSON value.
+///
+/// Fails on truncated input, trailing bytes, non-string map keys, binary or
+/// extension types, non-finite floats and nesting deeper than 127 levels.
+pub fn decode_msgpack(bytes: &[u8]) -> Result<Value, SqliteGraphError> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let value = reader.read_value(0)?;
+    if reader.pos != bytes.len() {
+        return Err(SqliteGraphError::invalid_input(format!(
+            "msgpack payload has {} trailing bytes",
+            bytes.len() - reader.pos
+        )));
+    }
+    Ok(value)
+}
+
+fn nested_too_deeply() -> SqliteGraphError {
+    SqliteGraphError::invalid_input(format!(
+        "data is nested more than {MAX_NESTING_DEPTH} levels deep"
+    ))
+}
+
+/// Refuse values whose arrays and maps nest deeper than [`MAX_NESTING_DEPTH`];
+/// `depth` counts the containers around `value`.
+fn check_nesting(value: &Value, depth: usize) -> Result<(), SqliteGraphError> {
+    match value {
+        Value::Array(_) | Value::Object(_) if depth >= MAX_NESTING_DEPTH => {
+            Err(nested_too_deeply())
+        }
+        Value::Array(items) => items
+            .iter()
+            .try_for_each(|item| check_nesting(item, depth + 1)),
+        Value::Object(map) => map
+            .values()
+            .try_for_each(|item| check_nesting(item, depth + 1)),
+        _ => Ok(()),
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value, depth: usize) -> Result<(), SqliteGraphError> {
+    if matches!(value, Value::Array(_) | Value::Object(_)) && depth >= MAX_NESTING_DEPTH {
+        return Err(nested_too_deeply());
+    }
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(false) => out.push(0xc2),
+        Value::Bool(true) => out.push(0xc3),
+        Value::Number(number) => write_number(out, number),
+        Value::String(text) => write_str(out, text),
+        Value::Array(items) => {
+            write_len(out, items.len(), 0x90, 0xdc, 0xdd);
+            for item in items {
+                write_value(out, item, depth + 1)?;
+            }
+        }
+        Value::Object(map) => {
+            write_len(out, map.len(), 0x80, 0xde, 0xdf);
+            for (key, item) in map {
+                write_str(out, key);
+                write_value(out, item, depth + 1)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_number(out: &mut Vec<u8>, number: &Number) {
+    if let Some(value) = number.as_u64() {
+        if value < 0x80 {
+            out.push(value as u8);
+        } else if value <= u8::MAX as u64 {
+            out.extend_from_slice(&[0xcc, value as u8]);
+        } else if value <= u16::MAX as u64 {
+            out.push(0xcd);
+            out.extend_from_slice(&(value as u16).to_be_bytes());
+        } else if value <= u32::MAX as u64 {
+            out.push(0xce);
+            out.extend_from_slice(&(value as u32).to_be_bytes());
+        } else {
+            out.push(0xcf);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+    } else if let Some(value) = number.as_i64() {
+        // Only negative values reach this branch.
+        if value >= -32 {
+            out.push(value as i8 as u8);
+        } else if value >= i8::MIN as i64 {
+            out.extend_from_slice(&[0xd0, value as i8 as u8]);
+        } else if value >= i16::MIN as i64 {
+            out.push(0xd1);
+            out.extend_from_slice(&(value as i16).to_be_bytes());
+        } else if value >= i32::MIN as i64 {
+            out.push(0xd2);
+            out.extend_from_slice(&(value as i32).to_be_bytes());
+        } else {
+            out.push(0xd3);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+    } else {
+        out.push(0xcb);
+        out.extend_from_slice(&number.as_f64().unwrap_or(0.0).to_be_bytes());
+    }
+}
+
+fn write_str(out: &mut Vec<u8>, text: &str) {
+    let len = text.len();
+    if len < 32 {
+        out.push(0xa0 | len as u8);
+    } else if len <= u8::MAX as usize {
+        out.extend_from_slice(&[0xd9, len as u8]);
+    } else if len <= u16::MAX as usize {
+        out.push(0xda);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(0xdb);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+    out.extend_from_slice(text.as_bytes());
+}
+
+fn write_len(out: &mut Vec<u8>, len: usize, fix: u8, marker16: u8, marker32: u8) {
+    if len < 16 {
+        out.push(fix | len as u8);
+    } else if len <= u16::MAX as usize {
+        out.push(marker16);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(marker32);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SqliteGraphError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| SqliteGraphError::invalid_input("truncated msgpack payload"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SqliteGraphError> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    /// Read the next value; `depth` counts the arrays and maps around it.
+    fn read_value(&mut self, depth: usize) -> Result<Value, SqliteGraphError> {
+        let marker = self.array::<1>()?[0];
+        let value = match marker {
+            0x00..=0x7f => Value::from(marker),
+            0x80..=0x8f => self.read_map((marker & 0x0f) as usize, depth)?,
+            0x90..=0x9f => self.read_array((marker & 0x0f) as usize, depth)?,
+            0xa0..=0xbf => self.read_str((marker & 0x1f) as usize)?,
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xca => float_value(f32::from_be_bytes(self.array()?) as f64)?,
+            0xcb => float_value(f64::from_be_bytes(self.array()?))?,
+            0xcc => Value::from(self.array::<1>()?[0]),
+            0xcd => Value::from(u16::from_be_bytes(self.array()?)),
+            0xce => Value::from(u32::from_be_bytes(self.array()?)),
+            0xcf => Value::from(u64::from_be_bytes(self.array()?)),
+            0xd0 => Value::from(i8::from_be_bytes(self.array()?)),
+            0xd1 => Value::from(i16::from_be_bytes(self.array()?)),
+            0xd2 => Value::from(i32::from_be_bytes(self.array()?)),
+            0xd3 => Value::from(i64::from_be_bytes(self.array()?)),
+            0xd9 => {
+                let len = self.array::<1>()?[0] as usize;
+                self.read_str(len)?
+            }
+            0xda => {
+                let len = u16::from_be_bytes(self.array()?) as usize;
+                self.read_str(len)?
+            }
+            0xdb => {
+                let len = u32::from_be_bytes(self.array()?) as usize;
+                self.read_str(len)?
+            }
+            0xdc => {
+                let len = u16::from_be_bytes(self.array()?) as usize;
+                self.read_array(len, depth)?
+            }
+            0xdd => {
+                let len = u32::from_be_bytes(self.array()?) as usize;
+                self.read_array(len, depth)?
+            }
+            0xde => {
+                let len = u16::from_be_bytes(self.array()?) as usize;
+                self.read_map(len, depth)?
+            }
+            0xdf => {
+                let len = u32::from_be_bytes(self.array()?) as usize;
+                self.read_map(len, depth)?
+            }
+            0xe0..=0xff => Value::from(marker as i8),
+            other => {
+                return Err(SqliteGraphError::invalid_input(format!(
+                    "unsupported msgpack type 0x{other:02x}"
+                )));
+            }
+        };
+        Ok(value)
+    }
+
+    fn read_str(&mut self, len: usize) -> Result<Value, SqliteGraphError> {
+        let bytes = self.take(len)?;
+        let text = std::str::from_utf8(bytes).map_err(SqliteGraphError::invalid_input)?;
+        Ok(Value::String(text.to_string()))
+    }
+
+    fn read_array(&mut self, len: usize, depth: usize) -> Result<Value, SqliteGraphError> {
+        if depth >= MAX_NESTING_DEPTH {
+            return Err(nested_too_deeply());
+        }
+        // Every element takes at least one byte, which bounds the preallocation.
+        let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.pos));
+        for _ in 0..len {
+            items.push(self.read_value(depth + 1)?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn read_map(&mut self, len: usize, depth: usize) -> Result<Value, SqliteGraphError> {
+        if depth >= MAX_NESTING_DEPTH {
+            return Err(nested_too_deeply());
+        }
+        let mut map = Map::new();
+        for _ in 0..len {
+            let key = match self.read_value(depth + 1)? {
+                Value::String(key) => key,
+                _ => {
+                    return Err(SqliteGraphError::invalid_input(
+                        "msgpack map keys must be strings",
+                    ));
+                }
+            };
+            let value = self.read_value(depth + 1)?;
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+fn float_value(value: f64) -> Result<Value, SqliteGraphError> {
+    Number::from_f64(value)
+        .map(Value::Number)
+        .ok_or_else(|| SqliteGraphError::invalid_input("msgpack float is not finite"))
+}
@@ -1,23 +1,68 @@
+//! A small text syntax for pattern queries.
+//!
+//! Two forms are accepted:
+//!
+//! - a chain of edge types, `CALLS->USES*2`, where `*n` repeats a leg;
+//! - a Cypher-like path, `(n:Function {language:"rust"})-[:CALLS]->(m)`.
+//!
+//! A path with a single relationship and node filters or a hop range
+//! compiles to a [`PatternTriple`], which carries the node labels and property
+//! filters. Other paths compile to a [`PatternQuery`] and cannot filter nodes,
+//! as its legs only constrain kind and name. [`execute`] runs either form
+//! against any [`GraphBackend`].
+
+use std::{collections::BTreeSet, fmt, ops::Range};
+
 use crate::{
-    backend::BackendDirection,
-    pattern::{PatternLeg, PatternQuery},
+    SqliteGraphError,
+    backend::{BackendDirection, GraphBackend},
+    pattern::{PatternLeg, PatternMatch, PatternQuery},
+    pattern_engine::PatternTriple,
 };
 
 #[derive(Debug, Clone)]
 pub enum DslResult {
     Pattern(PatternQuery),
-    Error(String),
+    Triple(PatternTriple),
+    Error(DslError),
+}
+
+/// Parse failure, located by the 1-based character column where it was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DslError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DslError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
 }
 
+impl std::error::Error for DslError {}
+
 pub fn parse_dsl(input: &str) -> DslResult {
     let trimmed = input.trim();
     if trimmed.is_empty() {
-        return DslResult::Error("empty DSL string".into());
+        return DslResult::Error(DslError {
+            column: 1,
+            message: "empty DSL string".into(),
+        });
+    }
+    if input.trim_start().starts_with('(') {
+        return match parse_path(input) {
+            Ok(result) => result,
+            Err(err) => DslResult::Error(err),
+        };
     }
     if trimmed.contains("->") || trimmed.contains('*') {
         return DslResult::Pattern(parse_repetitive_pattern(trimmed));
     }
-    DslResult::Error(format!("unsupported DSL form: {trimmed}"))
+    DslResult::Error(DslError {
+        column: input.len() - input.trim_start().len() + 1,
+        message: format!("unsupported DSL form: {trimmed}"),
+    })
 }
 
 fn parse_repetitive_pattern(segment: &str) -> PatternQuery {
@@ -41,5 +86,448 @@ fn parse_repetitive_pattern(segment: &str) -> PatternQuery {
             });
         }
     }
-    PatternQuery { root: None, legs }
+    PatternQuery {
+        root: None,
+        legs,
+        ..PatternQuery::default()
+    }
+}
+
+/// `(var:Label {key: value, ...})`, every part optional
+struct NodePattern {
+    column: usize,
+    label: Option<String>,
+    properties: Vec<(String, String)>,
+}
+
+impl NodePattern {
+    fn has_filters(&self) -> bool {
+        self.label.is_some() || !self.properties.is_empty()
+    }
+}
+
+/// `-[var:TYPE*min..max]->`, `<-[...]-` or `-[...]-`
+struct RelationshipPattern {
+    column: usize,
+    direction: BackendDirection,
+    edge_type: Option<String>,
+    hops: Option<Range<u32>>,
+}
+
+fn parse_path(input: &str) -> Result<DslResult, DslError> {
+    let mut parser = Parser::new(input);
+    let mut nodes = vec![parser.node()?];
+    let mut relationships = Vec::new();
+    while parser.peek().is_some() {
+        relationships.push(parser.relationship()?);
+        nodes.push(parser.node()?);
+    }
+    if relationships.is_empty() {
+        return Err(DslError {
+            column: parser.column(),
+            message: "expected a relationship after the node".into(),
+        });
+    }
+    if let [rel] = relationships.as_slice() {
+        let hop_range = rel.hops.as_ref().is_some_and(|hops| hops.start != hops.end);
+        if hop_range || nodes.iter().any(NodePattern::has_filters) {
+            return triple(&nodes[0], rel, &nodes[1]).map(DslResult::Triple);
+        }
+    }
+    if let Some(node) = nodes.iter().find(|node| node.has_filters()) {
+        return Err(DslError {
+            column: node.column,
+            message: "label and property filters need a single-relationship pattern".into(),
+        });
+    }
+
+    let mut legs = Vec::new();
+    for rel in relationships {
+        let repeats = match rel.hops {
+            None => 1,
+            Some(hops) if hops.start == hops.end => hops.start,
+            Some(_) => {
+                return Err(DslError {
+                    column: rel.column,
+                    message: "hop ranges need a single-relationship pattern".into(),
+                });
+            }
+        };
+        for _ in 0..repeats {
+            legs.push(PatternLeg {
+                direction: rel.direction,
+                edge_type: rel.edge_type.clone(),
+                constraint: None,
+            });
+        }
+    }
+    Ok(DslResult::Pattern(PatternQuery {
+        legs,
+        ..PatternQuery::default()
+    }))
+}
+
+fn triple(
+    start: &NodePattern,
+    rel: &RelationshipPattern,
+    end: &NodePattern,
+) -> Result<PatternTriple, DslError> {
+    let Some(edge_type) = &rel.edge_type else {
+        return Err(DslError {
+            column: rel.column,
+            message: "a filtered or variable-length pattern needs a relationship type".into(),
+        });
+    };
+    let mut triple = PatternTriple::new(edge_type.as_str()).direction(rel.direction);
+    triple.start_label = start.label.clone();
+    triple.end_label = end.label.clone();
+    triple.start_props = start.properties.iter().cloned().collect();
+    triple.end_props = end.properties.iter().cloned().collect();
+    triple.hops = rel.hops.clone();
+    triple.validate().map_err(|err| DslError {
+        column: rel.column,
+        message: err.to_string(),
+    })?;
+    Ok(triple)
+}
+
+/// Parse `query` and match it from every node that can start a match.
+///
+/// Start nodes are the endpoints of live edges on the side the first leg
+/// leaves from, or, for a filtered pattern, the nodes passing the start
+/// filters. A hop range runs one search per length. Matches are ordered by
+/// their node sequence. Parse errors are reported as
+/// [`SqliteGraphError::InvalidInput`] with the [`DslError`] as source.
+pub fn execute(
+    graph: &dyn GraphBackend,
+    query: &str,
+) -> Result<Vec<PatternMatch>, SqliteGraphError> {
+    match parse_dsl(query) {
+        DslResult::Pattern(pattern) => {
+            let Some(first) = pattern.legs.first() else {
+                return Ok(Vec::new());
+            };
+            let mut matches = Vec::new();
+            for start in edge_endpoints(graph, first)? {
+                matches.extend(graph.pattern_search(start, &pattern)?);
+            }
+            Ok(matches)
+        }
+        DslResult::Triple(triple) => execute_triple(graph, &triple),
+        DslResult::Error(err) => Err(SqliteGraphError::invalid_input(err)),
+    }
+}
+
+fn execute_triple(
+    graph: &dyn GraphBackend,
+    triple: &PatternTriple,
+) -> Result<Vec<PatternMatch>, SqliteGraphError> {
+    let leg = PatternLeg {
+        direction: triple.direction,
+        edge_type: Some(triple.edge_type.clone()),
+        constraint: None,
+    };
+    let starts = match filtered_nodes(graph, triple.start_label.as_deref(), &triple.start_props)? {
+        Some(starts) => starts,
+        None => edge_endpoints(graph, &leg)?,
+    };
+    let ends = filtered_nodes(graph, triple.end_label.as_deref(), &triple.end_props)?;
+    let lengths = triple
+        .hops
+        .as_ref()
+        .map_or(1..=1, |hops| hops.start as usize..=hops.end as usize);
+
+    let mut matches = Vec::new();
+    for start in starts {
+        for length in lengths.clone() {
+            let pattern = PatternQuery {
+                legs: vec![leg.clone(); length],
+                ..PatternQuery::default()
+            };
+            matches.extend(
+                graph
+                    .pattern_search(start, &pattern)?
+                    .into_iter()
+                    .filter(|found| {
+                        ends.as_ref().map_or(true, |ends| {
+                            found.nodes.last().is_some_and(|end| ends.contains(end))
+                        })
+                    }),
+            );
+        }
+    }
+    matches.sort_by(|a, b| a.nodes.cmp(&b.nodes));
+    Ok(matches)
+}
+
+/// Nodes carrying `label` and every `(key, value)` property, or `None` when
+/// there is no filter
+fn filtered_nodes(
+    graph: &dyn GraphBackend,
+    label: Option<&str>,
+    properties: &std::collections::HashMap<String, String>,
+) -> Result<Option<BTreeSet<i64>>, SqliteGraphError> {
+    let mut result: Option<BTreeSet<i64>> = None;
+    let mut narrow = |ids: Vec<i64>| {
+        let ids: BTreeSet<i64> = ids.into_iter().collect();
+        result = Some(match result.take() {
+            Some(current) => current.intersection(&ids).copied().collect(),
+            None => ids,
+        });
+    };
+    if let Some(label) = label {
+        narrow(graph.find_nodes_by_label(label)?);
+    }
+    for (key, value) in properties {
+        narrow(graph.find_nodes_by_property(key, value)?);
+    }
+    Ok(result)
+}
+
+/// Nodes with a live edge `leg` can leave them by, ascending
+fn edge_endpoints(
+    graph: &dyn GraphBackend,
+    leg: &PatternLeg,
+) -> Result<BTreeSet<i64>, SqliteGraphError> {
+    let mut nodes = BTreeSet::new();
+    for id in graph.list_edge_ids()? {
+        let edge = graph.get_edge(id)?;
+        if leg
+            .edge_type
+            .as_ref()
+            .is_some_and(|edge_type| &edge.edge_type != edge_type)
+        {
+            continue;
+        }
+        match leg.direction {
+            BackendDirection::Outgoing => {
+                nodes.insert(edge.from_id);
+            }
+            BackendDirection::Incoming => {
+                nodes.insert(edge.to_id);
+            }
+            BackendDirection::Both => {
+                nodes.extend([edge.from_id, edge.to_id]);
+            }
+        }
+    }
+    Ok(nodes)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    /// Column of the next non-whitespace character
+    fn column(&mut self) -> usize {
+        self.skip_whitespace();
+        self.pos + 1
+    }
+
+    fn error(&mut self, expected: &str) -> DslError {
+        let column = self.column();
+        let found = match self.chars.get(self.pos) {
+            Some(c) => format!("'{c}'"),
+            None => "end of input".into(),
+        };
+        DslError {
+            column,
+            message: format!("expected {expected}, found {found}"),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), DslError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{expected}'")))
+        }
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .chars
+            .get(self.pos)
+            .is_some_and(|&c| c.is_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| self.chars[start..self.pos].iter().collect())
+    }
+
+    fn expect_identifier(&mut self, what: &str) -> Result<String, DslError> {
+        match self.identifier() {
+            Some(name) => Ok(name),
+            None => Err(self.error(what)),
+        }
+    }
+
+    fn node(&mut self) -> Result<NodePattern, DslError> {
+        let column = self.column();
+        self.expect('(')?;
+        self.identifier();
+        let label = if self.eat(':') {
+            Some(self.expect_identifier("a label")?)
+        } else {
+            None
+        };
+        let mut properties = Vec::new();
+        if self.eat('{') {
+            loop {
+                let key = self.expect_identifier("a property key")?;
+                self.expect(':')?;
+                properties.push((key, self.value()?));
+                if !self.eat(',') {
+                    break;
+                }
+            }
+            self.expect('}')?;
+        }
+        self.expect(')')?;
+        Ok(NodePattern {
+            column,
+            label,
+            properties,
+        })
+    }
+
+    /// A quoted string, or a bare number, boolean or word, as the text it is
+    /// stored under
+    fn value(&mut self) -> Result<String, DslError> {
+        let column = self.column();
+        match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                self.pos += 1;
+                let mut value = String::new();
+                loop {
+                    match self.chars.get(self.pos).copied() {
+                        Some(c) if c == quote => {
+                            self.pos += 1;
+                            return Ok(value);
+                        }
+                        Some('\\') => {
+                            self.pos += 1;
+                            if let Some(&escaped) = self.chars.get(self.pos) {
+                                value.push(escaped);
+                                self.pos += 1;
+                            }
+                        }
+                        Some(c) => {
+                            value.push(c);
+                            self.pos += 1;
+                        }
+                        None => {
+                            return Err(DslError {
+                                column,
+                                message: "unterminated string".into(),
+                            });
+                        }
+                    }
+                }
+            }
+            _ => {
+                let start = self.pos;
+                while self
+                    .chars
+                    .get(self.pos)
+                    .is_some_and(|&c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-' | '+'))
+                {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return Err(self.error("a property value"));
+                }
+                Ok(self.chars[start..self.pos].iter().collect())
+            }
+        }
+    }
+
+    fn relationship(&mut self) -> Result<RelationshipPattern, DslError> {
+        let column = self.column();
+        let incoming = self.eat('<');
+        self.expect('-')?;
+        self.expect('[')?;
+        self.identifier();
+        let edge_type = if self.eat(':') {
+            Some(self.expect_identifier("a relationship type")?)
+        } else {
+            None
+        };
+        let hops = if self.eat('*') {
+            let start = self.hop_count()?;
+            let end = if self.eat('.') {
+                self.expect('.')?;
+                self.hop_count()?
+            } else {
+                start
+            };
+            Some(start..end)
+        } else {
+            None
+        };
+        self.expect(']')?;
+        self.expect('-')?;
+        let outgoing = self.eat('>');
+        let direction = match (incoming, outgoing) {
+            (true, true) => {
+                return Err(DslError {
+                    column,
+                    message: "a relationship cannot point both ways".into(),
+                });
+            }
+            (true, false) => BackendDirection::Incoming,
+            (false, true) => BackendDirection::Outgoing,
+            (false, false) => BackendDirection::Both,
+        };
+        Ok(RelationshipPattern {
+            column,
+            direction,
+            edge_type,
+            hops,
+        })
+    }
+
+    fn hop_count(&mut self) -> Result<u32, DslError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(char::is_ascii_digit) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().map_err(|_| {
+            self.pos = start;
+            self.error("a hop count")
+        })
+    }
 }
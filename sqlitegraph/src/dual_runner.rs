@@ -1,6 +1,12 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde_json::{Value, json};
+
 use crate::{
     SqliteGraphError,
-    backend::{GraphBackend, NeighborQuery},
+    backend::{BackendDirection, EdgeSpec, GraphBackend, NeighborQuery, NodeSpec},
+    graph::GraphStatistics,
+    pattern::PatternQuery,
 };
 
 #[derive(Clone, Debug)]
@@ -38,3 +44,380 @@ where
         })
     }
 }
+
+/// Edge of a [`GoldenGraph`], with endpoints given as indexes into its node list.
+#[derive(Clone, Debug)]
+pub struct GoldenEdge {
+    pub from: usize,
+    pub to: usize,
+    pub edge_type: String,
+    pub data: Value,
+}
+
+/// Fixed graph built identically on two backends by [`run_dual_dump_check`].
+#[derive(Clone, Debug)]
+pub struct GoldenGraph {
+    pub nodes: Vec<NodeSpec>,
+    pub edges: Vec<GoldenEdge>,
+}
+
+impl GoldenGraph {
+    /// Small graph covering cycles, parallel edges, self loops and mixed edge types.
+    pub fn standard() -> Self {
+        let nodes = ["main", "parse", "eval", "print", "util", "orphan"]
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| NodeSpec {
+                kind: if idx % 2 == 0 { "Fn" } else { "Module" }.to_string(),
+                name: name.to_string(),
+                file_path: Some(format!("src/{name}.rs")),
+                data: json!({ "idx": idx }),
+            })
+            .collect();
+        let edges = [
+            (0, 1, "CALLS"),
+            (0, 2, "CALLS"),
+            (1, 2, "CALLS"),
+            (2, 1, "CALLS"),
+            (2, 3, "USES"),
+            (2, 3, "CALLS"),
+            (3, 4, "USES"),
+            (4, 4, "USES"),
+            (4, 0, "REFERS"),
+        ]
+        .iter()
+        .map(|&(from, to, edge_type)| GoldenEdge {
+            from,
+            to,
+            edge_type: edge_type.to_string(),
+            data: json!({}),
+        })
+        .collect();
+        Self { nodes, edges }
+    }
+
+    fn edge_types(&self) -> BTreeSet<&str> {
+        self.edges.iter().map(|e| e.edge_type.as_str()).collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DualDumpResult {
+    Match,
+    /// First differing line of the two normalized dumps; `None` when one dump is shorter.
+    Mismatch {
+        line: usize,
+        base: Option<String>,
+        other: Option<String>,
+    },
+}
+
+/// Insert `golden` through the trait and return the backend ids of its nodes.
+pub fn build_golden<B: GraphBackend>(
+    backend: &B,
+    golden: &GoldenGraph,
+) -> Result<Vec<i64>, SqliteGraphError> {
+    let mut ids = Vec::with_capacity(golden.nodes.len());
+    for node in &golden.nodes {
+        ids.push(backend.insert_node(node.clone())?);
+    }
+    for edge in &golden.edges {
+        let (Some(&from), Some(&to)) = (ids.get(edge.from), ids.get(edge.to)) else {
+            return Err(SqliteGraphError::invalid_input(format!(
+                "golden edge {} -> {} references a missing node",
+                edge.from, edge.to
+            )));
+        };
+        backend.insert_edge(EdgeSpec {
+            from,
+            to,
+            edge_type: edge.edge_type.clone(),
+            data: edge.data.clone(),
+        })?;
+    }
+    Ok(ids)
+}
+
+/// JSONL dump of `golden` as stored in `backend` with ids replaced by node indexes.
+///
+/// Every line is derived through the trait (`get_node`, typed and untyped
+/// `neighbors`), so two backends holding the same graph produce identical dumps
+/// regardless of how they allocate ids. Neighbor ids outside `ids` dump as `null`.
+pub fn dump_normalized<B: GraphBackend>(
+    backend: &B,
+    golden: &GoldenGraph,
+    ids: &[i64],
+) -> Result<Vec<String>, SqliteGraphError> {
+    let index: HashMap<i64, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let normalize = |neighbors: Vec<i64>| {
+        let mut normalized: Vec<Option<usize>> = neighbors
+            .into_iter()
+            .map(|id| index.get(&id).copied())
+            .collect();
+        normalized.sort();
+        normalized
+    };
+    let query = |direction, edge_type: Option<&str>| NeighborQuery {
+        direction,
+        edge_type: edge_type.map(str::to_string),
+    };
+
+    let mut lines = Vec::new();
+    for (idx, &id) in ids.iter().enumerate() {
+        let node = backend.get_node(id)?;
+        lines.push(
+            json!({
+                "type": "entity",
+                "id": idx,
+                "kind": node.kind,
+                "name": node.name,
+                "file_path": node.file_path,
+                "data": node.data,
+            })
+            .to_string(),
+        );
+    }
+    let edge_types = golden.edge_types();
+    for (idx, &id) in ids.iter().enumerate() {
+        for edge_type in &edge_types {
+            for to in normalize(
+                backend.neighbors(id, query(BackendDirection::Outgoing, Some(edge_type)))?,
+            ) {
+                lines.push(
+                    json!({ "type": "edge", "from_id": idx, "to_id": to, "edge_type": edge_type })
+                        .to_string(),
+                );
+            }
+        }
+    }
+    for (idx, &id) in ids.iter().enumerate() {
+        let (out_degree, in_degree) = backend.node_degree(id)?;
+        lines.push(
+            json!({
+                "type": "adjacency",
+                "id": idx,
+                "outgoing": normalize(backend.neighbors(id, query(BackendDirection::Outgoing, None))?),
+                "incoming": normalize(backend.neighbors(id, query(BackendDirection::Incoming, None))?),
+                "out_degree": out_degree,
+                "in_degree": in_degree,
+            })
+            .to_string(),
+        );
+    }
+    Ok(lines)
+}
+
+/// Build `golden` on both backends and compare their normalized dumps line by line.
+pub fn run_dual_dump_check<B1, B2>(
+    base: B1,
+    other: B2,
+    golden: &GoldenGraph,
+) -> Result<DualDumpResult, SqliteGraphError>
+where
+    B1: GraphBackend,
+    B2: GraphBackend,
+{
+    let base_ids = build_golden(&base, golden)?;
+    let other_ids = build_golden(&other, golden)?;
+    let base_dump = dump_normalized(&base, golden, &base_ids)?;
+    let other_dump = dump_normalized(&other, golden, &other_ids)?;
+
+    let lines = base_dump.len().max(other_dump.len());
+    for line in 0..lines {
+        let (left, right) = (base_dump.get(line), other_dump.get(line));
+        if left != right {
+            return Ok(DualDumpResult::Mismatch {
+                line,
+                base: left.cloned(),
+                other: right.cloned(),
+            });
+        }
+    }
+    Ok(DualDumpResult::Match)
+}
+
+/// One step of a script run by [`compare_backends`].
+///
+/// Nodes are referred to by their position among the script's `InsertNode`
+/// steps, so the script does not depend on the ids a backend allocates.
+#[derive(Clone, Debug)]
+pub enum Operation {
+    InsertNode(NodeSpec),
+    InsertEdge {
+        from: usize,
+        to: usize,
+        edge_type: String,
+        data: Value,
+    },
+    Neighbors {
+        node: usize,
+        query: NeighborQuery,
+    },
+    Bfs {
+        start: usize,
+        depth: u32,
+    },
+    Pattern {
+        start: usize,
+        pattern: PatternQuery,
+    },
+}
+
+/// First step at which the two backends of [`compare_backends`] disagreed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// Index of the step in the script
+    pub step: usize,
+    /// What the SQLite backend returned, with node ids replaced by script positions
+    pub sqlite: String,
+    /// What the native backend returned, in the same form
+    pub native: String,
+}
+
+/// Outcome of [`compare_backends`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DualReport {
+    /// Steps run before the comparison stopped
+    pub steps_run: usize,
+    pub divergence: Option<Divergence>,
+}
+
+impl DualReport {
+    pub fn is_match(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+/// What one backend produced for one step.
+#[derive(Debug, PartialEq)]
+enum Observation {
+    /// Node sequences of a read, as script positions, sorted
+    Read(Vec<Vec<Option<usize>>>),
+    /// Graph statistics after a write
+    State(GraphStatistics),
+    Failed(String),
+}
+
+impl Observation {
+    fn agrees_with(&self, other: &Observation) -> bool {
+        match (self, other) {
+            // Backends word their errors differently; failing on both sides agrees
+            (Observation::Failed(_), Observation::Failed(_)) => true,
+            _ => self == other,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Observation::Read(sequences) => format!("{sequences:?}"),
+            Observation::State(stats) => format!("{stats:?}"),
+            Observation::Failed(message) => format!("error: {message}"),
+        }
+    }
+}
+
+/// Node ids allocated by one backend for the script's `InsertNode` steps.
+#[derive(Default)]
+struct ScriptNodes {
+    ids: Vec<Option<i64>>,
+    positions: HashMap<i64, usize>,
+}
+
+impl ScriptNodes {
+    fn id(&self, position: usize) -> Result<i64, SqliteGraphError> {
+        self.ids.get(position).copied().flatten().ok_or_else(|| {
+            SqliteGraphError::invalid_input(format!(
+                "script node {position} was not inserted before use"
+            ))
+        })
+    }
+
+    fn read(&self, sequences: impl IntoIterator<Item = Vec<i64>>) -> Observation {
+        let mut normalized: Vec<Vec<Option<usize>>> = sequences
+            .into_iter()
+            .map(|ids| {
+                ids.iter()
+                    .map(|id| self.positions.get(id).copied())
+                    .collect()
+            })
+            .collect();
+        normalized.sort();
+        Observation::Read(normalized)
+    }
+}
+
+/// Apply `ops` to both backends in lockstep and report the first step where
+/// they disagree.
+///
+/// Reads are compared on their results, as sets of node sequences; writes are
+/// compared on the [`GraphBackend::statistics`] of each backend afterwards. An
+/// operation failing on both backends counts as agreement. Returns an error
+/// only when the script refers to a node it has not inserted.
+pub fn compare_backends(
+    sqlite: &dyn GraphBackend,
+    native: &dyn GraphBackend,
+    ops: &[Operation],
+) -> Result<DualReport, SqliteGraphError> {
+    let (mut sqlite_nodes, mut native_nodes) = (ScriptNodes::default(), ScriptNodes::default());
+    for (step, op) in ops.iter().enumerate() {
+        let left = observe(sqlite, &mut sqlite_nodes, op)?;
+        let right = observe(native, &mut native_nodes, op)?;
+        if !left.agrees_with(&right) {
+            return Ok(DualReport {
+                steps_run: step + 1,
+                divergence: Some(Divergence {
+                    step,
+                    sqlite: left.describe(),
+                    native: right.describe(),
+                }),
+            });
+        }
+    }
+    Ok(DualReport {
+        steps_run: ops.len(),
+        divergence: None,
+    })
+}
+
+fn observe(
+    backend: &dyn GraphBackend,
+    nodes: &mut ScriptNodes,
+    op: &Operation,
+) -> Result<Observation, SqliteGraphError> {
+    let outcome = match op {
+        Operation::InsertNode(node) => {
+            let inserted = backend.insert_node(node.clone());
+            nodes.ids.push(inserted.as_ref().ok().copied());
+            if let Ok(id) = inserted {
+                nodes.positions.insert(id, nodes.ids.len() - 1);
+            }
+            inserted.and_then(|_| backend.statistics().map(Observation::State))
+        }
+        Operation::InsertEdge {
+            from,
+            to,
+            edge_type,
+            data,
+        } => {
+            let edge = EdgeSpec {
+                from: nodes.id(*from)?,
+                to: nodes.id(*to)?,
+                edge_type: edge_type.clone(),
+                data: data.clone(),
+            };
+            backend
+                .insert_edge(edge)
+                .and_then(|_| backend.statistics().map(Observation::State))
+        }
+        Operation::Neighbors { node, query } => backend
+            .neighbors(nodes.id(*node)?, query.clone())
+            .map(|ids| nodes.read(ids.into_iter().map(|id| vec![id]))),
+        Operation::Bfs { start, depth } => backend
+            .bfs(nodes.id(*start)?, *depth)
+            .map(|ids| nodes.read(ids.into_iter().map(|id| vec![id]))),
+        Operation::Pattern { start, pattern } => backend
+            .pattern_search(nodes.id(*start)?, pattern)
+            .map(|matches| nodes.read(matches.into_iter().map(|m| m.nodes))),
+    };
+    Ok(outcome.unwrap_or_else(|err| Observation::Failed(err.to_string())))
+}
@@ -1,56 +1,222 @@
+use std::{error::Error as StdError, fmt};
+
 use thiserror::Error;
 
+/// Underlying error kept as the [`source`](StdError::source) of a [`SqliteGraphError`].
+pub type ErrorSource = Box<dyn StdError + Send + Sync + 'static>;
+
+/// Kind of record reported missing by [`SqliteGraphError::NotFound`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    Node,
+    Edge,
+}
+
+impl fmt::Display for EntityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EntityKind::Node => "entity",
+            EntityKind::Edge => "edge",
+        })
+    }
+}
+
 /// Error type for SQLiteGraph operations.
+///
+/// Variants raised from a SQLite, I/O or serialization failure keep that error
+/// as their `source`, so the full chain is visible to callers.
 #[derive(Debug, Error)]
 pub enum SqliteGraphError {
-    #[error("connection error: {0}")]
-    ConnectionError(String),
-    #[error("schema error: {0}")]
-    SchemaError(String),
-    #[error("query error: {0}")]
-    QueryError(String),
-    #[error("entity not found: {0}")]
-    NotFound(String),
-    #[error("invalid input: {0}")]
-    InvalidInput(String),
+    #[error("connection error: {message}")]
+    ConnectionError {
+        message: String,
+        #[source]
+        source: Option<ErrorSource>,
+    },
+    #[error("schema error: {message}")]
+    SchemaError {
+        message: String,
+        #[source]
+        source: Option<ErrorSource>,
+    },
+    #[error("query error: {message}")]
+    QueryError {
+        message: String,
+        #[source]
+        source: Option<ErrorSource>,
+    },
+    #[error("entity not found: {kind} {id}")]
+    NotFound { kind: EntityKind, id: i64 },
+    #[error("invalid input: {message}")]
+    InvalidInput {
+        message: String,
+        #[source]
+        source: Option<ErrorSource>,
+    },
     #[error("fault injected: {0}")]
     FaultInjected(String),
-    #[error("transaction error: {0}")]
-    TransactionError(String),
+    #[error("transaction error: {message}")]
+    TransactionError {
+        message: String,
+        #[source]
+        source: Option<ErrorSource>,
+    },
     #[error("validation error: {0}")]
     ValidationError(String),
+    #[error("cycle detected: {0}")]
+    CycleDetected(String),
+    #[error("read-only: {0}")]
+    ReadOnly(String),
+    #[error("cancelled: {0}")]
+    Cancelled(String),
+}
+
+/// Message and optional underlying error accepted by the [`SqliteGraphError`]
+/// constructors.
+///
+/// Plain strings carry no source; SQLite, I/O, JSON, UTF-8 and DSL errors are kept
+/// as the source and their `Display` output becomes the message.
+#[derive(Debug)]
+pub struct ErrorDetail {
+    message: String,
+    source: Option<ErrorSource>,
+}
+
+impl ErrorDetail {
+    fn from_error(err: impl StdError + Send + Sync + 'static) -> Self {
+        ErrorDetail {
+            message: err.to_string(),
+            source: Some(Box::new(err)),
+        }
+    }
+}
+
+impl From<String> for ErrorDetail {
+    fn from(message: String) -> Self {
+        ErrorDetail {
+            message,
+            source: None,
+        }
+    }
+}
+
+impl From<&String> for ErrorDetail {
+    fn from(message: &String) -> Self {
+        ErrorDetail::from(message.clone())
+    }
+}
+
+impl From<&str> for ErrorDetail {
+    fn from(message: &str) -> Self {
+        ErrorDetail::from(message.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for ErrorDetail {
+    fn from(err: rusqlite::Error) -> Self {
+        ErrorDetail::from_error(err)
+    }
+}
+
+impl From<std::io::Error> for ErrorDetail {
+    fn from(err: std::io::Error) -> Self {
+        ErrorDetail::from_error(err)
+    }
+}
+
+impl From<serde_json::Error> for ErrorDetail {
+    fn from(err: serde_json::Error) -> Self {
+        ErrorDetail::from_error(err)
+    }
+}
+
+impl From<std::str::Utf8Error> for ErrorDetail {
+    fn from(err: std::str::Utf8Error) -> Self {
+        ErrorDetail::from_error(err)
+    }
+}
+
+impl From<std::string::FromUtf8Error> for ErrorDetail {
+    fn from(err: std::string::FromUtf8Error) -> Self {
+        ErrorDetail::from_error(err)
+    }
+}
+
+impl From<crate::dsl::DslError> for ErrorDetail {
+    fn from(err: crate::dsl::DslError) -> Self {
+        ErrorDetail::from_error(err)
+    }
 }
 
 impl SqliteGraphError {
-    pub fn connection<T: Into<String>>(msg: T) -> Self {
-        SqliteGraphError::ConnectionError(msg.into())
+    /// Whether the operation failed only because another connection held a
+    /// lock (`SQLITE_BUSY` or `SQLITE_LOCKED`), so running it again may succeed.
+    ///
+    /// Only errors that kept the underlying [`rusqlite::Error`] as their source
+    /// are recognised.
+    pub fn is_retryable(&self) -> bool {
+        let mut source = StdError::source(self);
+        while let Some(err) = source {
+            if let Some(code) = err
+                .downcast_ref::<rusqlite::Error>()
+                .and_then(rusqlite::Error::sqlite_error_code)
+            {
+                return matches!(
+                    code,
+                    rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+                );
+            }
+            source = err.source();
+        }
+        false
     }
 
-    pub fn schema<T: Into<String>>(msg: T) -> Self {
-        SqliteGraphError::SchemaError(msg.into())
+    pub fn connection<T: Into<ErrorDetail>>(detail: T) -> Self {
+        let ErrorDetail { message, source } = detail.into();
+        SqliteGraphError::ConnectionError { message, source }
     }
 
-    pub fn query<T: Into<String>>(msg: T) -> Self {
-        SqliteGraphError::QueryError(msg.into())
+    pub fn schema<T: Into<ErrorDetail>>(detail: T) -> Self {
+        let ErrorDetail { message, source } = detail.into();
+        SqliteGraphError::SchemaError { message, source }
     }
 
-    pub fn not_found<T: Into<String>>(msg: T) -> Self {
-        SqliteGraphError::NotFound(msg.into())
+    pub fn query<T: Into<ErrorDetail>>(detail: T) -> Self {
+        let ErrorDetail { message, source } = detail.into();
+        SqliteGraphError::QueryError { message, source }
     }
 
-    pub fn invalid_input<T: Into<String>>(msg: T) -> Self {
-        SqliteGraphError::InvalidInput(msg.into())
+    pub fn not_found(kind: EntityKind, id: i64) -> Self {
+        SqliteGraphError::NotFound { kind, id }
+    }
+
+    pub fn invalid_input<T: Into<ErrorDetail>>(detail: T) -> Self {
+        let ErrorDetail { message, source } = detail.into();
+        SqliteGraphError::InvalidInput { message, source }
     }
 
     pub fn fault_injection<T: Into<String>>(msg: T) -> Self {
         SqliteGraphError::FaultInjected(msg.into())
     }
 
-    pub fn transaction<T: Into<String>>(msg: T) -> Self {
-        SqliteGraphError::TransactionError(msg.into())
+    pub fn transaction<T: Into<ErrorDetail>>(detail: T) -> Self {
+        let ErrorDetail { message, source } = detail.into();
+        SqliteGraphError::TransactionError { message, source }
     }
 
     pub fn validation<T: Into<String>>(msg: T) -> Self {
         SqliteGraphError::ValidationError(msg.into())
     }
+
+    pub fn cycle_detected<T: Into<String>>(msg: T) -> Self {
+        SqliteGraphError::CycleDetected(msg.into())
+    }
+
+    pub fn read_only<T: Into<String>>(msg: T) -> Self {
+        SqliteGraphError::ReadOnly(msg.into())
+    }
+
+    pub fn cancelled<T: Into<String>>(msg: T) -> Self {
+        SqliteGraphError::Cancelled(msg.into())
+    }
 }
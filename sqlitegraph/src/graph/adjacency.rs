@@ -1,11 +1,19 @@
 //! Adjacency queries and cache management for SqliteGraph.
 
+use ahash::AHashMap;
 use rusqlite::{OptionalExtension, params};
 
-use crate::{cache::AdjacencyCache, errors::SqliteGraphError};
+use crate::{
+    backend::{BackendDirection, merge_undirected},
+    cache::AdjacencyCache,
+    errors::SqliteGraphError,
+};
 
 use super::{SqliteGraph, metrics::InstrumentedConnection};
 
+/// SQLite's default limit on bound parameters per statement.
+pub(crate) const MAX_SQL_VARIABLES: usize = 999;
+
 impl SqliteGraph {
     pub(crate) fn connection(&self) -> InstrumentedConnection<'_> {
         InstrumentedConnection::new(&self.conn, &self.metrics, &self.statement_tracker)
@@ -16,14 +24,26 @@ impl SqliteGraph {
         &self.conn
     }
 
+    /// Run `f` against the underlying connection for SQL the graph API does
+    /// not cover, such as custom joins and aggregates.
+    ///
+    /// Meant for reads. Writes bypass the adjacency and query caches, which
+    /// may then keep serving stale results, and changing the schema is
+    /// unsupported and can leave the graph unusable. A failure in `f` is
+    /// returned as [`SqliteGraphError::QueryError`] with the SQLite error as
+    /// its source.
+    pub fn with_connection<T, F>(&self, f: F) -> Result<T, SqliteGraphError>
+    where
+        F: FnOnce(&rusqlite::Connection) -> rusqlite::Result<T>,
+    {
+        f(&self.conn).map_err(SqliteGraphError::query)
+    }
+
     pub(crate) fn fetch_outgoing(&self, id: i64) -> Result<Vec<i64>, SqliteGraphError> {
         if let Some(cached) = self.outgoing_cache.get(id) {
             return Ok(cached);
         }
-        let result = self.collect_adjacency(
-            "SELECT to_id FROM graph_edges WHERE from_id=?1 ORDER BY to_id, edge_type, id",
-            id,
-        )?;
+        let result = self.load_outgoing(id)?;
         self.outgoing_cache.insert(id, result.clone());
         Ok(result)
     }
@@ -32,19 +52,228 @@ impl SqliteGraph {
         if let Some(cached) = self.incoming_cache.get(id) {
             return Ok(cached);
         }
-        let result = self.collect_adjacency(
-            "SELECT from_id FROM graph_edges WHERE to_id=?1 ORDER BY from_id, edge_type, id",
-            id,
-        )?;
+        let result = self.load_incoming(id)?;
         self.incoming_cache.insert(id, result.clone());
         Ok(result)
     }
 
+    /// Outgoing neighbors of `id` read from the database, bypassing the cache.
+    pub(crate) fn load_outgoing(&self, id: i64) -> Result<Vec<i64>, SqliteGraphError> {
+        self.collect_adjacency(
+            "SELECT to_id FROM graph_edges WHERE from_id=?1 \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=to_id) \
+             ORDER BY to_id, edge_type, id",
+            id,
+        )
+    }
+
+    /// Incoming neighbors of `id` read from the database, bypassing the cache.
+    pub(crate) fn load_incoming(&self, id: i64) -> Result<Vec<i64>, SqliteGraphError> {
+        self.collect_adjacency(
+            "SELECT from_id FROM graph_edges WHERE to_id=?1 \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=from_id) \
+             ORDER BY from_id, edge_type, id",
+            id,
+        )
+    }
+
+    /// One page of the neighbors of `id` along `anchor` -> `other`, ordered like
+    /// `load_outgoing`, together with the total number of neighbors.
+    pub(crate) fn load_adjacency_page(
+        &self,
+        id: i64,
+        anchor: &str,
+        other: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<i64>, usize), SqliteGraphError> {
+        let filter = format!(
+            "FROM graph_edges WHERE {anchor}=?1 \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id={other})"
+        );
+        let conn = self.connection();
+        let total: i64 = conn
+            .query_row(&format!("SELECT COUNT(*) {filter}"), params![id], |row| {
+                row.get(0)
+            })
+            .map_err(SqliteGraphError::query)?;
+        let mut stmt = conn
+            .prepare_cached(&format!(
+                "SELECT {other} {filter} ORDER BY {other}, edge_type, id LIMIT ?2 OFFSET ?3"
+            ))
+            .map_err(SqliteGraphError::query)?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let offset = i64::try_from(offset).unwrap_or(i64::MAX);
+        let rows = stmt
+            .query_map(params![id, limit, offset], |row| row.get(0))
+            .map_err(SqliteGraphError::query)?;
+        let mut page = Vec::new();
+        for item in rows {
+            page.push(item.map_err(SqliteGraphError::query)?);
+        }
+        Ok((page, total as usize))
+    }
+
+    /// Number of live neighbors of `id` in `direction`, optionally over edges
+    /// of `edge_type` only; `Both` counts each neighbor once.
+    pub(crate) fn count_neighbors(
+        &self,
+        id: i64,
+        direction: BackendDirection,
+        edge_type: Option<&str>,
+    ) -> Result<usize, SqliteGraphError> {
+        let (counted, anchor) = match direction {
+            BackendDirection::Outgoing => ("COUNT(*)", "from_id=?1"),
+            BackendDirection::Incoming => ("COUNT(*)", "to_id=?1"),
+            BackendDirection::Both => (
+                "COUNT(DISTINCT CASE WHEN from_id=?1 THEN to_id ELSE from_id END)",
+                "(from_id=?1 OR to_id=?1)",
+            ),
+        };
+        let other = match direction {
+            BackendDirection::Outgoing => "to_id",
+            BackendDirection::Incoming => "from_id",
+            BackendDirection::Both => "CASE WHEN from_id=?1 THEN to_id ELSE from_id END",
+        };
+        let sql = format!(
+            "SELECT {counted} FROM graph_edges WHERE {anchor} \
+             AND (?2 IS NULL OR edge_type=?2) \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id={other})"
+        );
+        let count: i64 = self
+            .connection()
+            .query_row(&sql, params![id, edge_type], |row| row.get(0))
+            .map_err(SqliteGraphError::query)?;
+        Ok(count as usize)
+    }
+
+    /// Cached neighbors of `id` in `direction`; `Both` merges the two orientations.
+    pub(crate) fn fetch_neighbors(
+        &self,
+        id: i64,
+        direction: BackendDirection,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        match direction {
+            BackendDirection::Outgoing => self.fetch_outgoing(id),
+            BackendDirection::Incoming => self.fetch_incoming(id),
+            BackendDirection::Both => Ok(merge_undirected(
+                self.fetch_outgoing(id)?,
+                self.fetch_incoming(id)?,
+            )),
+        }
+    }
+
+    /// Load the outgoing and incoming adjacency of `ids` into the caches.
+    ///
+    /// Runs one query per direction (per 999 ids) instead of one per node, so
+    /// latency-sensitive callers can pay for cold lookups up front. Nodes
+    /// without edges are cached as empty lists. Hit and miss counters are not
+    /// affected.
+    pub fn warm_adjacency_cache(&self, ids: &[i64]) -> Result<(), SqliteGraphError> {
+        let mut unique = ids.to_vec();
+        unique.sort_unstable();
+        unique.dedup();
+        let directions = [
+            (&self.outgoing_cache, "from_id", "to_id"),
+            (&self.incoming_cache, "to_id", "from_id"),
+        ];
+        for (cache, anchor, other) in directions {
+            for (id, neighbors) in self.load_adjacency_batch(&unique, anchor, other)? {
+                cache.insert(id, neighbors);
+            }
+        }
+        Ok(())
+    }
+
+    /// Warm the adjacency caches for every live entity, up to `limit` of them.
+    ///
+    /// Entities are taken in id order. Returns how many were warmed. Meant for
+    /// graphs small enough to keep all adjacency in memory.
+    pub fn warm_all_adjacency(&self, limit: Option<usize>) -> Result<usize, SqliteGraphError> {
+        let mut ids = self.all_entity_ids()?;
+        if let Some(limit) = limit {
+            ids.truncate(limit);
+        }
+        self.warm_adjacency_cache(&ids)?;
+        Ok(ids.len())
+    }
+
+    /// Neighbors of each of the sorted, unique `ids`, ordered like `load_outgoing`
+    fn load_adjacency_batch(
+        &self,
+        ids: &[i64],
+        anchor: &str,
+        other: &str,
+    ) -> Result<AHashMap<i64, Vec<i64>>, SqliteGraphError> {
+        let mut result: AHashMap<i64, Vec<i64>> = ids.iter().map(|&id| (id, Vec::new())).collect();
+        let conn = self.connection();
+        for chunk in ids.chunks(MAX_SQL_VARIABLES) {
+            let placeholders = vec!["?"; chunk.len()].join(",");
+            let sql = format!(
+                "SELECT {anchor}, {other} FROM graph_edges WHERE {anchor} IN ({placeholders}) \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id={other}) \
+                 ORDER BY {anchor}, {other}, edge_type, id"
+            );
+            let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(chunk), |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+                })
+                .map_err(SqliteGraphError::query)?;
+            for row in rows {
+                let (id, neighbor) = row.map_err(SqliteGraphError::query)?;
+                result.entry(id).or_default().push(neighbor);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Neighbors of `id` in `direction` read from the database, bypassing the cache.
+    pub(crate) fn load_neighbors(
+        &self,
+        id: i64,
+        direction: BackendDirection,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        match direction {
+            BackendDirection::Outgoing => self.load_outgoing(id),
+            BackendDirection::Incoming => self.load_incoming(id),
+            BackendDirection::Both => Ok(merge_undirected(
+                self.load_outgoing(id)?,
+                self.load_incoming(id)?,
+            )),
+        }
+    }
+
     pub(crate) fn invalidate_caches(&self) {
         self.outgoing_cache.clear();
         self.incoming_cache.clear();
     }
 
+    /// Fold a freshly inserted edge into any warm cache entries for its endpoints.
+    pub(crate) fn record_inserted_edge(&self, from_id: i64, to_id: i64) {
+        self.outgoing_cache.insert_neighbor(from_id, to_id);
+        self.incoming_cache.insert_neighbor(to_id, from_id);
+    }
+
+    /// Invalidate the cached adjacency of a single node.
+    ///
+    /// Drops the outgoing and incoming entries for `id`, plus the reverse entries
+    /// of the neighbors those cached lists pointed at, leaving the rest of the
+    /// cache warm. Nodes the cache never saw as neighbors of `id` are not touched,
+    /// so after adding edges externally call this for every affected endpoint.
+    pub fn invalidate_node_cache(&self, id: i64) {
+        if let Some(targets) = self.outgoing_cache.take(id) {
+            for target in targets {
+                self.incoming_cache.remove(target);
+            }
+        }
+        if let Some(sources) = self.incoming_cache.take(id) {
+            for source in sources {
+                self.outgoing_cache.remove(source);
+            }
+        }
+    }
+
     pub fn outgoing_cache_ref(&self) -> &AdjacencyCache {
         &self.outgoing_cache
     }
@@ -56,43 +285,62 @@ impl SqliteGraph {
     pub(crate) fn all_entity_ids(&self) -> Result<Vec<i64>, SqliteGraphError> {
         let conn = self.connection();
         let mut stmt = conn
-            .prepare_cached("SELECT id FROM graph_entities ORDER BY id")
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .prepare_cached(
+                "SELECT id FROM graph_entities e \
+                 WHERE NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+                 ORDER BY id",
+            )
+            .map_err(SqliteGraphError::query)?;
         let rows = stmt
             .query_map([], |row| row.get(0))
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         let mut ids = Vec::new();
         for id in rows {
-            ids.push(id.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+            ids.push(id.map_err(SqliteGraphError::query)?);
         }
         Ok(ids)
     }
 
     fn collect_adjacency(&self, sql: &str, id: i64) -> Result<Vec<i64>, SqliteGraphError> {
         let conn = self.connection();
-        let mut stmt = conn
-            .prepare_cached(sql)
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        let mut stmt = conn.prepare_cached(sql).map_err(SqliteGraphError::query)?;
         let rows = stmt
             .query_map(params![id], |row| row.get(0))
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         let mut result = Vec::new();
         for item in rows {
-            result.push(item.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+            result.push(item.map_err(SqliteGraphError::query)?);
         }
         Ok(result)
     }
 
+    /// `(from_id, to_id)` of every stored edge, ordered by edge id.
+    pub(crate) fn edge_endpoints(&self) -> Result<Vec<(i64, i64)>, SqliteGraphError> {
+        let conn = self.connection();
+        let mut stmt = conn
+            .prepare_cached("SELECT from_id, to_id FROM graph_edges ORDER BY id")
+            .map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(SqliteGraphError::query)?;
+        let mut edges = Vec::new();
+        for row in rows {
+            edges.push(row.map_err(SqliteGraphError::query)?);
+        }
+        Ok(edges)
+    }
+
     pub(crate) fn entity_exists(&self, id: i64) -> Result<bool, SqliteGraphError> {
         let exists: Option<i64> = self
             .connection()
             .query_row(
-                "SELECT 1 FROM graph_entities WHERE id=?1",
+                "SELECT 1 FROM graph_entities WHERE id=?1 \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=?1)",
                 params![id],
                 |row| row.get(0),
             )
             .optional()
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         Ok(exists.is_some())
     }
 }
@@ -1,14 +1,23 @@
 //! Core SqliteGraph struct and construction functionality.
 
-use std::path::Path;
+use std::{cell::Cell, path::Path};
 
-use rusqlite::Connection;
+use rusqlite::{Connection, DatabaseName, OpenFlags};
 
 use crate::{
-    cache::AdjacencyCache, errors::SqliteGraphError, mvcc::SnapshotManager, schema::ensure_schema,
+    cache::AdjacencyCache,
+    data_format::DataFormat,
+    errors::SqliteGraphError,
+    mvcc::SnapshotManager,
+    schema::{self, ensure_schema},
 };
 
-use super::metrics::{GraphMetrics, StatementTracker};
+use super::{
+    metrics::{
+        GraphMetrics, StatementTracker, statement_tracker::DEFAULT_STATEMENT_CACHE_CAPACITY,
+    },
+    types::validate_data_size,
+};
 
 /// Embedded SQLite-backed graph database.
 ///
@@ -21,8 +30,16 @@ pub struct SqliteGraph {
     pub(crate) metrics: GraphMetrics,
     pub(crate) statement_tracker: StatementTracker,
     pub(crate) snapshot_manager: SnapshotManager,
+    pub(crate) max_data_bytes: Option<usize>,
+    pub(crate) deferred_integrity: bool,
+    pub(crate) reject_self_loops: bool,
+    pub(crate) read_only: bool,
+    pub(crate) data_format: DataFormat,
+    pub(crate) transaction_active: Cell<bool>,
 }
 
+const DATA_FORMAT_SETTING: &str = "data_format";
+
 // Helper function to check if connection is in-memory
 fn is_in_memory_connection(conn: &Connection) -> bool {
     // Check database filename - in-memory databases have empty or special names
@@ -37,40 +54,55 @@ fn is_in_memory_connection(conn: &Connection) -> bool {
 
 impl SqliteGraph {
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SqliteGraphError> {
-        let conn =
-            Connection::open(path).map_err(|e| SqliteGraphError::connection(e.to_string()))?;
+        let conn = Connection::open(path).map_err(SqliteGraphError::connection)?;
         ensure_schema(&conn)?;
-        Ok(Self::from_connection(conn))
+        Self::from_connection(conn)
     }
 
     pub fn open_without_migrations<P: AsRef<Path>>(path: P) -> Result<Self, SqliteGraphError> {
-        let conn =
-            Connection::open(path).map_err(|e| SqliteGraphError::connection(e.to_string()))?;
+        let conn = Connection::open(path).map_err(SqliteGraphError::connection)?;
         crate::schema::ensure_schema_without_migrations(&conn)?;
-        Ok(Self::from_connection(conn))
+        Self::from_connection(conn)
+    }
+
+    /// Open an existing database without write access.
+    ///
+    /// The connection is opened with `SQLITE_OPEN_READONLY`, so the file can be
+    /// read while another process writes to it. No migrations run and no
+    /// settings are persisted; mutating calls fail with
+    /// [`SqliteGraphError::ReadOnly`].
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self, SqliteGraphError> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_URI
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(SqliteGraphError::connection)?;
+        Self::from_connection(conn)
     }
 
     pub fn open_in_memory() -> Result<Self, SqliteGraphError> {
-        let conn = Connection::open_in_memory()
-            .map_err(|e| SqliteGraphError::connection(e.to_string()))?;
+        let conn = Connection::open_in_memory().map_err(SqliteGraphError::connection)?;
         ensure_schema(&conn)?;
-        Ok(Self::from_connection(conn))
+        Self::from_connection(conn)
     }
 
     pub fn open_in_memory_without_migrations() -> Result<Self, SqliteGraphError> {
-        let conn = Connection::open_in_memory()
-            .map_err(|e| SqliteGraphError::connection(e.to_string()))?;
+        let conn = Connection::open_in_memory().map_err(SqliteGraphError::connection)?;
         crate::schema::ensure_schema_without_migrations(&conn)?;
-        Ok(Self::from_connection(conn))
+        Self::from_connection(conn)
     }
 
-    fn from_connection(conn: Connection) -> Self {
-        conn.set_prepared_statement_cache_capacity(128);
+    pub(super) fn from_connection(conn: Connection) -> Result<Self, SqliteGraphError> {
+        conn.set_prepared_statement_cache_capacity(DEFAULT_STATEMENT_CACHE_CAPACITY);
+        let read_only = conn.is_readonly(DatabaseName::Main).unwrap_or(false);
 
         // Configure WAL mode and performance optimizations for file-based databases
         if !is_in_memory_connection(&conn) {
-            // Enable WAL mode for better concurrency
-            if let Err(_e) = conn.pragma_update(None, "journal_mode", "WAL") {
+            // Enable WAL mode for better concurrency; the journal mode is stored
+            // in the file, so a read-only connection keeps whatever it finds
+            if !read_only && conn.pragma_update(None, "journal_mode", "WAL").is_err() {
                 // Fallback to DELETE mode if WAL fails (e.g., on some network filesystems)
                 let _ = conn.pragma_update(None, "journal_mode", "DELETE");
             }
@@ -82,13 +114,109 @@ impl SqliteGraph {
             let _ = conn.pragma_update(None, "mmap_size", "268435456"); // 256MB memory-mapped I/O
         }
 
-        Self {
+        let data_format = match schema::read_setting(&conn, DATA_FORMAT_SETTING)? {
+            Some(name) => DataFormat::from_name(&name)
+                .ok_or_else(|| SqliteGraphError::schema(format!("unknown data format '{name}'")))?,
+            None => DataFormat::Json,
+        };
+
+        Ok(Self {
             conn,
             outgoing_cache: AdjacencyCache::new(),
             incoming_cache: AdjacencyCache::new(),
             metrics: GraphMetrics::default(),
             statement_tracker: StatementTracker::default(),
             snapshot_manager: SnapshotManager::new(),
+            max_data_bytes: None,
+            deferred_integrity: false,
+            reject_self_loops: false,
+            read_only,
+            data_format,
+            transaction_active: Cell::new(false),
+        })
+    }
+
+    /// Limit the serialized size of entity and edge `data` payloads.
+    ///
+    /// Inserts and updates whose JSON payload exceeds `limit` bytes are rejected
+    /// with [`SqliteGraphError::InvalidInput`]. `None` disables the check.
+    pub fn set_max_data_bytes(&mut self, limit: Option<usize>) {
+        self.max_data_bytes = limit;
+    }
+
+    /// Current payload size limit, if any.
+    pub fn max_data_bytes(&self) -> Option<usize> {
+        self.max_data_bytes
+    }
+
+    /// Allow `insert_edge` to reference entities that do not exist yet.
+    ///
+    /// Intended for out-of-order streaming loads; call
+    /// [`check_integrity`](Self::check_integrity) once loading is done.
+    pub fn set_deferred_integrity(&mut self, deferred: bool) {
+        self.deferred_integrity = deferred;
+    }
+
+    /// Whether edge endpoint checks are deferred.
+    pub fn deferred_integrity(&self) -> bool {
+        self.deferred_integrity
+    }
+
+    /// Reject edges whose `from_id` equals their `to_id`.
+    ///
+    /// Such inserts fail with [`SqliteGraphError::InvalidInput`]. Self-loops
+    /// already stored are left alone; see [`crate::safety::validate_no_self_loops`].
+    pub fn set_reject_self_loops(&mut self, reject: bool) {
+        self.reject_self_loops = reject;
+    }
+
+    /// Whether self-loop edges are rejected on insert.
+    pub fn reject_self_loops(&self) -> bool {
+        self.reject_self_loops
+    }
+
+    /// Whether the database was opened without write access.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Fail with [`SqliteGraphError::ReadOnly`] if writes are not allowed.
+    pub(crate) fn ensure_writable(&self) -> Result<(), SqliteGraphError> {
+        if self.read_only {
+            return Err(SqliteGraphError::read_only("graph was opened read-only"));
         }
+        Ok(())
+    }
+
+    /// Choose the encoding for `data` payloads written from now on.
+    ///
+    /// The choice is persisted in the database. Rows already written keep their
+    /// encoding and still decode, since JSON is stored as TEXT and MessagePack as
+    /// BLOB. SQL-level JSON functions only see JSON rows.
+    pub fn set_data_format(&mut self, format: DataFormat) -> Result<(), SqliteGraphError> {
+        self.ensure_writable()?;
+        schema::write_setting(&self.conn, DATA_FORMAT_SETTING, format.as_str())?;
+        self.data_format = format;
+        Ok(())
+    }
+
+    /// Encoding used for newly written `data` payloads.
+    pub fn data_format(&self) -> DataFormat {
+        self.data_format
+    }
+
+    /// Encode a `data` payload for storage, enforcing the payload size limit.
+    pub(crate) fn encode_data(
+        &self,
+        data: &serde_json::Value,
+    ) -> Result<rusqlite::types::Value, SqliteGraphError> {
+        let encoded = self.data_format.encode(data)?;
+        validate_data_size(encoded.len(), self.max_data_bytes)?;
+        Ok(match self.data_format {
+            DataFormat::Json => rusqlite::types::Value::Text(
+                String::from_utf8(encoded).map_err(SqliteGraphError::invalid_input)?,
+            ),
+            DataFormat::MessagePack => rusqlite::types::Value::Blob(encoded),
+        })
     }
 }
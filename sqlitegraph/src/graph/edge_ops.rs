@@ -1,34 +1,88 @@
 //! Edge CRUD operations for SqliteGraph.
 
-use rusqlite::params;
+use rusqlite::{OptionalExtension, params};
 
-use crate::errors::SqliteGraphError;
+use crate::{
+    backend::NodeEdges,
+    errors::{EntityKind, SqliteGraphError},
+};
 
 use super::{
     SqliteGraph,
-    types::{GraphEdge, row_to_edge, validate_edge},
+    types::{GraphEdge, MutationOutcome, row_to_edge, validate_edge},
 };
 
 impl SqliteGraph {
     pub fn insert_edge(&self, edge: &GraphEdge) -> Result<i64, SqliteGraphError> {
+        self.ensure_writable()?;
         validate_edge(edge)?;
-        if !self.entity_exists(edge.from_id)? || !self.entity_exists(edge.to_id)? {
-            return Err(SqliteGraphError::invalid_input(
-                "edge endpoints must reference existing entities",
-            ));
+        self.check_self_loop(edge.from_id, edge.to_id)?;
+        if !self.deferred_integrity {
+            for id in [edge.from_id, edge.to_id] {
+                if !self.entity_exists(id)? {
+                    return Err(SqliteGraphError::not_found(EntityKind::Node, id));
+                }
+            }
         }
-        let data = serde_json::to_string(&edge.data)
-            .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+        let data = self.encode_data(&edge.data)?;
         self.connection()
             .execute(
                 "INSERT INTO graph_edges(from_id, to_id, edge_type, data) VALUES(?1, ?2, ?3, ?4)",
                 params![edge.from_id, edge.to_id, edge.edge_type.as_str(), data],
             )
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-        self.invalidate_caches();
+            .map_err(SqliteGraphError::query)?;
+        self.record_inserted_edge(edge.from_id, edge.to_id);
         Ok(self.conn.last_insert_rowid())
     }
 
+    /// Insert `edge`, or replace the `data` of an existing edge with the same
+    /// `(from_id, to_id, edge_type)`.
+    ///
+    /// Two edges are duplicates when that tuple matches; their `data` is not
+    /// compared. Returns the id of the inserted or updated edge. When several
+    /// matching edges already exist, the one with the lowest id is updated.
+    pub fn upsert_edge(&self, edge: &GraphEdge) -> Result<i64, SqliteGraphError> {
+        self.ensure_writable()?;
+        validate_edge(edge)?;
+        self.check_self_loop(edge.from_id, edge.to_id)?;
+        match self.find_edge_id(edge.from_id, edge.to_id, &edge.edge_type)? {
+            Some(id) => {
+                self.update_edge_data(id, &edge.data)?;
+                Ok(id)
+            }
+            None => self.insert_edge(edge),
+        }
+    }
+
+    /// Lowest id of a live edge matching `(from_id, to_id, edge_type)`.
+    pub(crate) fn find_edge_id(
+        &self,
+        from_id: i64,
+        to_id: i64,
+        edge_type: &str,
+    ) -> Result<Option<i64>, SqliteGraphError> {
+        self.connection()
+            .query_row(
+                "SELECT id FROM graph_edges e WHERE from_id=?1 AND to_id=?2 AND edge_type=?3 \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+                 WHERE t.entity_id IN (e.from_id, e.to_id)) \
+                 ORDER BY id LIMIT 1",
+                params![from_id, to_id, edge_type],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(SqliteGraphError::query)
+    }
+
+    pub(crate) fn check_self_loop(&self, from_id: i64, to_id: i64) -> Result<(), SqliteGraphError> {
+        if self.reject_self_loops && from_id == to_id {
+            return Err(SqliteGraphError::invalid_input(format!(
+                "self-loop edge on entity {from_id} rejected"
+            )));
+        }
+        Ok(())
+    }
+
     pub fn get_edge(&self, id: i64) -> Result<GraphEdge, SqliteGraphError> {
         self.connection()
             .query_row(
@@ -38,21 +92,113 @@ impl SqliteGraph {
             )
             .map_err(|err| match err {
                 rusqlite::Error::QueryReturnedNoRows => {
-                    SqliteGraphError::not_found(format!("edge {id}"))
+                    SqliteGraphError::not_found(EntityKind::Edge, id)
                 }
-                other => SqliteGraphError::query(other.to_string()),
+                other => SqliteGraphError::query(other),
             })
     }
 
-    pub fn delete_edge(&self, id: i64) -> Result<(), SqliteGraphError> {
+    /// Fetch an edge unless it is missing or touches a soft-deleted entity.
+    pub(crate) fn get_live_edge(&self, id: i64) -> Result<GraphEdge, SqliteGraphError> {
+        self.connection()
+            .query_row(
+                "SELECT id, from_id, to_id, edge_type, data FROM graph_edges e WHERE id=?1 \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+                 WHERE t.entity_id IN (e.from_id, e.to_id))",
+                params![id],
+                row_to_edge,
+            )
+            .map_err(|err| match err {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    SqliteGraphError::not_found(EntityKind::Edge, id)
+                }
+                other => SqliteGraphError::query(other),
+            })
+    }
+
+    /// Ids of every edge whose endpoints are both live, in ascending order.
+    pub(crate) fn live_edge_ids(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        let conn = self.connection();
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT id FROM graph_edges e \
+                 WHERE NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+                 WHERE t.entity_id IN (e.from_id, e.to_id)) \
+                 ORDER BY id",
+            )
+            .map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(SqliteGraphError::query)?;
+        let mut ids = Vec::new();
+        for id in rows {
+            ids.push(id.map_err(SqliteGraphError::query)?);
+        }
+        Ok(ids)
+    }
+
+    /// Replace only the `data` of an edge; endpoints and type are unchanged.
+    pub fn update_edge_data(
+        &self,
+        id: i64,
+        data: &serde_json::Value,
+    ) -> Result<MutationOutcome, SqliteGraphError> {
+        self.ensure_writable()?;
+        let data = self.encode_data(data)?;
+        let affected = self
+            .connection()
+            .execute(
+                "UPDATE graph_edges SET data=?1 WHERE id=?2",
+                params![data, id],
+            )
+            .map_err(SqliteGraphError::query)?;
+        if affected == 0 {
+            return Err(SqliteGraphError::not_found(EntityKind::Edge, id));
+        }
+        Ok(MutationOutcome::affected(affected))
+    }
+
+    /// Edges touching `id`, optionally only those whose other endpoint carries `label`.
+    pub(crate) fn fetch_node_edges(
+        &self,
+        id: i64,
+        label: Option<&str>,
+    ) -> Result<NodeEdges, SqliteGraphError> {
+        self.get_entity(id)?;
+        let mut edges = NodeEdges::default();
+        let queries = [
+            (&mut edges.outgoing, "from_id", "to_id"),
+            (&mut edges.incoming, "to_id", "from_id"),
+        ];
+        for (target, anchor, other) in queries {
+            let sql = format!(
+                "SELECT id, from_id, to_id, edge_type, data FROM graph_edges WHERE {anchor}=?1 \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id={other}) \
+                 AND (?2 IS NULL OR EXISTS (SELECT 1 FROM graph_labels gl WHERE gl.entity_id={other} AND gl.label=?2)) \
+                 ORDER BY {other}, id"
+            );
+            let conn = self.connection();
+            let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+            let rows = stmt
+                .query_map(params![id, label], row_to_edge)
+                .map_err(SqliteGraphError::query)?;
+            for row in rows {
+                target.push(row.map_err(SqliteGraphError::query)?);
+            }
+        }
+        Ok(edges)
+    }
+
+    pub fn delete_edge(&self, id: i64) -> Result<MutationOutcome, SqliteGraphError> {
+        self.ensure_writable()?;
         let affected = self
             .connection()
             .execute("DELETE FROM graph_edges WHERE id=?1", params![id])
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         if affected == 0 {
-            return Err(SqliteGraphError::not_found(format!("edge {id}")));
+            return Err(SqliteGraphError::not_found(EntityKind::Edge, id));
         }
         self.invalidate_caches();
-        Ok(())
+        Ok(MutationOutcome::affected(affected))
     }
 }
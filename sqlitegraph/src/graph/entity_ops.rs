@@ -2,18 +2,18 @@
 
 use rusqlite::params;
 
-use crate::errors::SqliteGraphError;
+use crate::errors::{EntityKind, SqliteGraphError};
 
 use super::{
     SqliteGraph,
-    types::{GraphEntity, row_to_entity, validate_entity},
+    types::{GraphEntity, MutationOutcome, row_to_entity, validate_entity},
 };
 
 impl SqliteGraph {
     pub fn insert_entity(&self, entity: &GraphEntity) -> Result<i64, SqliteGraphError> {
+        self.ensure_writable()?;
         validate_entity(entity)?;
-        let data = serde_json::to_string(&entity.data)
-            .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+        let data = self.encode_data(&entity.data)?;
         self.connection()
             .execute(
                 "INSERT INTO graph_entities(kind, name, file_path, data) VALUES(?1, ?2, ?3, ?4)",
@@ -24,34 +24,35 @@ impl SqliteGraph {
                     data,
                 ],
             )
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         Ok(self.conn.last_insert_rowid())
     }
 
     pub fn get_entity(&self, id: i64) -> Result<GraphEntity, SqliteGraphError> {
         self.connection()
             .query_row(
-                "SELECT id, kind, name, file_path, data FROM graph_entities WHERE id=?1",
+                "SELECT id, kind, name, file_path, data FROM graph_entities WHERE id=?1 \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=?1)",
                 params![id],
                 row_to_entity,
             )
             .map_err(|err| match err {
                 rusqlite::Error::QueryReturnedNoRows => {
-                    SqliteGraphError::not_found(format!("entity {id}"))
+                    SqliteGraphError::not_found(EntityKind::Node, id)
                 }
-                other => SqliteGraphError::query(other.to_string()),
+                other => SqliteGraphError::query(other),
             })
     }
 
-    pub fn update_entity(&self, entity: &GraphEntity) -> Result<(), SqliteGraphError> {
+    pub fn update_entity(&self, entity: &GraphEntity) -> Result<MutationOutcome, SqliteGraphError> {
+        self.ensure_writable()?;
         if entity.id <= 0 {
             return Err(SqliteGraphError::invalid_input(
                 "entity id must be positive for update",
             ));
         }
         validate_entity(entity)?;
-        let data = serde_json::to_string(&entity.data)
-            .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+        let data = self.encode_data(&entity.data)?;
         let affected = self
             .connection()
             .execute(
@@ -64,29 +65,60 @@ impl SqliteGraph {
                     entity.id,
                 ],
             )
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         if affected == 0 {
-            return Err(SqliteGraphError::not_found(format!("entity {}", entity.id)));
+            return Err(SqliteGraphError::not_found(EntityKind::Node, entity.id));
         }
-        Ok(())
+        Ok(MutationOutcome::affected(affected))
     }
 
-    pub fn delete_entity(&self, id: i64) -> Result<(), SqliteGraphError> {
+    /// Replace only the `data` of an entity, leaving its edges untouched.
+    pub fn update_entity_data(
+        &self,
+        id: i64,
+        data: &serde_json::Value,
+    ) -> Result<MutationOutcome, SqliteGraphError> {
+        self.ensure_writable()?;
+        let data = self.encode_data(data)?;
+        let affected = self
+            .connection()
+            .execute(
+                "UPDATE graph_entities SET data=?1 WHERE id=?2 \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=?2)",
+                params![data, id],
+            )
+            .map_err(SqliteGraphError::query)?;
+        if affected == 0 {
+            return Err(SqliteGraphError::not_found(EntityKind::Node, id));
+        }
+        Ok(MutationOutcome::affected(affected))
+    }
+
+    /// Delete an entity and every edge touching it; `affected` counts both.
+    pub fn delete_entity(&self, id: i64) -> Result<MutationOutcome, SqliteGraphError> {
+        self.ensure_writable()?;
         let affected = self
             .connection()
             .execute("DELETE FROM graph_entities WHERE id=?1", params![id])
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         if affected == 0 {
-            return Err(SqliteGraphError::not_found(format!("entity {id}")));
+            return Err(SqliteGraphError::not_found(EntityKind::Node, id));
         }
-        self.connection()
+        let edges = self
+            .connection()
             .execute(
                 "DELETE FROM graph_edges WHERE from_id=?1 OR to_id=?1",
                 params![id],
             )
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
+        self.connection()
+            .execute(
+                "DELETE FROM graph_tombstones WHERE entity_id=?1",
+                params![id],
+            )
+            .map_err(SqliteGraphError::query)?;
         self.invalidate_caches();
-        Ok(())
+        Ok(MutationOutcome::affected(affected + edges))
     }
 
     pub fn list_entity_ids(&self) -> Result<Vec<i64>, SqliteGraphError> {
@@ -0,0 +1,86 @@
+//! Referential integrity checks for SqliteGraph.
+
+use crate::errors::SqliteGraphError;
+
+use super::SqliteGraph;
+
+/// An edge whose endpoints do not both reference existing entities.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DanglingEdge {
+    pub edge_id: i64,
+    pub from_id: i64,
+    pub to_id: i64,
+    /// `true` when `from_id` has no matching entity
+    pub missing_from: bool,
+    /// `true` when `to_id` has no matching entity
+    pub missing_to: bool,
+}
+
+/// Result of [`SqliteGraph::check_integrity`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Dangling edges ordered by edge id
+    pub dangling_edges: Vec<DanglingEdge>,
+}
+
+impl IntegrityReport {
+    /// Whether no violations were found.
+    pub fn is_clean(&self) -> bool {
+        self.dangling_edges.is_empty()
+    }
+}
+
+impl SqliteGraph {
+    /// Report every edge whose `from_id` or `to_id` has no matching entity.
+    ///
+    /// Use after a load performed with deferred integrity to confirm that all
+    /// edges eventually found their endpoints.
+    pub fn check_integrity(&self) -> Result<IntegrityReport, SqliteGraphError> {
+        Ok(IntegrityReport {
+            dangling_edges: self.dangling_edges(
+                "SELECT ge.id, ge.from_id, ge.to_id, src.id IS NULL, dst.id IS NULL \
+                 FROM graph_edges ge \
+                 LEFT JOIN graph_entities src ON src.id = ge.from_id \
+                 LEFT JOIN graph_entities dst ON dst.id = ge.to_id \
+                 WHERE src.id IS NULL OR dst.id IS NULL \
+                 ORDER BY ge.id",
+            )?,
+        })
+    }
+
+    /// Like [`check_integrity`](Self::check_integrity), but soft-deleted
+    /// entities count as missing too.
+    pub(crate) fn live_dangling_edges(&self) -> Result<Vec<DanglingEdge>, SqliteGraphError> {
+        self.dangling_edges(
+            "SELECT ge.id, ge.from_id, ge.to_id, \
+             src.id IS NULL OR src_t.entity_id IS NOT NULL, \
+             dst.id IS NULL OR dst_t.entity_id IS NOT NULL \
+             FROM graph_edges ge \
+             LEFT JOIN graph_entities src ON src.id = ge.from_id \
+             LEFT JOIN graph_tombstones src_t ON src_t.entity_id = ge.from_id \
+             LEFT JOIN graph_entities dst ON dst.id = ge.to_id \
+             LEFT JOIN graph_tombstones dst_t ON dst_t.entity_id = ge.to_id \
+             WHERE src.id IS NULL OR dst.id IS NULL \
+             OR src_t.entity_id IS NOT NULL OR dst_t.entity_id IS NOT NULL \
+             ORDER BY ge.id",
+        )
+    }
+
+    fn dangling_edges(&self, sql: &str) -> Result<Vec<DanglingEdge>, SqliteGraphError> {
+        let conn = self.connection();
+        let mut stmt = conn.prepare_cached(sql).map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(DanglingEdge {
+                    edge_id: row.get(0)?,
+                    from_id: row.get(1)?,
+                    to_id: row.get(2)?,
+                    missing_from: row.get(3)?,
+                    missing_to: row.get(4)?,
+                })
+            })
+            .map_err(SqliteGraphError::query)?;
+        rows.collect::<Result<_, _>>()
+            .map_err(SqliteGraphError::query)
+    }
+}
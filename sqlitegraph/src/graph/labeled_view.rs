@@ -0,0 +1,665 @@
+//! Read-only label-scoped view over a SqliteGraph.
+//!
+//! A [`LabeledView`] exposes the read subset of [`GraphBackend`] restricted to
+//! nodes carrying a single label. Nodes without the label behave as if they do
+//! not exist, and only edges whose two endpoints both carry the label are
+//! traversed, so queries issued through the view cannot cross into other
+//! label partitions (e.g. tenants sharing one database).
+
+use std::collections::{HashMap, VecDeque, hash_map::Entry};
+
+use ahash::{AHashMap, AHashSet};
+use rusqlite::{OptionalExtension, params};
+
+use crate::{
+    algo,
+    backend::{
+        BackendDirection, EdgeSpec, GraphBackend, NeighborQuery, NodeEdges, NodeSpec,
+        merge_undirected,
+    },
+    cache::CacheStats,
+    errors::{EntityKind, SqliteGraphError},
+    index,
+    multi_hop::{self, ChainStep},
+    pattern::{self, PatternMatch, PatternQuery},
+    safety::SafetyReport,
+};
+
+use super::{
+    GraphStatistics, SqliteGraph,
+    types::{GraphEdge, GraphEntity},
+};
+
+const VIEW_OUTGOING_SQL: &str = "SELECT ge.to_id, ge.edge_type FROM graph_edges ge \
+     WHERE ge.from_id=?1 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=ge.to_id) \
+     AND EXISTS (SELECT 1 FROM graph_labels gl WHERE gl.entity_id=ge.to_id AND gl.label=?2) \
+     ORDER BY ge.to_id, ge.edge_type, ge.id";
+const VIEW_INCOMING_SQL: &str = "SELECT ge.from_id, ge.edge_type FROM graph_edges ge \
+     WHERE ge.to_id=?1 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=ge.from_id) \
+     AND EXISTS (SELECT 1 FROM graph_labels gl WHERE gl.entity_id=ge.from_id AND gl.label=?2) \
+     ORDER BY ge.from_id, ge.edge_type, ge.id";
+
+/// Read-only view of a graph restricted to nodes carrying `label`.
+///
+/// Write methods of [`GraphBackend`] return [`SqliteGraphError::InvalidInput`].
+/// Edges leaving the view are dropped because one endpoint is not visible, so
+/// degrees, components and statistics only count edges inside the view.
+pub struct LabeledView<'a> {
+    graph: &'a SqliteGraph,
+    label: String,
+}
+
+impl SqliteGraph {
+    /// Create a read-only view that only sees nodes labeled `label` and the edges between them.
+    pub fn labeled_view(&self, label: &str) -> LabeledView<'_> {
+        LabeledView {
+            graph: self,
+            label: label.to_string(),
+        }
+    }
+}
+
+impl<'a> LabeledView<'a> {
+    /// Label every visible node must carry.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Whether `id` is visible through this view.
+    pub fn contains(&self, id: i64) -> Result<bool, SqliteGraphError> {
+        let exists: Option<i64> = self
+            .graph
+            .connection()
+            .query_row(
+                "SELECT 1 FROM graph_labels gl JOIN graph_entities e ON e.id=gl.entity_id \
+                 WHERE gl.entity_id=?1 AND gl.label=?2 \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+                 LIMIT 1",
+                params![id, self.label],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(SqliteGraphError::query)?;
+        Ok(exists.is_some())
+    }
+
+    fn require_visible(&self, id: i64) -> Result<(), SqliteGraphError> {
+        if self.contains(id)? {
+            Ok(())
+        } else {
+            Err(SqliteGraphError::not_found(EntityKind::Node, id))
+        }
+    }
+
+    /// Ids of every node visible through the view.
+    fn visible_ids(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        let conn = self.graph.connection();
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT DISTINCT gl.entity_id FROM graph_labels gl \
+                 JOIN graph_entities e ON e.id=gl.entity_id WHERE gl.label=?1 \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+                 ORDER BY gl.entity_id",
+            )
+            .map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map(params![self.label], |row| row.get(0))
+            .map_err(SqliteGraphError::query)?;
+        let mut ids = Vec::new();
+        for row in rows {
+            ids.push(row.map_err(SqliteGraphError::query)?);
+        }
+        Ok(ids)
+    }
+
+    fn read_only_error(&self) -> SqliteGraphError {
+        SqliteGraphError::invalid_input(format!("labeled view '{}' is read-only", self.label))
+    }
+
+    /// Neighbors of `node` inside the view, optionally restricted to `allowed_types`.
+    fn view_neighbors(
+        &self,
+        node: i64,
+        direction: BackendDirection,
+        allowed_types: Option<&AHashSet<&str>>,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        if !self.contains(node)? {
+            return Ok(Vec::new());
+        }
+        let sql = match direction {
+            BackendDirection::Outgoing => VIEW_OUTGOING_SQL,
+            BackendDirection::Incoming => VIEW_INCOMING_SQL,
+            BackendDirection::Both => {
+                return Ok(merge_undirected(
+                    self.view_neighbors(node, BackendDirection::Outgoing, allowed_types)?,
+                    self.view_neighbors(node, BackendDirection::Incoming, allowed_types)?,
+                ));
+            }
+        };
+        let conn = self.graph.connection();
+        let mut stmt = conn.prepare_cached(sql).map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map(params![node, self.label], |row| {
+                let neighbor: i64 = row.get(0)?;
+                let edge_type: String = row.get(1)?;
+                Ok((neighbor, edge_type))
+            })
+            .map_err(SqliteGraphError::query)?;
+        let mut result = Vec::new();
+        for row in rows {
+            let (neighbor, edge_type) = row.map_err(SqliteGraphError::query)?;
+            if allowed_types.map_or(true, |set| set.contains(edge_type.as_str())) {
+                result.push(neighbor);
+            }
+        }
+        Ok(result)
+    }
+
+    fn view_bfs(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+        allowed_types: Option<&AHashSet<&str>>,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        self.require_visible(start)?;
+        let mut visited = Vec::new();
+        let mut seen = AHashSet::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((start, 0));
+        seen.insert(start);
+        while let Some((node, level)) = queue.pop_front() {
+            visited.push(node);
+            if level >= depth {
+                continue;
+            }
+            for next in self.view_neighbors(node, direction, allowed_types)? {
+                if seen.insert(next) {
+                    queue.push_back((next, level + 1));
+                }
+            }
+        }
+        Ok(visited)
+    }
+
+    fn view_shortest_path(
+        &self,
+        start: i64,
+        end: i64,
+        direction: BackendDirection,
+        allowed_types: Option<&AHashSet<&str>>,
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+        self.require_visible(start)?;
+        self.require_visible(end)?;
+        if start == end {
+            return Ok(Some(vec![start]));
+        }
+        let mut queue = VecDeque::new();
+        let mut parents = AHashMap::new();
+        let mut seen = AHashSet::new();
+        queue.push_back(start);
+        seen.insert(start);
+        while let Some(node) = queue.pop_front() {
+            for next in self.view_neighbors(node, direction, allowed_types)? {
+                if seen.insert(next) {
+                    parents.insert(next, node);
+                    if next == end {
+                        let mut path = vec![end];
+                        let mut current = end;
+                        while let Some(&parent) = parents.get(&current) {
+                            path.push(parent);
+                            current = parent;
+                        }
+                        path.reverse();
+                        return Ok(Some(path));
+                    }
+                    queue.push_back(next);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn k_hop_internal(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+        allowed_types: Option<&AHashSet<&str>>,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
+        if depth == 0 {
+            return Ok(Vec::new());
+        }
+        let mut visited = AHashSet::new();
+        let mut queue = VecDeque::new();
+        let mut ordered = Vec::new();
+        queue.push_back((start, 0));
+        visited.insert(start);
+        while let Some((node, level)) = queue.pop_front() {
+            if level == depth {
+                continue;
+            }
+            for neighbor in self.view_neighbors(node, direction, allowed_types)? {
+                if visited.insert(neighbor) {
+                    ordered.push((neighbor, level + 1));
+                    queue.push_back((neighbor, level + 1));
+                }
+            }
+        }
+        multi_hop::sort_by_distance(&mut ordered);
+        Ok(ordered)
+    }
+}
+
+impl GraphBackend for LabeledView<'_> {
+    fn insert_node(&self, _node: NodeSpec) -> Result<i64, SqliteGraphError> {
+        Err(self.read_only_error())
+    }
+
+    fn get_node(&self, id: i64) -> Result<GraphEntity, SqliteGraphError> {
+        self.require_visible(id)?;
+        self.graph.get_entity(id)
+    }
+
+    fn insert_edge(&self, _edge: EdgeSpec) -> Result<i64, SqliteGraphError> {
+        Err(self.read_only_error())
+    }
+
+    fn upsert_edge(&self, _edge: EdgeSpec) -> Result<i64, SqliteGraphError> {
+        Err(self.read_only_error())
+    }
+
+    fn get_edge(&self, id: i64) -> Result<GraphEdge, SqliteGraphError> {
+        let edge = self.graph.get_live_edge(id)?;
+        if self.contains(edge.from_id)? && self.contains(edge.to_id)? {
+            Ok(edge)
+        } else {
+            Err(SqliteGraphError::not_found(EntityKind::Edge, id))
+        }
+    }
+
+    fn list_edge_ids(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        let conn = self.graph.connection();
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT e.id FROM graph_edges e \
+                 WHERE EXISTS (SELECT 1 FROM graph_labels gl \
+                 WHERE gl.entity_id=e.from_id AND gl.label=?1) \
+                 AND EXISTS (SELECT 1 FROM graph_labels gl \
+                 WHERE gl.entity_id=e.to_id AND gl.label=?1) \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+                 WHERE t.entity_id IN (e.from_id, e.to_id)) \
+                 ORDER BY e.id",
+            )
+            .map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map(params![self.label], |row| row.get(0))
+            .map_err(SqliteGraphError::query)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(SqliteGraphError::query)
+    }
+
+    fn find_nodes_by_property(&self, key: &str, value: &str) -> Result<Vec<i64>, SqliteGraphError> {
+        let visible: AHashSet<i64> = self.visible_ids()?.into_iter().collect();
+        let mut ids = index::entity_ids_by_property(self.graph, key, value)?;
+        ids.retain(|id| visible.contains(id));
+        Ok(ids)
+    }
+
+    fn find_nodes_by_label(&self, label: &str) -> Result<Vec<i64>, SqliteGraphError> {
+        let visible: AHashSet<i64> = self.visible_ids()?.into_iter().collect();
+        let mut ids = index::entity_ids_by_label(self.graph, label)?;
+        ids.retain(|id| visible.contains(id));
+        Ok(ids)
+    }
+
+    fn delete_node(&self, _id: i64) -> Result<(), SqliteGraphError> {
+        Err(self.read_only_error())
+    }
+
+    fn delete_edge(&self, _id: i64) -> Result<(), SqliteGraphError> {
+        Err(self.read_only_error())
+    }
+
+    fn update_node_data(&self, _id: i64, _data: serde_json::Value) -> Result<(), SqliteGraphError> {
+        Err(self.read_only_error())
+    }
+
+    fn update_edge_data(&self, _id: i64, _data: serde_json::Value) -> Result<(), SqliteGraphError> {
+        Err(self.read_only_error())
+    }
+
+    fn neighbors(&self, node: i64, query: NeighborQuery) -> Result<Vec<i64>, SqliteGraphError> {
+        match query.edge_type.as_deref() {
+            Some(edge_type) => {
+                let allowed = AHashSet::from_iter([edge_type]);
+                self.view_neighbors(node, query.direction, Some(&allowed))
+            }
+            None => self.view_neighbors(node, query.direction, None),
+        }
+    }
+
+    fn neighbor_count(&self, node: i64, query: NeighborQuery) -> Result<usize, SqliteGraphError> {
+        // Visibility is checked per neighbor, so there is no cheaper count
+        Ok(self.neighbors(node, query)?.len())
+    }
+
+    fn neighbors_uncached(
+        &self,
+        node: i64,
+        query: NeighborQuery,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        // View lookups always run SQL and never touch the adjacency caches
+        self.neighbors(node, query)
+    }
+
+    fn neighbors_batch(
+        &self,
+        ids: &[i64],
+        query: NeighborQuery,
+    ) -> Result<HashMap<i64, Vec<i64>>, SqliteGraphError> {
+        let mut result = HashMap::with_capacity(ids.len());
+        for &id in ids {
+            if let Entry::Vacant(entry) = result.entry(id) {
+                entry.insert(self.neighbors(id, query.clone())?);
+            }
+        }
+        Ok(result)
+    }
+
+    fn bfs(&self, start: i64, depth: u32) -> Result<Vec<i64>, SqliteGraphError> {
+        self.bfs_directed(start, depth, BackendDirection::Outgoing)
+    }
+
+    fn bfs_directed(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        self.view_bfs(start, depth, direction, None)
+    }
+
+    fn bfs_filtered(
+        &self,
+        start: i64,
+        max_depth: u32,
+        edge_types: &[&str],
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        let allowed = AHashSet::from_iter(edge_types.iter().copied());
+        self.view_bfs(start, max_depth, BackendDirection::Outgoing, Some(&allowed))
+    }
+
+    fn shortest_path(&self, start: i64, end: i64) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+        self.shortest_path_directed(start, end, BackendDirection::Outgoing)
+    }
+
+    fn shortest_path_directed(
+        &self,
+        start: i64,
+        end: i64,
+        direction: BackendDirection,
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+        self.view_shortest_path(start, end, direction, None)
+    }
+
+    fn shortest_path_filtered(
+        &self,
+        start: i64,
+        end: i64,
+        edge_types: &[&str],
+    ) -> Result<Option<Vec<i64>>, SqliteGraphError> {
+        let allowed = AHashSet::from_iter(edge_types.iter().copied());
+        self.view_shortest_path(start, end, BackendDirection::Outgoing, Some(&allowed))
+    }
+
+    fn node_degree(&self, node: i64) -> Result<(usize, usize), SqliteGraphError> {
+        let out = self
+            .view_neighbors(node, BackendDirection::Outgoing, None)?
+            .len();
+        let incoming = self
+            .view_neighbors(node, BackendDirection::Incoming, None)?
+            .len();
+        Ok((out, incoming))
+    }
+
+    fn node_edges(&self, node: i64) -> Result<NodeEdges, SqliteGraphError> {
+        self.require_visible(node)?;
+        self.graph.fetch_node_edges(node, Some(&self.label))
+    }
+
+    fn top_nodes_by_degree(
+        &self,
+        k: usize,
+        direction: BackendDirection,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
+        // Only edges inside the view count; see `LabeledView`
+        Ok(algo::top_by_degree_from_edges(
+            &self.visible_ids()?,
+            self.graph.edge_endpoints()?,
+            k,
+            direction,
+        ))
+    }
+
+    fn k_hop(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        self.k_hop_internal(start, depth, direction, None)
+            .map(multi_hop::strip_distances)
+    }
+
+    fn k_hop_with_distance(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+    ) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
+        self.k_hop_internal(start, depth, direction, None)
+    }
+
+    fn k_hop_filtered(
+        &self,
+        start: i64,
+        depth: u32,
+        direction: BackendDirection,
+        allowed_edge_types: &[&str],
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        if allowed_edge_types.is_empty() {
+            return Ok(Vec::new());
+        }
+        let allowed = allowed_edge_types.iter().copied().collect::<AHashSet<_>>();
+        self.k_hop_internal(start, depth, direction, Some(&allowed))
+            .map(multi_hop::strip_distances)
+    }
+
+    fn k_hop_weighted(
+        &self,
+        start: i64,
+        budget: f64,
+        direction: BackendDirection,
+        type_costs: &HashMap<String, f64>,
+    ) -> Result<Vec<(i64, f64)>, SqliteGraphError> {
+        if !self.contains(start)? {
+            return Ok(Vec::new());
+        }
+        multi_hop::cost_bounded_expand(start, budget, type_costs, |node, edge_type| {
+            let allowed = AHashSet::from_iter([edge_type]);
+            self.view_neighbors(node, direction, Some(&allowed))
+        })
+    }
+
+    fn connected_components(&self) -> Result<Vec<Vec<i64>>, SqliteGraphError> {
+        // Only edges inside the view count; see `LabeledView`
+        Ok(algo::components_from_edges(
+            &self.visible_ids()?,
+            self.graph.edge_endpoints()?,
+        ))
+    }
+
+    fn pagerank(
+        &self,
+        damping: f64,
+        iterations: usize,
+    ) -> Result<HashMap<i64, f64>, SqliteGraphError> {
+        algo::pagerank_from_edges(
+            &self.visible_ids()?,
+            self.graph.edge_endpoints()?,
+            damping,
+            iterations,
+        )
+    }
+
+    fn topological_sort(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        algo::topological_sort_from_edges(&self.visible_ids()?, self.graph.edge_endpoints()?)
+    }
+
+    fn all_pairs_shortest_paths(
+        &self,
+        max_nodes: usize,
+    ) -> Result<HashMap<(i64, i64), u32>, SqliteGraphError> {
+        algo::all_pairs_shortest_paths_from_edges(
+            &self.visible_ids()?,
+            self.graph.edge_endpoints()?,
+            max_nodes,
+        )
+    }
+
+    fn betweenness_centrality(
+        &self,
+        normalized: bool,
+        max_nodes: usize,
+    ) -> Result<HashMap<i64, f64>, SqliteGraphError> {
+        algo::betweenness_from_edges(
+            &self.visible_ids()?,
+            self.graph.edge_endpoints()?,
+            normalized,
+            max_nodes,
+        )
+    }
+
+    fn astar(
+        &self,
+        from: i64,
+        to: i64,
+        weight_key: &str,
+        heuristic: &dyn Fn(i64) -> f64,
+    ) -> Result<Option<(Vec<i64>, f64)>, SqliteGraphError> {
+        if !self.contains(from)? {
+            return Ok(None);
+        }
+        algo::astar(from, to, weight_key, heuristic, |node| {
+            Ok(self
+                .graph
+                .fetch_node_edges(node, Some(&self.label))?
+                .outgoing)
+        })
+    }
+
+    fn chain_query(&self, start: i64, chain: &[ChainStep]) -> Result<Vec<i64>, SqliteGraphError> {
+        if !self.contains(start)? {
+            return Ok(Vec::new());
+        }
+        let mut current = vec![start];
+        for step in chain {
+            let allowed = step
+                .edge_type
+                .as_deref()
+                .map(|edge_type| AHashSet::from_iter([edge_type]));
+            let mut next = Vec::new();
+            for node in &current {
+                next.extend(self.view_neighbors(*node, step.direction, allowed.as_ref())?);
+            }
+            if next.is_empty() {
+                return Ok(Vec::new());
+            }
+            next.sort();
+            next.dedup();
+            current = next;
+        }
+        Ok(current)
+    }
+
+    fn pattern_search(
+        &self,
+        start: i64,
+        pattern: &PatternQuery,
+    ) -> Result<Vec<PatternMatch>, SqliteGraphError> {
+        self.require_visible(start)?;
+        let mut matches = Vec::new();
+        // Page after the visibility filter so hidden matches do not count.
+        for candidate in pattern::execute_pattern(self.graph, start, &pattern.unpaged())? {
+            let mut visible = true;
+            for &node in &candidate.nodes {
+                if !self.contains(node)? {
+                    visible = false;
+                    break;
+                }
+            }
+            if visible {
+                matches.push(candidate);
+            }
+        }
+        Ok(pattern.paginate(matches))
+    }
+
+    fn statistics(&self) -> Result<GraphStatistics, SqliteGraphError> {
+        let conn = self.graph.connection();
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT e.id, e.kind FROM graph_entities e \
+                 WHERE EXISTS (SELECT 1 FROM graph_labels gl \
+                 WHERE gl.entity_id=e.id AND gl.label=?1) \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id)",
+            )
+            .map_err(SqliteGraphError::query)?;
+        let nodes = stmt
+            .query_map(params![self.label], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(SqliteGraphError::query)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(SqliteGraphError::query)?;
+        let mut stmt = conn
+            .prepare_cached("SELECT from_id, to_id, edge_type FROM graph_edges")
+            .map_err(SqliteGraphError::query)?;
+        let edges = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(SqliteGraphError::query)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(SqliteGraphError::query)?;
+        // Only edges inside the view count; see `LabeledView`
+        Ok(GraphStatistics::from_records(nodes, edges))
+    }
+
+    fn cache_stats(&self) -> CacheStats {
+        // View lookups bypass the caches, so these are the underlying graph's own figures
+        crate::graph_opt::cache_stats(self.graph)
+    }
+
+    fn reset_cache_stats(&self) {
+        self.graph.outgoing_cache_ref().reset_stats();
+        self.graph.incoming_cache_ref().reset_stats();
+    }
+
+    fn validate_integrity(&self) -> Result<SafetyReport, SqliteGraphError> {
+        // Only report edges hanging off a visible node; the rest belong to other labels
+        let visible: AHashSet<i64> = self.visible_ids()?.into_iter().collect();
+        let mut dangling_edges = self.graph.live_dangling_edges()?;
+        dangling_edges
+            .retain(|edge| visible.contains(&edge.from_id) || visible.contains(&edge.to_id));
+        Ok(SafetyReport {
+            dangling_edges,
+            ..SafetyReport::default()
+        })
+    }
+
+    fn schema_version(&self) -> Result<u32, SqliteGraphError> {
+        self.graph.backend_schema_version()
+    }
+
+    fn check_compatibility(&self) -> Result<(), SqliteGraphError> {
+        self.graph.check_compatibility()
+    }
+}
@@ -1,19 +1,64 @@
-use std::{collections::HashSet, sync::Mutex};
+use std::{collections::VecDeque, sync::Mutex};
 
-#[derive(Default)]
+/// Prepared-statement cache capacity a connection is opened with.
+pub const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 128;
+
+/// Mirror of the connection's LRU prepared-statement cache, keyed like rusqlite
+/// by the trimmed SQL text, so hits and misses follow its evictions.
 pub struct StatementTracker {
-    seen: Mutex<HashSet<String>>,
+    state: Mutex<TrackerState>,
+}
+
+struct TrackerState {
+    capacity: usize,
+    /// Most recently used first
+    recent: VecDeque<String>,
+}
+
+impl Default for StatementTracker {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(TrackerState {
+                capacity: DEFAULT_STATEMENT_CACHE_CAPACITY,
+                recent: VecDeque::new(),
+            }),
+        }
+    }
 }
 
 impl StatementTracker {
     pub fn observe(&self, sql: &str) -> CacheObservation {
-        let normalized = sql.trim().to_string();
-        let mut guard = self.seen.lock().expect("statement tracker poisoned");
-        if guard.insert(normalized) {
-            CacheObservation::Miss
-        } else {
-            CacheObservation::Hit
+        let normalized = sql.trim();
+        let mut guard = self.state.lock().expect("statement tracker poisoned");
+        let observation = match guard.recent.iter().position(|seen| seen == normalized) {
+            Some(index) => {
+                guard.recent.remove(index);
+                CacheObservation::Hit
+            }
+            None => CacheObservation::Miss,
+        };
+        if guard.capacity > 0 {
+            guard.recent.push_front(normalized.to_string());
+            let capacity = guard.capacity;
+            guard.recent.truncate(capacity);
         }
+        observation
+    }
+
+    /// Change the capacity, dropping the least recently used entries beyond it.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut guard = self.state.lock().expect("statement tracker poisoned");
+        guard.capacity = capacity;
+        guard.recent.truncate(capacity);
+    }
+
+    /// Number of statements currently cached.
+    pub fn cached_count(&self) -> usize {
+        self.state
+            .lock()
+            .expect("statement tracker poisoned")
+            .recent
+            .len()
     }
 }
 
@@ -1,6 +1,8 @@
 //! Metrics and schema operations for SqliteGraph.
 
-use crate::schema::{MigrationReport, read_schema_version, run_pending_migrations};
+use crate::schema::{
+    MigrationReport, check_compatibility, read_schema_version, run_pending_migrations,
+};
 
 use super::{SqliteGraph, metrics::GraphMetricsSnapshot};
 
@@ -13,10 +15,40 @@ impl SqliteGraph {
         self.metrics.reset();
     }
 
+    /// Number of prepared statements the connection currently keeps cached,
+    /// at most the configured [`SqliteConfig::cache_size`](crate::SqliteConfig::cache_size).
+    ///
+    /// Only statements prepared by the graph itself are counted, not those
+    /// prepared inside [`SqliteGraph::with_connection`].
+    pub fn statement_cache_len(&self) -> usize {
+        self.statement_tracker.cached_count()
+    }
+
+    /// Limit the connection's prepared-statement cache to `capacity` entries.
+    pub(crate) fn set_statement_cache_capacity(&self, capacity: usize) {
+        self.conn.set_prepared_statement_cache_capacity(capacity);
+        self.statement_tracker.set_capacity(capacity);
+    }
+
     pub fn schema_version(&self) -> Result<i64, crate::errors::SqliteGraphError> {
         read_schema_version(&self.conn)
     }
 
+    /// [`schema_version`](Self::schema_version) as reported through
+    /// [`GraphBackend`](crate::backend::GraphBackend).
+    pub(crate) fn backend_schema_version(&self) -> Result<u32, crate::errors::SqliteGraphError> {
+        let version = self.schema_version()?;
+        u32::try_from(version).map_err(|_| {
+            crate::errors::SqliteGraphError::schema(format!("invalid schema version {version}"))
+        })
+    }
+
+    /// Check that the database needs no migration and is not newer than this
+    /// build supports; see [`crate::schema::check_compatibility`].
+    pub fn check_compatibility(&self) -> Result<(), crate::errors::SqliteGraphError> {
+        check_compatibility(&self.conn)
+    }
+
     pub fn run_pending_migrations(
         &self,
         dry_run: bool,
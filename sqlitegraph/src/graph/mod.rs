@@ -9,11 +9,27 @@ mod adjacency;
 mod core;
 mod edge_ops;
 mod entity_ops;
+mod integrity;
+mod labeled_view;
 mod metrics;
 mod metrics_schema;
 mod pattern_matching;
+mod repair;
+mod retry;
 mod snapshot;
+mod statistics;
+mod subgraph;
+mod tombstone;
+mod transaction;
 mod types;
 
+pub(crate) use adjacency::MAX_SQL_VARIABLES;
+pub use integrity::{DanglingEdge, IntegrityReport};
+pub use labeled_view::LabeledView;
 pub use metrics::{GraphMetricsSnapshot, InstrumentedConnection};
-pub use types::{GraphEdge, GraphEntity};
+pub use repair::{RepairMode, RepairReport};
+pub use statistics::GraphStatistics;
+pub use subgraph::Subgraph;
+pub use transaction::TransactionGuard;
+pub use types::{GraphEdge, GraphEntity, MutationOutcome};
+pub(crate) use types::{column_data, row_to_edge, row_to_entity, validate_edge, validate_entity};
@@ -22,6 +22,28 @@ impl SqliteGraph {
         crate::pattern_engine::match_triples(self, pattern)
     }
 
+    /// Match lightweight triple patterns, returning each matched edge's data too.
+    ///
+    /// Same matches and order as [`SqliteGraph::match_triples`]; the edge data
+    /// is read by the matching query itself rather than per match.
+    pub fn match_triples_with_edge_data(
+        &self,
+        pattern: &crate::pattern_engine::PatternTriple,
+    ) -> Result<Vec<(crate::pattern_engine::TripleMatch, serde_json::Value)>, SqliteGraphError>
+    {
+        crate::pattern_engine::match_triples_with_edge_data(self, pattern)
+    }
+
+    /// Count the matches of a triple pattern without materializing them.
+    ///
+    /// Always equals `self.match_triples(pattern)?.len()`.
+    pub fn count_triples(
+        &self,
+        pattern: &crate::pattern_engine::PatternTriple,
+    ) -> Result<usize, SqliteGraphError> {
+        crate::pattern_engine::count_triples(self, pattern)
+    }
+
     /// Match lightweight triple patterns using cache-enabled fast-path.
     ///
     /// This method provides an optimized version of pattern matching that:
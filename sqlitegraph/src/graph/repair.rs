@@ -0,0 +1,147 @@
+//! One-shot integrity repair for SqliteGraph.
+
+use crate::errors::SqliteGraphError;
+
+use super::{SqliteGraph, integrity::DanglingEdge};
+
+/// Whether [`SqliteGraph::repair`] changes the database.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RepairMode {
+    /// Report problems without changing anything.
+    #[default]
+    DryRun,
+    /// Delete the offending rows and rebuild the adjacency caches.
+    Fix,
+}
+
+/// Problems found by [`SqliteGraph::repair`]; in [`RepairMode::Fix`] each one was removed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    pub mode: RepairMode,
+    /// Edges whose endpoints do not both exist, ordered by edge id
+    pub dangling_edges: Vec<DanglingEdge>,
+    /// Ids of edges identical to a lower-id edge in `from_id`, `to_id`,
+    /// `edge_type` and `data`; the lowest id of each group is kept
+    pub duplicate_edges: Vec<i64>,
+    /// Label rows whose entity no longer exists
+    pub orphaned_labels: usize,
+    /// Property rows whose entity no longer exists
+    pub orphaned_properties: usize,
+    /// Soft-delete markers whose entity no longer exists
+    pub orphaned_tombstones: usize,
+    /// Cached adjacency lists that disagreed with the database
+    pub stale_cache_entries: usize,
+}
+
+impl RepairReport {
+    /// Whether no problems were found.
+    pub fn is_clean(&self) -> bool {
+        self.dangling_edges.is_empty()
+            && self.duplicate_edges.is_empty()
+            && self.orphaned_labels == 0
+            && self.orphaned_properties == 0
+            && self.orphaned_tombstones == 0
+            && self.stale_cache_entries == 0
+    }
+}
+
+const DUPLICATE_EDGES_SQL: &str = "SELECT ge.id FROM graph_edges ge \
+     WHERE EXISTS (SELECT 1 FROM graph_edges first WHERE first.from_id=ge.from_id \
+     AND first.to_id=ge.to_id AND first.edge_type=ge.edge_type AND first.data=ge.data \
+     AND first.id < ge.id) \
+     ORDER BY ge.id";
+
+/// (count query, delete statement) per metadata table keyed by `entity_id`.
+const ORPHAN_SQL: [(&str, &str); 3] = [
+    (
+        "SELECT COUNT(*) FROM graph_labels WHERE entity_id NOT IN (SELECT id FROM graph_entities)",
+        "DELETE FROM graph_labels WHERE entity_id NOT IN (SELECT id FROM graph_entities)",
+    ),
+    (
+        "SELECT COUNT(*) FROM graph_properties WHERE entity_id NOT IN (SELECT id FROM graph_entities)",
+        "DELETE FROM graph_properties WHERE entity_id NOT IN (SELECT id FROM graph_entities)",
+    ),
+    (
+        "SELECT COUNT(*) FROM graph_tombstones WHERE entity_id NOT IN (SELECT id FROM graph_entities)",
+        "DELETE FROM graph_tombstones WHERE entity_id NOT IN (SELECT id FROM graph_entities)",
+    ),
+];
+
+impl SqliteGraph {
+    /// Run every integrity check and, in [`RepairMode::Fix`], remove what fails.
+    ///
+    /// Checks for dangling edges, exact duplicate edges, labels, properties and
+    /// soft-delete markers of missing entities, and cached adjacency lists that
+    /// no longer match the database. Fixing deletes the offending rows in one
+    /// transaction and clears the adjacency caches so they reload from disk.
+    pub fn repair(&self, mode: RepairMode) -> Result<RepairReport, SqliteGraphError> {
+        let mut report = RepairReport {
+            mode,
+            dangling_edges: self.check_integrity()?.dangling_edges,
+            duplicate_edges: self.duplicate_edge_ids()?,
+            ..RepairReport::default()
+        };
+        let conn = self.connection();
+        let mut orphans = Vec::with_capacity(ORPHAN_SQL.len());
+        for (sql, _) in ORPHAN_SQL {
+            let found: i64 = conn
+                .query_row(sql, [], |row| row.get(0))
+                .map_err(SqliteGraphError::query)?;
+            orphans.push(found as usize);
+        }
+        report.orphaned_labels = orphans[0];
+        report.orphaned_properties = orphans[1];
+        report.orphaned_tombstones = orphans[2];
+        report.stale_cache_entries = self.stale_cache_entries()?;
+
+        if mode == RepairMode::Fix && !report.is_clean() {
+            self.with_transaction(|| {
+                let edge_ids = report
+                    .dangling_edges
+                    .iter()
+                    .map(|edge| edge.edge_id)
+                    .chain(report.duplicate_edges.iter().copied());
+                for id in edge_ids {
+                    conn.execute("DELETE FROM graph_edges WHERE id=?1", [id])
+                        .map_err(SqliteGraphError::query)?;
+                }
+                for (_, sql) in ORPHAN_SQL {
+                    conn.execute(sql, []).map_err(SqliteGraphError::query)?;
+                }
+                Ok(())
+            })?;
+            self.invalidate_caches();
+        }
+        Ok(report)
+    }
+
+    fn duplicate_edge_ids(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        let conn = self.connection();
+        let mut stmt = conn
+            .prepare_cached(DUPLICATE_EDGES_SQL)
+            .map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(SqliteGraphError::query)?;
+        let mut ids = Vec::new();
+        for id in rows {
+            ids.push(id.map_err(SqliteGraphError::query)?);
+        }
+        Ok(ids)
+    }
+
+    fn stale_cache_entries(&self) -> Result<usize, SqliteGraphError> {
+        let mut stale = 0;
+        for (id, cached) in self.outgoing_cache.inner() {
+            if self.load_outgoing(id)? != cached {
+                stale += 1;
+            }
+        }
+        for (id, cached) in self.incoming_cache.inner() {
+            if self.load_incoming(id)? != cached {
+                stale += 1;
+            }
+        }
+        Ok(stale)
+    }
+}
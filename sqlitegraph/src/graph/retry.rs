@@ -0,0 +1,39 @@
+//! Retrying operations that lose a lock race with another connection.
+
+use std::{thread, time::Duration};
+
+use crate::errors::SqliteGraphError;
+
+use super::SqliteGraph;
+
+/// Pause before the first retry; it doubles after every failed attempt.
+const RETRY_INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Longest pause between two attempts.
+const RETRY_MAX_BACKOFF: Duration = Duration::from_secs(1);
+
+impl SqliteGraph {
+    /// Run `f` until it succeeds, retrying with exponential backoff while it
+    /// fails with a [retryable](SqliteGraphError::is_retryable) error.
+    ///
+    /// `f` runs at most `attempts` times, and at least once. Any other error,
+    /// or the last busy error once the attempts are used up, is returned as is.
+    /// Run whole transactions inside `f`, so a retry starts from a clean state.
+    pub fn with_retry<T>(
+        &self,
+        attempts: usize,
+        mut f: impl FnMut() -> Result<T, SqliteGraphError>,
+    ) -> Result<T, SqliteGraphError> {
+        let mut backoff = RETRY_INITIAL_BACKOFF;
+        for _ in 1..attempts {
+            match f() {
+                Err(err) if err.is_retryable() => {
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(RETRY_MAX_BACKOFF);
+                }
+                result => return result,
+            }
+        }
+        f()
+    }
+}
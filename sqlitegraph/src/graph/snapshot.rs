@@ -1,6 +1,8 @@
 //! Snapshot management functionality for SqliteGraph.
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
+
+use rusqlite::{Connection, backup::Backup};
 
 use crate::errors::SqliteGraphError;
 
@@ -19,13 +21,18 @@ impl SqliteGraph {
     /// Acquire a deterministic snapshot of the current graph state
     ///
     /// Returns a read-only snapshot that provides isolated access to graph data.
-    /// The snapshot contains cloned adjacency maps and uses a read-only SQLite connection.
+    /// The snapshot holds the complete adjacency of every live (not soft-deleted)
+    /// entity, read from the database at acquisition time, so its counts and
+    /// neighbor lists do not depend on which nodes happen to be cached and stay
+    /// fixed while the graph keeps changing. A query-only in-memory copy of the
+    /// database is taken at the same moment so that
+    /// [`GraphSnapshot::match_triples`](crate::mvcc::GraphSnapshot::match_triples)
+    /// can evaluate labels and properties as they were.
     ///
     /// # Returns
     /// Result containing GraphSnapshot or error
     pub fn acquire_snapshot(&self) -> Result<crate::mvcc::GraphSnapshot, SqliteGraphError> {
-        // Update snapshot with current cache state
-        self.update_snapshot();
+        self.snapshot_manager.store(self.capture_snapshot_state()?);
 
         // Acquire snapshot state
         let snapshot_state = self.snapshot_manager.acquire_snapshot();
@@ -33,29 +40,124 @@ impl SqliteGraph {
         // Use in-memory database for snapshot operations
         let db_path = ":memory:";
 
-        crate::mvcc::GraphSnapshot::new(snapshot_state, db_path)
-            .map_err(|e| SqliteGraphError::connection(e.to_string()))
+        let snapshot = crate::mvcc::GraphSnapshot::new(snapshot_state, db_path)
+            .map_err(SqliteGraphError::connection)?;
+        Ok(snapshot.with_data(self.copy_to_memory()?))
+    }
+
+    /// Copy the whole database into a fresh query-only in-memory graph
+    fn copy_to_memory(&self) -> Result<SqliteGraph, SqliteGraphError> {
+        let mut copy = Connection::open_in_memory().map_err(SqliteGraphError::connection)?;
+        Backup::new(&self.conn, &mut copy)
+            .and_then(|backup| backup.step(-1).map(|_| ()))
+            .map_err(SqliteGraphError::connection)?;
+        copy.pragma_update(None, "query_only", true)
+            .map_err(SqliteGraphError::connection)?;
+        SqliteGraph::from_connection(copy)
+    }
+
+    /// Same as [`acquire_snapshot`](Self::acquire_snapshot).
+    pub fn create_snapshot(&self) -> Result<crate::mvcc::GraphSnapshot, SqliteGraphError> {
+        self.acquire_snapshot()
+    }
+
+    /// Capture the full adjacency of the graph as a snapshot state
+    pub(crate) fn current_snapshot_state(
+        &self,
+    ) -> Result<Arc<crate::mvcc::SnapshotState>, SqliteGraphError> {
+        self.capture_snapshot_state().map(Arc::new)
+    }
+
+    fn capture_snapshot_state(&self) -> Result<crate::mvcc::SnapshotState, SqliteGraphError> {
+        let (outgoing, incoming, edge_ids) = self.load_full_adjacency()?;
+        Ok(crate::mvcc::SnapshotState::new(&outgoing, &incoming).with_edge_ids(edge_ids))
     }
 
-    /// Get the current snapshot state without creating a new connection
-    /// This is useful for internal operations and testing
-    pub(crate) fn current_snapshot_state(&self) -> Arc<crate::mvcc::SnapshotState> {
-        self.update_snapshot();
-        self.snapshot_manager.current_snapshot()
+    /// Snapshot state for the infallible accessors: the full adjacency, or the
+    /// last stored snapshot if the database cannot be read
+    fn snapshot_state_or_last(&self) -> Arc<crate::mvcc::SnapshotState> {
+        self.current_snapshot_state()
+            .unwrap_or_else(|_| self.snapshot_manager.current_snapshot())
     }
 
-    /// Get the number of nodes in the current snapshot
+    /// Get the number of nodes a snapshot taken now would contain
+    ///
+    /// Falls back to the last stored snapshot if the database cannot be read;
+    /// use [`try_snapshot_node_count`](Self::try_snapshot_node_count) to see the error.
     pub fn snapshot_node_count(&self) -> usize {
-        self.current_snapshot_state().node_count()
+        self.snapshot_state_or_last().node_count()
     }
 
-    /// Get the number of edges in the current snapshot
+    /// Get the number of edges a snapshot taken now would contain
+    ///
+    /// Falls back like [`snapshot_node_count`](Self::snapshot_node_count).
     pub fn snapshot_edge_count(&self) -> usize {
-        self.current_snapshot_state().edge_count()
+        self.snapshot_state_or_last().edge_count()
     }
 
-    /// Check if a node exists in the current snapshot
+    /// Check if a node would be part of a snapshot taken now
+    ///
+    /// Falls back like [`snapshot_node_count`](Self::snapshot_node_count).
     pub fn snapshot_contains_node(&self, node_id: i64) -> bool {
-        self.current_snapshot_state().contains_node(node_id)
+        self.snapshot_state_or_last().contains_node(node_id)
+    }
+
+    /// Fallible form of [`snapshot_node_count`](Self::snapshot_node_count)
+    pub fn try_snapshot_node_count(&self) -> Result<usize, SqliteGraphError> {
+        Ok(self.current_snapshot_state()?.node_count())
+    }
+
+    /// Fallible form of [`snapshot_edge_count`](Self::snapshot_edge_count)
+    pub fn try_snapshot_edge_count(&self) -> Result<usize, SqliteGraphError> {
+        Ok(self.current_snapshot_state()?.edge_count())
+    }
+
+    /// Fallible form of [`snapshot_contains_node`](Self::snapshot_contains_node)
+    pub fn try_snapshot_contains_node(&self, node_id: i64) -> Result<bool, SqliteGraphError> {
+        Ok(self.current_snapshot_state()?.contains_node(node_id))
+    }
+
+    /// Outgoing and incoming adjacency for every live entity, keyed by entity id,
+    /// plus the ids of the edges included.
+    ///
+    /// Every entity gets an entry in both maps, even without edges. Lists use the
+    /// same ordering as the adjacency queries; edges touching soft-deleted or
+    /// missing entities are left out.
+    fn load_full_adjacency(
+        &self,
+    ) -> Result<(AdjacencyMap, AdjacencyMap, Vec<i64>), SqliteGraphError> {
+        let ids = self.all_entity_ids()?;
+        let mut outgoing: AdjacencyMap = ids.iter().map(|&id| (id, Vec::new())).collect();
+        let mut incoming: AdjacencyMap = ids.iter().map(|&id| (id, Vec::new())).collect();
+
+        let conn = self.connection();
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT from_id, to_id, id FROM graph_edges ORDER BY from_id, to_id, edge_type, id",
+            )
+            .map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })
+            .map_err(SqliteGraphError::query)?;
+        let mut edge_ids = Vec::new();
+        for row in rows {
+            let (from, to, id) = row.map_err(SqliteGraphError::query)?;
+            if let (Some(targets), Some(sources)) = (outgoing.get_mut(&from), incoming.get_mut(&to))
+            {
+                targets.push(to);
+                sources.push(from);
+                edge_ids.push(id);
+            }
+        }
+        // Rows arrive ordered by source, so each incoming list is already sorted.
+        Ok((outgoing, incoming, edge_ids))
     }
 }
+
+type AdjacencyMap = HashMap<i64, Vec<i64>>;
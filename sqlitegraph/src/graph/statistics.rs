@@ -0,0 +1,123 @@
+//! Whole-graph summary counts for SqliteGraph.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::errors::SqliteGraphError;
+
+use super::SqliteGraph;
+
+/// Overview of the live nodes and edges of a graph.
+///
+/// A node's degree counts its incoming and outgoing edges, so a self-loop adds
+/// two. Soft-deleted nodes and the edges touching them are left out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphStatistics {
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// Number of nodes of each kind
+    pub nodes_by_kind: BTreeMap<String, usize>,
+    /// Number of edges of each type
+    pub edges_by_type: BTreeMap<String, usize>,
+    /// Smallest node degree, or 0 for an empty graph
+    pub min_degree: usize,
+    /// Largest node degree, or 0 for an empty graph
+    pub max_degree: usize,
+    /// Mean node degree, or 0.0 for an empty graph
+    pub avg_degree: f64,
+    /// Nodes without any edge
+    pub isolated_nodes: usize,
+}
+
+impl GraphStatistics {
+    /// Compute the statistics from `(id, kind)` nodes and `(from, to, type)`
+    /// edges; edges with an endpoint outside `nodes` are ignored.
+    pub(crate) fn from_records(
+        nodes: impl IntoIterator<Item = (i64, String)>,
+        edges: impl IntoIterator<Item = (i64, i64, String)>,
+    ) -> Self {
+        let mut stats = Self::default();
+        let mut degrees = HashMap::new();
+        for (id, kind) in nodes {
+            degrees.insert(id, 0usize);
+            *stats.nodes_by_kind.entry(kind).or_default() += 1;
+        }
+        for (from, to, edge_type) in edges {
+            if !degrees.contains_key(&from) || !degrees.contains_key(&to) {
+                continue;
+            }
+            for end in [from, to] {
+                *degrees.entry(end).or_default() += 1;
+            }
+            *stats.edges_by_type.entry(edge_type).or_default() += 1;
+        }
+        stats.node_count = degrees.len();
+        stats.edge_count = stats.edges_by_type.values().sum();
+        stats.min_degree = degrees.values().copied().min().unwrap_or(0);
+        stats.max_degree = degrees.values().copied().max().unwrap_or(0);
+        stats.isolated_nodes = degrees.values().filter(|&&degree| degree == 0).count();
+        if stats.node_count > 0 {
+            stats.avg_degree = 2.0 * stats.edge_count as f64 / stats.node_count as f64;
+        }
+        stats
+    }
+}
+
+impl SqliteGraph {
+    /// Node and edge counts, per-kind and per-type breakdowns and degree
+    /// summary, computed with a few aggregate queries.
+    pub fn statistics(&self) -> Result<GraphStatistics, SqliteGraphError> {
+        let mut stats = GraphStatistics {
+            nodes_by_kind: self.grouped_counts(
+                "SELECT kind, COUNT(*) FROM graph_entities e \
+                 WHERE NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+                 GROUP BY kind",
+            )?,
+            edges_by_type: self.grouped_counts(
+                "SELECT edge_type, COUNT(*) FROM graph_edges e \
+                 WHERE NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+                 WHERE t.entity_id IN (e.from_id, e.to_id)) \
+                 GROUP BY edge_type",
+            )?,
+            ..GraphStatistics::default()
+        };
+        stats.node_count = stats.nodes_by_kind.values().sum();
+        stats.edge_count = stats.edges_by_type.values().sum();
+
+        // Degrees of the nodes with at least one live edge; the rest are isolated
+        let (connected, min, max, total): (usize, Option<usize>, Option<usize>, Option<i64>) = self
+            .connection()
+            .query_row(
+                "WITH live AS (SELECT from_id, to_id FROM graph_edges e \
+                 WHERE NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+                 WHERE t.entity_id IN (e.from_id, e.to_id))), \
+                 ends AS (SELECT from_id AS id FROM live UNION ALL SELECT to_id FROM live), \
+                 degrees AS (SELECT id, COUNT(*) AS degree FROM ends \
+                 WHERE id IN (SELECT id FROM graph_entities) GROUP BY id) \
+                 SELECT COUNT(*), MIN(degree), MAX(degree), SUM(degree) FROM degrees",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .map_err(SqliteGraphError::query)?;
+        stats.isolated_nodes = stats.node_count.saturating_sub(connected);
+        stats.min_degree = if stats.isolated_nodes > 0 {
+            0
+        } else {
+            min.unwrap_or(0)
+        };
+        stats.max_degree = max.unwrap_or(0);
+        if stats.node_count > 0 {
+            stats.avg_degree = total.unwrap_or(0) as f64 / stats.node_count as f64;
+        }
+        Ok(stats)
+    }
+
+    fn grouped_counts(&self, sql: &str) -> Result<BTreeMap<String, usize>, SqliteGraphError> {
+        let conn = self.connection();
+        let mut stmt = conn.prepare_cached(sql).map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(SqliteGraphError::query)?;
+        rows.collect::<Result<_, _>>()
+            .map_err(SqliteGraphError::query)
+    }
+}
@@ -0,0 +1,102 @@
+//! Induced subgraph extraction for SqliteGraph.
+
+use rusqlite::params;
+
+use crate::{
+    backend::BackendDirection,
+    errors::{EntityKind, SqliteGraphError},
+    multi_hop,
+};
+
+use super::{GraphEdge, GraphEntity, MAX_SQL_VARIABLES, SqliteGraph, row_to_edge, row_to_entity};
+
+/// Nodes and edges copied out of a graph.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Subgraph {
+    /// Entities ordered by id
+    pub nodes: Vec<GraphEntity>,
+    /// Edges ordered by id
+    pub edges: Vec<GraphEdge>,
+}
+
+impl SqliteGraph {
+    /// The subgraph induced by `node_ids`: those entities and every edge whose
+    /// endpoints are both among them.
+    ///
+    /// Duplicate ids are ignored. Fails with [`SqliteGraphError::NotFound`] if
+    /// any id is missing or soft-deleted.
+    pub fn induced_subgraph(&self, node_ids: &[i64]) -> Result<Subgraph, SqliteGraphError> {
+        let mut ids = node_ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let conn = self.connection();
+        let mut nodes = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(MAX_SQL_VARIABLES) {
+            let placeholders = vec!["?"; chunk.len()].join(",");
+            let sql = format!(
+                "SELECT id, kind, name, file_path, data FROM graph_entities e \
+                 WHERE id IN ({placeholders}) \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+                 ORDER BY id"
+            );
+            let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(chunk), row_to_entity)
+                .map_err(SqliteGraphError::query)?;
+            for row in rows {
+                nodes.push(row.map_err(SqliteGraphError::query)?);
+            }
+        }
+        // Both lists are sorted, so the first mismatch is the smallest missing id
+        let missing = ids
+            .iter()
+            .enumerate()
+            .find(|&(i, &id)| nodes.get(i).map(|node| node.id) != Some(id));
+        if let Some((_, id)) = missing {
+            return Err(SqliteGraphError::not_found(EntityKind::Node, *id));
+        }
+
+        // The id set is bound once as a JSON array, so the edge query needs no
+        // chunking however many nodes are requested.
+        let id_list = format!(
+            "[{}]",
+            ids.iter().map(i64::to_string).collect::<Vec<_>>().join(",")
+        );
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT id, from_id, to_id, edge_type, data FROM graph_edges \
+                 WHERE from_id IN (SELECT value FROM json_each(?1)) \
+                 AND to_id IN (SELECT value FROM json_each(?1)) \
+                 ORDER BY id",
+            )
+            .map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map(params![id_list], row_to_edge)
+            .map_err(SqliteGraphError::query)?;
+        let mut edges = Vec::new();
+        for row in rows {
+            edges.push(row.map_err(SqliteGraphError::query)?);
+        }
+        Ok(Subgraph { nodes, edges })
+    }
+
+    /// The subgraph induced by every entity within `radius` hops of `center`.
+    ///
+    /// Hops follow edges in `direction`; `Both` ignores edge direction. The
+    /// center is always included, so a radius of 0 yields just the center and
+    /// its self-loops. Edges are induced like
+    /// [`induced_subgraph`](Self::induced_subgraph), so an edge between two
+    /// members is returned even if it points against `direction`.
+    pub fn ego_subgraph(
+        &self,
+        center: i64,
+        radius: u32,
+        direction: BackendDirection,
+    ) -> Result<Subgraph, SqliteGraphError> {
+        self.get_entity(center)?;
+        let mut members = multi_hop::k_hop(self, center, radius, direction)?;
+        members.push(center);
+        self.induced_subgraph(&members)
+    }
+}
@@ -0,0 +1,76 @@
+//! Soft deletes (tombstones) for SqliteGraph entities.
+//!
+//! A soft-deleted entity keeps its row, labels, properties and edges, but reads
+//! and traversals behave as if it did not exist: `get_entity` reports it as not
+//! found, it is left out of entity listings, and edges leading to it are not
+//! followed. [`SqliteGraph::undelete_node`] restores it unchanged.
+
+use rusqlite::{OptionalExtension, params};
+
+use crate::errors::{EntityKind, SqliteGraphError};
+
+use super::{
+    SqliteGraph,
+    types::{GraphEntity, row_to_entity},
+};
+
+impl SqliteGraph {
+    /// Hide an entity from reads and traversals without removing it.
+    ///
+    /// Soft-deleting an already soft-deleted entity keeps its original
+    /// deletion time.
+    pub fn soft_delete_node(&self, id: i64) -> Result<(), SqliteGraphError> {
+        self.ensure_writable()?;
+        self.get_node_including_deleted(id)?;
+        self.connection()
+            .execute(
+                "INSERT OR IGNORE INTO graph_tombstones(entity_id) VALUES(?1)",
+                params![id],
+            )
+            .map_err(SqliteGraphError::query)?;
+        self.invalidate_caches();
+        Ok(())
+    }
+
+    /// Restore a soft-deleted entity; a no-op for entities that are not deleted.
+    pub fn undelete_node(&self, id: i64) -> Result<(), SqliteGraphError> {
+        self.ensure_writable()?;
+        self.get_node_including_deleted(id)?;
+        self.connection()
+            .execute(
+                "DELETE FROM graph_tombstones WHERE entity_id=?1",
+                params![id],
+            )
+            .map_err(SqliteGraphError::query)?;
+        self.invalidate_caches();
+        Ok(())
+    }
+
+    /// Fetch an entity whether or not it is soft-deleted.
+    pub fn get_node_including_deleted(&self, id: i64) -> Result<GraphEntity, SqliteGraphError> {
+        self.connection()
+            .query_row(
+                "SELECT id, kind, name, file_path, data FROM graph_entities WHERE id=?1",
+                params![id],
+                row_to_entity,
+            )
+            .map_err(|err| match err {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    SqliteGraphError::not_found(EntityKind::Node, id)
+                }
+                other => SqliteGraphError::query(other),
+            })
+    }
+
+    /// When the entity was soft-deleted (SQLite `CURRENT_TIMESTAMP`, UTC), if it is.
+    pub fn deleted_at(&self, id: i64) -> Result<Option<String>, SqliteGraphError> {
+        self.connection()
+            .query_row(
+                "SELECT deleted_at FROM graph_tombstones WHERE entity_id=?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(SqliteGraphError::query)
+    }
+}
@@ -0,0 +1,119 @@
+//! Explicit transactions spanning several SqliteGraph writes.
+
+use crate::errors::SqliteGraphError;
+
+use super::SqliteGraph;
+
+/// Savepoint used by internal multi-statement writes inside an open guard.
+const NESTED_SAVEPOINT: &str = "sqlitegraph_nested";
+
+/// An open SQLite transaction on a [`SqliteGraph`], created by
+/// [`SqliteGraph::transaction_guard`].
+///
+/// Every write made through the graph while the guard is alive, including bulk
+/// inserts, repairs and delta loads, becomes part of the transaction. Call
+/// [`commit`](Self::commit) to keep the changes; dropping the guard without
+/// committing rolls them back and clears the adjacency caches so later reads
+/// reload the restored state.
+pub struct TransactionGuard<'a> {
+    graph: &'a SqliteGraph,
+    committed: bool,
+}
+
+impl TransactionGuard<'_> {
+    /// Commit the transaction and refresh the MVCC snapshot.
+    ///
+    /// If the commit fails the guard is dropped, which rolls the transaction back.
+    pub fn commit(mut self) -> Result<(), SqliteGraphError> {
+        self.graph
+            .connection()
+            .execute("COMMIT", [])
+            .map_err(SqliteGraphError::transaction)?;
+        self.committed = true;
+        self.graph.transaction_active.set(false);
+        self.graph.invalidate_caches();
+        self.graph.update_snapshot();
+        Ok(())
+    }
+
+    /// Roll the transaction back now instead of when the guard is dropped.
+    pub fn rollback(self) {
+        drop(self);
+    }
+}
+
+impl Drop for TransactionGuard<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = self.graph.connection().execute("ROLLBACK", []);
+            self.graph.transaction_active.set(false);
+            // Writes inside the transaction already updated warm cache entries.
+            self.graph.invalidate_caches();
+            self.graph.update_snapshot();
+        }
+    }
+}
+
+impl SqliteGraph {
+    /// Begin a transaction that lasts until the returned guard is committed or dropped.
+    ///
+    /// Only one guard may be active per graph at a time; requesting a second
+    /// one while the first is alive fails with
+    /// [`SqliteGraphError::TransactionError`].
+    pub fn transaction_guard(&self) -> Result<TransactionGuard<'_>, SqliteGraphError> {
+        if self.transaction_active.get() {
+            return Err(SqliteGraphError::transaction(
+                "a transaction guard is already active on this graph",
+            ));
+        }
+        self.connection()
+            .execute("BEGIN IMMEDIATE", [])
+            .map_err(SqliteGraphError::transaction)?;
+        self.transaction_active.set(true);
+        Ok(TransactionGuard {
+            graph: self,
+            committed: false,
+        })
+    }
+
+    /// Whether a [`TransactionGuard`] is currently open on this graph.
+    pub fn in_transaction(&self) -> bool {
+        self.transaction_active.get()
+    }
+
+    /// Run `f` atomically: in its own transaction, or inside a savepoint when a
+    /// [`TransactionGuard`] is already open so the work joins that transaction.
+    pub(crate) fn with_transaction<R>(
+        &self,
+        f: impl FnOnce() -> Result<R, SqliteGraphError>,
+    ) -> Result<R, SqliteGraphError> {
+        let conn = self.connection();
+        let nested = self.transaction_active.get();
+        let begin = if nested {
+            format!("SAVEPOINT {NESTED_SAVEPOINT}")
+        } else {
+            "BEGIN IMMEDIATE".to_string()
+        };
+        conn.execute(&begin, []).map_err(SqliteGraphError::query)?;
+        match f() {
+            Ok(result) => {
+                let commit = if nested {
+                    format!("RELEASE {NESTED_SAVEPOINT}")
+                } else {
+                    "COMMIT".to_string()
+                };
+                conn.execute(&commit, []).map_err(SqliteGraphError::query)?;
+                Ok(result)
+            }
+            Err(err) => {
+                if nested {
+                    let _ = conn.execute(&format!("ROLLBACK TO {NESTED_SAVEPOINT}"), []);
+                    let _ = conn.execute(&format!("RELEASE {NESTED_SAVEPOINT}"), []);
+                } else {
+                    let _ = conn.execute("ROLLBACK", []);
+                }
+                Err(err)
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::errors::SqliteGraphError;
+use rusqlite::types::{Type, ValueRef};
+
+use crate::{data_format::DataFormat, errors::SqliteGraphError};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GraphEntity {
@@ -20,6 +22,24 @@ pub struct GraphEdge {
     pub data: serde_json::Value,
 }
 
+/// Rows touched by a mutation, for callers that log or verify change volumes.
+///
+/// Only updates and deletes report one; inserts return the new ids directly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MutationOutcome {
+    /// Rows changed across all tables, including cascaded edge deletes.
+    pub affected: u64,
+}
+
+impl MutationOutcome {
+    /// Outcome of a mutation that changed `affected` rows without creating any.
+    pub fn affected(affected: usize) -> Self {
+        Self {
+            affected: affected as u64,
+        }
+    }
+}
+
 pub fn validate_entity(entity: &GraphEntity) -> Result<(), SqliteGraphError> {
     if entity.kind.trim().is_empty() {
         return Err(SqliteGraphError::invalid_input("entity kind must be set"));
@@ -42,15 +62,43 @@ pub fn validate_edge(edge: &GraphEdge) -> Result<(), SqliteGraphError> {
     Ok(())
 }
 
+pub fn validate_data_size(
+    payload_len: usize,
+    limit: Option<usize>,
+) -> Result<(), SqliteGraphError> {
+    if let Some(max) = limit
+        && payload_len > max
+    {
+        return Err(SqliteGraphError::invalid_input(format!(
+            "data payload is {payload_len} bytes, exceeding the limit of {max} bytes"
+        )));
+    }
+    Ok(())
+}
+
+/// Decode a `data` column by its storage type: TEXT is JSON, BLOB is MessagePack.
+pub(crate) fn column_data(
+    row: &rusqlite::Row<'_>,
+    idx: usize,
+) -> Result<serde_json::Value, rusqlite::Error> {
+    let (format, bytes, column_type) = match row.get_ref(idx)? {
+        ValueRef::Text(text) => (DataFormat::Json, text, Type::Text),
+        ValueRef::Blob(blob) => (DataFormat::MessagePack, blob, Type::Blob),
+        other => {
+            return Err(rusqlite::Error::InvalidColumnType(
+                idx,
+                "data".into(),
+                other.data_type(),
+            ));
+        }
+    };
+    format
+        .decode(bytes)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, column_type, Box::new(e)))
+}
+
 pub fn row_to_entity(row: &rusqlite::Row<'_>) -> Result<GraphEntity, rusqlite::Error> {
-    let data: String = row.get(4)?;
-    let value: serde_json::Value = serde_json::from_str(&data).map_err(|e| {
-        rusqlite::Error::FromSqlConversionFailure(
-            data.len(),
-            rusqlite::types::Type::Text,
-            Box::new(e),
-        )
-    })?;
+    let value = column_data(row, 4)?;
     Ok(GraphEntity {
         id: row.get(0)?,
         kind: row.get(1)?,
@@ -61,14 +109,7 @@ pub fn row_to_entity(row: &rusqlite::Row<'_>) -> Result<GraphEntity, rusqlite::E
 }
 
 pub fn row_to_edge(row: &rusqlite::Row<'_>) -> Result<GraphEdge, rusqlite::Error> {
-    let data: String = row.get(4)?;
-    let value: serde_json::Value = serde_json::from_str(&data).map_err(|e| {
-        rusqlite::Error::FromSqlConversionFailure(
-            data.len(),
-            rusqlite::types::Type::Text,
-            Box::new(e),
-        )
-    })?;
+    let value = column_data(row, 4)?;
     Ok(GraphEdge {
         id: row.get(0)?,
         from_id: row.get(1)?,
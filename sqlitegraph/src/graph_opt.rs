@@ -25,16 +25,22 @@ pub struct GraphEdgeCreate {
 }
 
 /// Transaction safety wrapper for automatic rollback on errors
+///
+/// Kept for existing callers. It drives the transaction directly on the
+/// connection, so the graph does not know about it; new code should use
+/// [`SqliteGraph::transaction_guard`], which writes made through the graph join.
+#[deprecated(note = "use `SqliteGraph::transaction_guard` instead")]
 pub struct TransactionGuard<'a> {
     conn: InstrumentedConnection<'a>,
     committed: bool,
 }
 
+#[allow(deprecated)]
 impl<'a> TransactionGuard<'a> {
     /// Start a new transaction with IMMEDIATE mode for better write performance
     pub fn new(conn: InstrumentedConnection<'a>) -> Result<Self, SqliteGraphError> {
         conn.execute("BEGIN IMMEDIATE", [])
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::transaction)?;
         Ok(Self {
             conn,
             committed: false,
@@ -45,10 +51,10 @@ impl<'a> TransactionGuard<'a> {
     pub fn commit(mut self, graph: &SqliteGraph) -> Result<(), SqliteGraphError> {
         self.conn
             .execute("COMMIT", [])
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::transaction)?;
+        self.committed = true;
         graph.invalidate_caches();
         graph.update_snapshot();
-        self.committed = true;
         Ok(())
     }
 
@@ -57,29 +63,22 @@ impl<'a> TransactionGuard<'a> {
         &self.conn
     }
 
-    /// Execute a function with automatic rollback on error
+    /// Execute a function, committing on success and rolling back on error
     pub fn execute<F, R>(mut self, graph: &SqliteGraph, f: F) -> Result<R, SqliteGraphError>
     where
         F: FnOnce(&mut InstrumentedConnection<'a>) -> Result<R, SqliteGraphError>,
     {
-        match f(&mut self.conn) {
-            Ok(result) => {
-                self.commit(graph)?;
-                Ok(result)
-            }
-            Err(err) => {
-                // Don't rollback here - Drop will handle it automatically
-                self.committed = false; // Ensure Drop knows to rollback
-                Err(err)
-            }
-        }
+        // On error the guard is dropped uncommitted, which rolls back
+        let result = f(&mut self.conn)?;
+        self.commit(graph)?;
+        Ok(result)
     }
 }
 
-impl<'a> Drop for TransactionGuard<'a> {
+#[allow(deprecated)]
+impl Drop for TransactionGuard<'_> {
     fn drop(&mut self) {
         if !self.committed {
-            // Auto-rollback if not explicitly committed
             let _ = self.conn.execute("ROLLBACK", []);
         }
     }
@@ -124,6 +123,10 @@ where
     Ok(all_results)
 }
 
+/// Insert `entries` and return their ids.
+///
+/// The result is aligned with the input: `ids[i]` is the id assigned to
+/// `entries[i]`, so it can be used directly to wire up edges afterwards.
 pub fn bulk_insert_entities(
     graph: &SqliteGraph,
     entries: &[GraphEntityCreate],
@@ -131,43 +134,102 @@ pub fn bulk_insert_entities(
     bulk_insert_entities_with_config(graph, entries, &BatchConfig::default())
 }
 
+/// Like [`bulk_insert_entities`], committing in chunks of
+/// `config.max_batch_size`; the ids stay aligned with `entries` across chunks.
 pub fn bulk_insert_entities_with_config(
     graph: &SqliteGraph,
     entries: &[GraphEntityCreate],
     config: &BatchConfig,
 ) -> Result<Vec<i64>, SqliteGraphError> {
+    graph.ensure_writable()?;
     if entries.is_empty() {
         return Ok(Vec::new());
     }
 
-    execute_batch(entries, config, |chunk| {
-        let conn = graph.connection();
-        TransactionGuard::new(conn)?.execute(graph, |conn| {
-            let mut stmt = conn
-                .prepare_cached(
-                    "INSERT INTO graph_entities(kind,name,file_path,data) VALUES(?1,?2,?3,?4)",
-                )
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-            let mut ids = Vec::new();
-            for entry in chunk {
-                validate_entity_create(entry)?;
-                let payload = serde_json::to_string(&entry.data)
-                    .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
-                stmt.execute(rusqlite::params![
-                    entry.kind,
-                    entry.name,
-                    entry.file_path,
-                    payload
-                ])
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-                ids.push(conn.last_insert_rowid());
-            }
+    execute_batch(entries, config, |chunk| insert_entity_batch(graph, chunk))
+}
 
-            // Check for fault injection before commit
-            fault_injection::check_fault(FaultPoint::BulkInsertEntitiesBeforeCommit)?;
-            Ok(ids)
-        })
-    })
+/// Aggregate outcome of [`bulk_insert_entities_iter`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BulkResult {
+    /// Number of entities inserted.
+    pub inserted: usize,
+    /// Number of committed transactions.
+    pub batches: usize,
+    /// Id of the first inserted entity, if any.
+    pub first_id: Option<i64>,
+    /// Id of the last inserted entity, if any.
+    pub last_id: Option<i64>,
+}
+
+/// Insert entities from a streaming source, committing every `batch_size` entries.
+///
+/// At most one batch is held in memory at a time. Each batch is its own
+/// transaction, so when a batch fails the batches committed before it remain in
+/// the graph and the error is returned.
+pub fn bulk_insert_entities_iter<I>(
+    graph: &SqliteGraph,
+    entries: I,
+    batch_size: usize,
+) -> Result<BulkResult, SqliteGraphError>
+where
+    I: IntoIterator<Item = GraphEntityCreate>,
+{
+    graph.ensure_writable()?;
+    if batch_size == 0 {
+        return Err(SqliteGraphError::invalid_input(
+            "batch_size must be greater than zero",
+        ));
+    }
+    let mut result = BulkResult::default();
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut entries = entries.into_iter().peekable();
+    while entries.peek().is_some() {
+        batch.clear();
+        batch.extend(entries.by_ref().take(batch_size));
+        let ids = insert_entity_batch(graph, &batch)?;
+        result.inserted += ids.len();
+        result.batches += 1;
+        if result.first_id.is_none() {
+            result.first_id = ids.first().copied();
+        }
+        result.last_id = ids.last().copied().or(result.last_id);
+    }
+    Ok(result)
+}
+
+fn insert_entity_batch(
+    graph: &SqliteGraph,
+    chunk: &[GraphEntityCreate],
+) -> Result<Vec<i64>, SqliteGraphError> {
+    let conn = graph.connection();
+    let ids = graph.with_transaction(|| {
+        let mut stmt = conn
+            .prepare_cached(
+                "INSERT INTO graph_entities(kind,name,file_path,data) VALUES(?1,?2,?3,?4)",
+            )
+            .map_err(SqliteGraphError::query)?;
+        let mut ids = Vec::new();
+        for entry in chunk {
+            validate_entity_create(entry)?;
+            let payload = graph.encode_data(&entry.data)?;
+            stmt.execute(rusqlite::params![
+                entry.kind,
+                entry.name,
+                entry.file_path,
+                payload
+            ])
+            .map_err(SqliteGraphError::query)?;
+            ids.push(conn.last_insert_rowid());
+        }
+
+        // Check for fault injection before commit
+        fault_injection::check_fault(FaultPoint::BulkInsertEntitiesBeforeCommit)?;
+        Ok(ids)
+    })?;
+    graph.invalidate_caches();
+    graph.update_snapshot();
+    Ok(ids)
 }
 
 pub fn bulk_insert_edges(
@@ -182,45 +244,123 @@ pub fn bulk_insert_edges_with_config(
     entries: &[GraphEdgeCreate],
     config: &BatchConfig,
 ) -> Result<Vec<i64>, SqliteGraphError> {
+    graph.ensure_writable()?;
     if entries.is_empty() {
         return Ok(Vec::new());
     }
 
     execute_batch(entries, config, |chunk| {
         let conn = graph.connection();
-        TransactionGuard::new(conn)?.execute(graph, |conn| {
+        let ids = graph.with_transaction(|| {
             let mut stmt = conn
                 .prepare_cached(
                     "INSERT INTO graph_edges(from_id,to_id,edge_type,data) VALUES(?1,?2,?3,?4)",
                 )
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                .map_err(SqliteGraphError::query)?;
             let mut ids = Vec::new();
             let mut seen = AHashSet::new();
             for entry in chunk {
                 validate_edge_create(entry)?;
+                graph.check_self_loop(entry.from_id, entry.to_id)?;
                 if !seen.insert((entry.from_id, entry.to_id, entry.edge_type.clone())) {
                     continue;
                 }
                 validate_endpoints_exist(&conn, entry.from_id, entry.to_id)?;
-                let payload = serde_json::to_string(&entry.data)
-                    .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+                let payload = graph.encode_data(&entry.data)?;
                 stmt.execute(rusqlite::params![
                     entry.from_id,
                     entry.to_id,
                     entry.edge_type,
                     payload
                 ])
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                .map_err(SqliteGraphError::query)?;
                 ids.push(conn.last_insert_rowid());
             }
 
             // Check for fault injection before commit
             fault_injection::check_fault(FaultPoint::BulkInsertEdgesBeforeCommit)?;
             Ok(ids)
-        })
+        })?;
+        graph.invalidate_caches();
+        graph.update_snapshot();
+        Ok(ids)
     })
 }
 
+/// What [`bulk_upsert_edges`] did with one input edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    /// A new edge was inserted with this id.
+    Inserted(i64),
+    /// The data of the existing edge with this id was replaced.
+    Updated(i64),
+}
+
+impl UpsertOutcome {
+    /// Id of the inserted or updated edge.
+    pub fn id(self) -> i64 {
+        match self {
+            UpsertOutcome::Inserted(id) | UpsertOutcome::Updated(id) => id,
+        }
+    }
+}
+
+/// Reconcile `entries` with the graph in a single transaction.
+///
+/// Edges are keyed by `(from_id, to_id, edge_type)`. When a live edge with that
+/// key exists (the lowest id if there are several), its data is replaced by the
+/// entry's data, not merged; otherwise a new edge is inserted. Later entries
+/// repeating a key update the edge written for the earlier one. The outcomes
+/// are aligned with `entries`, and nothing is written if any entry fails.
+pub fn bulk_upsert_edges(
+    graph: &SqliteGraph,
+    entries: &[GraphEdgeCreate],
+) -> Result<Vec<UpsertOutcome>, SqliteGraphError> {
+    graph.ensure_writable()?;
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let conn = graph.connection();
+    let outcomes = graph.with_transaction(|| {
+        let mut outcomes = Vec::with_capacity(entries.len());
+        for entry in entries {
+            validate_edge_create(entry)?;
+            graph.check_self_loop(entry.from_id, entry.to_id)?;
+            let payload = graph.encode_data(&entry.data)?;
+            let outcome = match graph.find_edge_id(entry.from_id, entry.to_id, &entry.edge_type)? {
+                Some(id) => {
+                    conn.prepare_cached("UPDATE graph_edges SET data=?1 WHERE id=?2")
+                        .and_then(|mut stmt| stmt.execute(rusqlite::params![payload, id]))
+                        .map_err(SqliteGraphError::query)?;
+                    UpsertOutcome::Updated(id)
+                }
+                None => {
+                    validate_endpoints_exist(&conn, entry.from_id, entry.to_id)?;
+                    conn.prepare_cached(
+                        "INSERT INTO graph_edges(from_id,to_id,edge_type,data) VALUES(?1,?2,?3,?4)",
+                    )
+                    .and_then(|mut stmt| {
+                        stmt.execute(rusqlite::params![
+                            entry.from_id,
+                            entry.to_id,
+                            entry.edge_type,
+                            payload
+                        ])
+                    })
+                    .map_err(SqliteGraphError::query)?;
+                    UpsertOutcome::Inserted(conn.last_insert_rowid())
+                }
+            };
+            outcomes.push(outcome);
+        }
+        Ok(outcomes)
+    })?;
+    graph.invalidate_caches();
+    graph.update_snapshot();
+    Ok(outcomes)
+}
+
 pub fn adjacency_fetch_outgoing_batch(
     graph: &SqliteGraph,
     ids: &[i64],
@@ -291,12 +431,41 @@ fn validate_endpoints_exist(
 ) -> Result<(), SqliteGraphError> {
     let mut stmt = conn
         .prepare_cached("SELECT COUNT(1) FROM graph_entities WHERE id IN (?1, ?2)")
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     let count: i64 = stmt
         .query_row(rusqlite::params![from, to], |row| row.get(0))
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     if count < 2 {
         return Err(SqliteGraphError::invalid_input("edge endpoints must exist"));
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_transaction_guard_commits_and_rolls_back() {
+        let graph = SqliteGraph::open_in_memory().unwrap();
+        let insert = "INSERT INTO graph_entities(kind,name,data) VALUES('Node','x','{}')";
+        let run = |fail: bool| {
+            TransactionGuard::new(graph.connection())?.execute(&graph, |conn| {
+                conn.execute(insert, []).map_err(SqliteGraphError::query)?;
+                if fail {
+                    return Err(SqliteGraphError::invalid_input("abort"));
+                }
+                Ok(())
+            })
+        };
+        run(false).unwrap();
+        assert!(run(true).is_err());
+        assert_eq!(graph.list_entity_ids().unwrap().len(), 1);
+
+        let guard = TransactionGuard::new(graph.connection()).unwrap();
+        guard.conn().execute(insert, []).unwrap();
+        guard.commit(&graph).unwrap();
+        assert_eq!(graph.list_entity_ids().unwrap().len(), 2);
+    }
+}
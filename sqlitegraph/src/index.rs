@@ -1,21 +1,178 @@
-use rusqlite::params;
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::{OptionalExtension, params, params_from_iter, types::Value};
 
 use crate::{
-    SqliteGraphError,
-    graph::{GraphEntity, SqliteGraph},
+    EntityKind, PropertyValue, SqliteGraphError,
+    graph::{GraphEntity, SqliteGraph, row_to_entity},
+    schema,
 };
 
+/// Sort key for [`get_entities_by_label_ordered`].
+///
+/// Ties on `Name` and `Kind` are broken by entity id so ordering stays deterministic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OrderBy {
+    #[default]
+    Id,
+    Name,
+    Kind,
+}
+
+impl OrderBy {
+    fn sql(self) -> &'static str {
+        match self {
+            OrderBy::Id => "e.id",
+            OrderBy::Name => "e.name, e.id",
+            OrderBy::Kind => "e.kind, e.id",
+        }
+    }
+}
+
+/// Comparison applied to property values by [`get_entities_by_property_filter`].
+///
+/// Property values are stored as text, so each comparison picks its domain per row:
+/// when the operand parses as a number *and* the stored value is numeric text
+/// (`CAST(value AS REAL)` reproduces it), both sides are compared as `REAL`.
+/// Otherwise both sides are compared as strings using SQLite's binary collation.
+/// This makes `"10" > "9"` hold numerically while `"abc" > "9"` still compares
+/// as text. `Between` is inclusive on both ends and `In` matches any listed
+/// operand under the same rules as `Eq`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyFilter {
+    Eq(String),
+    Ne(String),
+    Lt(String),
+    Le(String),
+    Gt(String),
+    Ge(String),
+    Between(String, String),
+    In(Vec<String>),
+}
+
+impl PropertyFilter {
+    /// SQL condition on `p.value`, pushing its operands onto `params`.
+    fn sql(&self, params: &mut Vec<Value>) -> String {
+        match self {
+            PropertyFilter::Eq(v) => compare("=", v, params),
+            PropertyFilter::Ne(v) => compare("<>", v, params),
+            PropertyFilter::Lt(v) => compare("<", v, params),
+            PropertyFilter::Le(v) => compare("<=", v, params),
+            PropertyFilter::Gt(v) => compare(">", v, params),
+            PropertyFilter::Ge(v) => compare(">=", v, params),
+            PropertyFilter::Between(lo, hi) => format!(
+                "({} AND {})",
+                compare(">=", lo, params),
+                compare("<=", hi, params)
+            ),
+            PropertyFilter::In(values) if values.is_empty() => "0".to_string(),
+            PropertyFilter::In(values) => {
+                let alternatives: Vec<String> =
+                    values.iter().map(|v| compare("=", v, params)).collect();
+                format!("({})", alternatives.join(" OR "))
+            }
+        }
+    }
+}
+
+/// One comparison of `p.value` against `operand`, following the [`PropertyFilter`] coercion rules.
+fn compare(op: &str, operand: &str, params: &mut Vec<Value>) -> String {
+    params.push(Value::Text(operand.to_string()));
+    let text = params.len();
+    match operand.trim().parse::<f64>() {
+        Ok(number) if number.is_finite() => {
+            params.push(Value::Real(number));
+            let real = params.len();
+            format!(
+                "(CASE WHEN CAST(p.value AS REAL)=p.value \
+                 THEN CAST(p.value AS REAL) {op} ?{real} ELSE p.value {op} ?{text} END)"
+            )
+        }
+        _ => format!("p.value {op} ?{text}"),
+    }
+}
+
 pub fn add_label(graph: &SqliteGraph, entity_id: i64, label: &str) -> Result<(), SqliteGraphError> {
+    graph.ensure_writable()?;
     graph
         .connection()
         .execute(
             "INSERT OR IGNORE INTO graph_labels(entity_id, label) VALUES(?1, ?2)",
             params![entity_id, label],
         )
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     Ok(())
 }
 
+/// Add every `(entity_id, label)` pair in one transaction.
+///
+/// All entity ids are checked first; if any is missing, nothing is written and
+/// the first missing id is reported as [`SqliteGraphError::NotFound`].
+pub fn add_labels_bulk(graph: &SqliteGraph, pairs: &[(i64, &str)]) -> Result<(), SqliteGraphError> {
+    graph.ensure_writable()?;
+    graph.with_transaction(|| {
+        ensure_entities_exist(graph, pairs.iter().map(|&(id, _)| id))?;
+        let conn = graph.connection();
+        let mut stmt = conn
+            .prepare_cached("INSERT OR IGNORE INTO graph_labels(entity_id, label) VALUES(?1, ?2)")
+            .map_err(SqliteGraphError::query)?;
+        for (entity_id, label) in pairs {
+            stmt.execute(params![entity_id, label])
+                .map_err(SqliteGraphError::query)?;
+        }
+        Ok(())
+    })
+}
+
+/// Take `label` off `entity_id`; a no-op if the entity does not carry it.
+pub fn remove_label(
+    graph: &SqliteGraph,
+    entity_id: i64,
+    label: &str,
+) -> Result<(), SqliteGraphError> {
+    graph.ensure_writable()?;
+    let removed = graph
+        .connection()
+        .execute(
+            "DELETE FROM graph_labels WHERE entity_id=?1 AND label=?2",
+            params![entity_id, label],
+        )
+        .map_err(SqliteGraphError::query)?;
+    if removed > 0 {
+        graph.invalidate_node_cache(entity_id);
+    }
+    Ok(())
+}
+
+/// Labels carried by `entity_id`, sorted and without duplicates.
+pub fn get_labels(graph: &SqliteGraph, entity_id: i64) -> Result<Vec<String>, SqliteGraphError> {
+    let conn = graph.connection();
+    let mut stmt = conn
+        .prepare_cached("SELECT DISTINCT label FROM graph_labels WHERE entity_id=?1 ORDER BY label")
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map(params![entity_id], |row| row.get(0))
+        .map_err(SqliteGraphError::query)?;
+    rows.collect::<Result<_, _>>()
+        .map_err(SqliteGraphError::query)
+}
+
+/// Whether `entity_id` carries `label`.
+pub fn has_label(
+    graph: &SqliteGraph,
+    entity_id: i64,
+    label: &str,
+) -> Result<bool, SqliteGraphError> {
+    graph
+        .connection()
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM graph_labels WHERE entity_id=?1 AND label=?2)",
+            params![entity_id, label],
+            |row| row.get(0),
+        )
+        .map_err(SqliteGraphError::query)
+}
+
 pub fn get_entities_by_label(
     graph: &SqliteGraph,
     label: &str,
@@ -23,33 +180,202 @@ pub fn get_entities_by_label(
     let conn = graph.connection();
     let mut stmt = conn
         .prepare_cached("SELECT entity_id FROM graph_labels WHERE label=?1 ORDER BY entity_id")
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     let rows = stmt
         .query_map(params![label], |row| row.get(0))
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     let mut ids = Vec::new();
     for row in rows {
-        ids.push(row.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+        ids.push(row.map_err(SqliteGraphError::query)?);
     }
     fetch_entities(graph, ids)
 }
 
+/// Fetch full entities carrying `label`, sorted by `order`, in a single query.
+///
+/// `limit` caps the number of returned entities; `None` returns all of them.
+pub fn get_entities_by_label_ordered(
+    graph: &SqliteGraph,
+    label: &str,
+    order: OrderBy,
+    limit: Option<usize>,
+) -> Result<Vec<GraphEntity>, SqliteGraphError> {
+    let sql = format!(
+        "SELECT e.id, e.kind, e.name, e.file_path, e.data FROM graph_entities e \
+         WHERE e.id IN (SELECT entity_id FROM graph_labels WHERE label=?1) \
+         ORDER BY {} LIMIT ?2",
+        order.sql()
+    );
+    let limit = limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+    let conn = graph.connection();
+    let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map(params![label, limit], row_to_entity)
+        .map_err(SqliteGraphError::query)?;
+    let mut entities = Vec::new();
+    for row in rows {
+        entities.push(row.map_err(SqliteGraphError::query)?);
+    }
+    Ok(entities)
+}
+
+/// Record `key = value` on `entity_id`.
+///
+/// This appends a value: a key added twice keeps both rows, and lookups such as
+/// [`get_entities_by_property`] match either one. Readers that return a single
+/// value per key ([`get_properties`], [`get_properties_bulk`]) take the most
+/// recently written. Use [`update_property`] to replace every previous value.
+///
+/// The value is stored as [`PropertyValue::String`]; see [`add_typed_property`]
+/// to keep numbers and booleans typed.
 pub fn add_property(
     graph: &SqliteGraph,
     entity_id: i64,
     key: &str,
     value: &str,
 ) -> Result<(), SqliteGraphError> {
+    add_typed_property(graph, entity_id, key, PropertyValue::from(value))
+}
+
+/// Record a typed `key = value` on `entity_id`, with the append semantics of
+/// [`add_property`].
+///
+/// The value is stored as text alongside its type tag, so string lookups and
+/// [`PropertyFilter`] comparisons see e.g. `42` and `true`, while
+/// [`get_typed_property`] returns the original variant. A database opened
+/// without migrations may predate the type tag; it then only accepts
+/// [`PropertyValue::String`].
+pub fn add_typed_property(
+    graph: &SqliteGraph,
+    entity_id: i64,
+    key: &str,
+    value: PropertyValue,
+) -> Result<(), SqliteGraphError> {
+    graph.ensure_writable()?;
+    let conn = graph.connection();
+    if schema::has_property_types(&graph.conn)? {
+        conn.execute(
+            "INSERT INTO graph_properties(entity_id, key, value, value_type) \
+             VALUES(?1, ?2, ?3, ?4)",
+            params![entity_id, key, value.to_string(), value.type_tag()],
+        )
+    } else if matches!(value, PropertyValue::String(_)) {
+        conn.execute(
+            "INSERT INTO graph_properties(entity_id, key, value) VALUES(?1, ?2, ?3)",
+            params![entity_id, key, value.to_string()],
+        )
+    } else {
+        return Err(SqliteGraphError::schema(
+            "typed property values need schema version 4; open the graph with migrations",
+        ));
+    }
+    .map_err(SqliteGraphError::query)?;
+    Ok(())
+}
+
+/// The most recently written value of `key` on `entity_id`, with its type.
+///
+/// Values written through the string API, or stored before the type tag
+/// existed, read back as [`PropertyValue::String`].
+pub fn get_typed_property(
+    graph: &SqliteGraph,
+    entity_id: i64,
+    key: &str,
+) -> Result<Option<PropertyValue>, SqliteGraphError> {
+    let sql = if schema::has_property_types(&graph.conn)? {
+        "SELECT value_type, value FROM graph_properties \
+         WHERE entity_id=?1 AND key=?2 ORDER BY rowid DESC LIMIT 1"
+    } else {
+        "SELECT 'string', value FROM graph_properties \
+         WHERE entity_id=?1 AND key=?2 ORDER BY rowid DESC LIMIT 1"
+    };
+    graph
+        .connection()
+        .query_row(sql, params![entity_id, key], |row| {
+            Ok(PropertyValue::from_stored(
+                &row.get::<_, String>(0)?,
+                row.get(1)?,
+            ))
+        })
+        .optional()
+        .map_err(SqliteGraphError::query)
+}
+
+/// Add every `(entity_id, key, value)` triple in one transaction, with the
+/// append semantics of [`add_property`].
+///
+/// All entity ids are checked first; if any is missing, nothing is written and
+/// the first missing id is reported as [`SqliteGraphError::NotFound`].
+pub fn add_properties_bulk(
+    graph: &SqliteGraph,
+    triples: &[(i64, &str, &str)],
+) -> Result<(), SqliteGraphError> {
+    graph.ensure_writable()?;
+    graph.with_transaction(|| {
+        ensure_entities_exist(graph, triples.iter().map(|&(id, _, _)| id))?;
+        let conn = graph.connection();
+        let mut stmt = conn
+            .prepare_cached(
+                "INSERT INTO graph_properties(entity_id, key, value) VALUES(?1, ?2, ?3)",
+            )
+            .map_err(SqliteGraphError::query)?;
+        for (entity_id, key, value) in triples {
+            stmt.execute(params![entity_id, key, value])
+                .map_err(SqliteGraphError::query)?;
+        }
+        Ok(())
+    })
+}
+
+/// Set `key` on `entity_id` to `value`, replacing any previous values of the
+/// key, or adding it if absent.
+pub fn update_property(
+    graph: &SqliteGraph,
+    entity_id: i64,
+    key: &str,
+    value: &str,
+) -> Result<(), SqliteGraphError> {
+    graph.ensure_writable()?;
+    graph.with_transaction(|| {
+        remove_property(graph, entity_id, key)?;
+        add_property(graph, entity_id, key, value)
+    })
+}
+
+/// Drop every value of `key` from `entity_id`; a no-op if the key is absent.
+pub fn remove_property(
+    graph: &SqliteGraph,
+    entity_id: i64,
+    key: &str,
+) -> Result<(), SqliteGraphError> {
+    graph.ensure_writable()?;
     graph
         .connection()
         .execute(
-            "INSERT INTO graph_properties(entity_id, key, value) VALUES(?1, ?2, ?3)",
-            params![entity_id, key, value],
+            "DELETE FROM graph_properties WHERE entity_id=?1 AND key=?2",
+            params![entity_id, key],
         )
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     Ok(())
 }
 
+/// All properties of `entity_id`, with the most recently written value of each key.
+pub fn get_properties(
+    graph: &SqliteGraph,
+    entity_id: i64,
+) -> Result<HashMap<String, String>, SqliteGraphError> {
+    let conn = graph.connection();
+    let mut stmt = conn
+        .prepare_cached("SELECT key, value FROM graph_properties WHERE entity_id=?1 ORDER BY rowid")
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map(params![entity_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(SqliteGraphError::query)?;
+    // Later rows overwrite earlier ones, so the last write wins
+    rows.collect::<Result<_, _>>()
+        .map_err(SqliteGraphError::query)
+}
+
 pub fn get_entities_by_property(
     graph: &SqliteGraph,
     key: &str,
@@ -61,17 +387,263 @@ pub fn get_entities_by_property(
             "SELECT entity_id FROM graph_properties \
              WHERE key=?1 AND value=?2 ORDER BY entity_id",
         )
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     let rows = stmt
         .query_map(params![key, value], |row| row.get(0))
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     let mut ids = Vec::new();
     for row in rows {
-        ids.push(row.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+        ids.push(row.map_err(SqliteGraphError::query)?);
     }
     fetch_entities(graph, ids)
 }
 
+/// Ids of live entities carrying `label`, ascending and without duplicates.
+///
+/// Unlike [`get_entities_by_label`], deleted and soft-deleted entities are
+/// skipped rather than reported as errors.
+pub fn entity_ids_by_label(graph: &SqliteGraph, label: &str) -> Result<Vec<i64>, SqliteGraphError> {
+    collect_ids(
+        graph,
+        "SELECT DISTINCT l.entity_id FROM graph_labels l \
+         JOIN graph_entities e ON e.id=l.entity_id WHERE l.label=?1 \
+         AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+         ORDER BY l.entity_id",
+        params![label],
+    )
+}
+
+/// Ids of live entities with property `key` equal to `value`, ascending and without duplicates.
+pub fn entity_ids_by_property(
+    graph: &SqliteGraph,
+    key: &str,
+    value: &str,
+) -> Result<Vec<i64>, SqliteGraphError> {
+    collect_ids(
+        graph,
+        "SELECT DISTINCT p.entity_id FROM graph_properties p \
+         JOIN graph_entities e ON e.id=p.entity_id WHERE p.key=?1 AND p.value=?2 \
+         AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+         ORDER BY p.entity_id",
+        params![key, value],
+    )
+}
+
+fn collect_ids(
+    graph: &SqliteGraph,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<i64>, SqliteGraphError> {
+    let conn = graph.connection();
+    let mut stmt = conn.prepare_cached(sql).map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map(params, |row| row.get(0))
+        .map_err(SqliteGraphError::query)?;
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row.map_err(SqliteGraphError::query)?);
+    }
+    Ok(ids)
+}
+
+/// Fetch live entities having a `key` property that satisfies `filter`, ascending by id.
+///
+/// An entity matches when any of its values for `key` passes; see
+/// [`PropertyFilter`] for how numeric and text values are compared.
+pub fn get_entities_by_property_filter(
+    graph: &SqliteGraph,
+    key: &str,
+    filter: &PropertyFilter,
+) -> Result<Vec<GraphEntity>, SqliteGraphError> {
+    let mut values = vec![Value::Text(key.to_string())];
+    let condition = filter.sql(&mut values);
+    let sql = format!(
+        "SELECT e.id, e.kind, e.name, e.file_path, e.data FROM graph_entities e \
+         WHERE EXISTS (SELECT 1 FROM graph_properties p \
+         WHERE p.entity_id=e.id AND p.key=?1 AND {condition}) \
+         AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+         ORDER BY e.id"
+    );
+    let conn = graph.connection();
+    let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map(params_from_iter(values), row_to_entity)
+        .map_err(SqliteGraphError::query)?;
+    let mut entities = Vec::new();
+    for row in rows {
+        entities.push(row.map_err(SqliteGraphError::query)?);
+    }
+    Ok(entities)
+}
+
+/// Boolean combination of property equality terms, compiled to one SQL statement.
+///
+/// Terms are chained left to right and follow SQL precedence: `and` binds
+/// tighter than `or`, so `a.and_eq(..).or_eq(..)` reads `(a AND b) OR c`.
+/// Use [`PropertyQuery::and_group`] / [`PropertyQuery::or_group`] to nest
+/// sub-queries explicitly. The connective of the first term is ignored, and an
+/// empty query matches every live entity.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PropertyQuery {
+    terms: Vec<(Connective, QueryTerm)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Connective {
+    And,
+    Or,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum QueryTerm {
+    Eq(String, String),
+    Group(PropertyQuery),
+}
+
+impl PropertyQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// AND a `key = value` term onto the query.
+    pub fn and_eq(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.push(Connective::And, QueryTerm::Eq(key.into(), value.into()))
+    }
+
+    /// OR a `key = value` term onto the query.
+    pub fn or_eq(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.push(Connective::Or, QueryTerm::Eq(key.into(), value.into()))
+    }
+
+    /// AND a parenthesized sub-query onto the query.
+    pub fn and_group(self, group: PropertyQuery) -> Self {
+        self.push(Connective::And, QueryTerm::Group(group))
+    }
+
+    /// OR a parenthesized sub-query onto the query.
+    pub fn or_group(self, group: PropertyQuery) -> Self {
+        self.push(Connective::Or, QueryTerm::Group(group))
+    }
+
+    fn push(mut self, connective: Connective, term: QueryTerm) -> Self {
+        self.terms.push((connective, term));
+        self
+    }
+
+    /// Ids of live entities matching the query, ascending.
+    pub fn execute(&self, graph: &SqliteGraph) -> Result<Vec<i64>, SqliteGraphError> {
+        let mut values = Vec::new();
+        let condition = self.sql(&mut values);
+        let sql = format!(
+            "SELECT e.id FROM graph_entities e WHERE {condition} \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+             ORDER BY e.id"
+        );
+        collect_ids(graph, &sql, params_from_iter(values))
+    }
+
+    /// SQL condition over `e.id`, pushing every key and value onto `params`.
+    fn sql(&self, params: &mut Vec<Value>) -> String {
+        if self.terms.is_empty() {
+            return "1".to_string();
+        }
+        let mut sql = String::from("(");
+        for (idx, (connective, term)) in self.terms.iter().enumerate() {
+            if idx > 0 {
+                sql.push_str(match connective {
+                    Connective::And => " AND ",
+                    Connective::Or => " OR ",
+                });
+            }
+            match term {
+                QueryTerm::Eq(key, value) => {
+                    params.push(Value::Text(key.clone()));
+                    params.push(Value::Text(value.clone()));
+                    sql.push_str(&format!(
+                        "EXISTS (SELECT 1 FROM graph_properties p WHERE p.entity_id=e.id \
+                         AND p.key=?{} AND p.value=?{})",
+                        params.len() - 1,
+                        params.len()
+                    ));
+                }
+                QueryTerm::Group(group) => sql.push_str(&group.sql(params)),
+            }
+        }
+        sql.push(')');
+        sql
+    }
+}
+
+/// Entity ids bound per `IN (...)` query in [`get_properties_bulk`], keeping well below
+/// SQLite's host parameter limit.
+const BULK_PROPERTY_CHUNK: usize = 500;
+
+/// Fetch the requested property `keys` for every entity in `ids` with one query per chunk.
+///
+/// Entities with none of the keys are absent from the result. When a key was
+/// added more than once for an entity, the most recently inserted value wins.
+pub fn get_properties_bulk(
+    graph: &SqliteGraph,
+    ids: &[i64],
+    keys: &[&str],
+) -> Result<HashMap<i64, HashMap<String, String>>, SqliteGraphError> {
+    let mut result: HashMap<i64, HashMap<String, String>> = HashMap::new();
+    if ids.is_empty() || keys.is_empty() {
+        return Ok(result);
+    }
+    let conn = graph.connection();
+    for chunk in ids.chunks(BULK_PROPERTY_CHUNK) {
+        let id_marks = vec!["?"; chunk.len()].join(", ");
+        let key_marks = vec!["?"; keys.len()].join(", ");
+        let sql = format!(
+            "SELECT entity_id, key, value FROM graph_properties \
+             WHERE entity_id IN ({id_marks}) AND key IN ({key_marks}) ORDER BY rowid"
+        );
+        let values = chunk
+            .iter()
+            .map(|&id| Value::Integer(id))
+            .chain(keys.iter().map(|key| Value::Text((*key).to_string())));
+        let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(SqliteGraphError::query)?;
+        for row in rows {
+            let (entity_id, key, value) = row.map_err(SqliteGraphError::query)?;
+            result.entry(entity_id).or_default().insert(key, value);
+        }
+    }
+    Ok(result)
+}
+
+fn ensure_entities_exist(
+    graph: &SqliteGraph,
+    ids: impl Iterator<Item = i64>,
+) -> Result<(), SqliteGraphError> {
+    let conn = graph.connection();
+    let mut stmt = conn
+        .prepare_cached("SELECT EXISTS(SELECT 1 FROM graph_entities WHERE id=?1)")
+        .map_err(SqliteGraphError::query)?;
+    let mut checked = HashSet::new();
+    for id in ids {
+        if !checked.insert(id) {
+            continue;
+        }
+        let exists: bool = stmt
+            .query_row(params![id], |row| row.get(0))
+            .map_err(SqliteGraphError::query)?;
+        if !exists {
+            return Err(SqliteGraphError::not_found(EntityKind::Node, id));
+        }
+    }
+    Ok(())
+}
+
 fn fetch_entities(
     graph: &SqliteGraph,
     ids: Vec<i64>,
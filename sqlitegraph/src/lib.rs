@@ -62,6 +62,7 @@
 //! - [`SqliteConfig`] - SQLite-specific options
 //! - [`NativeConfig`] - Native-specific options
 //! - [`open_graph()`] - Unified factory function
+//! - [`open_graph_in_memory()`] - Same, for a throwaway in-memory graph
 //!
 //! ## Operations
 //! - [`insert_node()`], [`insert_edge()`] - Single entity/edge insertion
@@ -74,38 +75,54 @@
 //! - [`SqliteGraphError`] - Comprehensive error handling
 //! - [`GraphSnapshot`] - MVCC snapshot system
 //! - [`recovery`] - Database backup and restore utilities
+//! - [`reindex`] - Rebuilding and validating the SQLite indexes
+//! - [`safety`] - Audits of stored data such as self-loop detection
 //! - [`query::GraphQuery`] - High-level query interface
 
 // Core public modules
 pub mod backend;
 pub mod config;
+pub mod data_format;
 pub mod errors;
 pub mod graph;
 
 // Re-export core utilities that are stable public APIs
 pub use api_ergonomics::{Label, NodeId, PropertyKey, PropertyValue};
 pub use graph_opt::{
-    GraphEdgeCreate, GraphEntityCreate, bulk_insert_edges, bulk_insert_entities, cache_stats,
+    BulkResult, GraphEdgeCreate, GraphEntityCreate, UpsertOutcome, bulk_insert_edges,
+    bulk_insert_entities, bulk_insert_entities_iter, bulk_upsert_edges, cache_stats,
 };
 pub use index::{add_label, add_property};
-pub use mvcc::{GraphSnapshot, SnapshotState};
-pub use pattern_engine::{PatternTriple, TripleMatch, match_triples};
+pub use mvcc::{GraphDiff, GraphSnapshot, SnapshotState};
+pub use pattern_engine::{
+    PatternTriple, TripleMatch, count_triples, match_triples, match_triples_labeled,
+    match_triples_with_edge_data,
+};
 pub use pattern_engine_cache::match_triples_fast;
-pub use query::GraphQuery;
+pub use query::{GraphQuery, NeighborFilter};
 pub use recovery::{dump_graph_to_path, load_graph_from_path, load_graph_from_reader};
+pub use reindex::{GraphReindexer, ReindexConfig, ReindexProgress, ReindexResult, ReindexStage};
+pub use safety::{SafetyReport, validate_no_self_loops};
 
 // Re-export backend implementations
 pub use backend::{BackendDirection, ChainStep, GraphBackend};
 pub use backend::{EdgeSpec, NativeGraphBackend, NeighborQuery, NodeSpec, SqliteGraphBackend};
 
 // Re-export configuration and factory
-pub use config::{BackendKind, GraphConfig, NativeConfig, SqliteConfig, open_graph};
+pub use backend::native::FlushPolicy;
+pub use config::{
+    BackendKind, GraphConfig, NativeConfig, SqliteConfig, open_graph, open_graph_in_memory,
+};
+pub use data_format::DataFormat;
 
 // Re-export error types
-pub use errors::SqliteGraphError;
+pub use errors::{EntityKind, SqliteGraphError};
 
 // Re-export graph core types
-pub use graph::{GraphEdge, GraphEntity, SqliteGraph};
+pub use graph::{
+    DanglingEdge, GraphEdge, GraphEntity, GraphStatistics, IntegrityReport, LabeledView,
+    MutationOutcome, RepairMode, RepairReport, SqliteGraph, Subgraph, TransactionGuard,
+};
 
 // Internal modules - not part of public API
 pub mod algo; // Public for tests
@@ -124,10 +141,13 @@ mod reasoning; // Public for binary
 pub mod schema; // Public for tests // Public for tests
 
 // Core public modules (these were accidentally removed)
+pub mod migration;
 pub mod mvcc; // Already exported above
 pub mod pattern_engine; // Already exported above
 pub mod query; // Already exported above
 pub mod recovery; // Already exported above
+pub mod reindex; // Already exported above
+pub mod safety;
 
 // Modules that need to remain public for specific use cases
 pub mod bench_gates; // Public for tests
@@ -0,0 +1,306 @@
+//! Copying a graph between the SQLite and native backends.
+//!
+//! Node and edge ids are preserved in both directions. The native format only
+//! holds ids as a dense sequence starting at 1, so ids missing on the SQLite
+//! side, and soft-deleted nodes, are written as deleted records that keep later
+//! ids in place; going back, deleted native records are left out. Anything the
+//! target cannot hold is counted as skipped in [`MigrationStats`] rather than
+//! failing the migration.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::{
+    SqliteGraphError,
+    backend::NativeGraphBackend,
+    backend::native::{EdgeFlags, EdgeRecord, NodeFlags, NodeRecord},
+    graph::{SqliteGraph, row_to_edge, row_to_entity},
+};
+
+/// Outcome of a migration between backends.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationStats {
+    pub nodes_migrated: usize,
+    pub edges_migrated: usize,
+    /// Soft-deleted nodes, written as deleted records
+    pub nodes_skipped: usize,
+    /// Edges touching a soft-deleted or missing node, written as deleted records
+    pub edges_skipped: usize,
+    /// Labels the target format has no room for
+    pub labels_skipped: usize,
+    /// Properties the target format has no room for
+    pub properties_skipped: usize,
+    /// Node file paths the target format has no room for
+    pub file_paths_dropped: usize,
+}
+
+/// Copy every node and edge of `src` into a new native graph file at `dest`.
+///
+/// `dest` must not exist yet or be an empty file. The records are written in a
+/// single pass with adjacency already laid out, so neighbor queries work on the
+/// result right away. Labels go into a `labels` array in the node data, where
+/// the native label lookups read them; a node whose data is neither an object
+/// nor null cannot take them, and its labels are counted as skipped. Node
+/// `file_path`s are dropped and properties are only counted, since the native
+/// format stores neither.
+pub fn sqlite_to_native<P: AsRef<Path>>(
+    src: &SqliteGraph,
+    dest: P,
+) -> Result<MigrationStats, SqliteGraphError> {
+    let dest = dest.as_ref();
+    if std::fs::metadata(dest).is_ok_and(|meta| meta.len() > 0) {
+        return Err(SqliteGraphError::invalid_input(format!(
+            "migration target {} already exists",
+            dest.display()
+        )));
+    }
+
+    let mut stats = MigrationStats::default();
+    let mut labels = read_labels(src)?;
+    let (nodes, live) = read_nodes(src, &mut labels, &mut stats)?;
+    let edges = read_edges(src, nodes.is_empty(), &live, &mut stats)?;
+    // Labels of nodes that were not migrated live
+    stats.labels_skipped += labels.values().map(Vec::len).sum::<usize>();
+    stats.properties_skipped = count_rows(src, "graph_properties")?;
+
+    let native = NativeGraphBackend::new(dest)?;
+    native.load_records(&nodes, &edges)?;
+    Ok(stats)
+}
+
+/// Copy every live node and edge of `src` into a new SQLite graph at
+/// `dest_path`.
+///
+/// The database is opened with schema migrations applied and must not hold
+/// any node or edge yet. Records keep their native ids and are inserted in a
+/// single transaction, so a failure leaves the database empty. Deleted native
+/// records, and edges touching a deleted node, are counted as skipped and their
+/// ids stay unused. Nodes get no `file_path`.
+pub fn native_to_sqlite<P: AsRef<Path>>(
+    src: &NativeGraphBackend,
+    dest_path: P,
+) -> Result<MigrationStats, SqliteGraphError> {
+    let (nodes, edges) = src.records()?;
+    let graph = SqliteGraph::open(dest_path)?;
+    if count_rows(&graph, "graph_entities")? > 0 || count_rows(&graph, "graph_edges")? > 0 {
+        return Err(SqliteGraphError::invalid_input(
+            "migration target already holds a graph",
+        ));
+    }
+
+    let mut stats = MigrationStats::default();
+    let conn = graph.connection();
+    graph.with_transaction(|| {
+        let mut stmt_entity = conn
+            .prepare_cached(
+                "INSERT INTO graph_entities(id,kind,name,file_path,data) VALUES(?1,?2,?3,NULL,?4)",
+            )
+            .map_err(SqliteGraphError::query)?;
+        let mut stmt_edge = conn
+            .prepare_cached(
+                "INSERT INTO graph_edges(id,from_id,to_id,edge_type,data) VALUES(?1,?2,?3,?4,?5)",
+            )
+            .map_err(SqliteGraphError::query)?;
+
+        let mut live = HashSet::new();
+        for node in &nodes {
+            if node.flags.contains(NodeFlags::DELETED) {
+                stats.nodes_skipped += 1;
+                continue;
+            }
+            let payload = graph.encode_data(&node.data)?;
+            stmt_entity
+                .execute(params![node.id, node.kind, node.name, payload])
+                .map_err(SqliteGraphError::query)?;
+            live.insert(node.id);
+            stats.nodes_migrated += 1;
+        }
+        for edge in &edges {
+            if edge.flags.contains(EdgeFlags::DELETED)
+                || !live.contains(&edge.from_id)
+                || !live.contains(&edge.to_id)
+            {
+                stats.edges_skipped += 1;
+                continue;
+            }
+            let payload = graph.encode_data(&edge.data)?;
+            stmt_edge
+                .execute(params![
+                    edge.id,
+                    edge.from_id,
+                    edge.to_id,
+                    edge.edge_type,
+                    payload
+                ])
+                .map_err(SqliteGraphError::query)?;
+            stats.edges_migrated += 1;
+        }
+        Ok(())
+    })?;
+    Ok(stats)
+}
+
+/// Labels of every entity, in label order
+fn read_labels(src: &SqliteGraph) -> Result<HashMap<i64, Vec<String>>, SqliteGraphError> {
+    let conn = src.connection();
+    let mut stmt = conn
+        .prepare_cached("SELECT entity_id, label FROM graph_labels ORDER BY entity_id, label")
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(SqliteGraphError::query)?;
+    let mut labels: HashMap<i64, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (id, label) = row.map_err(SqliteGraphError::query)?;
+        labels.entry(id).or_default().push(label);
+    }
+    Ok(labels)
+}
+
+/// Add `labels` to the `labels` array of `data`, returning false if `data`
+/// has no room for them.
+fn merge_labels(data: &mut serde_json::Value, labels: Vec<String>) -> bool {
+    if data.is_null() {
+        *data = serde_json::json!({});
+    }
+    let Some(object) = data.as_object_mut() else {
+        return false;
+    };
+    let entry = object
+        .entry("labels")
+        .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+    let Some(existing) = entry.as_array_mut() else {
+        return false;
+    };
+    for label in labels {
+        if !existing.iter().any(|l| l.as_str() == Some(label.as_str())) {
+            existing.push(serde_json::Value::String(label));
+        }
+    }
+    true
+}
+
+/// Node records for ids 1 up to the largest SQLite id, along with the ids of
+/// the nodes that were migrated live.
+///
+/// The labels of live nodes are taken out of `labels` and merged into their data.
+fn read_nodes(
+    src: &SqliteGraph,
+    labels: &mut HashMap<i64, Vec<String>>,
+    stats: &mut MigrationStats,
+) -> Result<(Vec<NodeRecord>, HashSet<i64>), SqliteGraphError> {
+    let conn = src.connection();
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT id, kind, name, file_path, data, \
+             EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+             FROM graph_entities e WHERE id > 0 ORDER BY id",
+        )
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([], |row| Ok((row_to_entity(row)?, row.get::<_, bool>(5)?)))
+        .map_err(SqliteGraphError::query)?;
+
+    let mut nodes = Vec::new();
+    let mut live = HashSet::new();
+    for row in rows {
+        let (entity, deleted) = row.map_err(SqliteGraphError::query)?;
+        while (nodes.len() as i64) + 1 < entity.id {
+            nodes.push(deleted_node(nodes.len() as i64 + 1));
+        }
+        if deleted {
+            stats.nodes_skipped += 1;
+            nodes.push(deleted_node(entity.id));
+        } else {
+            stats.nodes_migrated += 1;
+            if entity.file_path.is_some() {
+                stats.file_paths_dropped += 1;
+            }
+            let mut data = entity.data;
+            if let Some(node_labels) = labels.remove(&entity.id) {
+                let count = node_labels.len();
+                if !merge_labels(&mut data, node_labels) {
+                    stats.labels_skipped += count;
+                }
+            }
+            live.insert(entity.id);
+            nodes.push(NodeRecord::new(entity.id, entity.kind, entity.name, data));
+        }
+    }
+    Ok((nodes, live))
+}
+
+/// Edge records for ids 1 up to the largest SQLite id; edges whose endpoints
+/// were not migrated live are written as deleted.
+fn read_edges(
+    src: &SqliteGraph,
+    no_nodes: bool,
+    live: &HashSet<i64>,
+    stats: &mut MigrationStats,
+) -> Result<Vec<EdgeRecord>, SqliteGraphError> {
+    let conn = src.connection();
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT id, from_id, to_id, edge_type, data FROM graph_edges \
+             WHERE id > 0 ORDER BY id",
+        )
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([], row_to_edge)
+        .map_err(SqliteGraphError::query)?;
+
+    let mut edges = Vec::new();
+    for row in rows {
+        let edge = row.map_err(SqliteGraphError::query)?;
+        // Without any node there is no valid endpoint for a deleted placeholder
+        if no_nodes {
+            stats.edges_skipped += 1;
+            continue;
+        }
+        while (edges.len() as i64) + 1 < edge.id {
+            edges.push(deleted_edge(edges.len() as i64 + 1));
+        }
+        if live.contains(&edge.from_id) && live.contains(&edge.to_id) {
+            stats.edges_migrated += 1;
+            edges.push(EdgeRecord::new(
+                edge.id,
+                edge.from_id,
+                edge.to_id,
+                edge.edge_type,
+                edge.data,
+            ));
+        } else {
+            stats.edges_skipped += 1;
+            edges.push(deleted_edge(edge.id));
+        }
+    }
+    Ok(edges)
+}
+
+fn count_rows(src: &SqliteGraph, table: &str) -> Result<usize, SqliteGraphError> {
+    src.connection()
+        .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+            row.get(0)
+        })
+        .map_err(SqliteGraphError::query)
+}
+
+fn deleted_node(id: i64) -> NodeRecord {
+    let mut node = NodeRecord::new(id, String::new(), String::new(), serde_json::Value::Null);
+    node.flags = node.flags.set(NodeFlags::DELETED);
+    node
+}
+
+/// Deleted edges still need endpoints in range, so they point at node 1.
+fn deleted_edge(id: i64) -> EdgeRecord {
+    let mut edge = EdgeRecord::new(id, 1, 1, String::new(), serde_json::Value::Null);
+    edge.flags = edge.flags.set(EdgeFlags::DELETED);
+    edge
+}
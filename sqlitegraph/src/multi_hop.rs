@@ -1,13 +1,22 @@
-use std::collections::VecDeque;
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
 
-use ahash::AHashSet;
+use ahash::{AHashMap, AHashSet};
 
-use crate::{SqliteGraphError, backend::BackendDirection, graph::SqliteGraph};
+use crate::{
+    SqliteGraphError,
+    backend::{BackendDirection, merge_undirected},
+    graph::SqliteGraph,
+};
 
-const OUTGOING_TYPED_SQL: &str =
-    "SELECT to_id, edge_type FROM graph_edges WHERE from_id=?1 ORDER BY to_id, edge_type, id";
-const INCOMING_TYPED_SQL: &str =
-    "SELECT from_id, edge_type FROM graph_edges WHERE to_id=?1 ORDER BY from_id, edge_type, id";
+const OUTGOING_TYPED_SQL: &str = "SELECT to_id, edge_type FROM graph_edges WHERE from_id=?1 \
+     AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=to_id) \
+     ORDER BY to_id, edge_type, id";
+const INCOMING_TYPED_SQL: &str = "SELECT from_id, edge_type FROM graph_edges WHERE to_id=?1 \
+     AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=from_id) \
+     ORDER BY from_id, edge_type, id";
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ChainStep {
@@ -21,6 +30,16 @@ pub fn k_hop(
     depth: u32,
     direction: BackendDirection,
 ) -> Result<Vec<i64>, SqliteGraphError> {
+    k_hop_internal(graph, start, depth, direction, None).map(strip_distances)
+}
+
+/// Like [`k_hop`], pairing each reached node with its minimum hop distance from `start`.
+pub fn k_hop_with_distance(
+    graph: &SqliteGraph,
+    start: i64,
+    depth: u32,
+    direction: BackendDirection,
+) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
     k_hop_internal(graph, start, depth, direction, None)
 }
 
@@ -67,7 +86,118 @@ pub fn k_hop_filtered(
     if allowed_edge_types.is_empty() {
         return Ok(Vec::new());
     }
-    k_hop_internal(graph, start, depth, direction, Some(allowed_edge_types))
+    k_hop_internal(graph, start, depth, direction, Some(allowed_edge_types)).map(strip_distances)
+}
+
+/// Cost-bounded expansion from `start` where each hop costs its edge type's entry in
+/// `type_costs`; edge types missing from the map are not traversed.
+///
+/// Returns every node other than `start` whose cheapest accumulated cost is within
+/// `budget`, ordered by cost and then id.
+pub fn k_hop_weighted(
+    graph: &SqliteGraph,
+    start: i64,
+    budget: f64,
+    direction: BackendDirection,
+    type_costs: &HashMap<String, f64>,
+) -> Result<Vec<(i64, f64)>, SqliteGraphError> {
+    cost_bounded_expand(start, budget, type_costs, |node, edge_type| {
+        let allowed = AHashSet::from_iter([edge_type]);
+        filter_neighbors(graph, node, direction, &allowed)
+    })
+}
+
+/// Backend-independent Dijkstra expansion behind `k_hop_weighted`.
+///
+/// `neighbors(node, edge_type)` must return the neighbors of `node` reachable over
+/// edges of `edge_type` in the caller's direction.
+pub(crate) fn cost_bounded_expand<F>(
+    start: i64,
+    budget: f64,
+    type_costs: &HashMap<String, f64>,
+    mut neighbors: F,
+) -> Result<Vec<(i64, f64)>, SqliteGraphError>
+where
+    F: FnMut(i64, &str) -> Result<Vec<i64>, SqliteGraphError>,
+{
+    if budget.is_nan() {
+        return Err(SqliteGraphError::invalid_input("budget must be a number"));
+    }
+    let mut costs: Vec<(&str, f64)> = Vec::with_capacity(type_costs.len());
+    for (edge_type, &cost) in type_costs {
+        if !cost.is_finite() || cost < 0.0 {
+            return Err(SqliteGraphError::invalid_input(format!(
+                "cost for edge type '{edge_type}' must be finite and non-negative"
+            )));
+        }
+        costs.push((edge_type.as_str(), cost));
+    }
+    costs.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut best: AHashMap<i64, f64> = AHashMap::new();
+    let mut settled = AHashSet::new();
+    let mut frontier = BinaryHeap::new();
+    best.insert(start, 0.0);
+    frontier.push(Frontier {
+        cost: 0.0,
+        node: start,
+    });
+    while let Some(Frontier { cost, node }) = frontier.pop() {
+        if !settled.insert(node) {
+            continue;
+        }
+        for &(edge_type, step) in &costs {
+            let next_cost = cost + step;
+            if next_cost > budget {
+                continue;
+            }
+            for next in neighbors(node, edge_type)? {
+                if settled.contains(&next) {
+                    continue;
+                }
+                if best.get(&next).map_or(true, |&known| next_cost < known) {
+                    best.insert(next, next_cost);
+                    frontier.push(Frontier {
+                        cost: next_cost,
+                        node: next,
+                    });
+                }
+            }
+        }
+    }
+    best.remove(&start);
+    let mut reached: Vec<(i64, f64)> = best.into_iter().collect();
+    reached.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+    Ok(reached)
+}
+
+/// Min-heap entry for `cost_bounded_expand`, ordered by cost then node id.
+struct Frontier {
+    cost: f64,
+    node: i64,
+}
+
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Frontier {}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.node.cmp(&self.node))
+    }
 }
 
 pub fn chain_query(
@@ -107,7 +237,7 @@ fn k_hop_internal(
     depth: u32,
     direction: BackendDirection,
     allowed_edge_types: Option<&[&str]>,
-) -> Result<Vec<i64>, SqliteGraphError> {
+) -> Result<Vec<(i64, u32)>, SqliteGraphError> {
     if depth == 0 {
         return Ok(Vec::new());
     }
@@ -125,13 +255,22 @@ fn k_hop_internal(
         let neighbors = adjacency_for(graph, node, direction, allowed_lookup.as_ref())?;
         for neighbor in neighbors {
             if visited.insert(neighbor) {
-                ordered.push((level + 1, neighbor));
+                ordered.push((neighbor, level + 1));
                 queue.push_back((neighbor, level + 1));
             }
         }
     }
-    ordered.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
-    Ok(ordered.into_iter().map(|(_, node)| node).collect())
+    sort_by_distance(&mut ordered);
+    Ok(ordered)
+}
+
+/// Order `(node, distance)` pairs by distance, then node id, as `k_hop` returns them.
+pub(crate) fn sort_by_distance(reached: &mut [(i64, u32)]) {
+    reached.sort_unstable_by_key(|&(node, distance)| (distance, node));
+}
+
+pub(crate) fn strip_distances(reached: Vec<(i64, u32)>) -> Vec<i64> {
+    reached.into_iter().map(|(node, _)| node).collect()
 }
 
 fn adjacency_for(
@@ -142,10 +281,7 @@ fn adjacency_for(
 ) -> Result<Vec<i64>, SqliteGraphError> {
     match allowed_types {
         Some(set) => filter_neighbors(graph, node, direction, set),
-        None => match direction {
-            BackendDirection::Outgoing => graph.fetch_outgoing(node),
-            BackendDirection::Incoming => graph.fetch_incoming(node),
-        },
+        None => graph.fetch_neighbors(node, direction),
     }
 }
 
@@ -161,21 +297,25 @@ fn filter_neighbors(
     let sql = match direction {
         BackendDirection::Outgoing => OUTGOING_TYPED_SQL,
         BackendDirection::Incoming => INCOMING_TYPED_SQL,
+        BackendDirection::Both => {
+            return Ok(merge_undirected(
+                filter_neighbors(graph, node, BackendDirection::Outgoing, allowed_types)?,
+                filter_neighbors(graph, node, BackendDirection::Incoming, allowed_types)?,
+            ));
+        }
     };
     let conn = graph.connection();
-    let mut stmt = conn
-        .prepare_cached(sql)
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+    let mut stmt = conn.prepare_cached(sql).map_err(SqliteGraphError::query)?;
     let rows = stmt
         .query_map([node], |row| {
             let neighbor: i64 = row.get(0)?;
             let edge_type: String = row.get(1)?;
             Ok((neighbor, edge_type))
         })
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     let mut result = Vec::new();
     for row in rows {
-        let (neighbor, edge_type) = row.map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        let (neighbor, edge_type) = row.map_err(SqliteGraphError::query)?;
         if allowed_types.contains(edge_type.as_str()) && result.last().copied() != Some(neighbor) {
             result.push(neighbor);
         }
@@ -8,9 +8,13 @@
 
 use arc_swap::ArcSwap;
 use rusqlite::{Connection, OpenFlags, Result as SqliteResult};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 
+use crate::errors::{EntityKind, SqliteGraphError};
+use crate::graph::SqliteGraph;
+use crate::pattern_engine::{PatternTriple, TripleMatch};
+
 pub type NodeId = i64;
 
 /// Immutable snapshot state containing cloned adjacency data
@@ -23,6 +27,9 @@ pub struct SnapshotState {
     pub outgoing: HashMap<NodeId, Vec<NodeId>>,
     /// Immutable copy of incoming adjacency map  
     pub incoming: HashMap<NodeId, Vec<NodeId>>,
+    /// Ids of the live edges in ascending order; empty unless set with
+    /// [`with_edge_ids`](Self::with_edge_ids)
+    pub edge_ids: Vec<i64>,
     /// Snapshot creation timestamp
     pub created_at: std::time::SystemTime,
 }
@@ -44,10 +51,18 @@ impl SnapshotState {
             // Use .clone() to create deep copies, not Arc::clone()
             outgoing: outgoing.clone(),
             incoming: incoming.clone(),
+            edge_ids: Vec::new(),
             created_at: std::time::SystemTime::now(),
         }
     }
 
+    /// Record the ids of the edges captured in this state
+    pub fn with_edge_ids(mut self, mut edge_ids: Vec<i64>) -> Self {
+        edge_ids.sort_unstable();
+        self.edge_ids = edge_ids;
+        self
+    }
+
     /// Get the number of nodes in this snapshot
     pub fn node_count(&self) -> usize {
         self.outgoing.len()
@@ -118,6 +133,11 @@ impl SnapshotManager {
         self.current.store(Arc::new(new_state));
     }
 
+    /// Atomically replace the snapshot state with a prepared one
+    pub fn store(&self, state: SnapshotState) {
+        self.current.store(Arc::new(state));
+    }
+
     /// Acquire a deterministic snapshot of current state
     ///
     /// # Returns
@@ -147,6 +167,8 @@ pub struct GraphSnapshot {
     state: Arc<SnapshotState>,
     /// Read-only SQLite connection for database queries
     conn: Connection,
+    /// Query-only copy of the database taken with the adjacency, if any
+    data: Option<SqliteGraph>,
 }
 
 impl GraphSnapshot {
@@ -165,7 +187,17 @@ impl GraphSnapshot {
             OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
         )?;
 
-        Ok(Self { state, conn })
+        Ok(Self {
+            state,
+            conn,
+            data: None,
+        })
+    }
+
+    /// Attach the database copy captured alongside the snapshot state
+    pub(crate) fn with_data(mut self, data: SqliteGraph) -> Self {
+        self.data = Some(data);
+        self
     }
 
     /// Get the snapshot state
@@ -203,10 +235,120 @@ impl GraphSnapshot {
         self.state.get_incoming(node_id)
     }
 
+    /// Outgoing neighbors of `node_id` as captured when the snapshot was taken
+    ///
+    /// Returns `NotFound` if the node was not part of the graph at that time.
+    pub fn neighbors(&self, node_id: NodeId) -> Result<Vec<NodeId>, SqliteGraphError> {
+        self.state
+            .get_outgoing(node_id)
+            .cloned()
+            .ok_or_else(|| SqliteGraphError::not_found(EntityKind::Node, node_id))
+    }
+
+    /// Incoming neighbors of `node_id` as captured when the snapshot was taken
+    ///
+    /// Returns `NotFound` if the node was not part of the graph at that time.
+    pub fn incoming(&self, node_id: NodeId) -> Result<Vec<NodeId>, SqliteGraphError> {
+        if !self.state.contains_node(node_id) {
+            return Err(SqliteGraphError::not_found(EntityKind::Node, node_id));
+        }
+        Ok(self
+            .state
+            .get_incoming(node_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
     /// Get snapshot creation timestamp
     pub fn created_at(&self) -> std::time::SystemTime {
         self.state.created_at
     }
+
+    /// Nodes and edges added to or removed from `current` since the snapshot
+    ///
+    /// Compares the live (not soft-deleted) node ids and edge ids of `current`
+    /// against those captured by the snapshot. Only membership is compared;
+    /// data rewritten in place does not show up. Snapshots built from adjacency
+    /// alone, without [`SnapshotState::with_edge_ids`], report every current
+    /// edge as added.
+    pub fn diff(&self, current: &SqliteGraph) -> Result<GraphDiff, SqliteGraphError> {
+        let before_nodes: BTreeSet<NodeId> = self.state.outgoing.keys().copied().collect();
+        let after_nodes: BTreeSet<NodeId> = current.all_entity_ids()?.into_iter().collect();
+        let before_edges: BTreeSet<i64> = self.state.edge_ids.iter().copied().collect();
+        let after_edges: BTreeSet<i64> = current.live_edge_ids()?.into_iter().collect();
+        Ok(GraphDiff {
+            added_nodes: after_nodes.difference(&before_nodes).copied().collect(),
+            removed_nodes: before_nodes.difference(&after_nodes).copied().collect(),
+            added_edges: after_edges.difference(&before_edges).copied().collect(),
+            removed_edges: before_edges.difference(&after_edges).copied().collect(),
+        })
+    }
+
+    /// Match `pattern` against the graph as it was when the snapshot was taken
+    ///
+    /// Runs the same evaluation as [`match_triples`](crate::match_triples) over
+    /// the database copy captured by
+    /// [`SqliteGraph::acquire_snapshot`], so results come back in the same
+    /// order and ignore any later writes to the parent graph. Snapshots built
+    /// directly with [`GraphSnapshot::new`] carry no such copy and return
+    /// `InvalidInput`.
+    pub fn match_triples(
+        &self,
+        pattern: &PatternTriple,
+    ) -> Result<Vec<TripleMatch>, SqliteGraphError> {
+        let data = self.data.as_ref().ok_or_else(|| {
+            SqliteGraphError::invalid_input("snapshot holds adjacency only, no graph data")
+        })?;
+        crate::pattern_engine::match_triples(data, pattern)
+    }
+
+    /// Serialize the frozen snapshot state for inspection or offline comparison
+    ///
+    /// Emits `node_ids` and `edges` (`{"from", "to"}` objects) in ascending order
+    /// plus `node_count` and `edge_count`. The creation timestamp is left out so
+    /// two snapshots of the same graph serialize identically.
+    pub fn to_json(&self) -> Result<serde_json::Value, SqliteGraphError> {
+        let mut node_ids: Vec<NodeId> = self.state.outgoing.keys().copied().collect();
+        node_ids.sort_unstable();
+        let mut edges: Vec<(NodeId, NodeId)> = self
+            .state
+            .outgoing
+            .iter()
+            .flat_map(|(&from, targets)| targets.iter().map(move |&to| (from, to)))
+            .collect();
+        edges.sort_unstable();
+        let edges: Vec<serde_json::Value> = edges
+            .into_iter()
+            .map(|(from, to)| serde_json::json!({ "from": from, "to": to }))
+            .collect();
+        Ok(serde_json::json!({
+            "node_count": self.node_count(),
+            "edge_count": self.edge_count(),
+            "node_ids": node_ids,
+            "edges": edges,
+        }))
+    }
+}
+
+/// Changes between a snapshot and the live graph, from [`GraphSnapshot::diff`]
+///
+/// Every list is sorted ascending.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<NodeId>,
+    pub removed_nodes: Vec<NodeId>,
+    pub added_edges: Vec<i64>,
+    pub removed_edges: Vec<i64>,
+}
+
+impl GraphDiff {
+    /// Whether nothing was added or removed
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -254,4 +396,27 @@ mod tests {
         // Original snapshot should be unchanged
         assert_eq!(snapshot.node_count(), 1);
     }
+
+    #[test]
+    fn test_graph_snapshot_to_json() {
+        let mut outgoing = HashMap::new();
+        outgoing.insert(2, vec![1]);
+        outgoing.insert(1, vec![3, 2]);
+        let state = Arc::new(SnapshotState::new(&outgoing, &HashMap::new()));
+        let snapshot = GraphSnapshot::new(state, ":memory:").unwrap();
+
+        assert_eq!(
+            snapshot.to_json().unwrap(),
+            serde_json::json!({
+                "node_count": 2,
+                "edge_count": 3,
+                "node_ids": [1, 2],
+                "edges": [
+                    { "from": 1, "to": 2 },
+                    { "from": 1, "to": 3 },
+                    { "from": 2, "to": 1 },
+                ],
+            })
+        );
+    }
 }
@@ -3,14 +3,16 @@ use rusqlite::params;
 
 use crate::{
     SqliteGraphError,
-    backend::BackendDirection,
+    backend::{BackendDirection, merge_undirected},
     graph::{GraphEntity, SqliteGraph},
 };
 
-const OUTGOING_FILTER_SQL: &str =
-    "SELECT to_id FROM graph_edges WHERE from_id=?1 AND edge_type=?2 ORDER BY to_id, id";
-const INCOMING_FILTER_SQL: &str =
-    "SELECT from_id FROM graph_edges WHERE to_id=?1 AND edge_type=?2 ORDER BY from_id, id";
+const OUTGOING_FILTER_SQL: &str = "SELECT to_id FROM graph_edges WHERE from_id=?1 AND edge_type=?2 \
+     AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=to_id) \
+     ORDER BY to_id, id";
+const INCOMING_FILTER_SQL: &str = "SELECT from_id FROM graph_edges WHERE to_id=?1 AND edge_type=?2 \
+     AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=from_id) \
+     ORDER BY from_id, id";
 
 #[derive(Clone, Debug, Default)]
 pub struct NodeConstraint {
@@ -66,11 +68,15 @@ pub fn entity_ids_with_constraint(
 fn query_kind(graph: &SqliteGraph, kind: &str) -> Result<Vec<i64>, SqliteGraphError> {
     let conn = graph.connection();
     let mut stmt = conn
-        .prepare_cached("SELECT id FROM graph_entities WHERE kind=?1 ORDER BY id")
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .prepare_cached(
+            "SELECT id FROM graph_entities WHERE kind=?1 \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=id) \
+             ORDER BY id",
+        )
+        .map_err(SqliteGraphError::query)?;
     let rows = stmt
         .query_map(params![kind], |row| row.get(0))
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     collect_ids(rows)
 }
 
@@ -78,11 +84,15 @@ fn query_prefix(graph: &SqliteGraph, prefix: &str) -> Result<Vec<i64>, SqliteGra
     let like = format!("{prefix}%");
     let conn = graph.connection();
     let mut stmt = conn
-        .prepare_cached("SELECT id FROM graph_entities WHERE name LIKE ?1 ORDER BY id")
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .prepare_cached(
+            "SELECT id FROM graph_entities WHERE name LIKE ?1 \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=id) \
+             ORDER BY id",
+        )
+        .map_err(SqliteGraphError::query)?;
     let rows = stmt
         .query_map(params![like], |row| row.get(0))
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     collect_ids(rows)
 }
 
@@ -94,11 +104,15 @@ fn query_kind_and_prefix(
     let like = format!("{prefix}%");
     let conn = graph.connection();
     let mut stmt = conn
-        .prepare_cached("SELECT id FROM graph_entities WHERE kind=?1 AND name LIKE ?2 ORDER BY id")
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .prepare_cached(
+            "SELECT id FROM graph_entities WHERE kind=?1 AND name LIKE ?2 \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=id) \
+             ORDER BY id",
+        )
+        .map_err(SqliteGraphError::query)?;
     let rows = stmt
         .query_map(params![kind, like], |row| row.get(0))
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     collect_ids(rows)
 }
 
@@ -108,7 +122,7 @@ where
 {
     let mut ids = Vec::new();
     for entry in rows {
-        ids.push(entry.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+        ids.push(entry.map_err(SqliteGraphError::query)?);
     }
     Ok(ids)
 }
@@ -124,6 +138,39 @@ pub struct PatternLeg {
 pub struct PatternQuery {
     pub root: Option<NodeConstraint>,
     pub legs: Vec<PatternLeg>,
+    /// Number of matches to skip, counted in the deterministic result order.
+    pub offset: usize,
+    /// Maximum number of matches to return after `offset`; `None` returns all.
+    pub limit: Option<usize>,
+}
+
+impl PatternQuery {
+    /// Skip the first `offset` matches.
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Return at most `limit` matches.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// The same pattern without `offset` and `limit`.
+    pub fn unpaged(&self) -> Self {
+        Self {
+            offset: 0,
+            limit: None,
+            ..self.clone()
+        }
+    }
+
+    /// Apply `offset` and `limit` to matches that are already in result order.
+    pub fn paginate(&self, matches: Vec<PatternMatch>) -> Vec<PatternMatch> {
+        let limit = self.limit.unwrap_or(usize::MAX);
+        matches.into_iter().skip(self.offset).take(limit).collect()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -131,6 +178,12 @@ pub struct PatternMatch {
     pub nodes: Vec<i64>,
 }
 
+/// Match `query` starting at `start`.
+///
+/// Matches are ordered lexicographically by their node sequence, and
+/// `query.offset` / `query.limit` are applied in that order. Legs are expanded
+/// depth-first in ascending neighbor order, so a limit stops the search as soon
+/// as enough matches are found; single-leg patterns push the page into SQL.
 pub fn execute_pattern(
     graph: &SqliteGraph,
     start: i64,
@@ -142,35 +195,117 @@ pub fn execute_pattern(
             return Ok(Vec::new());
         }
     }
+    if let (Some(limit), [leg]) = (query.limit, query.legs.as_slice()) {
+        let neighbors = single_leg_page(graph, start, leg, query.offset, limit)?;
+        return Ok(neighbors
+            .into_iter()
+            .map(|neighbor| PatternMatch {
+                nodes: vec![start, neighbor],
+            })
+            .collect());
+    }
+    let wanted = query.limit.map(|limit| query.offset.saturating_add(limit));
     let mut cache: AHashMap<i64, GraphEntity> = AHashMap::new();
-    let mut sequences: Vec<Vec<i64>> = vec![vec![start]];
-    for leg in &query.legs {
-        let mut next_sequences = Vec::new();
-        for seq in &sequences {
-            let current = *seq.last().expect("sequence non-empty");
-            let neighbors =
-                neighbors_with_filters(graph, current, leg.direction, leg.edge_type.as_deref())?;
-            for neighbor in neighbors {
-                if matches_constraint(graph, neighbor, leg.constraint.as_ref(), &mut cache)? {
-                    let mut new_seq = seq.clone();
-                    new_seq.push(neighbor);
-                    next_sequences.push(new_seq);
-                }
-            }
+    let mut matches = Vec::new();
+    let mut path = vec![start];
+    collect_matches(
+        graph,
+        &query.legs,
+        &mut path,
+        &mut cache,
+        wanted,
+        &mut matches,
+    )?;
+    Ok(matches.into_iter().skip(query.offset).collect())
+}
+
+fn collect_matches(
+    graph: &SqliteGraph,
+    legs: &[PatternLeg],
+    path: &mut Vec<i64>,
+    cache: &mut AHashMap<i64, GraphEntity>,
+    wanted: Option<usize>,
+    matches: &mut Vec<PatternMatch>,
+) -> Result<(), SqliteGraphError> {
+    let Some((leg, rest)) = legs.split_first() else {
+        matches.push(PatternMatch {
+            nodes: path.clone(),
+        });
+        return Ok(());
+    };
+    let current = *path.last().expect("path non-empty");
+    let mut neighbors =
+        neighbors_with_filters(graph, current, leg.direction, leg.edge_type.as_deref())?;
+    neighbors.sort_unstable();
+    neighbors.dedup();
+    for neighbor in neighbors {
+        if wanted.is_some_and(|wanted| matches.len() >= wanted) {
+            break;
         }
-        if next_sequences.is_empty() {
-            return Ok(Vec::new());
+        if matches_constraint(graph, neighbor, leg.constraint.as_ref(), cache)? {
+            path.push(neighbor);
+            collect_matches(graph, rest, path, cache, wanted, matches)?;
+            path.pop();
         }
-        next_sequences.sort();
-        next_sequences.dedup();
-        sequences = next_sequences;
     }
-    let mut matches: Vec<PatternMatch> = sequences
-        .into_iter()
-        .map(|nodes| PatternMatch { nodes })
-        .collect();
-    matches.sort_by(|a, b| a.nodes.cmp(&b.nodes));
-    Ok(matches)
+    Ok(())
+}
+
+/// One page of distinct neighbors matching a single leg, in ascending id order.
+fn single_leg_page(
+    graph: &SqliteGraph,
+    start: i64,
+    leg: &PatternLeg,
+    offset: usize,
+    limit: usize,
+) -> Result<Vec<i64>, SqliteGraphError> {
+    let adjacency = match leg.direction {
+        BackendDirection::Outgoing => {
+            "SELECT to_id AS neighbor, edge_type FROM graph_edges WHERE from_id=?1"
+        }
+        BackendDirection::Incoming => {
+            "SELECT from_id AS neighbor, edge_type FROM graph_edges WHERE to_id=?1"
+        }
+        BackendDirection::Both => {
+            "SELECT to_id AS neighbor, edge_type FROM graph_edges WHERE from_id=?1 \
+             UNION ALL SELECT from_id, edge_type FROM graph_edges WHERE to_id=?1"
+        }
+    };
+    let (kind, prefix) = leg.constraint.as_ref().map_or((None, None), |c| {
+        (c.kind.as_deref(), c.name_prefix.as_deref())
+    });
+    let (join, entity_filter) = if leg.constraint.is_some() {
+        (
+            "JOIN graph_entities e ON e.id=adj.neighbor",
+            "AND (?3 IS NULL OR e.kind=?3) \
+             AND (?4 IS NULL OR substr(e.name, 1, length(?4))=?4)",
+        )
+    } else {
+        // Keep ?3 and ?4 referenced so both statement shapes bind the same parameters.
+        ("", "AND ?3 IS NULL AND ?4 IS NULL")
+    };
+    let sql = format!(
+        "SELECT DISTINCT adj.neighbor FROM ({adjacency}) adj {join} \
+         WHERE (?2 IS NULL OR adj.edge_type=?2) {entity_filter} \
+         AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=adj.neighbor) \
+         ORDER BY adj.neighbor LIMIT ?5 OFFSET ?6"
+    );
+    let conn = graph.connection();
+    let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map(
+            params![
+                start,
+                leg.edge_type.as_deref(),
+                kind,
+                prefix,
+                i64::try_from(limit).unwrap_or(i64::MAX),
+                i64::try_from(offset).unwrap_or(i64::MAX),
+            ],
+            |row| row.get(0),
+        )
+        .map_err(SqliteGraphError::query)?;
+    collect_ids(rows)
 }
 
 fn neighbors_with_filters(
@@ -180,14 +315,17 @@ fn neighbors_with_filters(
     edge_type: Option<&str>,
 ) -> Result<Vec<i64>, SqliteGraphError> {
     match (direction, edge_type) {
-        (BackendDirection::Outgoing, None) => graph.fetch_outgoing(node),
-        (BackendDirection::Incoming, None) => graph.fetch_incoming(node),
+        (direction, None) => graph.fetch_neighbors(node, direction),
         (BackendDirection::Outgoing, Some(ty)) => {
             filter_neighbors(graph, node, OUTGOING_FILTER_SQL, ty)
         }
         (BackendDirection::Incoming, Some(ty)) => {
             filter_neighbors(graph, node, INCOMING_FILTER_SQL, ty)
         }
+        (BackendDirection::Both, Some(ty)) => Ok(merge_undirected(
+            filter_neighbors(graph, node, OUTGOING_FILTER_SQL, ty)?,
+            filter_neighbors(graph, node, INCOMING_FILTER_SQL, ty)?,
+        )),
     }
 }
 
@@ -198,15 +336,13 @@ fn filter_neighbors(
     edge_type: &str,
 ) -> Result<Vec<i64>, SqliteGraphError> {
     let conn = graph.connection();
-    let mut stmt = conn
-        .prepare_cached(sql)
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+    let mut stmt = conn.prepare_cached(sql).map_err(SqliteGraphError::query)?;
     let rows = stmt
         .query_map(params![node, edge_type], |row| row.get(0))
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     let mut values = Vec::new();
     for value in rows {
-        values.push(value.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+        values.push(value.map_err(SqliteGraphError::query)?);
     }
     Ok(values)
 }
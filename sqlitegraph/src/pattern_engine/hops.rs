@@ -0,0 +1,145 @@
+//! Variable-length matching for patterns with a hop range.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rusqlite::{OptionalExtension, params};
+
+use crate::{backend::BackendDirection, errors::SqliteGraphError, graph::SqliteGraph};
+
+use super::matcher::TripleMatch;
+use super::pattern::{MAX_PATTERN_HOPS, PatternTriple};
+
+/// Check the hop range of `pattern`, if any, against the supported bounds.
+pub fn validate_hops(pattern: &PatternTriple) -> Result<(), SqliteGraphError> {
+    let Some(hops) = &pattern.hops else {
+        return Ok(());
+    };
+    if hops.start > hops.end {
+        return Err(SqliteGraphError::invalid_input(format!(
+            "hop range {}..{} has min greater than max",
+            hops.start, hops.end
+        )));
+    }
+    if hops.start == 0 {
+        return Err(SqliteGraphError::invalid_input(
+            "hop range must start at 1 or more",
+        ));
+    }
+    if hops.end > MAX_PATTERN_HOPS {
+        return Err(SqliteGraphError::invalid_input(format!(
+            "hop range max {} exceeds the limit of {MAX_PATTERN_HOPS}",
+            hops.end
+        )));
+    }
+    Ok(())
+}
+
+/// Match `(start)-[edge_type*min..max]->(end)` with a bounded breadth-first walk.
+///
+/// Each level holds the nodes reachable by a walk of exactly that many hops, so
+/// a node already seen at a shorter distance is still found at a longer one.
+/// A `(start, end)` pair is reported once, at the first level inside the range
+/// where it appears; its edge id is the smallest id of an `edge_type` edge
+/// entering `end` from the previous level. Label and property filters apply to
+/// the first and last node of the path only.
+pub fn match_hop_range(
+    graph: &SqliteGraph,
+    pattern: &PatternTriple,
+) -> Result<Vec<TripleMatch>, SqliteGraphError> {
+    let Some(hops) = pattern.hops.clone() else {
+        return Ok(Vec::new());
+    };
+    let mut matches = Vec::new();
+    for start in graph.all_entity_ids()? {
+        if !has_label(graph, start, pattern.start_label.as_deref())? {
+            continue;
+        }
+        let mut reported = BTreeSet::new();
+        let mut frontier = BTreeSet::from([start]);
+        for depth in 1..=hops.end {
+            let mut next: BTreeMap<i64, i64> = BTreeMap::new();
+            for &node in &frontier {
+                for (edge_id, neighbor) in typed_neighbors(graph, node, pattern)? {
+                    next.entry(neighbor)
+                        .and_modify(|id| *id = (*id).min(edge_id))
+                        .or_insert(edge_id);
+                }
+            }
+            if depth >= hops.start {
+                for (&end, &edge_id) in &next {
+                    if !reported.contains(&end)
+                        && has_label(graph, end, pattern.end_label.as_deref())?
+                    {
+                        reported.insert(end);
+                        matches.push(TripleMatch::new(start, edge_id, end));
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next.into_keys().collect();
+        }
+    }
+    Ok(matches)
+}
+
+/// `(edge_id, neighbor)` for every `edge_type` edge of `node` in the pattern's direction.
+fn typed_neighbors(
+    graph: &SqliteGraph,
+    node: i64,
+    pattern: &PatternTriple,
+) -> Result<Vec<(i64, i64)>, SqliteGraphError> {
+    let conn = graph.connection();
+    let sql = match pattern.direction {
+        BackendDirection::Outgoing => {
+            "SELECT id, to_id FROM graph_edges WHERE from_id = ?1 AND edge_type = ?2 \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id = to_id) \
+             ORDER BY id"
+        }
+        BackendDirection::Incoming => {
+            "SELECT id, from_id FROM graph_edges WHERE to_id = ?1 AND edge_type = ?2 \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id = from_id) \
+             ORDER BY id"
+        }
+        BackendDirection::Both => {
+            "SELECT id, CASE WHEN from_id = ?1 THEN to_id ELSE from_id END FROM graph_edges \
+             WHERE (from_id = ?1 OR to_id = ?1) AND edge_type = ?2 \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+             WHERE t.entity_id IN (from_id, to_id)) \
+             ORDER BY id"
+        }
+    };
+    let mut stmt = conn.prepare_cached(sql).map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map(params![node, &pattern.edge_type], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(SqliteGraphError::query)?;
+    let mut neighbors = Vec::new();
+    for row in rows {
+        neighbors.push(row.map_err(SqliteGraphError::query)?);
+    }
+    Ok(neighbors)
+}
+
+/// Whether `entity_id` carries `label`; always true when no label is required.
+fn has_label(
+    graph: &SqliteGraph,
+    entity_id: i64,
+    label: Option<&str>,
+) -> Result<bool, SqliteGraphError> {
+    let Some(label) = label else {
+        return Ok(true);
+    };
+    let exists: Option<i32> = graph
+        .connection()
+        .query_row(
+            "SELECT 1 FROM graph_labels WHERE entity_id = ?1 AND label = ?2 LIMIT 1",
+            params![entity_id, label],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(SqliteGraphError::query)?;
+    Ok(exists.is_some())
+}
@@ -1,11 +1,19 @@
 //! Main pattern matching logic and result types.
 
-use crate::{errors::SqliteGraphError, graph::SqliteGraph};
+use crate::{
+    backend::BackendDirection,
+    errors::{EntityKind, SqliteGraphError},
+    graph::{SqliteGraph, column_data},
+};
 
 use super::{
+    hops::match_hop_range,
     pattern::PatternTriple,
     property::matches_property_filters,
-    query::{execute_complex_edge_query, execute_simple_edge_query},
+    query::{
+        count_edge_query, edge_data_bulk, execute_complex_edge_query,
+        execute_label_seeded_edge_query, execute_simple_edge_query,
+    },
 };
 
 /// Result of a triple pattern match.
@@ -47,34 +55,197 @@ pub fn match_triples(
     graph: &SqliteGraph,
     pattern: &PatternTriple,
 ) -> Result<Vec<TripleMatch>, SqliteGraphError> {
+    match_rows(graph, pattern)
+}
+
+/// Execute a triple pattern match, returning each matched edge's `data` too.
+///
+/// The data is read by the same query that finds the matches, so this costs no
+/// extra lookups per match; hop-range patterns fetch the data of every terminal
+/// edge in one batched query instead. Results are in the same order as
+/// [`match_triples`].
+pub fn match_triples_with_edge_data(
+    graph: &SqliteGraph,
+    pattern: &PatternTriple,
+) -> Result<Vec<(TripleMatch, serde_json::Value)>, SqliteGraphError> {
+    match_rows(graph, pattern)
+}
+
+/// Count the matches of a triple pattern without materializing them.
+///
+/// Equals `match_triples(graph, pattern)?.len()`. Label and property filters
+/// are evaluated inside a single `COUNT(*)` query per orientation; undirected
+/// patterns subtract self-loops, which [`match_triples`] reports once.
+/// Hop-range patterns are walked as in [`match_triples`] and only counted.
+pub fn count_triples(
+    graph: &SqliteGraph,
+    pattern: &PatternTriple,
+) -> Result<usize, SqliteGraphError> {
+    pattern.validate()?;
+
+    if pattern.hops.is_some() {
+        let mut count = 0;
+        for triple_match in match_hop_range(graph, pattern)? {
+            if matches_property_filters(graph, &triple_match, pattern)? {
+                count += 1;
+            }
+        }
+        return Ok(count);
+    }
+
+    if pattern.direction == BackendDirection::Both {
+        let outgoing = pattern.clone().direction(BackendDirection::Outgoing);
+        let incoming = pattern.clone().direction(BackendDirection::Incoming);
+        return Ok(count_edge_query(graph, &outgoing, false)?
+            + count_edge_query(graph, &incoming, false)?
+            - count_edge_query(graph, &outgoing, true)?);
+    }
+
+    count_edge_query(graph, pattern, false)
+}
+
+/// Shared implementation of [`match_triples`] and [`match_triples_with_edge_data`].
+fn match_rows<T: MatchRow>(
+    graph: &SqliteGraph,
+    pattern: &PatternTriple,
+) -> Result<Vec<T>, SqliteGraphError> {
     pattern.validate()?;
 
     let _conn = graph.connection();
 
-    // Build and execute the query based on pattern complexity
-    let matches = if pattern.start_label.is_none() && pattern.end_label.is_none() {
-        // Simple case: no label filters
-        execute_simple_edge_query(graph, pattern)?
+    // Undirected patterns match each stored edge in both orientations
+    let matches = if pattern.hops.is_some() {
+        T::attach_edge_data(graph, match_hop_range(graph, pattern)?)?
+    } else if pattern.direction == BackendDirection::Both {
+        let mut matches = execute_directed_query(
+            graph,
+            &pattern.clone().direction(BackendDirection::Outgoing),
+        )?;
+        matches.extend(execute_directed_query(
+            graph,
+            &pattern.clone().direction(BackendDirection::Incoming),
+        )?);
+        matches
     } else {
-        // Complex case: with label filters
-        execute_complex_edge_query(graph, pattern)?
+        execute_directed_query(graph, pattern)?
     };
 
     // Apply property filters if specified
     let mut filtered_matches = Vec::new();
-    for triple_match in matches {
-        if matches_property_filters(graph, &triple_match, pattern)? {
-            filtered_matches.push(triple_match);
+    for row in matches {
+        if matches_property_filters(graph, row.triple(), pattern)? {
+            filtered_matches.push(row);
         }
     }
 
     // Ensure deterministic ordering
     filtered_matches.sort_by(|a, b| {
+        let (a, b) = (a.triple(), b.triple());
         a.start_id
             .cmp(&b.start_id)
             .then_with(|| a.edge_id.cmp(&b.edge_id))
             .then_with(|| a.end_id.cmp(&b.end_id))
     });
+    // Self-loops yield the same triple in both orientations
+    filtered_matches.dedup_by(|a, b| a.triple() == b.triple());
 
     Ok(filtered_matches)
 }
+
+/// Build and execute the query for a single-orientation pattern.
+fn execute_directed_query<T: MatchRow>(
+    graph: &SqliteGraph,
+    pattern: &PatternTriple,
+) -> Result<Vec<T>, SqliteGraphError> {
+    if pattern.start_label.is_none() && pattern.end_label.is_none() {
+        // Simple case: no label filters
+        execute_simple_edge_query(graph, pattern)
+    } else {
+        // Complex case: with label filters
+        execute_complex_edge_query(graph, pattern)
+    }
+}
+
+/// A row produced by the pattern queries: a bare match, or a match with its edge data.
+pub(crate) trait MatchRow: Sized {
+    /// Whether the queries must also select `ge.data`.
+    const WITH_DATA: bool;
+
+    /// Decode `(start_id, edge_id, end_id[, data])` columns.
+    fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self>;
+
+    fn triple(&self) -> &TripleMatch;
+
+    /// Complete matches found without the data column, such as hop-range matches.
+    fn attach_edge_data(
+        graph: &SqliteGraph,
+        matches: Vec<TripleMatch>,
+    ) -> Result<Vec<Self>, SqliteGraphError>;
+}
+
+impl MatchRow for TripleMatch {
+    const WITH_DATA: bool = false;
+
+    fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        Ok(TripleMatch::new(row.get(0)?, row.get(1)?, row.get(2)?))
+    }
+
+    fn triple(&self) -> &TripleMatch {
+        self
+    }
+
+    fn attach_edge_data(
+        _graph: &SqliteGraph,
+        matches: Vec<TripleMatch>,
+    ) -> Result<Vec<Self>, SqliteGraphError> {
+        Ok(matches)
+    }
+}
+
+impl MatchRow for (TripleMatch, serde_json::Value) {
+    const WITH_DATA: bool = true;
+
+    fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        Ok((TripleMatch::from_row(row)?, column_data(row, 3)?))
+    }
+
+    fn triple(&self) -> &TripleMatch {
+        &self.0
+    }
+
+    fn attach_edge_data(
+        graph: &SqliteGraph,
+        matches: Vec<TripleMatch>,
+    ) -> Result<Vec<Self>, SqliteGraphError> {
+        let edge_ids: Vec<i64> = matches.iter().map(|m| m.edge_id).collect();
+        let data = edge_data_bulk(graph, &edge_ids)?;
+        matches
+            .into_iter()
+            .map(|m| {
+                let value = data
+                    .get(&m.edge_id)
+                    .cloned()
+                    .ok_or_else(|| SqliteGraphError::not_found(EntityKind::Edge, m.edge_id))?;
+                Ok((m, value))
+            })
+            .collect()
+    }
+}
+
+/// Match `(start_label)-[edge_type]->(end_label)` by seeding from the label index.
+///
+/// Equivalent to [`match_triples`] with a pattern carrying both labels, but the
+/// start set is read from `graph_labels` first and joined to its outgoing edges,
+/// which avoids scanning every edge of `edge_type` when `start_label` is selective.
+///
+/// # Returns
+/// A vector of triple matches in the same deterministic order as [`match_triples`]
+pub fn match_triples_labeled(
+    graph: &SqliteGraph,
+    start_label: &str,
+    edge_type: &str,
+    end_label: &str,
+) -> Result<Vec<TripleMatch>, SqliteGraphError> {
+    PatternTriple::new(edge_type).validate()?;
+    execute_label_seeded_edge_query(graph, start_label, edge_type, end_label)
+}
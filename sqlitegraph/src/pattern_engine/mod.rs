@@ -1,14 +1,18 @@
 //! Lightweight triple pattern matcher for SQLiteGraph.
 //!
 //! Provides a minimal, deterministic pattern matcher for basic Neo4j-style patterns:
-//! (start_label)-[edge_type]->(end_label) with optional property filters.
+//! (start_label)-[edge_type]->(end_label) with optional property filters, or a
+//! bounded variable-length form (start_label)-[edge_type*min..max]->(end_label).
 //!
 //! This is designed to be a simpler alternative to the full pattern system,
 //! focusing on single-hop patterns with equality-based property filtering.
 
-pub use matcher::{TripleMatch, match_triples};
-pub use pattern::PatternTriple;
+pub use matcher::{
+    TripleMatch, count_triples, match_triples, match_triples_labeled, match_triples_with_edge_data,
+};
+pub use pattern::{MAX_PATTERN_HOPS, PatternTriple};
 
+mod hops;
 mod matcher;
 mod pattern;
 mod property;
@@ -1,9 +1,14 @@
 //! Pattern triple definition and builder functionality.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Range};
 
 use crate::{backend::BackendDirection, errors::SqliteGraphError};
 
+use super::hops::validate_hops;
+
+/// Largest hop count accepted by [`PatternTriple::hops`].
+pub const MAX_PATTERN_HOPS: u32 = 16;
+
 /// A lightweight triple pattern for basic graph pattern matching.
 ///
 /// Represents a single-hop pattern: (start_label)-[edge_type]->(end_label)
@@ -20,8 +25,18 @@ pub struct PatternTriple {
     pub start_props: HashMap<String, String>,
     /// Optional property filters for the end node (key -> value)
     pub end_props: HashMap<String, String>,
+    /// Excluded `(key, value)` pairs for the start node
+    pub start_props_not: Vec<(String, String)>,
+    /// Excluded `(key, value)` pairs for the end node
+    pub end_props_not: Vec<(String, String)>,
+    /// Property keys the start node must have, with any value
+    pub start_keys: Vec<String>,
+    /// Property keys the end node must have, with any value
+    pub end_keys: Vec<String>,
     /// Direction of the pattern (default: Outgoing)
     pub direction: BackendDirection,
+    /// Optional hop range for variable-length paths (default: a single hop)
+    pub hops: Option<Range<u32>>,
 }
 
 impl Default for PatternTriple {
@@ -32,7 +47,12 @@ impl Default for PatternTriple {
             end_label: None,
             start_props: HashMap::new(),
             end_props: HashMap::new(),
+            start_props_not: Vec::new(),
+            end_props_not: Vec::new(),
+            start_keys: Vec::new(),
+            end_keys: Vec::new(),
             direction: BackendDirection::Outgoing,
+            hops: None,
         }
     }
 }
@@ -70,6 +90,53 @@ impl PatternTriple {
         self
     }
 
+    /// Match paths repeating `edge_type` between `hops.start` and `hops.end` times.
+    ///
+    /// Both bounds are inclusive, as in Cypher's `(a)-[CALLS*1..3]->(b)`. The
+    /// range must satisfy `1 <= start <= end <= MAX_PATTERN_HOPS`.
+    pub fn hops(mut self, hops: Range<u32>) -> Self {
+        self.hops = Some(hops);
+        self
+    }
+
+    /// Exclude start nodes having `value` for `key`.
+    ///
+    /// Start nodes without the key, or with only other values, still match.
+    pub fn start_property_not(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.start_props_not.push((key.into(), value.into()));
+        self
+    }
+
+    /// Exclude end nodes having `value` for `key`.
+    ///
+    /// End nodes without the key, or with only other values, still match.
+    pub fn end_property_not(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.end_props_not.push((key.into(), value.into()));
+        self
+    }
+
+    /// Require the start node to have any value for `key`.
+    pub fn start_has_key(mut self, key: impl Into<String>) -> Self {
+        self.start_keys.push(key.into());
+        self
+    }
+
+    /// Require the end node to have any value for `key`.
+    pub fn end_has_key(mut self, key: impl Into<String>) -> Self {
+        self.end_keys.push(key.into());
+        self
+    }
+
+    /// Whether any property filter (equality, exclusion or key presence) is set.
+    pub fn has_property_filters(&self) -> bool {
+        !self.start_props.is_empty()
+            || !self.end_props.is_empty()
+            || !self.start_props_not.is_empty()
+            || !self.end_props_not.is_empty()
+            || !self.start_keys.is_empty()
+            || !self.end_keys.is_empty()
+    }
+
     /// Set the direction of the pattern.
     pub fn direction(mut self, direction: BackendDirection) -> Self {
         self.direction = direction;
@@ -81,6 +148,6 @@ impl PatternTriple {
         if self.edge_type.trim().is_empty() {
             return Err(SqliteGraphError::invalid_input("edge_type is required"));
         }
-        Ok(())
+        validate_hops(self)
     }
 }
@@ -15,20 +15,45 @@ pub fn matches_property_filters(
     triple_match: &TripleMatch,
     pattern: &PatternTriple,
 ) -> Result<bool, SqliteGraphError> {
-    // Check start node properties
-    if !pattern.start_props.is_empty() {
-        if !entity_has_properties(graph, triple_match.start_id, &pattern.start_props)? {
+    if !entity_matches(
+        graph,
+        triple_match.start_id,
+        &pattern.start_props,
+        &pattern.start_props_not,
+        &pattern.start_keys,
+    )? {
+        return Ok(false);
+    }
+    entity_matches(
+        graph,
+        triple_match.end_id,
+        &pattern.end_props,
+        &pattern.end_props_not,
+        &pattern.end_keys,
+    )
+}
+
+/// Check one endpoint against its equality, exclusion and key-presence filters.
+fn entity_matches(
+    graph: &SqliteGraph,
+    entity_id: i64,
+    required_props: &HashMap<String, String>,
+    excluded_props: &[(String, String)],
+    required_keys: &[String],
+) -> Result<bool, SqliteGraphError> {
+    if !required_props.is_empty() && !entity_has_properties(graph, entity_id, required_props)? {
+        return Ok(false);
+    }
+    for (key, value) in excluded_props {
+        if entity_has_property(graph, entity_id, key, Some(value))? {
             return Ok(false);
         }
     }
-
-    // Check end node properties
-    if !pattern.end_props.is_empty() {
-        if !entity_has_properties(graph, triple_match.end_id, &pattern.end_props)? {
+    for key in required_keys {
+        if !entity_has_property(graph, entity_id, key, None)? {
             return Ok(false);
         }
     }
-
     Ok(true)
 }
 
@@ -45,12 +70,12 @@ pub fn entity_has_properties(
             .prepare_cached(
                 "SELECT 1 FROM graph_properties WHERE entity_id = ?1 AND key = ?2 AND value = ?3 LIMIT 1"
             )
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
 
         let exists: Option<i32> = stmt
             .query_row(params![entity_id, key, expected_value], |row| row.get(0))
             .optional()
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
 
         if exists.is_none() {
             return Ok(false);
@@ -59,3 +84,24 @@ pub fn entity_has_properties(
 
     Ok(true)
 }
+
+/// Check if an entity has `key`, with `value` when given or any value otherwise.
+fn entity_has_property(
+    graph: &SqliteGraph,
+    entity_id: i64,
+    key: &str,
+    value: Option<&str>,
+) -> Result<bool, SqliteGraphError> {
+    let conn = graph.connection();
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT 1 FROM graph_properties WHERE entity_id = ?1 AND key = ?2 \
+             AND (?3 IS NULL OR value = ?3) LIMIT 1",
+        )
+        .map_err(SqliteGraphError::query)?;
+    let exists: Option<i32> = stmt
+        .query_row(params![entity_id, key, value], |row| row.get(0))
+        .optional()
+        .map_err(SqliteGraphError::query)?;
+    Ok(exists.is_some())
+}
@@ -1,161 +1,257 @@
 //! SQL query execution for pattern matching.
 
-use rusqlite::params;
+use std::collections::HashMap;
 
-use crate::{backend::BackendDirection, errors::SqliteGraphError, graph::SqliteGraph};
+use rusqlite::{params, params_from_iter, types::Value};
 
-use super::matcher::TripleMatch;
+use crate::{
+    backend::BackendDirection,
+    errors::SqliteGraphError,
+    graph::{SqliteGraph, column_data},
+};
+
+use super::matcher::{MatchRow, TripleMatch};
 use super::pattern::PatternTriple;
 
+/// Keeps only edges whose endpoints are both live (not soft-deleted).
+const LIVE_ENDPOINTS_SQL: &str = " AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id IN (ge.from_id, ge.to_id))";
+
 /// Execute simple edge query without label filters.
-pub fn execute_simple_edge_query(
+pub fn execute_simple_edge_query<T: MatchRow>(
     graph: &SqliteGraph,
     pattern: &PatternTriple,
-) -> Result<Vec<TripleMatch>, SqliteGraphError> {
+) -> Result<Vec<T>, SqliteGraphError> {
     let conn = graph.connection();
 
-    let sql = match pattern.direction {
-        BackendDirection::Outgoing => {
-            "SELECT ge.from_id, ge.id, ge.to_id FROM graph_edges ge WHERE ge.edge_type = ?1 ORDER BY ge.from_id, ge.id, ge.to_id"
-        }
-        BackendDirection::Incoming => {
-            "SELECT ge.to_id, ge.id, ge.from_id FROM graph_edges ge WHERE ge.edge_type = ?1 ORDER BY ge.to_id, ge.id, ge.from_id"
-        }
-    };
+    let (select, order) = select_and_order(pattern.direction, T::WITH_DATA);
+    let sql = format!("{select} WHERE ge.edge_type = ?1{LIVE_ENDPOINTS_SQL} ORDER BY {order}");
 
-    let mut stmt = conn
-        .prepare_cached(sql)
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+    let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
 
     let rows = stmt
-        .query_map(params![&pattern.edge_type], |row| {
-            Ok(TripleMatch::new(
-                row.get(0)?, // start_id
-                row.get(1)?, // edge_id
-                row.get(2)?, // end_id
-            ))
-        })
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-
-    let mut matches = Vec::new();
-    for row in rows {
-        matches.push(row.map_err(|e| SqliteGraphError::query(e.to_string()))?);
-    }
+        .query_map(params![&pattern.edge_type], T::from_row)
+        .map_err(SqliteGraphError::query)?;
 
-    Ok(matches)
+    collect_triple_matches(rows)
 }
 
 /// Execute complex edge query with label filters.
-pub fn execute_complex_edge_query(
+pub fn execute_complex_edge_query<T: MatchRow>(
     graph: &SqliteGraph,
     pattern: &PatternTriple,
-) -> Result<Vec<TripleMatch>, SqliteGraphError> {
+) -> Result<Vec<T>, SqliteGraphError> {
     let conn = graph.connection();
 
     // Build SQL dynamically based on which labels are present
-    let mut sql = match pattern.direction {
-        BackendDirection::Outgoing => {
-            "SELECT ge.from_id, ge.id, ge.to_id FROM graph_edges ge".to_string()
-        }
-        BackendDirection::Incoming => {
-            "SELECT ge.to_id, ge.id, ge.from_id FROM graph_edges ge".to_string()
-        }
-    };
+    let (select, order) = select_and_order(pattern.direction, T::WITH_DATA);
+    let mut sql = select;
 
     sql.push_str(" WHERE ge.edge_type = ?1");
+    sql.push_str(LIVE_ENDPOINTS_SQL);
 
-    let mut param_count = 1;
-
-    // Add start label filter
-    if let Some(_start_label) = &pattern.start_label {
-        param_count += 1;
-        sql.push_str(" AND EXISTS (");
-        sql.push_str("  SELECT 1 FROM graph_labels gl");
-        sql.push_str("  WHERE gl.entity_id = ");
-        sql.push_str(if pattern.direction == BackendDirection::Outgoing {
-            "ge.from_id"
-        } else {
-            "ge.to_id"
-        });
-        sql.push_str(&format!("  AND gl.label = ?{}", param_count));
-        sql.push_str(" )");
-    }
+    let mut values = vec![Value::Text(pattern.edge_type.clone())];
+    let (start_column, end_column) = if pattern.direction == BackendDirection::Outgoing {
+        ("ge.from_id", "ge.to_id")
+    } else {
+        ("ge.to_id", "ge.from_id")
+    };
 
-    // Add end label filter
-    if let Some(_end_label) = &pattern.end_label {
-        param_count += 1;
-        sql.push_str(" AND EXISTS (");
-        sql.push_str("  SELECT 1 FROM graph_labels gl");
-        sql.push_str("  WHERE gl.entity_id = ");
-        sql.push_str(if pattern.direction == BackendDirection::Outgoing {
-            "ge.to_id"
-        } else {
-            "ge.from_id"
-        });
-        sql.push_str(&format!("  AND gl.label = ?{}", param_count));
-        sql.push_str(" )");
+    // Add start and end label filters
+    for (label, column) in [
+        (&pattern.start_label, start_column),
+        (&pattern.end_label, end_column),
+    ] {
+        if let Some(label) = label {
+            values.push(Value::Text(label.clone()));
+            sql.push_str(" AND EXISTS (");
+            sql.push_str("  SELECT 1 FROM graph_labels gl");
+            sql.push_str("  WHERE gl.entity_id = ");
+            sql.push_str(column);
+            sql.push_str(&format!("  AND gl.label = ?{}", values.len()));
+            sql.push_str(" )");
+        }
     }
 
     // Add deterministic ordering
     sql.push_str(" ORDER BY ");
-    if pattern.direction == BackendDirection::Outgoing {
-        sql.push_str("ge.from_id, ge.id, ge.to_id");
-    } else {
-        sql.push_str("ge.to_id, ge.id, ge.from_id");
+    sql.push_str(order);
+
+    let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+
+    let rows = stmt
+        .query_map(params_from_iter(values), T::from_row)
+        .map_err(SqliteGraphError::query)?;
+
+    collect_triple_matches(rows)
+}
+
+/// Count the matches of a single-orientation pattern with one `COUNT(*)` query.
+///
+/// Labels and every property filter become `EXISTS` / `NOT EXISTS` clauses, so
+/// no match is materialized. With `self_loops_only` only edges whose endpoints
+/// coincide are counted.
+pub fn count_edge_query(
+    graph: &SqliteGraph,
+    pattern: &PatternTriple,
+    self_loops_only: bool,
+) -> Result<usize, SqliteGraphError> {
+    let (start_column, end_column) = match pattern.direction {
+        BackendDirection::Outgoing => ("ge.from_id", "ge.to_id"),
+        BackendDirection::Incoming => ("ge.to_id", "ge.from_id"),
+        BackendDirection::Both => {
+            unreachable!("count_triples splits Both into Outgoing and Incoming queries")
+        }
+    };
+    let mut values = vec![Value::Text(pattern.edge_type.clone())];
+    let mut sql =
+        format!("SELECT COUNT(*) FROM graph_edges ge WHERE ge.edge_type = ?1{LIVE_ENDPOINTS_SQL}");
+    if self_loops_only {
+        sql.push_str(" AND ge.from_id = ge.to_id");
     }
 
-    // Execute query with appropriate parameters
-    let matches =
-        if let (Some(start_label), Some(end_label)) = (&pattern.start_label, &pattern.end_label) {
-            let mut stmt = conn
-                .prepare_cached(&sql)
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-
-            let rows = stmt
-                .query_map(params![&pattern.edge_type, start_label, end_label], |row| {
-                    Ok(TripleMatch::new(row.get(0)?, row.get(1)?, row.get(2)?))
-                })
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-
-            collect_triple_matches(rows)?
-        } else if let Some(start_label) = &pattern.start_label {
-            let mut stmt = conn
-                .prepare_cached(&sql)
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-
-            let rows = stmt
-                .query_map(params![&pattern.edge_type, start_label], |row| {
-                    Ok(TripleMatch::new(row.get(0)?, row.get(1)?, row.get(2)?))
-                })
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-
-            collect_triple_matches(rows)?
-        } else if let Some(end_label) = &pattern.end_label {
-            let mut stmt = conn
-                .prepare_cached(&sql)
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-
-            let rows = stmt
-                .query_map(params![&pattern.edge_type, end_label], |row| {
-                    Ok(TripleMatch::new(row.get(0)?, row.get(1)?, row.get(2)?))
-                })
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-
-            collect_triple_matches(rows)?
-        } else {
-            unreachable!("This case should be handled by execute_simple_edge_query")
-        };
+    let sides = [
+        (
+            start_column,
+            &pattern.start_label,
+            &pattern.start_props,
+            &pattern.start_props_not,
+            &pattern.start_keys,
+        ),
+        (
+            end_column,
+            &pattern.end_label,
+            &pattern.end_props,
+            &pattern.end_props_not,
+            &pattern.end_keys,
+        ),
+    ];
+    for (column, label, props, props_not, keys) in sides {
+        if let Some(label) = label {
+            values.push(Value::Text(label.clone()));
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM graph_labels gl \
+                 WHERE gl.entity_id = {column} AND gl.label = ?{})",
+                values.len()
+            ));
+        }
+        let filters = props
+            .iter()
+            .map(|(key, value)| ("", key, Some(value)))
+            .chain(
+                props_not
+                    .iter()
+                    .map(|(key, value)| ("NOT ", key, Some(value))),
+            )
+            .chain(keys.iter().map(|key| ("", key, None)));
+        for (negation, key, value) in filters {
+            values.push(Value::Text(key.clone()));
+            sql.push_str(&format!(
+                " AND {negation}EXISTS (SELECT 1 FROM graph_properties gp \
+                 WHERE gp.entity_id = {column} AND gp.key = ?{}",
+                values.len()
+            ));
+            if let Some(value) = value {
+                values.push(Value::Text(value.clone()));
+                sql.push_str(&format!(" AND gp.value = ?{}", values.len()));
+            }
+            sql.push(')');
+        }
+    }
 
-    Ok(matches)
+    let conn = graph.connection();
+    let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+    let count: i64 = stmt
+        .query_row(params_from_iter(values), |row| row.get(0))
+        .map_err(SqliteGraphError::query)?;
+    Ok(count as usize)
 }
 
-/// Collect triple matches from query rows.
-fn collect_triple_matches(
-    rows: rusqlite::MappedRows<'_, impl FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<TripleMatch>>,
+/// `SELECT ... FROM graph_edges ge` projecting `(start, edge, end[, data])` for
+/// `direction`, and the matching deterministic `ORDER BY` columns.
+fn select_and_order(direction: BackendDirection, with_data: bool) -> (String, &'static str) {
+    let (columns, order) = match direction {
+        BackendDirection::Outgoing => {
+            ("ge.from_id, ge.id, ge.to_id", "ge.from_id, ge.id, ge.to_id")
+        }
+        BackendDirection::Incoming => {
+            ("ge.to_id, ge.id, ge.from_id", "ge.to_id, ge.id, ge.from_id")
+        }
+        BackendDirection::Both => {
+            unreachable!("match_triples splits Both into Outgoing and Incoming queries")
+        }
+    };
+    let data = if with_data { ", ge.data" } else { "" };
+    (format!("SELECT {columns}{data} FROM graph_edges ge"), order)
+}
+
+/// Execute a label-seeded edge query for outgoing `(start_label)-[edge_type]->(end_label)`.
+///
+/// The distinct set of entities carrying `start_label` drives the outer loop, so
+/// only their outgoing edges are probed via `idx_edges_from`.
+pub fn execute_label_seeded_edge_query(
+    graph: &SqliteGraph,
+    start_label: &str,
+    edge_type: &str,
+    end_label: &str,
 ) -> Result<Vec<TripleMatch>, SqliteGraphError> {
+    let conn = graph.connection();
+
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT ge.from_id, ge.id, ge.to_id \
+             FROM (SELECT DISTINCT entity_id FROM graph_labels WHERE label = ?1) seed \
+             CROSS JOIN graph_edges ge \
+             WHERE ge.from_id = seed.entity_id AND ge.edge_type = ?2 \
+             AND EXISTS (SELECT 1 FROM graph_labels gl WHERE gl.entity_id = ge.to_id AND gl.label = ?3) \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+             WHERE t.entity_id IN (ge.from_id, ge.to_id)) \
+             ORDER BY ge.from_id, ge.id, ge.to_id",
+        )
+        .map_err(SqliteGraphError::query)?;
+
+    let rows = stmt
+        .query_map(params![start_label, edge_type, end_label], |row| {
+            Ok(TripleMatch::new(row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(SqliteGraphError::query)?;
+
+    collect_triple_matches(rows)
+}
+
+/// Edge ids bound per `IN (...)` query in [`edge_data_bulk`].
+const EDGE_DATA_CHUNK: usize = 500;
+
+/// `data` of each edge in `edge_ids`, keyed by edge id, one query per chunk.
+pub fn edge_data_bulk(
+    graph: &SqliteGraph,
+    edge_ids: &[i64],
+) -> Result<HashMap<i64, serde_json::Value>, SqliteGraphError> {
+    let conn = graph.connection();
+    let mut data = HashMap::with_capacity(edge_ids.len());
+    for chunk in edge_ids.chunks(EDGE_DATA_CHUNK) {
+        let marks = vec!["?"; chunk.len()].join(", ");
+        let sql = format!("SELECT id, data FROM graph_edges WHERE id IN ({marks})");
+        let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map(params_from_iter(chunk), |row| {
+                Ok((row.get::<_, i64>(0)?, column_data(row, 1)?))
+            })
+            .map_err(SqliteGraphError::query)?;
+        for row in rows {
+            let (id, value) = row.map_err(SqliteGraphError::query)?;
+            data.insert(id, value);
+        }
+    }
+    Ok(data)
+}
+
+/// Collect triple matches from query rows.
+fn collect_triple_matches<T>(
+    rows: rusqlite::MappedRows<'_, impl FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<T>>,
+) -> Result<Vec<T>, SqliteGraphError> {
     let mut matches = Vec::new();
     for row in rows {
-        matches.push(row.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+        matches.push(row.map_err(SqliteGraphError::query)?);
     }
     Ok(matches)
 }
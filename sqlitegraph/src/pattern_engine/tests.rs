@@ -3,7 +3,9 @@
 use crate::{GraphEdge, GraphEntity, backend::BackendDirection};
 use serde_json::json;
 
-use super::matcher::match_triples;
+use super::matcher::{
+    count_triples, match_triples, match_triples_labeled, match_triples_with_edge_data,
+};
 use super::pattern::PatternTriple;
 
 fn create_test_graph() -> crate::graph::SqliteGraph {
@@ -171,3 +173,227 @@ fn test_match_triples_direction() {
     assert_eq!(matches_incoming[0].end_id, f1); // End is now original source
     assert_eq!(matches_incoming[0].edge_id, edge_id);
 }
+
+#[test]
+fn test_match_triples_labeled_matches_match_triples() {
+    let graph = create_test_graph();
+
+    let f1 = insert_entity(&graph, "Function", "func1");
+    let f2 = insert_entity(&graph, "Function", "func2");
+    let f3 = insert_entity(&graph, "Function", "func3");
+    let s1 = insert_entity(&graph, "Struct", "struct1");
+
+    add_label_to_entity(&graph, f1, "Function");
+    add_label_to_entity(&graph, f1, "Function");
+    add_label_to_entity(&graph, f2, "Function");
+    add_label_to_entity(&graph, f3, "Function");
+    add_label_to_entity(&graph, s1, "Struct");
+
+    insert_edge(&graph, f1, f2, "CALLS");
+    insert_edge(&graph, f1, f3, "CALLS");
+    insert_edge(&graph, f2, f3, "CALLS");
+    insert_edge(&graph, f3, s1, "CALLS");
+    insert_edge(&graph, f2, f1, "USES");
+
+    let pattern = PatternTriple::new("CALLS")
+        .start_label("Function")
+        .end_label("Function");
+    let expected = match_triples(&graph, &pattern).expect("Failed to match triples");
+    let labeled = match_triples_labeled(&graph, "Function", "CALLS", "Function")
+        .expect("Failed to match labeled triples");
+
+    assert_eq!(labeled.len(), 3);
+    assert_eq!(labeled, expected);
+    assert!(match_triples_labeled(&graph, "Function", "", "Function").is_err());
+}
+
+#[test]
+fn test_match_triples_both_direction() {
+    let graph = create_test_graph();
+
+    let f1 = insert_entity(&graph, "Function", "func1");
+    let f2 = insert_entity(&graph, "Function", "func2");
+    let edge_id = insert_edge(&graph, f1, f2, "CALLS");
+    let loop_id = insert_edge(&graph, f2, f2, "CALLS");
+
+    let pattern = PatternTriple::new("CALLS").direction(BackendDirection::Both);
+    let matches = match_triples(&graph, &pattern).expect("Failed to match triples");
+
+    assert_eq!(
+        matches,
+        vec![
+            super::matcher::TripleMatch::new(f1, edge_id, f2),
+            super::matcher::TripleMatch::new(f2, edge_id, f1),
+            super::matcher::TripleMatch::new(f2, loop_id, f2),
+        ]
+    );
+}
+
+#[test]
+fn test_hop_range_reachable_set_grows_with_upper_bound() {
+    let graph = create_test_graph();
+    let a = insert_entity(&graph, "Function", "a");
+    let b = insert_entity(&graph, "Function", "b");
+    let c = insert_entity(&graph, "Function", "c");
+    let d = insert_entity(&graph, "Function", "d");
+    let ab = insert_edge(&graph, a, b, "CALLS");
+    let bc = insert_edge(&graph, b, c, "CALLS");
+    let cd = insert_edge(&graph, c, d, "CALLS");
+    insert_edge(&graph, a, d, "USES");
+
+    let pairs = |max: u32| -> Vec<(i64, i64)> {
+        let pattern = PatternTriple::new("CALLS").hops(1..max);
+        match_triples(&graph, &pattern)
+            .unwrap()
+            .iter()
+            .map(|m| (m.start_id, m.end_id))
+            .collect()
+    };
+    assert_eq!(pairs(1), vec![(a, b), (b, c), (c, d)]);
+    assert_eq!(pairs(2), vec![(a, b), (a, c), (b, c), (b, d), (c, d)]);
+    assert_eq!(
+        pairs(3),
+        vec![(a, b), (a, c), (a, d), (b, c), (b, d), (c, d)]
+    );
+    assert_eq!(pairs(4), pairs(3));
+
+    // The edge id is the terminal edge of each path.
+    let pattern = PatternTriple::new("CALLS").hops(2..3);
+    let matches = match_triples(&graph, &pattern).unwrap();
+    let edges: Vec<(i64, i64, i64)> = matches
+        .iter()
+        .map(|m| (m.start_id, m.edge_id, m.end_id))
+        .collect();
+    assert_eq!(edges, vec![(a, bc, c), (a, cd, d), (b, cd, d)]);
+
+    // A single-hop range agrees with the plain pattern.
+    let single = PatternTriple::new("CALLS").hops(1..1);
+    assert_eq!(
+        match_triples(&graph, &single).unwrap(),
+        match_triples(&graph, &PatternTriple::new("CALLS")).unwrap()
+    );
+    assert_eq!(match_triples(&graph, &single).unwrap()[0].edge_id, ab);
+
+    // End filters apply to the last node of the path.
+    add_label_to_entity(&graph, d, "Sink");
+    let pattern = PatternTriple::new("CALLS").hops(1..3).end_label("Sink");
+    let starts: Vec<i64> = match_triples(&graph, &pattern)
+        .unwrap()
+        .iter()
+        .map(|m| m.start_id)
+        .collect();
+    assert_eq!(starts, vec![a, b, c]);
+}
+
+#[test]
+fn test_hop_range_rejects_invalid_bounds() {
+    let graph = create_test_graph();
+    let (lo, hi) = (3, 1);
+    for hops in [lo..hi, 0..2, 1..(super::MAX_PATTERN_HOPS + 1)] {
+        let pattern = PatternTriple::new("CALLS").hops(hops.clone());
+        assert!(
+            matches!(
+                match_triples(&graph, &pattern),
+                Err(crate::SqliteGraphError::InvalidInput { .. })
+            ),
+            "{hops:?}"
+        );
+    }
+}
+
+#[test]
+fn test_match_triples_with_edge_data_pairs_data_with_each_match() {
+    let graph = create_test_graph();
+    let a = insert_entity(&graph, "Function", "a");
+    let b = insert_entity(&graph, "Function", "b");
+    let c = insert_entity(&graph, "Function", "c");
+    add_label_to_entity(&graph, b, "Target");
+    for (from, to, weight) in [(b, c, 3), (a, b, 1), (a, c, 2), (c, c, 4)] {
+        graph
+            .insert_edge(&GraphEdge {
+                id: 0,
+                from_id: from,
+                to_id: to,
+                edge_type: "CALLS".into(),
+                data: json!({ "weight": weight, "from": from, "to": to }),
+            })
+            .unwrap();
+    }
+
+    for pattern in [
+        PatternTriple::new("CALLS"),
+        PatternTriple::new("CALLS").direction(BackendDirection::Incoming),
+        PatternTriple::new("CALLS").direction(BackendDirection::Both),
+        PatternTriple::new("CALLS").end_label("Target"),
+        PatternTriple::new("CALLS").hops(1..2),
+    ] {
+        let with_data = match_triples_with_edge_data(&graph, &pattern).unwrap();
+        let plain = match_triples(&graph, &pattern).unwrap();
+        assert_eq!(
+            with_data.iter().map(|(m, _)| m.clone()).collect::<Vec<_>>(),
+            plain,
+            "{pattern:?}"
+        );
+        for (m, data) in &with_data {
+            assert_eq!(*data, graph.get_edge(m.edge_id).unwrap().data);
+        }
+    }
+
+    let matches = match_triples_with_edge_data(&graph, &PatternTriple::new("CALLS")).unwrap();
+    let weights: Vec<i64> = matches
+        .iter()
+        .map(|(_, data)| data["weight"].as_i64().unwrap())
+        .collect();
+    assert_eq!(weights, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn test_count_triples_matches_match_triples_len() {
+    let graph = create_test_graph();
+    let f1 = insert_entity(&graph, "Function", "f1");
+    let f2 = insert_entity(&graph, "Function", "f2");
+    let f3 = insert_entity(&graph, "Function", "f3");
+    let s1 = insert_entity(&graph, "Struct", "s1");
+    add_label_to_entity(&graph, f1, "public");
+    add_label_to_entity(&graph, f2, "public");
+    add_label_to_entity(&graph, s1, "exported");
+    add_property_to_entity(&graph, f1, "async", "true");
+    add_property_to_entity(&graph, f2, "async", "false");
+    add_property_to_entity(&graph, f3, "lang", "rust");
+    insert_edge(&graph, f1, f2, "CALLS");
+    insert_edge(&graph, f1, f3, "CALLS");
+    insert_edge(&graph, f2, f3, "CALLS");
+    insert_edge(&graph, f3, f3, "CALLS");
+    insert_edge(&graph, f2, f1, "CALLS");
+    insert_edge(&graph, f1, s1, "USES");
+    insert_edge(&graph, f2, s1, "USES");
+
+    let patterns = [
+        PatternTriple::new("CALLS"),
+        PatternTriple::new("CALLS").start_label("public"),
+        PatternTriple::new("USES")
+            .start_label("public")
+            .end_label("exported"),
+        PatternTriple::new("CALLS").start_property("async", "true"),
+        PatternTriple::new("CALLS").end_property_not("async", "false"),
+        PatternTriple::new("CALLS").end_has_key("lang"),
+        PatternTriple::new("CALLS").direction(BackendDirection::Incoming),
+        PatternTriple::new("CALLS").direction(BackendDirection::Both),
+        PatternTriple::new("CALLS")
+            .direction(BackendDirection::Both)
+            .start_has_key("lang"),
+        PatternTriple::new("CALLS").hops(1..3),
+        PatternTriple::new("CALLS")
+            .hops(2..2)
+            .start_property("async", "true"),
+        PatternTriple::new("MISSING"),
+    ];
+    for pattern in &patterns {
+        assert_eq!(
+            count_triples(&graph, pattern).unwrap(),
+            match_triples(&graph, pattern).unwrap().len(),
+            "{pattern:?}"
+        );
+    }
+    assert!(count_triples(&graph, &PatternTriple::new("")).is_err());
+}
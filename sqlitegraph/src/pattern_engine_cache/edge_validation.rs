@@ -21,7 +21,7 @@ pub fn edge_exists_with_type(
     let exists: Option<i32> = conn
         .query_row(sql, params![from_id, to_id, edge_type], |row| row.get(0))
         .optional()
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
 
     Ok(exists.is_some())
 }
@@ -43,7 +43,7 @@ pub fn get_edge_id(
             params![from_id, to_id, edge_type],
             |row| row.get(0),
         )
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
 
     Ok(edge_id)
 }
@@ -1,4 +1,4 @@
-use crate::pattern_engine::PatternTriple;
+use crate::{backend::BackendDirection, pattern_engine::PatternTriple};
 
 /// Determine if a pattern can use the fast-path (Case 1)
 ///
@@ -6,12 +6,15 @@ use crate::pattern_engine::PatternTriple;
 /// - edge_type = Some("X")
 /// - NO start_label
 /// - NO end_label  
-/// - NO property filters
+/// - NO property filters (equality, exclusion or key presence)
+/// - NOT undirected (`BackendDirection::Both`)
+/// - NO hop range
 pub fn can_use_fast_path(pattern: &PatternTriple) -> bool {
-    pattern.start_label.is_none()
+    pattern.direction != BackendDirection::Both
+        && pattern.hops.is_none()
+        && pattern.start_label.is_none()
         && pattern.end_label.is_none()
-        && pattern.start_props.is_empty()
-        && pattern.end_props.is_empty()
+        && !pattern.has_property_filters()
 }
 
 /// Determine if pattern can use partial fast-path (Case 2)
@@ -20,8 +23,5 @@ pub fn can_use_fast_path(pattern: &PatternTriple) -> bool {
 /// - Has label filters OR property filters
 /// - Can use cache to narrow candidates
 pub fn can_use_partial_fast_path(pattern: &PatternTriple) -> bool {
-    !pattern.start_props.is_empty()
-        || !pattern.end_props.is_empty()
-        || pattern.start_label.is_some()
-        || pattern.end_label.is_some()
+    pattern.has_property_filters() || pattern.start_label.is_some() || pattern.end_label.is_some()
 }
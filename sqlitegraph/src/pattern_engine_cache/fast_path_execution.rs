@@ -60,6 +60,7 @@ fn execute_fast_path(
         let candidates = match pattern.direction {
             BackendDirection::Outgoing => graph.fetch_outgoing(source_id)?,
             BackendDirection::Incoming => graph.fetch_incoming(source_id)?,
+            BackendDirection::Both => unreachable!("can_use_fast_path rejects Both"),
         };
 
         // For each candidate, validate edge exists with correct type
@@ -68,6 +69,7 @@ fn execute_fast_path(
             let (from_id, to_id) = match pattern.direction {
                 BackendDirection::Outgoing => (source_id, target_id),
                 BackendDirection::Incoming => (target_id, source_id),
+                BackendDirection::Both => unreachable!("can_use_fast_path rejects Both"),
             };
 
             // Validate edge exists with correct type
@@ -84,6 +86,7 @@ fn execute_fast_path(
                 let triple_match = match pattern.direction {
                     BackendDirection::Outgoing => TripleMatch::new(source_id, edge_id, target_id),
                     BackendDirection::Incoming => TripleMatch::new(source_id, edge_id, target_id),
+                    BackendDirection::Both => unreachable!("can_use_fast_path rejects Both"),
                 };
                 matches.push(triple_match);
             }
@@ -51,6 +51,12 @@ mod tests {
         // Should NOT use fast path - has end label
         let pattern4 = PatternTriple::new("CALLS").end_label("Function");
         assert!(!can_use_fast_path(&pattern4));
+
+        // Should NOT use fast path - has exclusion or key-presence filter
+        let pattern5 = PatternTriple::new("CALLS").end_property_not("lang", "rust");
+        assert!(!can_use_fast_path(&pattern5));
+        let pattern6 = PatternTriple::new("CALLS").start_has_key("lang");
+        assert!(!can_use_fast_path(&pattern6));
     }
 
     #[test]
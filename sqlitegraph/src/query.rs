@@ -1,12 +1,13 @@
 use std::collections::VecDeque;
 
 use ahash::AHashSet;
-use rusqlite::params;
+use rusqlite::{params, params_from_iter, types::Value};
 
 use crate::{
     backend::BackendDirection,
     errors::SqliteGraphError,
-    graph::SqliteGraph,
+    graph::{GraphEntity, SqliteGraph},
+    index,
     multi_hop::{self, ChainStep},
     pattern::{self, PatternMatch, PatternQuery},
 };
@@ -32,6 +33,48 @@ impl<'a> GraphQuery<'a> {
         self.graph.fetch_outgoing(id)
     }
 
+    /// Up to `limit` outgoing neighbors of `id`, skipping the first `offset`.
+    ///
+    /// Pages follow the same order as [`GraphQuery::outgoing`], so consecutive
+    /// pages of an unchanged graph neither overlap nor leave gaps.
+    pub fn outgoing_paged(
+        &self,
+        id: i64,
+        offset: usize,
+        limit: usize,
+    ) -> Result<NeighborPage, SqliteGraphError> {
+        self.neighbor_page(id, "from_id", "to_id", offset, limit)
+    }
+
+    /// Up to `limit` incoming neighbors of `id`, skipping the first `offset`,
+    /// in the order of [`GraphQuery::incoming`].
+    pub fn incoming_paged(
+        &self,
+        id: i64,
+        offset: usize,
+        limit: usize,
+    ) -> Result<NeighborPage, SqliteGraphError> {
+        self.neighbor_page(id, "to_id", "from_id", offset, limit)
+    }
+
+    fn neighbor_page(
+        &self,
+        id: i64,
+        anchor: &str,
+        other: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<NeighborPage, SqliteGraphError> {
+        let (neighbors, total) = self
+            .graph
+            .load_adjacency_page(id, anchor, other, offset, limit)?;
+        Ok(NeighborPage {
+            neighbors,
+            offset,
+            total,
+        })
+    }
+
     pub fn edges_of_type(&self, id: i64, edge_type: &str) -> Result<Vec<i64>, SqliteGraphError> {
         if edge_type.trim().is_empty() {
             return Err(SqliteGraphError::invalid_input("edge_type required"));
@@ -39,15 +82,17 @@ impl<'a> GraphQuery<'a> {
         let conn = self.graph.connection();
         let mut stmt = conn
             .prepare_cached(
-                "SELECT to_id FROM graph_edges WHERE from_id=?1 AND edge_type=?2 ORDER BY to_id, id",
+                "SELECT to_id FROM graph_edges WHERE from_id=?1 AND edge_type=?2 \
+                 AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=to_id) \
+                 ORDER BY to_id, id",
             )
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         let rows = stmt
             .query_map(params![id, edge_type], |row| row.get(0))
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         let mut ids = Vec::new();
         for entry in rows {
-            ids.push(entry.map_err(|e| SqliteGraphError::query(e.to_string()))?);
+            ids.push(entry.map_err(SqliteGraphError::query)?);
         }
         Ok(ids)
     }
@@ -106,6 +151,28 @@ impl<'a> GraphQuery<'a> {
         multi_hop::chain_query(self.graph, start, chain)
     }
 
+    /// Start a filtered single-hop neighbor query from `id`.
+    pub fn from(&self, id: i64) -> NeighborFilter<'a> {
+        NeighborFilter {
+            graph: self.graph,
+            start: id,
+            direction: BackendDirection::Outgoing,
+            edge_type: None,
+            target_labels: Vec::new(),
+            target_properties: Vec::new(),
+        }
+    }
+
+    /// Start a fluent multi-hop traversal from `id`.
+    pub fn traverse(&self, id: i64) -> Traversal<'a> {
+        Traversal {
+            graph: self.graph,
+            start: id,
+            steps: Vec::new(),
+            filters: Vec::new(),
+        }
+    }
+
     pub fn pattern_matches(
         &self,
         start: i64,
@@ -115,6 +182,255 @@ impl<'a> GraphQuery<'a> {
     }
 }
 
+/// One page of neighbors returned by [`GraphQuery::outgoing_paged`] and
+/// [`GraphQuery::incoming_paged`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NeighborPage {
+    pub neighbors: Vec<i64>,
+    /// Offset the page was requested at
+    pub offset: usize,
+    /// Number of neighbors across all pages
+    pub total: usize,
+}
+
+impl NeighborPage {
+    /// Whether neighbors remain after this page.
+    pub fn has_more(&self) -> bool {
+        self.offset.saturating_add(self.neighbors.len()) < self.total
+    }
+
+    /// Offset of the following page.
+    pub fn next_offset(&self) -> usize {
+        self.offset.saturating_add(self.neighbors.len())
+    }
+}
+
+/// Fluent single-hop neighbor filter created by [`GraphQuery::from`].
+///
+/// All filters are combined with AND and lowered to one SQL statement. The
+/// direction defaults to outgoing.
+pub struct NeighborFilter<'a> {
+    graph: &'a SqliteGraph,
+    start: i64,
+    direction: BackendDirection,
+    edge_type: Option<String>,
+    target_labels: Vec<String>,
+    target_properties: Vec<(String, String)>,
+}
+
+impl<'a> NeighborFilter<'a> {
+    pub fn direction(mut self, direction: BackendDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Only follow edges of `edge_type`.
+    pub fn edge_type(mut self, edge_type: &str) -> Self {
+        self.edge_type = Some(edge_type.to_string());
+        self
+    }
+
+    /// Require the neighbor to carry `label`; may be called repeatedly.
+    pub fn target_label(mut self, label: &str) -> Self {
+        self.target_labels.push(label.to_string());
+        self
+    }
+
+    /// Require the neighbor to have property `key` equal to `value`; may be called repeatedly.
+    pub fn target_property(mut self, key: &str, value: &str) -> Self {
+        self.target_properties
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Distinct matching neighbor ids in ascending order.
+    pub fn nodes(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        if self
+            .edge_type
+            .as_deref()
+            .is_some_and(|edge_type| edge_type.trim().is_empty())
+        {
+            return Err(SqliteGraphError::invalid_input("edge_type required"));
+        }
+        let (neighbor, anchor) = match self.direction {
+            BackendDirection::Outgoing => ("ge.to_id", "ge.from_id=?1"),
+            BackendDirection::Incoming => ("ge.from_id", "ge.to_id=?1"),
+            BackendDirection::Both => (
+                "CASE WHEN ge.from_id=?1 THEN ge.to_id ELSE ge.from_id END",
+                "(ge.from_id=?1 OR ge.to_id=?1)",
+            ),
+        };
+        let mut sql = format!(
+            "SELECT DISTINCT {neighbor} AS neighbor FROM graph_edges ge WHERE {anchor} \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id={neighbor})"
+        );
+        let mut values = vec![Value::Integer(self.start)];
+        if let Some(edge_type) = &self.edge_type {
+            values.push(Value::Text(edge_type.clone()));
+            sql.push_str(&format!(" AND ge.edge_type=?{}", values.len()));
+        }
+        for label in &self.target_labels {
+            values.push(Value::Text(label.clone()));
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM graph_labels gl WHERE gl.entity_id={neighbor} AND gl.label=?{})",
+                values.len()
+            ));
+        }
+        for (key, value) in &self.target_properties {
+            values.push(Value::Text(key.clone()));
+            values.push(Value::Text(value.clone()));
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM graph_properties gp WHERE gp.entity_id={neighbor} AND gp.key=?{} AND gp.value=?{})",
+                values.len() - 1,
+                values.len()
+            ));
+        }
+        sql.push_str(" ORDER BY neighbor");
+
+        let conn = self.graph.connection();
+        let mut stmt = conn.prepare_cached(&sql).map_err(SqliteGraphError::query)?;
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| row.get(0))
+            .map_err(SqliteGraphError::query)?;
+        let mut ids = Vec::new();
+        for entry in rows {
+            ids.push(entry.map_err(SqliteGraphError::query)?);
+        }
+        Ok(ids)
+    }
+}
+
+/// Fluent multi-hop traversal created by [`GraphQuery::traverse`].
+///
+/// Hops accumulate as [`ChainStep`]s and run through
+/// [`multi_hop::chain_query`]. A label or property filter keeps only the nodes
+/// reached by the hops added before it, so it narrows the frontier the
+/// following hops start from.
+pub struct Traversal<'a> {
+    graph: &'a SqliteGraph,
+    start: i64,
+    steps: Vec<ChainStep>,
+    /// Filters paired with the number of hops that precede them
+    filters: Vec<(usize, NodeFilter)>,
+}
+
+enum NodeFilter {
+    Label(String),
+    Property(String, String),
+}
+
+impl<'a> Traversal<'a> {
+    /// Follow outgoing edges of `edge_type`.
+    pub fn out(self, edge_type: &str) -> Self {
+        self.step(ChainStep {
+            direction: BackendDirection::Outgoing,
+            edge_type: Some(edge_type.to_string()),
+        })
+    }
+
+    /// Follow incoming edges of `edge_type`.
+    pub fn in_(self, edge_type: &str) -> Self {
+        self.step(ChainStep {
+            direction: BackendDirection::Incoming,
+            edge_type: Some(edge_type.to_string()),
+        })
+    }
+
+    /// Follow edges of `edge_type` in either direction.
+    pub fn both(self, edge_type: &str) -> Self {
+        self.step(ChainStep {
+            direction: BackendDirection::Both,
+            edge_type: Some(edge_type.to_string()),
+        })
+    }
+
+    /// Append an arbitrary hop; an `edge_type` of `None` follows every edge.
+    pub fn step(mut self, step: ChainStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Keep only the nodes reached so far that carry `label`.
+    pub fn filter_label(mut self, label: &str) -> Self {
+        self.filters
+            .push((self.steps.len(), NodeFilter::Label(label.to_string())));
+        self
+    }
+
+    /// Keep only the nodes reached so far whose property `key` equals `value`.
+    pub fn filter_property(mut self, key: &str, value: &str) -> Self {
+        self.filters.push((
+            self.steps.len(),
+            NodeFilter::Property(key.to_string(), value.to_string()),
+        ));
+        self
+    }
+
+    /// Distinct ids reached by the last hop, in ascending order.
+    pub fn nodes(&self) -> Result<Vec<i64>, SqliteGraphError> {
+        if self.steps.iter().any(|step| {
+            step.edge_type
+                .as_deref()
+                .is_some_and(|edge_type| edge_type.trim().is_empty())
+        }) {
+            return Err(SqliteGraphError::invalid_input("edge_type required"));
+        }
+        let mut frontier = vec![self.start];
+        let mut done = 0;
+        let mut filters = self.filters.iter().peekable();
+        loop {
+            let until = filters.peek().map_or(self.steps.len(), |(at, _)| *at);
+            if until > done {
+                frontier = self.advance(&frontier, &self.steps[done..until])?;
+                done = until;
+            }
+            let Some((_, filter)) = filters.next() else {
+                break;
+            };
+            frontier = self.retain(frontier, filter)?;
+        }
+        Ok(frontier)
+    }
+
+    /// The entities behind [`Traversal::nodes`], in the same order.
+    pub fn entities(&self) -> Result<Vec<GraphEntity>, SqliteGraphError> {
+        self.nodes()?
+            .into_iter()
+            .map(|id| self.graph.get_entity(id))
+            .collect()
+    }
+
+    fn advance(&self, frontier: &[i64], steps: &[ChainStep]) -> Result<Vec<i64>, SqliteGraphError> {
+        let mut next = Vec::new();
+        for &node in frontier {
+            next.extend(multi_hop::chain_query(self.graph, node, steps)?);
+        }
+        next.sort_unstable();
+        next.dedup();
+        Ok(next)
+    }
+
+    fn retain(
+        &self,
+        frontier: Vec<i64>,
+        filter: &NodeFilter,
+    ) -> Result<Vec<i64>, SqliteGraphError> {
+        let mut kept = Vec::with_capacity(frontier.len());
+        for node in frontier {
+            let keep = match filter {
+                NodeFilter::Label(label) => index::has_label(self.graph, node, label)?,
+                NodeFilter::Property(key, value) => index::get_properties(self.graph, node)?
+                    .get(key)
+                    .is_some_and(|found| found == value),
+            };
+            if keep {
+                kept.push(node);
+            }
+        }
+        Ok(kept)
+    }
+}
+
 impl SqliteGraph {
     pub fn query(&self) -> GraphQuery<'_> {
         GraphQuery::new(self)
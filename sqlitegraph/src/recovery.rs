@@ -1,16 +1,22 @@
 use std::{
+    collections::{BTreeMap, HashMap},
     fs::File,
     io::{BufRead, BufReader, BufWriter, Write},
     path::Path,
 };
 
+use rusqlite::backup::StepResult;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
-    SqliteGraphError,
+    EntityKind, SqliteGraphError,
     fault_injection::{self, FaultPoint},
-    graph::SqliteGraph,
+    graph::{
+        GraphEdge, GraphEntity, SqliteGraph, row_to_edge, row_to_entity, validate_edge,
+        validate_entity,
+    },
+    schema,
 };
 
 #[derive(Serialize, Deserialize)]
@@ -38,6 +44,9 @@ enum DumpRecord {
         entity_id: i64,
         key: String,
         value: String,
+        /// Type tag of a typed property; absent for plain strings
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value_type: Option<String>,
     },
 }
 
@@ -45,8 +54,7 @@ pub fn dump_graph_to_path<P: AsRef<Path>>(
     graph: &SqliteGraph,
     path: P,
 ) -> Result<(), SqliteGraphError> {
-    let file =
-        File::create(path.as_ref()).map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+    let file = File::create(path.as_ref()).map_err(SqliteGraphError::invalid_input)?;
     dump_graph_to_writer(graph, BufWriter::new(file))
 }
 
@@ -54,31 +62,55 @@ pub fn dump_graph_to_writer<W: Write>(
     graph: &SqliteGraph,
     mut writer: W,
 ) -> Result<(), SqliteGraphError> {
-    for id in graph.list_entity_ids()? {
-        let entity = graph.get_entity(id)?;
-        write_record(
-            &mut writer,
-            &DumpRecord::Entity {
-                id: entity.id,
-                kind: entity.kind,
-                name: entity.name,
-                file_path: entity.file_path,
-                data: entity.data,
-            },
-        )?;
-    }
-    dump_edges(graph, &mut writer)?;
-    dump_labels(graph, &mut writer)?;
-    dump_properties(graph, &mut writer)?;
+    dump_entities(graph, &mut writer, 0)?;
+    dump_edges(graph, &mut writer, 0)?;
+    dump_labels(graph, &mut writer, 0)?;
+    dump_properties(graph, &mut writer, 0)?;
     Ok(())
 }
 
+/// Write, in the [`dump_graph_to_writer`] format, the entities and edges with
+/// an id greater than `since_id`, plus the labels and properties of those
+/// entities. Returns the mark to pass as `since_id` next time.
+///
+/// Ids are only ever handed out in increasing order, so chaining dumps through
+/// the returned mark captures every appended record without rereading the
+/// rest of the graph. Entities and edges are numbered separately, and the mark
+/// is the lower of the two highest ids so that neither sequence can skip
+/// records; when one runs ahead, its records above the mark are written again
+/// by the next dump.
+///
+/// Only appends are captured. In-place updates, deletions, and labels or
+/// properties added to entities at or below `since_id` never appear, so a
+/// chain of incremental dumps must be rebased on a full dump whenever those
+/// matter.
+pub fn dump_incremental<W: Write>(
+    graph: &SqliteGraph,
+    mut writer: W,
+    since_id: i64,
+) -> Result<i64, SqliteGraphError> {
+    let conn = graph.connection();
+    let highest_id = |table: &str| {
+        conn.query_row(
+            &format!("SELECT COALESCE(MAX(id), 0) FROM {table}"),
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map_err(SqliteGraphError::query)
+    };
+    let mark = highest_id("graph_entities")?.min(highest_id("graph_edges")?);
+    dump_entities(graph, &mut writer, since_id)?;
+    dump_edges(graph, &mut writer, since_id)?;
+    dump_labels(graph, &mut writer, since_id)?;
+    dump_properties(graph, &mut writer, since_id)?;
+    Ok(mark)
+}
+
 pub fn load_graph_from_path<P: AsRef<Path>>(
     graph: &SqliteGraph,
     path: P,
 ) -> Result<(), SqliteGraphError> {
-    let file =
-        File::open(path.as_ref()).map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+    let file = File::open(path.as_ref()).map_err(SqliteGraphError::invalid_input)?;
     load_graph_from_reader(graph, BufReader::new(file))
 }
 
@@ -87,42 +119,46 @@ pub fn load_graph_from_reader<R: BufRead>(
     reader: R,
 ) -> Result<(), SqliteGraphError> {
     let conn = graph.connection();
-    conn.execute("BEGIN IMMEDIATE", [])
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-    let result: Result<(), SqliteGraphError> = (|| {
+    let typed = schema::has_property_types(&graph.conn)?;
+    graph.with_transaction(|| {
         conn.execute("DELETE FROM graph_labels", [])
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         conn.execute("DELETE FROM graph_properties", [])
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         conn.execute("DELETE FROM graph_edges", [])
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         conn.execute("DELETE FROM graph_entities", [])
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
 
         let mut stmt_entity = conn
             .prepare_cached(
                 "INSERT INTO graph_entities(id,kind,name,file_path,data) VALUES(?1,?2,?3,?4,?5)",
             )
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         let mut stmt_edge = conn
             .prepare_cached(
                 "INSERT INTO graph_edges(id,from_id,to_id,edge_type,data) VALUES(?1,?2,?3,?4,?5)",
             )
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         let mut stmt_label = conn
             .prepare_cached("INSERT INTO graph_labels(entity_id,label) VALUES(?1,?2)")
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .map_err(SqliteGraphError::query)?;
         let mut stmt_property = conn
-            .prepare_cached("INSERT INTO graph_properties(entity_id,key,value) VALUES(?1,?2,?3)")
-            .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+            .prepare_cached(if typed {
+                "INSERT INTO graph_properties(entity_id,key,value,value_type) \
+                 VALUES(?1,?2,?3,?4)"
+            } else {
+                "INSERT INTO graph_properties(entity_id,key,value) VALUES(?1,?2,?3)"
+            })
+            .map_err(SqliteGraphError::query)?;
 
         for line in reader.lines() {
-            let line = line.map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+            let line = line.map_err(SqliteGraphError::invalid_input)?;
             if line.trim().is_empty() {
                 continue;
             }
-            let record: DumpRecord = serde_json::from_str(&line)
-                .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+            let record: DumpRecord =
+                serde_json::from_str(&line).map_err(SqliteGraphError::invalid_input)?;
             match record {
                 DumpRecord::Entity {
                     id,
@@ -131,11 +167,10 @@ pub fn load_graph_from_reader<R: BufRead>(
                     file_path,
                     data,
                 } => {
-                    let payload = serde_json::to_string(&data)
-                        .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+                    let payload = graph.encode_data(&data)?;
                     stmt_entity
                         .execute(rusqlite::params![id, kind, name, file_path, payload])
-                        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                        .map_err(SqliteGraphError::query)?;
                 }
                 DumpRecord::Edge {
                     id,
@@ -144,58 +179,910 @@ pub fn load_graph_from_reader<R: BufRead>(
                     edge_type,
                     data,
                 } => {
-                    let payload = serde_json::to_string(&data)
-                        .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+                    let payload = graph.encode_data(&data)?;
                     stmt_edge
                         .execute(rusqlite::params![id, from_id, to_id, edge_type, payload])
-                        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                        .map_err(SqliteGraphError::query)?;
                 }
                 DumpRecord::Label { entity_id, label } => {
                     stmt_label
                         .execute(rusqlite::params![entity_id, label])
-                        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                        .map_err(SqliteGraphError::query)?;
                 }
                 DumpRecord::Property {
                     entity_id,
                     key,
                     value,
+                    value_type,
                 } => {
-                    stmt_property
-                        .execute(rusqlite::params![entity_id, key, value])
-                        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+                    let value_type = value_type.as_deref().unwrap_or("string");
+                    if typed {
+                        stmt_property.execute(rusqlite::params![entity_id, key, value, value_type])
+                    } else if value_type == "string" {
+                        stmt_property.execute(rusqlite::params![entity_id, key, value])
+                    } else {
+                        return Err(SqliteGraphError::schema(
+                            "typed property values need schema version 4; \
+                             open the graph with migrations",
+                        ));
+                    }
+                    .map_err(SqliteGraphError::query)?;
                 }
             }
         }
-        Ok(())
-    })();
-    let result = result.and_then(|_| {
         fault_injection::check_fault(FaultPoint::RecoveryLoadBeforeCommit)?;
         Ok(())
+    })?;
+    graph.invalidate_caches();
+    Ok(())
+}
+
+/// How long [`backup_online`] waits before retrying a copy that found a
+/// database locked
+const BACKUP_RETRY_PAUSE: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Copy the database behind `graph` to a new SQLite file at `dest`, without
+/// stopping writers.
+///
+/// Uses SQLite's online backup API: every page is copied in a single step
+/// inside one read transaction, so the result is the graph as of one commit
+/// and opens as a standalone graph with [`SqliteGraph::open`]. In WAL mode
+/// other connections keep writing during the copy; in rollback-journal mode
+/// they wait for it to finish. An existing database at `dest` is overwritten.
+///
+/// Copying the database file instead is unsafe while writes are possible: a
+/// commit landing mid-copy leaves a mix of old and new pages, and in WAL mode
+/// recent commits still sit in the `-wal` file and are missed entirely.
+pub fn backup_online<P: AsRef<Path>>(graph: &SqliteGraph, dest: P) -> Result<(), SqliteGraphError> {
+    let mut target =
+        rusqlite::Connection::open(dest.as_ref()).map_err(SqliteGraphError::connection)?;
+    let backup = rusqlite::backup::Backup::new(graph.underlying_connection(), &mut target)
+        .map_err(SqliteGraphError::connection)?;
+    loop {
+        match backup.step(-1).map_err(SqliteGraphError::connection)? {
+            StepResult::Done => return Ok(()),
+            StepResult::More => {}
+            _ => std::thread::sleep(BACKUP_RETRY_PAUSE),
+        }
+    }
+}
+
+/// One line of the JSON Lines format used by [`stream_export_jsonl`] and
+/// [`stream_import_jsonl`].
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "t", rename_all = "snake_case")]
+enum JsonlRecord {
+    Node {
+        id: i64,
+        kind: String,
+        name: String,
+        file_path: Option<String>,
+        data: Value,
+    },
+    Edge {
+        id: i64,
+        from_id: i64,
+        to_id: i64,
+        edge_type: String,
+        data: Value,
+    },
+}
+
+/// Counts of records written by [`stream_export_jsonl`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportStats {
+    pub nodes: usize,
+    pub edges: usize,
+}
+
+/// Counts of records read by [`stream_import_jsonl`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportStats {
+    pub nodes: usize,
+    pub edges: usize,
+    /// Number of committed transactions.
+    pub batches: usize,
+}
+
+/// Every live node, in id order, as `row_to_entity` columns.
+const LIVE_ENTITIES_SQL: &str = "SELECT id, kind, name, file_path, data FROM graph_entities e \
+     WHERE NOT EXISTS (SELECT 1 FROM graph_tombstones t WHERE t.entity_id=e.id) \
+     ORDER BY id";
+
+/// Every edge between live nodes, in id order, as `row_to_edge` columns.
+const LIVE_EDGES_SQL: &str = "SELECT id, from_id, to_id, edge_type, data FROM graph_edges g \
+     WHERE EXISTS (SELECT 1 FROM graph_entities e WHERE e.id=g.from_id) \
+     AND EXISTS (SELECT 1 FROM graph_entities e WHERE e.id=g.to_id) \
+     AND NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+                     WHERE t.entity_id IN (g.from_id, g.to_id)) \
+     ORDER BY id";
+
+/// Write every live node, then every edge between live nodes, as one JSON object per line.
+///
+/// Node lines look like `{"t":"node","id":..,"kind":..,"name":..,"file_path":..,"data":..}`
+/// and edge lines like `{"t":"edge","id":..,"from_id":..,"to_id":..,"edge_type":..,"data":..}`.
+/// Rows are streamed from the database one at a time, so memory use does not
+/// grow with the size of the graph. Labels and properties are not included.
+pub fn stream_export_jsonl(
+    graph: &SqliteGraph,
+    writer: &mut impl Write,
+) -> Result<ExportStats, SqliteGraphError> {
+    let mut stats = ExportStats::default();
+    let conn = graph.connection();
+    let mut stmt = conn
+        .prepare_cached(LIVE_ENTITIES_SQL)
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([], row_to_entity)
+        .map_err(SqliteGraphError::query)?;
+    for row in rows {
+        let entity = row.map_err(SqliteGraphError::query)?;
+        write_jsonl(
+            writer,
+            &JsonlRecord::Node {
+                id: entity.id,
+                kind: entity.kind,
+                name: entity.name,
+                file_path: entity.file_path,
+                data: entity.data,
+            },
+        )?;
+        stats.nodes += 1;
+    }
+
+    let mut stmt = conn
+        .prepare_cached(LIVE_EDGES_SQL)
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([], row_to_edge)
+        .map_err(SqliteGraphError::query)?;
+    for row in rows {
+        let edge = row.map_err(SqliteGraphError::query)?;
+        write_jsonl(
+            writer,
+            &JsonlRecord::Edge {
+                id: edge.id,
+                from_id: edge.from_id,
+                to_id: edge.to_id,
+                edge_type: edge.edge_type,
+                data: edge.data,
+            },
+        )?;
+        stats.edges += 1;
+    }
+    writer.flush().map_err(SqliteGraphError::invalid_input)?;
+    Ok(stats)
+}
+
+/// Read the output of [`stream_export_jsonl`] line by line and insert it into `graph`.
+///
+/// Records keep their ids and are committed every `batch_size` lines, so at
+/// most one batch is held in memory. As with
+/// [`bulk_insert_entities_iter`](crate::bulk_insert_entities_iter), batches
+/// committed before a failing line stay in the graph. Edge endpoints must
+/// already exist unless the graph defers integrity checks. Blank lines are
+/// ignored.
+pub fn stream_import_jsonl(
+    graph: &SqliteGraph,
+    reader: impl BufRead,
+    batch_size: usize,
+) -> Result<ImportStats, SqliteGraphError> {
+    graph.ensure_writable()?;
+    if batch_size == 0 {
+        return Err(SqliteGraphError::invalid_input(
+            "batch_size must be greater than zero",
+        ));
+    }
+    let mut stats = ImportStats::default();
+    let mut batch = Vec::with_capacity(batch_size);
+    for line in reader.lines() {
+        let line = line.map_err(SqliteGraphError::invalid_input)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: JsonlRecord =
+            serde_json::from_str(&line).map_err(SqliteGraphError::invalid_input)?;
+        batch.push(record);
+        if batch.len() == batch_size {
+            import_jsonl_batch(graph, &batch, &mut stats)?;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty() {
+        import_jsonl_batch(graph, &batch, &mut stats)?;
+    }
+    Ok(stats)
+}
+
+fn import_jsonl_batch(
+    graph: &SqliteGraph,
+    batch: &[JsonlRecord],
+    stats: &mut ImportStats,
+) -> Result<(), SqliteGraphError> {
+    let conn = graph.connection();
+    let result = graph.with_transaction(|| {
+        let mut stmt_entity = conn
+            .prepare_cached(
+                "INSERT INTO graph_entities(id,kind,name,file_path,data) VALUES(?1,?2,?3,?4,?5)",
+            )
+            .map_err(SqliteGraphError::query)?;
+        let mut stmt_edge = conn
+            .prepare_cached(
+                "INSERT INTO graph_edges(id,from_id,to_id,edge_type,data) VALUES(?1,?2,?3,?4,?5)",
+            )
+            .map_err(SqliteGraphError::query)?;
+        let (mut nodes, mut edges) = (0, 0);
+        for record in batch {
+            match record {
+                JsonlRecord::Node {
+                    id,
+                    kind,
+                    name,
+                    file_path,
+                    data,
+                } => {
+                    let entity = GraphEntity {
+                        id: *id,
+                        kind: kind.clone(),
+                        name: name.clone(),
+                        file_path: file_path.clone(),
+                        data: data.clone(),
+                    };
+                    validate_entity(&entity)?;
+                    let payload = graph.encode_data(data)?;
+                    stmt_entity
+                        .execute(rusqlite::params![id, kind, name, file_path, payload])
+                        .map_err(SqliteGraphError::query)?;
+                    nodes += 1;
+                }
+                JsonlRecord::Edge {
+                    id,
+                    from_id,
+                    to_id,
+                    edge_type,
+                    data,
+                } => {
+                    let edge = GraphEdge {
+                        id: *id,
+                        from_id: *from_id,
+                        to_id: *to_id,
+                        edge_type: edge_type.clone(),
+                        data: data.clone(),
+                    };
+                    validate_edge(&edge)?;
+                    if !graph.deferred_integrity()
+                        && (!graph.entity_exists(*from_id)? || !graph.entity_exists(*to_id)?)
+                    {
+                        return Err(SqliteGraphError::invalid_input(
+                            "edge endpoints must reference existing entities",
+                        ));
+                    }
+                    let payload = graph.encode_data(data)?;
+                    stmt_edge
+                        .execute(rusqlite::params![id, from_id, to_id, edge_type, payload])
+                        .map_err(SqliteGraphError::query)?;
+                    edges += 1;
+                }
+            }
+        }
+        Ok((nodes, edges))
+    });
+    graph.invalidate_caches();
+    let (nodes, edges) = result?;
+    stats.nodes += nodes;
+    stats.edges += edges;
+    stats.batches += 1;
+    Ok(())
+}
+
+/// A set of node and edge changes that can be serialized and replayed onto another graph.
+///
+/// Removals are applied before additions, so a node can be replaced by removing and
+/// re-adding the same id in one delta. Nodes and edges with `id > 0` keep that id;
+/// `id <= 0` lets SQLite assign one.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphDelta {
+    pub add_nodes: Vec<GraphEntity>,
+    pub add_edges: Vec<GraphEdge>,
+    pub remove_node_ids: Vec<i64>,
+    pub remove_edge_ids: Vec<i64>,
+}
+
+/// Counts of changes made by [`apply_delta`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeltaStats {
+    pub nodes_added: usize,
+    pub edges_added: usize,
+    pub nodes_removed: usize,
+    pub edges_removed: usize,
+}
+
+/// Apply `delta` to `graph` inside a single transaction.
+///
+/// Removing a node also removes its incident edges, labels and properties. Any
+/// failure (missing id, invalid record, dangling edge endpoint) rolls back the
+/// whole delta.
+pub fn apply_delta(
+    graph: &SqliteGraph,
+    delta: &GraphDelta,
+) -> Result<DeltaStats, SqliteGraphError> {
+    let conn = graph.connection();
+    let result = graph.with_transaction(|| {
+        let mut stats = DeltaStats::default();
+        for &id in &delta.remove_edge_ids {
+            let affected = conn
+                .execute("DELETE FROM graph_edges WHERE id=?1", rusqlite::params![id])
+                .map_err(SqliteGraphError::query)?;
+            if affected == 0 {
+                return Err(SqliteGraphError::not_found(EntityKind::Edge, id));
+            }
+            stats.edges_removed += 1;
+        }
+        for &id in &delta.remove_node_ids {
+            let affected = conn
+                .execute(
+                    "DELETE FROM graph_entities WHERE id=?1",
+                    rusqlite::params![id],
+                )
+                .map_err(SqliteGraphError::query)?;
+            if affected == 0 {
+                return Err(SqliteGraphError::not_found(EntityKind::Node, id));
+            }
+            conn.execute(
+                "DELETE FROM graph_edges WHERE from_id=?1 OR to_id=?1",
+                rusqlite::params![id],
+            )
+            .map_err(SqliteGraphError::query)?;
+            conn.execute(
+                "DELETE FROM graph_labels WHERE entity_id=?1",
+                rusqlite::params![id],
+            )
+            .map_err(SqliteGraphError::query)?;
+            conn.execute(
+                "DELETE FROM graph_properties WHERE entity_id=?1",
+                rusqlite::params![id],
+            )
+            .map_err(SqliteGraphError::query)?;
+            stats.nodes_removed += 1;
+        }
+
+        let mut stmt_entity = conn
+            .prepare_cached(
+                "INSERT INTO graph_entities(id,kind,name,file_path,data) VALUES(?1,?2,?3,?4,?5)",
+            )
+            .map_err(SqliteGraphError::query)?;
+        for entity in &delta.add_nodes {
+            validate_entity(entity)?;
+            let payload = graph.encode_data(&entity.data)?;
+            stmt_entity
+                .execute(rusqlite::params![
+                    (entity.id > 0).then_some(entity.id),
+                    entity.kind,
+                    entity.name,
+                    entity.file_path,
+                    payload
+                ])
+                .map_err(SqliteGraphError::query)?;
+            stats.nodes_added += 1;
+        }
+
+        let mut stmt_edge = conn
+            .prepare_cached(
+                "INSERT INTO graph_edges(id,from_id,to_id,edge_type,data) VALUES(?1,?2,?3,?4,?5)",
+            )
+            .map_err(SqliteGraphError::query)?;
+        for edge in &delta.add_edges {
+            validate_edge(edge)?;
+            if !graph.entity_exists(edge.from_id)? || !graph.entity_exists(edge.to_id)? {
+                return Err(SqliteGraphError::invalid_input(
+                    "edge endpoints must reference existing entities",
+                ));
+            }
+            let payload = graph.encode_data(&edge.data)?;
+            stmt_edge
+                .execute(rusqlite::params![
+                    (edge.id > 0).then_some(edge.id),
+                    edge.from_id,
+                    edge.to_id,
+                    edge.edge_type,
+                    payload
+                ])
+                .map_err(SqliteGraphError::query)?;
+            stats.edges_added += 1;
+        }
+        Ok(stats)
     });
-    match result {
-        Ok(()) => {
-            conn.execute("COMMIT", [])
-                .map_err(|e| SqliteGraphError::query(e.to_string()))?;
-            graph.invalidate_caches();
-            Ok(())
+    graph.invalidate_caches();
+    result
+}
+
+/// Copy `graph` into a new in-memory graph with entity ids renumbered densely.
+///
+/// Entities receive ids `1..=n` in ascending order of their original ids, and
+/// edges are renumbered the same way; edge endpoints, labels and properties are
+/// rewritten to the new ids. Returns the new graph and the old-to-new entity id map.
+pub fn remap_ids(
+    graph: &SqliteGraph,
+) -> Result<(SqliteGraph, HashMap<i64, i64>), SqliteGraphError> {
+    let mut target = SqliteGraph::open_in_memory()?;
+    target.set_max_data_bytes(graph.max_data_bytes());
+    let mapping: HashMap<i64, i64> = graph.list_entity_ids()?.into_iter().zip(1..).collect();
+    // Edges, labels and properties left behind by deleted entities are dropped
+    let remap = |id: i64| mapping.get(&id).copied();
+    let mut records = Vec::new();
+    dump_graph_to_writer(graph, &mut records)?;
+    let mut remapped = Vec::with_capacity(records.len());
+    let mut next_edge_id = 0i64;
+    for line in records
+        .split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+    {
+        let record: DumpRecord =
+            serde_json::from_slice(line).map_err(SqliteGraphError::invalid_input)?;
+        let record = match record {
+            DumpRecord::Entity {
+                id,
+                kind,
+                name,
+                file_path,
+                data,
+            } => remap(id).map(|id| DumpRecord::Entity {
+                id,
+                kind,
+                name,
+                file_path,
+                data,
+            }),
+            DumpRecord::Edge {
+                from_id,
+                to_id,
+                edge_type,
+                data,
+                ..
+            } => remap(from_id).zip(remap(to_id)).map(|(from_id, to_id)| {
+                next_edge_id += 1;
+                DumpRecord::Edge {
+                    id: next_edge_id,
+                    from_id,
+                    to_id,
+                    edge_type,
+                    data,
+                }
+            }),
+            DumpRecord::Label { entity_id, label } => {
+                remap(entity_id).map(|entity_id| DumpRecord::Label { entity_id, label })
+            }
+            DumpRecord::Property {
+                entity_id,
+                key,
+                value,
+                value_type,
+            } => remap(entity_id).map(|entity_id| DumpRecord::Property {
+                entity_id,
+                key,
+                value,
+                value_type,
+            }),
+        };
+        if let Some(record) = record {
+            write_record(&mut remapped, &record)?;
         }
-        Err(err) => {
-            let _ = conn.execute("ROLLBACK", []);
-            Err(err)
+    }
+    load_graph_from_reader(&target, &remapped[..])?;
+    Ok((target, mapping))
+}
+
+/// Node columns written before any property columns by [`export_neo4j_csv`].
+const NEO4J_NODE_COLUMNS: [&str; 5] = ["id:ID", "kind", "name", "file_path", "data"];
+
+/// Export the graph as the node and relationship CSV files consumed by
+/// `neo4j-admin database import`.
+///
+/// Labels from the label table are written to the `:LABEL` column separated by
+/// `;`. Every property key becomes its own column typed `long`, `double`,
+/// `boolean` or `string` depending on the values stored for it; keys that clash
+/// with the fixed node columns are prefixed with `prop_`.
+pub fn export_neo4j_csv<P: AsRef<Path>, Q: AsRef<Path>>(
+    graph: &SqliteGraph,
+    nodes_path: P,
+    edges_path: Q,
+) -> Result<(), SqliteGraphError> {
+    let mut labels: BTreeMap<i64, Vec<String>> = BTreeMap::new();
+    collect_pairs(
+        graph,
+        "SELECT entity_id, label, NULL FROM graph_labels ORDER BY entity_id, label",
+        |entity_id, label, _| labels.entry(entity_id).or_default().push(label),
+    )?;
+    let mut properties: BTreeMap<i64, BTreeMap<String, String>> = BTreeMap::new();
+    let mut property_types: BTreeMap<String, ValueType> = BTreeMap::new();
+    collect_pairs(
+        graph,
+        "SELECT entity_id, key, value FROM graph_properties ORDER BY entity_id, key, value",
+        |entity_id, key, value| {
+            let value = value.unwrap_or_default();
+            let observed = ValueType::infer(&value);
+            property_types
+                .entry(key.clone())
+                .and_modify(|current| *current = current.widen(observed))
+                .or_insert(observed);
+            properties.entry(entity_id).or_default().insert(key, value);
+        },
+    )?;
+
+    let mut nodes = create_output(nodes_path.as_ref())?;
+    let mut header: Vec<String> = NEO4J_NODE_COLUMNS.iter().map(|c| c.to_string()).collect();
+    for (key, ty) in &property_types {
+        let column = if NEO4J_NODE_COLUMNS
+            .iter()
+            .any(|c| c.split(':').next() == Some(key.as_str()))
+        {
+            format!("prop_{key}")
+        } else {
+            key.clone()
+        };
+        header.push(format!("{column}:{}", ty.as_str()));
+    }
+    header.push(":LABEL".to_string());
+    write_csv_row(&mut nodes, &header)?;
+    let conn = graph.connection();
+    let mut stmt = conn
+        .prepare_cached(LIVE_ENTITIES_SQL)
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([], row_to_entity)
+        .map_err(SqliteGraphError::query)?;
+    for row in rows {
+        let entity = row.map_err(SqliteGraphError::query)?;
+        let id = entity.id;
+        let entity_properties = properties.remove(&id).unwrap_or_default();
+        let mut row = vec![
+            entity.id.to_string(),
+            entity.kind,
+            entity.name,
+            entity.file_path.unwrap_or_default(),
+            entity.data.to_string(),
+        ];
+        for key in property_types.keys() {
+            row.push(entity_properties.get(key).cloned().unwrap_or_default());
         }
+        row.push(labels.remove(&id).unwrap_or_default().join(";"));
+        write_csv_row(&mut nodes, &row)?;
     }
+    nodes.flush().map_err(SqliteGraphError::connection)?;
+
+    let mut edges = create_output(edges_path.as_ref())?;
+    write_csv_row(
+        &mut edges,
+        &[":START_ID", ":END_ID", ":TYPE", "id:long", "data"],
+    )?;
+    let mut stmt = conn
+        .prepare_cached(LIVE_EDGES_SQL)
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([], row_to_edge)
+        .map_err(SqliteGraphError::query)?;
+    for row in rows {
+        let edge = row.map_err(SqliteGraphError::query)?;
+        write_csv_row(
+            &mut edges,
+            &[
+                edge.from_id.to_string(),
+                edge.to_id.to_string(),
+                edge.edge_type,
+                edge.id.to_string(),
+                edge.data.to_string(),
+            ],
+        )?;
+    }
+    edges.flush().map_err(SqliteGraphError::connection)
 }
 
-fn dump_edges<W: Write>(graph: &SqliteGraph, writer: &mut W) -> Result<(), SqliteGraphError> {
+/// Export the graph as a GraphML document readable by Gephi, yEd and similar tools.
+///
+/// Nodes carry `kind`, `name` and `file_path` plus every scalar in their `data`,
+/// with nested object fields flattened to dotted names (`data.a.b`); arrays and
+/// nulls are skipped. Edges carry `edge_type` and their flattened `data` the same
+/// way. Each attribute gets a `<key>` declaration whose `attr.type` is inferred
+/// from the values stored under it.
+pub fn export_graphml<P: AsRef<Path>>(
+    graph: &SqliteGraph,
+    path: P,
+) -> Result<(), SqliteGraphError> {
+    let mut nodes = Vec::new();
+    for id in graph.list_entity_ids()? {
+        let entity = graph.get_entity(id)?;
+        let mut attrs = BTreeMap::new();
+        attrs.insert("kind".to_string(), Value::String(entity.kind));
+        attrs.insert("name".to_string(), Value::String(entity.name));
+        if let Some(file_path) = entity.file_path {
+            attrs.insert("file_path".to_string(), Value::String(file_path));
+        }
+        flatten_scalars("data", &entity.data, &mut attrs);
+        nodes.push((entity.id, attrs));
+    }
+    let mut edges = Vec::new();
+    for id in live_edge_ids(graph)? {
+        let edge = graph.get_edge(id)?;
+        let mut attrs = BTreeMap::new();
+        attrs.insert(
+            "edge_type".to_string(),
+            Value::String(edge.edge_type.clone()),
+        );
+        flatten_scalars("data", &edge.data, &mut attrs);
+        edges.push((edge, attrs));
+    }
+
+    let node_keys = graphml_keys(nodes.iter().map(|(_, attrs)| attrs), 0);
+    let edge_keys = graphml_keys(edges.iter().map(|(_, attrs)| attrs), node_keys.len());
+
+    let mut out = create_output(path.as_ref())?;
+    let mut doc = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+    );
+    for (target, keys) in [("node", &node_keys), ("edge", &edge_keys)] {
+        for (name, (key_id, ty)) in keys {
+            doc.push_str(&format!(
+                "  <key id=\"{key_id}\" for=\"{target}\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+                xml_escape(name),
+                ty.as_str()
+            ));
+        }
+    }
+    doc.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+    for (id, attrs) in &nodes {
+        doc.push_str(&format!("    <node id=\"n{id}\">\n"));
+        push_graphml_data(&mut doc, attrs, &node_keys);
+        doc.push_str("    </node>\n");
+    }
+    for (edge, attrs) in &edges {
+        doc.push_str(&format!(
+            "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\">\n",
+            edge.id, edge.from_id, edge.to_id
+        ));
+        push_graphml_data(&mut doc, attrs, &edge_keys);
+        doc.push_str("    </edge>\n");
+    }
+    doc.push_str("  </graph>\n</graphml>\n");
+    out.write_all(doc.as_bytes())
+        .and_then(|_| out.flush())
+        .map_err(SqliteGraphError::connection)
+}
+
+/// GraphML key id and type for each attribute name, ids numbered `d{first}..`.
+fn graphml_keys<'a, I>(records: I, first: usize) -> BTreeMap<String, (String, ValueType)>
+where
+    I: Iterator<Item = &'a BTreeMap<String, Value>>,
+{
+    let mut types: BTreeMap<String, ValueType> = BTreeMap::new();
+    for attrs in records {
+        for (name, value) in attrs {
+            let observed = ValueType::of_json(value);
+            types
+                .entry(name.clone())
+                .and_modify(|current| *current = current.widen(observed))
+                .or_insert(observed);
+        }
+    }
+    types
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, ty))| (name, (format!("d{}", first + index), ty)))
+        .collect()
+}
+
+fn push_graphml_data(
+    doc: &mut String,
+    attrs: &BTreeMap<String, Value>,
+    keys: &BTreeMap<String, (String, ValueType)>,
+) {
+    for (name, value) in attrs {
+        let text = match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        doc.push_str(&format!(
+            "      <data key=\"{}\">{}</data>\n",
+            keys[name].0,
+            xml_escape(&text)
+        ));
+    }
+}
+
+/// Collect the scalars of `value` into `out`, naming nested fields `prefix.key`.
+fn flatten_scalars(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, nested) in map {
+                flatten_scalars(&format!("{prefix}.{key}"), nested, out);
+            }
+        }
+        Value::Null | Value::Array(_) => {}
+        scalar => {
+            out.insert(prefix.to_string(), scalar.clone());
+        }
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+/// Ids of the edges whose endpoints both exist and are not soft-deleted, so an
+/// export never references a node it does not declare.
+fn live_edge_ids(graph: &SqliteGraph) -> Result<Vec<i64>, SqliteGraphError> {
     let conn = graph.connection();
     let mut stmt = conn
-        .prepare_cached("SELECT id FROM graph_edges ORDER BY id")
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .prepare_cached(
+            "SELECT id FROM graph_edges g \
+             WHERE EXISTS (SELECT 1 FROM graph_entities e WHERE e.id=g.from_id) \
+             AND EXISTS (SELECT 1 FROM graph_entities e WHERE e.id=g.to_id) \
+             AND NOT EXISTS (SELECT 1 FROM graph_tombstones t \
+                             WHERE t.entity_id IN (g.from_id, g.to_id)) \
+             ORDER BY id",
+        )
+        .map_err(SqliteGraphError::query)?;
     let rows = stmt
         .query_map([], |row| row.get::<_, i64>(0))
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(SqliteGraphError::query)
+}
+
+/// Column type of a property in the Neo4j import header or a GraphML key declaration.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ValueType {
+    Long,
+    Double,
+    Boolean,
+    String,
+}
+
+impl ValueType {
+    fn infer(value: &str) -> Self {
+        if value.parse::<i64>().is_ok() {
+            ValueType::Long
+        } else if value.parse::<f64>().is_ok() {
+            ValueType::Double
+        } else if value == "true" || value == "false" {
+            ValueType::Boolean
+        } else {
+            ValueType::String
+        }
+    }
+
+    fn of_json(value: &Value) -> Self {
+        match value {
+            Value::Number(n) if n.is_i64() || n.is_u64() => ValueType::Long,
+            Value::Number(_) => ValueType::Double,
+            Value::Bool(_) => ValueType::Boolean,
+            _ => ValueType::String,
+        }
+    }
+
+    /// Narrowest type able to represent values of both `self` and `other`.
+    fn widen(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (ValueType::Long, ValueType::Double) | (ValueType::Double, ValueType::Long) => {
+                ValueType::Double
+            }
+            _ => ValueType::String,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            ValueType::Long => "long",
+            ValueType::Double => "double",
+            ValueType::Boolean => "boolean",
+            ValueType::String => "string",
+        }
+    }
+}
+
+/// Visit `(entity_id, key, value)` rows of a three-column metadata query.
+fn collect_pairs<F>(graph: &SqliteGraph, sql: &str, mut visit: F) -> Result<(), SqliteGraphError>
+where
+    F: FnMut(i64, String, Option<String>),
+{
+    let conn = graph.connection();
+    let mut stmt = conn.prepare_cached(sql).map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })
+        .map_err(SqliteGraphError::query)?;
+    for row in rows {
+        let (entity_id, key, value) = row.map_err(SqliteGraphError::query)?;
+        visit(entity_id, key, value);
+    }
+    Ok(())
+}
+
+fn create_output(path: &Path) -> Result<BufWriter<File>, SqliteGraphError> {
+    let file = File::create(path).map_err(SqliteGraphError::connection)?;
+    Ok(BufWriter::new(file))
+}
+
+fn write_csv_row<W: Write, S: AsRef<str>>(
+    writer: &mut W,
+    fields: &[S],
+) -> Result<(), SqliteGraphError> {
+    let mut line = String::new();
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            line.push(',');
+        }
+        let field = field.as_ref();
+        if field.contains([',', '"', '\n', '\r']) {
+            line.push('"');
+            line.push_str(&field.replace('"', "\"\""));
+            line.push('"');
+        } else {
+            line.push_str(field);
+        }
+    }
+    line.push('\n');
+    writer
+        .write_all(line.as_bytes())
+        .map_err(SqliteGraphError::connection)
+}
+
+fn dump_entities<W: Write>(
+    graph: &SqliteGraph,
+    writer: &mut W,
+    since_id: i64,
+) -> Result<(), SqliteGraphError> {
+    for id in graph.list_entity_ids()? {
+        if id <= since_id {
+            continue;
+        }
+        let entity = graph.get_entity(id)?;
+        write_record(
+            writer,
+            &DumpRecord::Entity {
+                id: entity.id,
+                kind: entity.kind,
+                name: entity.name,
+                file_path: entity.file_path,
+                data: entity.data,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn dump_edges<W: Write>(
+    graph: &SqliteGraph,
+    writer: &mut W,
+    since_id: i64,
+) -> Result<(), SqliteGraphError> {
+    let conn = graph.connection();
+    let mut stmt = conn
+        .prepare_cached("SELECT id FROM graph_edges WHERE id > ?1 ORDER BY id")
+        .map_err(SqliteGraphError::query)?;
+    let rows = stmt
+        .query_map([since_id], |row| row.get::<_, i64>(0))
+        .map_err(SqliteGraphError::query)?;
     for row in rows {
-        let id = row.map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        let id = row.map_err(SqliteGraphError::query)?;
         let edge = graph.get_edge(id)?;
         write_record(
             writer,
@@ -211,57 +1098,80 @@ fn dump_edges<W: Write>(graph: &SqliteGraph, writer: &mut W) -> Result<(), Sqlit
     Ok(())
 }
 
-fn dump_labels<W: Write>(graph: &SqliteGraph, writer: &mut W) -> Result<(), SqliteGraphError> {
+fn dump_labels<W: Write>(
+    graph: &SqliteGraph,
+    writer: &mut W,
+    since_id: i64,
+) -> Result<(), SqliteGraphError> {
     let conn = graph.connection();
     let mut stmt = conn
-        .prepare_cached("SELECT entity_id, label FROM graph_labels ORDER BY entity_id, label")
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .prepare_cached(
+            "SELECT entity_id, label FROM graph_labels WHERE entity_id > ?1 \
+             ORDER BY entity_id, label",
+        )
+        .map_err(SqliteGraphError::query)?;
     let rows = stmt
-        .query_map([], |row| {
+        .query_map([since_id], |row| {
             Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
         })
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     for row in rows {
-        let (entity_id, label) = row.map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        let (entity_id, label) = row.map_err(SqliteGraphError::query)?;
         write_record(writer, &DumpRecord::Label { entity_id, label })?;
     }
     Ok(())
 }
 
-fn dump_properties<W: Write>(graph: &SqliteGraph, writer: &mut W) -> Result<(), SqliteGraphError> {
+fn dump_properties<W: Write>(
+    graph: &SqliteGraph,
+    writer: &mut W,
+    since_id: i64,
+) -> Result<(), SqliteGraphError> {
     let conn = graph.connection();
     let mut stmt = conn
-        .prepare_cached(
-            "SELECT entity_id, key, value FROM graph_properties ORDER BY entity_id, key, value",
-        )
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .prepare_cached(if schema::has_property_types(&graph.conn)? {
+            "SELECT entity_id, key, value, value_type FROM graph_properties \
+             WHERE entity_id > ?1 ORDER BY entity_id, key, value"
+        } else {
+            "SELECT entity_id, key, value, 'string' FROM graph_properties \
+             WHERE entity_id > ?1 ORDER BY entity_id, key, value"
+        })
+        .map_err(SqliteGraphError::query)?;
     let rows = stmt
-        .query_map([], |row| {
+        .query_map([since_id], |row| {
             Ok((
                 row.get::<_, i64>(0)?,
                 row.get::<_, String>(1)?,
                 row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
             ))
         })
-        .map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        .map_err(SqliteGraphError::query)?;
     for row in rows {
-        let (entity_id, key, value) = row.map_err(|e| SqliteGraphError::query(e.to_string()))?;
+        let (entity_id, key, value, value_type) = row.map_err(SqliteGraphError::query)?;
         write_record(
             writer,
             &DumpRecord::Property {
                 entity_id,
                 key,
                 value,
+                value_type: (value_type != "string").then_some(value_type),
             },
         )?;
     }
     Ok(())
 }
 
+fn write_jsonl<W: Write>(writer: &mut W, record: &JsonlRecord) -> Result<(), SqliteGraphError> {
+    serde_json::to_writer(&mut *writer, record).map_err(SqliteGraphError::invalid_input)?;
+    writer
+        .write_all(b"\n")
+        .map_err(SqliteGraphError::invalid_input)
+}
+
 fn write_record<W: Write>(writer: &mut W, record: &DumpRecord) -> Result<(), SqliteGraphError> {
-    serde_json::to_writer(&mut *writer, record)
-        .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))?;
+    serde_json::to_writer(&mut *writer, record).map_err(SqliteGraphError::invalid_input)?;
     writer
         .write_all(b"\n")
-        .map_err(|e| SqliteGraphError::invalid_input(e.to_string()))
+        .map_err(SqliteGraphError::invalid_input)
 }
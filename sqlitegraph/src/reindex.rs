@@ -0,0 +1,322 @@
+//! Rebuilding the secondary indexes of a [`SqliteGraph`].
+//!
+//! Each stage covers one table: its indexes are dropped and recreated inside a
+//! single transaction, so a failure leaves the old indexes in place, then the
+//! table is read back in batches and `ANALYZE` refreshes the planner
+//! statistics. Progress is reported per batch, so it tracks rows actually read.
+//! An optional validation stage checks with `EXPLAIN QUERY PLAN` that lookups
+//! on the indexed columns use the rebuilt indexes.
+//!
+//! A run can be stopped through [`ReindexConfig::cancel`]. The flag is checked
+//! between batches; the stage in progress is rolled back, so its table keeps
+//! its previous indexes, while stages that already finished stay committed.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use rusqlite::{params, types::Null};
+use serde::Serialize;
+
+use crate::{
+    SqliteGraphError,
+    graph::SqliteGraph,
+    schema::{GRAPH_INDEXES, IndexDef},
+};
+
+/// Phase of a reindex run reported through [`ReindexProgress`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum ReindexStage {
+    Entities,
+    Edges,
+    Labels,
+    Properties,
+    Validation,
+}
+
+impl ReindexStage {
+    fn table(self) -> Option<&'static str> {
+        match self {
+            Self::Entities => Some("graph_entities"),
+            Self::Edges => Some("graph_edges"),
+            Self::Labels => Some("graph_labels"),
+            Self::Properties => Some("graph_properties"),
+            Self::Validation => None,
+        }
+    }
+}
+
+/// Progress of the current stage, passed to [`ReindexConfig::progress_callback`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReindexProgress {
+    pub stage: ReindexStage,
+    /// Rows read, or indexes checked during validation, so far in this stage
+    pub current: u64,
+    /// Rows, or indexes, the stage will go through
+    pub total: u64,
+    /// Time since the run started
+    pub elapsed: Duration,
+    /// Time left in this stage, extrapolated from its throughput so far
+    pub estimated_remaining: Option<Duration>,
+}
+
+impl ReindexProgress {
+    /// Share of the stage completed, from 0 to 100.
+    pub fn progress_percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.current as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+/// Which stages a reindex runs and how it reports progress.
+pub struct ReindexConfig {
+    /// Rebuild the entity and edge indexes
+    pub syncore: bool,
+    /// Rebuild the label and property indexes and drop cached query results
+    pub sync_graph: bool,
+    /// Check the rebuilt indexes with `EXPLAIN QUERY PLAN`
+    pub validate: bool,
+    /// Rows read per batch, and so how often progress is reported
+    pub batch_size: usize,
+    /// Called after every batch
+    pub progress_callback: Option<Box<dyn Fn(ReindexProgress) + Send + Sync>>,
+    /// Set to stop the run with [`SqliteGraphError::Cancelled`] before the
+    /// next batch
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Default for ReindexConfig {
+    fn default() -> Self {
+        Self {
+            syncore: true,
+            sync_graph: true,
+            validate: true,
+            batch_size: 1000,
+            progress_callback: None,
+            cancel: None,
+        }
+    }
+}
+
+/// Outcome of [`GraphReindexer::run`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ReindexResult {
+    /// Whether validation, if it ran, found no problem
+    pub success: bool,
+    pub total_duration: Duration,
+    pub entities_processed: u64,
+    pub edges_processed: u64,
+    pub labels_processed: u64,
+    pub properties_processed: u64,
+    /// Names of the recreated indexes, in rebuild order
+    pub indexes_rebuilt: Vec<String>,
+    /// One message per index that a lookup on its columns does not use
+    pub validation_errors: Vec<String>,
+}
+
+/// Runs the stages selected by a [`ReindexConfig`] against one graph.
+pub struct GraphReindexer<'a> {
+    graph: &'a SqliteGraph,
+    config: ReindexConfig,
+    started: Instant,
+}
+
+impl<'a> GraphReindexer<'a> {
+    pub fn new(graph: &'a SqliteGraph, config: ReindexConfig) -> Self {
+        Self {
+            graph,
+            config,
+            started: Instant::now(),
+        }
+    }
+
+    /// Run every selected stage in order: entities, edges, labels, properties,
+    /// then validation.
+    pub fn run(mut self) -> Result<ReindexResult, SqliteGraphError> {
+        self.graph.ensure_writable()?;
+        self.started = Instant::now();
+        let mut result = ReindexResult::default();
+        let mut rebuilt = Vec::new();
+        if self.config.syncore {
+            result.entities_processed = self.reindex_table(ReindexStage::Entities, &mut rebuilt)?;
+            result.edges_processed = self.reindex_table(ReindexStage::Edges, &mut rebuilt)?;
+        }
+        if self.config.sync_graph {
+            result.labels_processed = self.reindex_table(ReindexStage::Labels, &mut rebuilt)?;
+            result.properties_processed =
+                self.reindex_table(ReindexStage::Properties, &mut rebuilt)?;
+            self.graph.invalidate_caches();
+        }
+        if self.config.validate {
+            result.validation_errors = self.validate(&rebuilt)?;
+        }
+        result.success = result.validation_errors.is_empty();
+        result.indexes_rebuilt = rebuilt.iter().map(|index| index.name.to_string()).collect();
+        result.total_duration = self.started.elapsed();
+        Ok(result)
+    }
+
+    /// Recreate the indexes of the stage's table, read the table back and
+    /// analyze it, all in one transaction. Returns the number of rows read.
+    fn reindex_table(
+        &self,
+        stage: ReindexStage,
+        rebuilt: &mut Vec<&'static IndexDef>,
+    ) -> Result<u64, SqliteGraphError> {
+        let Some(table) = stage.table() else {
+            return Ok(0);
+        };
+        let indexes: Vec<&'static IndexDef> = GRAPH_INDEXES
+            .iter()
+            .filter(|index| index.table == table)
+            .collect();
+        let processed = self
+            .graph
+            .with_transaction(|| self.rebuild_and_scan(stage, table, &indexes))?;
+        rebuilt.extend(&indexes);
+        Ok(processed)
+    }
+
+    fn rebuild_and_scan(
+        &self,
+        stage: ReindexStage,
+        table: &str,
+        indexes: &[&'static IndexDef],
+    ) -> Result<u64, SqliteGraphError> {
+        let conn = self.graph.connection();
+        for index in indexes {
+            conn.execute(&format!("DROP INDEX IF EXISTS {}", index.name), [])
+                .map_err(SqliteGraphError::query)?;
+            conn.execute(&index.create_sql(false), [])
+                .map_err(SqliteGraphError::query)?;
+        }
+
+        let total: u64 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .map_err(SqliteGraphError::query)?;
+        let stage_started = Instant::now();
+        let mut stmt = conn
+            .prepare_cached(&format!(
+                "SELECT rowid FROM {table} WHERE rowid > ?1 ORDER BY rowid LIMIT ?2"
+            ))
+            .map_err(SqliteGraphError::query)?;
+        let batch_size = self.config.batch_size.max(1) as i64;
+        let (mut processed, mut last_rowid) = (0u64, i64::MIN);
+        loop {
+            self.check_cancelled(stage)?;
+            let rowids = stmt
+                .query_map(params![last_rowid, batch_size], |row| row.get::<_, i64>(0))
+                .map_err(SqliteGraphError::query)?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(SqliteGraphError::query)?;
+            let Some(&last) = rowids.last() else {
+                break;
+            };
+            last_rowid = last;
+            processed += rowids.len() as u64;
+            self.report(stage, processed, total.max(processed), stage_started);
+        }
+        if processed == 0 {
+            self.report(stage, 0, 0, stage_started);
+        }
+
+        conn.execute(&format!("ANALYZE {table}"), [])
+            .map_err(SqliteGraphError::query)?;
+        Ok(processed)
+    }
+
+    /// Check that an equality lookup on the leading column of each index is
+    /// planned through one of its table's indexes.
+    fn validate(&self, rebuilt: &[&'static IndexDef]) -> Result<Vec<String>, SqliteGraphError> {
+        let conn = self.graph.connection();
+        let stage_started = Instant::now();
+        let mut errors = Vec::new();
+        for (checked, index) in rebuilt.iter().enumerate() {
+            self.check_cancelled(ReindexStage::Validation)?;
+            let lead = index.columns.split(',').next().unwrap_or_default().trim();
+            let mut stmt = conn
+                .prepare_cached(&format!(
+                    "EXPLAIN QUERY PLAN SELECT {} FROM {} WHERE {lead} = ?1",
+                    index.columns, index.table
+                ))
+                .map_err(SqliteGraphError::query)?;
+            let plan = stmt
+                .query_map([Null], |row| row.get::<_, String>(3))
+                .map_err(SqliteGraphError::query)?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(SqliteGraphError::query)?
+                .join("; ");
+            let uses_index = plan
+                .split(|c: char| c.is_whitespace() || c == ';')
+                .any(|word| {
+                    GRAPH_INDEXES
+                        .iter()
+                        .any(|candidate| candidate.table == index.table && candidate.name == word)
+                });
+            if !uses_index {
+                errors.push(format!(
+                    "lookup on {}({lead}) does not use an index: {plan}",
+                    index.table
+                ));
+            }
+            self.report(
+                ReindexStage::Validation,
+                checked as u64 + 1,
+                rebuilt.len() as u64,
+                stage_started,
+            );
+        }
+        Ok(errors)
+    }
+
+    fn check_cancelled(&self, stage: ReindexStage) -> Result<(), SqliteGraphError> {
+        match &self.config.cancel {
+            Some(flag) if flag.load(Ordering::Relaxed) => Err(SqliteGraphError::cancelled(
+                format!("reindex stopped during the {stage:?} stage"),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn report(&self, stage: ReindexStage, current: u64, total: u64, stage_started: Instant) {
+        let Some(callback) = &self.config.progress_callback else {
+            return;
+        };
+        let estimated_remaining = (current > 0).then(|| {
+            stage_started
+                .elapsed()
+                .mul_f64(total.saturating_sub(current) as f64 / current as f64)
+        });
+        callback(ReindexProgress {
+            stage,
+            current,
+            total,
+            elapsed: self.started.elapsed(),
+            estimated_remaining,
+        });
+    }
+}
+
+impl SqliteGraph {
+    /// Rebuild every index and validate the result; see [`crate::reindex`].
+    pub fn reindex(&self) -> Result<ReindexResult, SqliteGraphError> {
+        self.reindex_with_config(ReindexConfig::default())
+    }
+
+    /// Run the reindex stages selected by `config`.
+    pub fn reindex_with_config(
+        &self,
+        config: ReindexConfig,
+    ) -> Result<ReindexResult, SqliteGraphError> {
+        GraphReindexer::new(self, config).run()
+    }
+}
@@ -0,0 +1,148 @@
+//! After-the-fact structural audits that work against any [`GraphBackend`].
+//!
+//! Insert-time guards such as [`GraphConfig::reject_self_loops`](crate::GraphConfig::reject_self_loops)
+//! only cover edges written while they are enabled. The checks here scan what
+//! is already stored and report offenders instead of failing.
+//! [`GraphBackend::validate_integrity`] runs the dangling-edge audit natively
+//! on each backend.
+
+use crate::{SqliteGraphError, backend::GraphBackend, graph::DanglingEdge};
+
+/// Result of a safety audit; each audit fills in only its own field.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SafetyReport {
+    /// Ids of edges whose source and target are the same node, in ascending order
+    pub self_loop_edges: Vec<i64>,
+    /// Edges with a missing or deleted endpoint, ordered by edge id
+    pub dangling_edges: Vec<DanglingEdge>,
+}
+
+impl SafetyReport {
+    /// Whether no violations were found.
+    pub fn is_clean(&self) -> bool {
+        self.self_loop_edges.is_empty() && self.dangling_edges.is_empty()
+    }
+}
+
+/// Report every edge whose `from_id` equals its `to_id`.
+pub fn validate_no_self_loops<B>(graph: &B) -> Result<SafetyReport, SqliteGraphError>
+where
+    B: GraphBackend + ?Sized,
+{
+    let mut report = SafetyReport::default();
+    let mut edge_ids = graph.list_edge_ids()?;
+    edge_ids.sort_unstable();
+    for id in edge_ids {
+        let edge = graph.get_edge(id)?;
+        if edge.from_id == edge.to_id {
+            report.self_loop_edges.push(id);
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::native::{GraphFile, NodeStore};
+    use crate::backend::{EdgeSpec, NativeGraphBackend, NodeSpec, SqliteGraphBackend};
+
+    fn populate(graph: &dyn GraphBackend) -> Vec<i64> {
+        let nodes: Vec<i64> = (0..3)
+            .map(|i| {
+                graph
+                    .insert_node(NodeSpec {
+                        kind: "Node".to_string(),
+                        name: format!("n{i}"),
+                        file_path: None,
+                        data: serde_json::json!({}),
+                    })
+                    .unwrap()
+            })
+            .collect();
+        let mut self_loops = Vec::new();
+        for (from, to) in [(0, 1), (1, 1), (1, 2), (2, 2)] {
+            let id = graph
+                .insert_edge(EdgeSpec {
+                    from: nodes[from],
+                    to: nodes[to],
+                    edge_type: "LINK".to_string(),
+                    data: serde_json::json!({}),
+                })
+                .unwrap();
+            if from == to {
+                self_loops.push(id);
+            }
+        }
+        self_loops
+    }
+
+    #[test]
+    fn test_validate_no_self_loops_lists_offending_edges() {
+        let sqlite = SqliteGraphBackend::in_memory().unwrap();
+        let expected = populate(&sqlite);
+        let report = validate_no_self_loops(&sqlite).unwrap();
+        assert_eq!(report.self_loop_edges, expected);
+        assert!(!report.is_clean());
+
+        let native = NativeGraphBackend::new_temp().unwrap();
+        let expected = populate(&native);
+        assert_eq!(
+            validate_no_self_loops(&native).unwrap().self_loop_edges,
+            expected
+        );
+
+        let empty = SqliteGraphBackend::in_memory().unwrap();
+        assert!(validate_no_self_loops(&empty).unwrap().is_clean());
+    }
+
+    #[test]
+    fn test_validate_integrity_reports_dangling_edges() {
+        let sqlite = SqliteGraphBackend::in_memory().unwrap();
+        populate(&sqlite);
+        assert!(sqlite.validate_integrity().unwrap().is_clean());
+        // Drop node 3 behind the graph's back, plus a tombstone for node 1
+        let conn = sqlite.graph().connection();
+        for sql in [
+            "PRAGMA foreign_keys=OFF",
+            "DELETE FROM graph_entities WHERE id=3",
+            "INSERT INTO graph_tombstones(entity_id) VALUES(1)",
+        ] {
+            conn.execute(sql, []).unwrap();
+        }
+        let dangling: Vec<_> = sqlite
+            .validate_integrity()
+            .unwrap()
+            .dangling_edges
+            .iter()
+            .map(|edge| (edge.edge_id, edge.missing_from, edge.missing_to))
+            .collect();
+        assert_eq!(
+            dangling,
+            vec![(1, true, false), (3, false, true), (4, true, true)]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.native");
+        let native = NativeGraphBackend::new(&path).unwrap();
+        populate(&native);
+        assert!(native.validate_integrity().unwrap().is_clean());
+        drop(native);
+        // Mark node 3 deleted without touching its edges
+        let mut file = GraphFile::open(&path).unwrap();
+        NodeStore::new(&mut file).mark_deleted(3).unwrap();
+        file.persist_header().unwrap();
+        drop(file);
+        let report = NativeGraphBackend::open(&path)
+            .unwrap()
+            .validate_integrity()
+            .unwrap();
+        let dangling: Vec<_> = report
+            .dangling_edges
+            .iter()
+            .map(|edge| (edge.edge_id, edge.missing_from, edge.missing_to))
+            .collect();
+        assert_eq!(dangling, vec![(3, false, true), (4, true, true)]);
+        assert!(report.self_loop_edges.is_empty());
+    }
+}
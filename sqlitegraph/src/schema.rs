@@ -6,16 +6,44 @@ pub const BASE_SCHEMA_VERSION: i64 = 1;
 
 struct MigrationStep {
     target_version: i64,
+    /// Columns added before `statements`, skipped where they already exist
+    columns: &'static [AddedColumn],
     statements: &'static [&'static str],
 }
 
-const MIGRATION_STEPS: &[MigrationStep] = &[MigrationStep {
-    target_version: 2,
-    statements: &[
-        "CREATE TABLE IF NOT EXISTS graph_meta_history(version INTEGER NOT NULL, applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP)",
-        "INSERT INTO graph_meta_history(version) VALUES(2)",
-    ],
-}];
+struct AddedColumn {
+    table: &'static str,
+    column: &'static str,
+    sql: &'static str,
+}
+
+const MIGRATION_STEPS: &[MigrationStep] = &[
+    MigrationStep {
+        target_version: 2,
+        columns: &[],
+        statements: &[
+            "CREATE TABLE IF NOT EXISTS graph_meta_history(version INTEGER NOT NULL, applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+            "INSERT INTO graph_meta_history(version) VALUES(2)",
+        ],
+    },
+    MigrationStep {
+        target_version: 3,
+        columns: &[],
+        statements: &[
+            "CREATE TABLE IF NOT EXISTS graph_settings(key TEXT PRIMARY KEY, value TEXT NOT NULL)",
+            "INSERT INTO graph_meta_history(version) VALUES(3)",
+        ],
+    },
+    MigrationStep {
+        target_version: 4,
+        columns: &[AddedColumn {
+            table: "graph_properties",
+            column: "value_type",
+            sql: "ALTER TABLE graph_properties ADD COLUMN value_type TEXT NOT NULL DEFAULT 'string'",
+        }],
+        statements: &["INSERT INTO graph_meta_history(version) VALUES(4)"],
+    },
+];
 
 pub const SCHEMA_VERSION: i64 = BASE_SCHEMA_VERSION + MIGRATION_STEPS.len() as i64;
 
@@ -62,35 +90,130 @@ fn ensure_base_schema(conn: &Connection) -> Result<(), SqliteGraphError> {
             entity_id INTEGER NOT NULL,
             label     TEXT NOT NULL
         );
+        CREATE TABLE IF NOT EXISTS graph_tombstones (
+            entity_id  INTEGER PRIMARY KEY,
+            deleted_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        );
         CREATE TABLE IF NOT EXISTS graph_properties (
             entity_id INTEGER NOT NULL,
-            key       TEXT NOT NULL,
+            key        TEXT NOT NULL,
             value     TEXT NOT NULL
         );
-        CREATE INDEX IF NOT EXISTS idx_edges_from ON graph_edges(from_id);
-        CREATE INDEX IF NOT EXISTS idx_edges_to ON graph_edges(to_id);
-        CREATE INDEX IF NOT EXISTS idx_edges_type ON graph_edges(edge_type);
-        CREATE INDEX IF NOT EXISTS idx_labels_label ON graph_labels(label);
-        CREATE INDEX IF NOT EXISTS idx_labels_label_entity_id ON graph_labels(label, entity_id);
-        CREATE INDEX IF NOT EXISTS idx_props_key_value ON graph_properties(key, value);
-        CREATE INDEX IF NOT EXISTS idx_props_key_value_entity_id ON graph_properties(key, value, entity_id);
-        CREATE INDEX IF NOT EXISTS idx_entities_kind_id ON graph_entities(kind, id);
         CREATE TABLE IF NOT EXISTS graph_meta (
             id INTEGER PRIMARY KEY CHECK (id = 1),
             schema_version INTEGER NOT NULL
         );
         "#,
     )
-    .map_err(|e| SqliteGraphError::schema(e.to_string()))
+    .map_err(SqliteGraphError::schema)?;
+    for index in GRAPH_INDEXES {
+        conn.execute_batch(&index.create_sql(true))
+            .map_err(SqliteGraphError::schema)?;
+    }
+    Ok(())
+}
+
+/// A secondary index of the base schema.
+pub(crate) struct IndexDef {
+    pub name: &'static str,
+    pub table: &'static str,
+    pub columns: &'static str,
+}
+
+impl IndexDef {
+    pub fn create_sql(&self, if_not_exists: bool) -> String {
+        let guard = if if_not_exists { "IF NOT EXISTS " } else { "" };
+        format!(
+            "CREATE INDEX {guard}{} ON {}({})",
+            self.name, self.table, self.columns
+        )
+    }
 }
 
+/// Every secondary index created by [`ensure_schema`], in creation order.
+pub(crate) const GRAPH_INDEXES: &[IndexDef] = &[
+    IndexDef {
+        name: "idx_edges_from",
+        table: "graph_edges",
+        columns: "from_id",
+    },
+    IndexDef {
+        name: "idx_edges_to",
+        table: "graph_edges",
+        columns: "to_id",
+    },
+    IndexDef {
+        name: "idx_edges_type",
+        table: "graph_edges",
+        columns: "edge_type",
+    },
+    IndexDef {
+        name: "idx_labels_label",
+        table: "graph_labels",
+        columns: "label",
+    },
+    IndexDef {
+        name: "idx_labels_label_entity_id",
+        table: "graph_labels",
+        columns: "label, entity_id",
+    },
+    IndexDef {
+        name: "idx_props_key_value",
+        table: "graph_properties",
+        columns: "key, value",
+    },
+    IndexDef {
+        name: "idx_props_key_value_entity_id",
+        table: "graph_properties",
+        columns: "key, value, entity_id",
+    },
+    IndexDef {
+        name: "idx_entities_kind_id",
+        table: "graph_entities",
+        columns: "kind, id",
+    },
+];
+
 pub fn read_schema_version(conn: &Connection) -> Result<i64, SqliteGraphError> {
     conn.query_row(
         "SELECT schema_version FROM graph_meta WHERE id=1",
         [],
         |row| row.get(0),
     )
-    .map_err(|e| SqliteGraphError::schema(e.to_string()))
+    .map_err(SqliteGraphError::schema)
+}
+
+/// Target versions of the migrations a database at `version` still needs,
+/// in the order they run.
+pub fn pending_migrations(version: i64) -> Vec<i64> {
+    MIGRATION_STEPS
+        .iter()
+        .map(|step| step.target_version)
+        .filter(|&target| target > version)
+        .collect()
+}
+
+/// Check that this build can use the database as it is, without migrating.
+///
+/// Fails with [`SqliteGraphError::SchemaError`] when the schema is newer than
+/// [`SCHEMA_VERSION`], or older with migrations pending; the message then
+/// lists the versions those migrations lead to.
+pub fn check_compatibility(conn: &Connection) -> Result<(), SqliteGraphError> {
+    let version = read_schema_version(conn)?;
+    if version > SCHEMA_VERSION {
+        return Err(SqliteGraphError::schema(format!(
+            "database schema version {version} is newer than supported {SCHEMA_VERSION}"
+        )));
+    }
+    let pending = pending_migrations(version);
+    if !pending.is_empty() {
+        let targets: Vec<String> = pending.iter().map(i64::to_string).collect();
+        return Err(SqliteGraphError::schema(format!(
+            "database schema version {version} needs migrations to version {}",
+            targets.join(", ")
+        )));
+    }
+    Ok(())
 }
 
 pub fn run_pending_migrations(
@@ -98,13 +221,16 @@ pub fn run_pending_migrations(
     dry_run: bool,
 ) -> Result<MigrationReport, SqliteGraphError> {
     let current = read_schema_version(conn)?;
+    let pending: Vec<&MigrationStep> = MIGRATION_STEPS
+        .iter()
+        .filter(|step| step.target_version > current)
+        .collect();
     let mut statements: Vec<&'static str> = Vec::new();
     let mut target = current;
-    for step in MIGRATION_STEPS {
-        if step.target_version > current {
-            target = step.target_version;
-            statements.extend_from_slice(step.statements);
-        }
+    for step in &pending {
+        target = step.target_version;
+        statements.extend(step.columns.iter().map(|column| column.sql));
+        statements.extend_from_slice(step.statements);
     }
     if statements.is_empty() {
         return Ok(MigrationReport {
@@ -123,23 +249,30 @@ pub fn run_pending_migrations(
         });
     }
     conn.execute("BEGIN IMMEDIATE", [])
-        .map_err(|e| SqliteGraphError::schema(e.to_string()))?;
+        .map_err(SqliteGraphError::schema)?;
     let result: Result<(), SqliteGraphError> = (|| {
-        for sql in statements.iter().copied() {
-            conn.execute(sql, [])
-                .map_err(|e| SqliteGraphError::schema(e.to_string()))?;
+        for step in &pending {
+            for column in step.columns {
+                if !column_exists(conn, column.table, column.column)? {
+                    conn.execute(column.sql, [])
+                        .map_err(SqliteGraphError::schema)?;
+                }
+            }
+            for sql in step.statements.iter().copied() {
+                conn.execute(sql, []).map_err(SqliteGraphError::schema)?;
+            }
         }
         conn.execute(
             "UPDATE graph_meta SET schema_version=?1 WHERE id=1",
             [target],
         )
-        .map_err(|e| SqliteGraphError::schema(e.to_string()))?;
+        .map_err(SqliteGraphError::schema)?;
         Ok(())
     })();
     match result {
         Ok(()) => {
             conn.execute("COMMIT", [])
-                .map_err(|e| SqliteGraphError::schema(e.to_string()))?;
+                .map_err(SqliteGraphError::schema)?;
         }
         Err(err) => {
             let _ = conn.execute("ROLLBACK", []);
@@ -154,6 +287,30 @@ pub fn run_pending_migrations(
     })
 }
 
+/// Whether `graph_properties` has the `value_type` column added by schema
+/// version 4; databases opened without migrations can still lack it.
+pub(crate) fn has_property_types(conn: &Connection) -> Result<bool, SqliteGraphError> {
+    column_exists(conn, "graph_properties", "value_type")
+}
+
+fn has_settings(conn: &Connection) -> Result<bool, SqliteGraphError> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name='graph_settings')",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(SqliteGraphError::schema)
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool, SqliteGraphError> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?1) WHERE name=?2)",
+        [table, column],
+        |row| row.get(0),
+    )
+    .map_err(SqliteGraphError::schema)
+}
+
 fn ensure_meta(conn: &Connection) -> Result<(), SqliteGraphError> {
     let version: Option<i64> = conn
         .query_row(
@@ -162,7 +319,7 @@ fn ensure_meta(conn: &Connection) -> Result<(), SqliteGraphError> {
             |row| row.get(0),
         )
         .optional()
-        .map_err(|e| SqliteGraphError::schema(e.to_string()))?;
+        .map_err(SqliteGraphError::schema)?;
     match version {
         Some(existing) => {
             if existing > SCHEMA_VERSION {
@@ -175,7 +332,7 @@ fn ensure_meta(conn: &Connection) -> Result<(), SqliteGraphError> {
                     "UPDATE graph_meta SET schema_version=?1 WHERE id=1",
                     [BASE_SCHEMA_VERSION],
                 )
-                .map_err(|e| SqliteGraphError::schema(e.to_string()))?;
+                .map_err(SqliteGraphError::schema)?;
             }
         }
         None => {
@@ -183,8 +340,41 @@ fn ensure_meta(conn: &Connection) -> Result<(), SqliteGraphError> {
                 "INSERT INTO graph_meta(id, schema_version) VALUES(1, ?1)",
                 [BASE_SCHEMA_VERSION],
             )
-            .map_err(|e| SqliteGraphError::schema(e.to_string()))?;
+            .map_err(SqliteGraphError::schema)?;
         }
     }
     Ok(())
 }
+
+/// Read a persisted graph setting; `None` when unset or when the settings table
+/// has not been created yet (databases opened without migrations).
+pub fn read_setting(conn: &Connection, key: &str) -> Result<Option<String>, SqliteGraphError> {
+    if !has_settings(conn)? {
+        return Ok(None);
+    }
+    conn.query_row(
+        "SELECT value FROM graph_settings WHERE key=?1",
+        [key],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(SqliteGraphError::schema)
+}
+
+/// Persist a graph setting, replacing any previous value.
+///
+/// Fails with [`SqliteGraphError::SchemaError`] when the settings table has not
+/// been created yet.
+pub fn write_setting(conn: &Connection, key: &str, value: &str) -> Result<(), SqliteGraphError> {
+    if !has_settings(conn)? {
+        return Err(SqliteGraphError::schema(
+            "graph settings need schema version 3; open the graph with migrations",
+        ));
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO graph_settings(key, value) VALUES(?1, ?2)",
+        [key, value],
+    )
+    .map_err(SqliteGraphError::schema)?;
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use serde_json::json;
 use sqlitegraph::{
-    GraphEdge, GraphEntity, SqliteGraph,
-    algo::{connected_components, find_cycles_limited, nodes_by_degree},
+    GraphEdge, GraphEntity, SqliteGraph, SqliteGraphError,
+    algo::{
+        connected_components, find_cycles_limited, nodes_by_degree, pagerank, topological_sort,
+    },
 };
 
 fn insert_entity(graph: &SqliteGraph, name: &str) -> i64 {
@@ -47,6 +49,30 @@ fn test_connected_components_returns_sorted_groups() {
     assert_eq!(components[1], vec![d, e]);
 }
 
+#[test]
+fn test_connected_components_keeps_isolated_nodes_as_singletons() {
+    let graph = SqliteGraph::open_in_memory().unwrap();
+    let ids: Vec<i64> = ["A", "B", "C", "D", "E", "F"]
+        .iter()
+        .map(|name| insert_entity(&graph, name))
+        .collect();
+    // Edge directions are ignored and the clusters are inserted out of order.
+    insert_edge(&graph, ids[5], ids[3], "LINK");
+    insert_edge(&graph, ids[2], ids[0], "LINK");
+    insert_edge(&graph, ids[3], ids[4], "LINK");
+    insert_edge(&graph, ids[0], ids[2], "BACK");
+
+    let components = connected_components(&graph).expect("components");
+    assert_eq!(
+        components,
+        vec![
+            vec![ids[0], ids[2]],
+            vec![ids[1]],
+            vec![ids[3], ids[4], ids[5]]
+        ]
+    );
+}
+
 #[test]
 fn test_find_cycles_limited_returns_deterministic_cycle() {
     let graph = SqliteGraph::open_in_memory().unwrap();
@@ -83,3 +109,86 @@ fn test_nodes_by_degree_orders_descending() {
     let ascending = nodes_by_degree(&graph, false).expect("degrees");
     assert_eq!(ascending.last().unwrap().0, a);
 }
+
+#[test]
+fn test_pagerank_matches_hand_computed_scores() {
+    let graph = SqliteGraph::open_in_memory().unwrap();
+    let a = insert_entity(&graph, "A");
+    let b = insert_entity(&graph, "B");
+    let c = insert_entity(&graph, "C");
+    insert_edge(&graph, a, b, "LINK");
+    insert_edge(&graph, a, c, "LINK");
+    insert_edge(&graph, b, c, "LINK");
+    insert_edge(&graph, c, a, "LINK");
+
+    let scores = pagerank(&graph, 0.85, 100).expect("pagerank");
+    for (id, expected) in [(a, 0.387_790), (b, 0.214_811), (c, 0.397_400)] {
+        assert!(
+            (scores[&id] - expected).abs() < 1e-5,
+            "{id}: {}",
+            scores[&id]
+        );
+    }
+    assert!((scores.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert_eq!(pagerank(&graph, 0.85, 100).unwrap(), scores);
+}
+
+#[test]
+fn test_pagerank_spreads_dangling_mass_and_validates_damping() {
+    let graph = SqliteGraph::open_in_memory().unwrap();
+    let a = insert_entity(&graph, "A");
+    let b = insert_entity(&graph, "B");
+    insert_edge(&graph, a, b, "LINK");
+
+    // One step by hand: B is dangling, so its 0.5 is shared by A and B.
+    // A = 0.15/2 + 0.85*0.5/2, B = A + 0.85*0.5.
+    let scores = pagerank(&graph, 0.85, 1).unwrap();
+    assert!((scores[&a] - 0.2875).abs() < 1e-12);
+    assert!((scores[&b] - 0.7125).abs() < 1e-12);
+
+    for damping in [0.0, 1.0, -0.5, f64::NAN] {
+        assert!(matches!(
+            pagerank(&graph, damping, 10),
+            Err(SqliteGraphError::InvalidInput { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_topological_sort_orders_every_edge() {
+    let graph = SqliteGraph::open_in_memory().unwrap();
+    let ids: Vec<i64> = ["lib", "app", "util", "core", "test"]
+        .iter()
+        .map(|name| insert_entity(&graph, name))
+        .collect();
+    // core -> util -> lib -> app, core -> lib, and an unconnected node
+    insert_edge(&graph, ids[3], ids[2], "DEP");
+    insert_edge(&graph, ids[2], ids[0], "DEP");
+    insert_edge(&graph, ids[0], ids[1], "DEP");
+    insert_edge(&graph, ids[3], ids[0], "DEP");
+
+    let order = topological_sort(&graph).expect("order");
+    assert_eq!(order.len(), ids.len());
+    let position = |id: i64| order.iter().position(|&n| n == id).unwrap();
+    for (from, to) in [(3, 2), (2, 0), (0, 1), (3, 0)] {
+        assert!(position(ids[from]) < position(ids[to]));
+    }
+    // Smallest ready id first: core and test start ready, core < test.
+    assert_eq!(order, vec![ids[3], ids[2], ids[0], ids[1], ids[4]]);
+}
+
+#[test]
+fn test_topological_sort_rejects_cycles() {
+    let graph = SqliteGraph::open_in_memory().unwrap();
+    let a = insert_entity(&graph, "A");
+    let b = insert_entity(&graph, "B");
+    let c = insert_entity(&graph, "C");
+    insert_edge(&graph, a, b, "DEP");
+    insert_edge(&graph, b, c, "DEP");
+    insert_edge(&graph, c, b, "DEP");
+
+    assert!(matches!(
+        topological_sort(&graph),
+        Err(SqliteGraphError::CycleDetected(_))
+    ));
+}
@@ -1,6 +1,6 @@
 use serde_json::json;
 use sqlitegraph::{
-    SqliteGraphError,
+    BackendKind, EntityKind, GraphConfig, SqliteGraphError,
     backend::{
         BackendDirection, ChainStep, EdgeSpec, GraphBackend, NeighborQuery, NodeSpec,
         SqliteGraphBackend,
@@ -200,6 +200,11 @@ fn test_backend_multi_hop_and_chain_queries() {
         .expect("k-hop");
     assert_eq!(hops, vec![b, e, c, d]);
 
+    let distances = backend
+        .k_hop_with_distance(a, 2, BackendDirection::Outgoing)
+        .expect("k-hop with distance");
+    assert_eq!(distances, vec![(b, 1), (e, 1), (c, 2), (d, 2)]);
+
     let filtered = backend
         .k_hop_filtered(a, 3, BackendDirection::Outgoing, &["CALLS"])
         .expect("filtered");
@@ -241,6 +246,7 @@ fn test_backend_multi_hop_and_chain_queries() {
                 constraint: Some(NodeConstraint::name_prefix("D")),
             },
         ],
+        ..PatternQuery::default()
     };
     let pattern_matches = backend.pattern_search(a, &pattern).expect("pattern");
     let sequences: Vec<Vec<i64>> = pattern_matches.into_iter().map(|m| m.nodes).collect();
@@ -374,6 +380,7 @@ fn run_trait_suite(api: &impl GraphBackend) {
                 constraint: Some(NodeConstraint::kind("Node")),
             },
         ],
+        ..PatternQuery::default()
     };
     let matches = api.pattern_search(root, &pattern).unwrap();
     assert_eq!(matches.len(), 1);
@@ -397,7 +404,7 @@ fn test_insert_node_invalid_empty_kind() {
     let result = backend.insert_node(invalid_node);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::InvalidInput(_) => {} // Expected
+        SqliteGraphError::InvalidInput { .. } => {} // Expected
         other => panic!("Expected InvalidInput error, got: {:?}", other),
     }
 }
@@ -415,7 +422,7 @@ fn test_insert_node_invalid_empty_name() {
     let result = backend.insert_node(invalid_node);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::InvalidInput(_) => {} // Expected
+        SqliteGraphError::InvalidInput { .. } => {} // Expected
         other => panic!("Expected InvalidInput error, got: {:?}", other),
     }
 }
@@ -484,7 +491,7 @@ fn test_get_node_invalid_negative_id() {
     let result = backend.get_node(-1);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::NotFound(_) => {} // Expected
+        SqliteGraphError::NotFound { .. } => {} // Expected
         other => panic!("Expected NotFound error, got: {:?}", other),
     }
 }
@@ -496,7 +503,7 @@ fn test_get_node_invalid_zero_id() {
     let result = backend.get_node(0);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::NotFound(_) => {} // Expected
+        SqliteGraphError::NotFound { .. } => {} // Expected
         other => panic!("Expected NotFound error, got: {:?}", other),
     }
 }
@@ -508,7 +515,7 @@ fn test_get_node_nonexistent() {
     let result = backend.get_node(99999);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::NotFound(_) => {} // Expected
+        SqliteGraphError::NotFound { .. } => {} // Expected
         other => panic!("Expected NotFound error, got: {:?}", other),
     }
 }
@@ -533,7 +540,7 @@ fn test_insert_edge_invalid_empty_type() {
     let result = backend.insert_edge(invalid_edge);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::InvalidInput(_) => {} // Expected
+        SqliteGraphError::InvalidInput { .. } => {} // Expected
         other => panic!("Expected InvalidInput error, got: {:?}", other),
     }
 }
@@ -553,7 +560,7 @@ fn test_insert_edge_invalid_negative_from() {
     let result = backend.insert_edge(invalid_edge);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::InvalidInput(_) => {} // Expected
+        SqliteGraphError::InvalidInput { .. } => {} // Expected
         other => panic!("Expected InvalidInput error, got: {:?}", other),
     }
 }
@@ -573,7 +580,7 @@ fn test_insert_edge_invalid_negative_to() {
     let result = backend.insert_edge(invalid_edge);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::InvalidInput(_) => {} // Expected
+        SqliteGraphError::InvalidInput { .. } => {} // Expected
         other => panic!("Expected InvalidInput error, got: {:?}", other),
     }
 }
@@ -669,7 +676,7 @@ fn test_bfs_invalid_start_node() {
     let result = backend.bfs(-1, 2);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::NotFound(_) => {} // Expected
+        SqliteGraphError::NotFound { .. } => {} // Expected
         other => panic!("Expected NotFound error, got: {:?}", other),
     }
 }
@@ -681,7 +688,7 @@ fn test_bfs_nonexistent_start_node() {
     let result = backend.bfs(99999, 2);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::NotFound(_) => {} // Expected
+        SqliteGraphError::NotFound { .. } => {} // Expected
         other => panic!("Expected NotFound error, got: {:?}", other),
     }
 }
@@ -734,12 +741,34 @@ fn test_shortest_path_same_node() {
     let backend = SqliteGraphBackend::in_memory().expect("backend");
     let node = backend.insert_node(sample_node("node")).unwrap();
 
-    let result = backend.shortest_path(node, node);
-    assert!(result.is_ok());
-    let path = result.unwrap();
-    // Behavior for same node - should return either None or [node]
-    // Let's assert it's not an error and check what the actual implementation does
-    assert!(path.is_none() || path.as_ref() == Some(&vec![node]));
+    let path = backend.shortest_path(node, node).unwrap();
+    assert_eq!(path, Some(vec![node]));
+}
+
+#[test]
+fn test_shortest_path_picks_lexicographically_smallest_tie() {
+    let backend = SqliteGraphBackend::in_memory().expect("backend");
+    let [a, b, c, x, y, t] =
+        ["a", "b", "c", "x", "y", "t"].map(|name| backend.insert_node(sample_node(name)).unwrap());
+    // Two length-3 paths: a-b-y-t and a-c-x-t. Insert the larger branch first so
+    // edge order cannot decide the result.
+    backend.insert_edge(sample_edge(a, c, "LINK")).unwrap();
+    backend.insert_edge(sample_edge(c, x, "LINK")).unwrap();
+    backend.insert_edge(sample_edge(x, t, "LINK")).unwrap();
+    backend.insert_edge(sample_edge(a, b, "LINK")).unwrap();
+    backend.insert_edge(sample_edge(b, y, "LINK")).unwrap();
+    backend.insert_edge(sample_edge(y, t, "LINK")).unwrap();
+
+    let expected = Some(vec![a, b, y, t]);
+    for _ in 0..3 {
+        assert_eq!(backend.shortest_path(a, t).unwrap(), expected);
+    }
+    assert_eq!(
+        backend
+            .shortest_path_directed(t, a, BackendDirection::Incoming)
+            .unwrap(),
+        Some(vec![t, x, c, a])
+    );
 }
 
 #[test]
@@ -750,7 +779,7 @@ fn test_shortest_path_invalid_start() {
     let result = backend.shortest_path(-1, end);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::NotFound(_) => {} // Expected
+        SqliteGraphError::NotFound { .. } => {} // Expected
         other => panic!("Expected NotFound error, got: {:?}", other),
     }
 }
@@ -763,7 +792,7 @@ fn test_shortest_path_invalid_end() {
     let result = backend.shortest_path(start, -1);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::NotFound(_) => {} // Expected
+        SqliteGraphError::NotFound { .. } => {} // Expected
         other => panic!("Expected NotFound error, got: {:?}", other),
     }
 }
@@ -944,6 +973,7 @@ fn test_pattern_search_empty_pattern() {
     let empty_pattern = PatternQuery {
         root: None,
         legs: vec![],
+        ..PatternQuery::default()
     };
 
     let result = backend.pattern_search(start, &empty_pattern);
@@ -971,6 +1001,7 @@ fn test_pattern_search_no_matches() {
             edge_type: Some("LINK".to_string()),
             constraint: Some(NodeConstraint::name_prefix("nonexistent")),
         }],
+        ..PatternQuery::default()
     };
 
     let result = backend.pattern_search(start, &pattern);
@@ -990,12 +1021,13 @@ fn test_pattern_search_invalid_start() {
             edge_type: Some("TEST".to_string()),
             constraint: Some(NodeConstraint::kind("Node")),
         }],
+        ..PatternQuery::default()
     };
 
     let result = backend.pattern_search(-1, &pattern);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::NotFound(_) => {} // Expected
+        SqliteGraphError::NotFound { .. } => {} // Expected
         other => panic!("Expected NotFound error, got: {:?}", other),
     }
 }
@@ -1125,3 +1157,1257 @@ fn test_k_hop_deterministic_ordering() {
     assert_eq!(result1, result2);
     assert_eq!(result2, result3);
 }
+
+#[test]
+fn test_backend_both_direction_traverses_undirected() {
+    let backend = SqliteGraphBackend::in_memory().expect("backend");
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    let c = backend.insert_node(sample_node("C")).unwrap();
+    let d = backend.insert_node(sample_node("D")).unwrap();
+    // Stored orientation: b -> a, b -> c, c -> b, d -> c
+    backend.insert_edge(sample_edge(b, a, "KNOWS")).unwrap();
+    backend.insert_edge(sample_edge(b, c, "KNOWS")).unwrap();
+    backend.insert_edge(sample_edge(c, b, "KNOWS")).unwrap();
+    backend.insert_edge(sample_edge(d, c, "LIKES")).unwrap();
+
+    let both = NeighborQuery {
+        direction: BackendDirection::Both,
+        edge_type: None,
+    };
+    assert_eq!(backend.neighbors(b, both.clone()).unwrap(), vec![a, c]);
+    assert_eq!(backend.neighbors(c, both).unwrap(), vec![b, d]);
+    let typed = NeighborQuery {
+        direction: BackendDirection::Both,
+        edge_type: Some("KNOWS".into()),
+    };
+    assert_eq!(backend.neighbors(c, typed).unwrap(), vec![b]);
+
+    assert_eq!(
+        backend.k_hop(a, 2, BackendDirection::Both).unwrap(),
+        vec![b, c]
+    );
+    assert_eq!(
+        backend.bfs_directed(a, 3, BackendDirection::Both).unwrap(),
+        vec![a, b, c, d]
+    );
+    assert_eq!(backend.shortest_path(a, d).unwrap(), None);
+    assert_eq!(
+        backend
+            .shortest_path_directed(a, d, BackendDirection::Both)
+            .unwrap(),
+        Some(vec![a, b, c, d])
+    );
+}
+
+#[test]
+fn test_k_hop_weighted_respects_type_costs_and_budget() {
+    let backend = SqliteGraphBackend::in_memory().expect("backend");
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    let c = backend.insert_node(sample_node("C")).unwrap();
+    let d = backend.insert_node(sample_node("D")).unwrap();
+    let e = backend.insert_node(sample_node("E")).unwrap();
+    let f = backend.insert_node(sample_node("F")).unwrap();
+    backend.insert_edge(sample_edge(a, b, "CALLS")).unwrap();
+    backend.insert_edge(sample_edge(b, c, "CALLS")).unwrap();
+    backend.insert_edge(sample_edge(a, c, "USES")).unwrap();
+    backend.insert_edge(sample_edge(a, d, "USES")).unwrap();
+    backend.insert_edge(sample_edge(d, e, "CALLS")).unwrap();
+    backend.insert_edge(sample_edge(a, f, "OTHER")).unwrap();
+
+    let costs = [("CALLS".to_string(), 1.0), ("USES".to_string(), 3.0)]
+        .into_iter()
+        .collect();
+    let reached = backend
+        .k_hop_weighted(a, 3.0, BackendDirection::Outgoing, &costs)
+        .unwrap();
+    assert_eq!(reached, vec![(b, 1.0), (c, 2.0), (d, 3.0)]);
+
+    let incoming = backend
+        .k_hop_weighted(c, 10.0, BackendDirection::Incoming, &costs)
+        .unwrap();
+    assert_eq!(incoming, vec![(b, 1.0), (a, 2.0)]);
+
+    let negative = [("CALLS".to_string(), -1.0)].into_iter().collect();
+    assert!(
+        backend
+            .k_hop_weighted(a, 3.0, BackendDirection::Outgoing, &negative)
+            .is_err()
+    );
+}
+
+fn assert_node_edges(backend: &dyn GraphBackend) {
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    let c = backend.insert_node(sample_node("C")).unwrap();
+    backend.insert_edge(sample_edge(a, c, "CALLS")).unwrap();
+    backend.insert_edge(sample_edge(a, b, "USES")).unwrap();
+    backend.insert_edge(sample_edge(c, a, "CALLS")).unwrap();
+
+    let edges = backend.node_edges(a).unwrap();
+    let outgoing: Vec<(i64, &str)> = edges
+        .outgoing
+        .iter()
+        .map(|edge| (edge.to_id, edge.edge_type.as_str()))
+        .collect();
+    assert_eq!(outgoing, vec![(b, "USES"), (c, "CALLS")]);
+    assert_eq!(edges.incoming.len(), 1);
+    assert_eq!(edges.incoming[0].from_id, c);
+    assert_eq!(edges.degree(), (2, 1));
+
+    assert!(backend.node_edges(b).unwrap().outgoing.is_empty());
+    assert!(backend.node_edges(999).is_err());
+}
+
+#[test]
+fn test_node_edges_returns_both_directions() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_node_edges(&sqlite);
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_node_edges(&native);
+}
+
+fn assert_delete_middle_of_chain(backend: &dyn GraphBackend) {
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    let c = backend.insert_node(sample_node("C")).unwrap();
+    let ab = backend.insert_edge(sample_edge(a, b, "NEXT")).unwrap();
+    backend.insert_edge(sample_edge(b, c, "NEXT")).unwrap();
+    let ac = backend.insert_edge(sample_edge(a, c, "SKIP")).unwrap();
+
+    backend.delete_node(b).unwrap();
+    assert!(matches!(
+        backend.get_node(b),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+    assert!(backend.delete_node(b).is_err());
+    assert!(backend.delete_edge(ab).is_err());
+    assert_eq!(backend.node_degree(a).unwrap(), (1, 0));
+    assert_eq!(backend.node_degree(c).unwrap(), (0, 1));
+    let edges = backend.node_edges(a).unwrap();
+    assert_eq!(edges.outgoing.len(), 1);
+    assert_eq!(edges.outgoing[0].id, ac);
+
+    backend.delete_edge(ac).unwrap();
+    assert_eq!(backend.node_degree(a).unwrap(), (0, 0));
+    assert!(backend.node_edges(c).unwrap().incoming.is_empty());
+    assert_eq!(backend.get_node(c).unwrap().name, "C");
+}
+
+#[test]
+fn test_delete_node_cascades_to_its_edges() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_delete_middle_of_chain(&sqlite);
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_delete_middle_of_chain(&native);
+}
+
+#[test]
+fn test_delete_middle_node_breaks_chain_traversals() {
+    let backend = SqliteGraphBackend::in_memory().expect("backend");
+    let ids: Vec<i64> = ["A", "B", "C", "D"]
+        .iter()
+        .map(|name| backend.insert_node(sample_node(name)).unwrap())
+        .collect();
+    for pair in ids.windows(2) {
+        backend
+            .insert_edge(sample_edge(pair[0], pair[1], "NEXT"))
+            .unwrap();
+    }
+    // Warm the adjacency caches before deleting.
+    assert_eq!(backend.bfs(ids[0], 3).unwrap(), ids);
+
+    backend.delete_node(ids[1]).unwrap();
+    assert!(
+        backend
+            .neighbors(ids[0], NeighborQuery::default())
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        backend
+            .neighbors(
+                ids[2],
+                NeighborQuery {
+                    direction: BackendDirection::Incoming,
+                    edge_type: None,
+                }
+            )
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(backend.node_degree(ids[2]).unwrap(), (1, 0));
+    assert_eq!(backend.bfs(ids[0], 3).unwrap(), vec![ids[0]]);
+    assert_eq!(backend.bfs(ids[2], 3).unwrap(), vec![ids[2], ids[3]]);
+    assert_eq!(backend.shortest_path(ids[0], ids[3]).unwrap(), None);
+}
+
+fn assert_update_data_keeps_ids_and_edges(backend: &dyn GraphBackend) {
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    let ab = backend.insert_edge(sample_edge(a, b, "LINK")).unwrap();
+
+    // Shrinking and same-size payloads round-trip on every backend.
+    for data in [json!({}), json!({ "name": "Z" }), json!([1])] {
+        backend.update_node_data(a, data.clone()).unwrap();
+        let node = backend.get_node(a).unwrap();
+        assert_eq!(node.data, data);
+        assert_eq!((node.kind.as_str(), node.name.as_str()), ("Node", "A"));
+    }
+    backend
+        .update_edge_data(ab, json!({ "weight": 3 }))
+        .unwrap();
+
+    let edges = backend.node_edges(a).unwrap();
+    assert_eq!(edges.outgoing.len(), 1);
+    assert_eq!(edges.outgoing[0].id, ab);
+    assert_eq!(edges.outgoing[0].to_id, b);
+    assert_eq!(edges.outgoing[0].data, json!({ "weight": 3 }));
+    assert_eq!(backend.node_degree(b).unwrap(), (0, 1));
+    assert_eq!(backend.get_node(b).unwrap().data, json!({ "name": "B" }));
+
+    assert!(matches!(
+        backend.update_node_data(999, json!({})),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+    assert!(matches!(
+        backend.update_edge_data(999, json!({})),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn test_update_data_keeps_ids_and_edges() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_update_data_keeps_ids_and_edges(&sqlite);
+    assert_eq!(
+        sqlite.neighbors(1, NeighborQuery::default()).unwrap(),
+        vec![2]
+    );
+
+    let grown = json!({ "name": "A", "tags": ["x", "y", "z"], "weight": 12.5 });
+    sqlite.update_node_data(1, grown.clone()).unwrap();
+    assert_eq!(sqlite.get_node(1).unwrap().data, grown);
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_update_data_keeps_ids_and_edges(&native);
+    // A record that outgrows its space is moved, keeping its adjacency.
+    native.update_node_data(1, grown.clone()).unwrap();
+    assert_eq!(native.get_node(1).unwrap().data, grown);
+    drop(native);
+    let native = sqlitegraph::backend::NativeGraphBackend::open(temp_file.path()).unwrap();
+    assert_eq!(native.get_node(1).unwrap().data, grown);
+    assert_eq!(
+        native.neighbors(1, NeighborQuery::default()).unwrap(),
+        vec![2]
+    );
+}
+
+fn assert_batch_matches_single_lookups(backend: &dyn GraphBackend) {
+    let ids: Vec<i64> = (0..50)
+        .map(|i| backend.insert_node(sample_node(&format!("N{i}"))).unwrap())
+        .collect();
+    for (i, &from) in ids.iter().enumerate() {
+        for step in [1, 7, 13] {
+            let to = ids[(i * 3 + step) % ids.len()];
+            let edge_type = if step == 7 { "CALLS" } else { "USES" };
+            backend
+                .insert_edge(sample_edge(from, to, edge_type))
+                .unwrap();
+        }
+    }
+
+    for direction in [
+        BackendDirection::Outgoing,
+        BackendDirection::Incoming,
+        BackendDirection::Both,
+    ] {
+        for edge_type in [None, Some("CALLS".to_string())] {
+            let query = NeighborQuery {
+                direction,
+                edge_type,
+            };
+            let batch = backend.neighbors_batch(&ids, query.clone()).unwrap();
+            assert_eq!(batch.len(), ids.len());
+            for &id in &ids {
+                assert_eq!(batch[&id], backend.neighbors(id, query.clone()).unwrap());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_neighbors_batch_matches_single_lookups() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_batch_matches_single_lookups(&sqlite);
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_batch_matches_single_lookups(&native);
+}
+
+#[test]
+fn test_neighbors_batch_chunks_past_the_variable_limit() {
+    let backend = SqliteGraphBackend::in_memory().expect("backend");
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    backend.insert_edge(sample_edge(a, b, "LINK")).unwrap();
+    backend.insert_edge(sample_edge(b, a, "LINK")).unwrap();
+
+    // Missing ids still get an (empty) entry; duplicates collapse.
+    let mut ids: Vec<i64> = (1..=2500).collect();
+    ids.push(a);
+    let batch = backend
+        .neighbors_batch(
+            &ids,
+            NeighborQuery {
+                direction: BackendDirection::Outgoing,
+                edge_type: Some("LINK".into()),
+            },
+        )
+        .unwrap();
+    assert_eq!(batch.len(), 2500);
+    assert_eq!(batch[&a], vec![b]);
+    assert_eq!(batch[&b], vec![a]);
+    assert!(batch[&2500].is_empty());
+}
+
+fn assert_two_clusters_and_an_isolated_node(backend: &dyn GraphBackend) {
+    let ids: Vec<i64> = ["A", "B", "C", "D", "E", "F"]
+        .iter()
+        .map(|name| backend.insert_node(sample_node(name)).unwrap())
+        .collect();
+    backend
+        .insert_edge(sample_edge(ids[4], ids[1], "LINK"))
+        .unwrap();
+    backend
+        .insert_edge(sample_edge(ids[0], ids[3], "LINK"))
+        .unwrap();
+    backend
+        .insert_edge(sample_edge(ids[3], ids[5], "LINK"))
+        .unwrap();
+
+    assert_eq!(
+        backend.connected_components().unwrap(),
+        vec![
+            vec![ids[0], ids[3], ids[5]],
+            vec![ids[1], ids[4]],
+            vec![ids[2]],
+        ]
+    );
+}
+
+#[test]
+fn test_connected_components_across_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_two_clusters_and_an_isolated_node(&sqlite);
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_two_clusters_and_an_isolated_node(&native);
+}
+
+#[test]
+fn test_pagerank_agrees_across_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    for backend in [&sqlite as &dyn GraphBackend, &native] {
+        let ids: Vec<i64> = (0..4)
+            .map(|i| backend.insert_node(sample_node(&format!("N{i}"))).unwrap())
+            .collect();
+        backend
+            .insert_edge(sample_edge(ids[0], ids[1], "LINK"))
+            .unwrap();
+        backend
+            .insert_edge(sample_edge(ids[1], ids[2], "LINK"))
+            .unwrap();
+        backend
+            .insert_edge(sample_edge(ids[2], ids[0], "LINK"))
+            .unwrap();
+    }
+    let expected = sqlite.pagerank(0.85, 50).unwrap();
+    assert_eq!(native.pagerank(0.85, 50).unwrap(), expected);
+    assert!((expected.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert!(native.pagerank(1.5, 50).is_err());
+}
+
+#[test]
+fn test_topological_sort_agrees_across_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    for backend in [&sqlite as &dyn GraphBackend, &native] {
+        let ids: Vec<i64> = (0..4)
+            .map(|i| backend.insert_node(sample_node(&format!("N{i}"))).unwrap())
+            .collect();
+        backend
+            .insert_edge(sample_edge(ids[2], ids[0], "DEP"))
+            .unwrap();
+        backend
+            .insert_edge(sample_edge(ids[3], ids[1], "DEP"))
+            .unwrap();
+        assert_eq!(
+            backend.topological_sort().unwrap(),
+            vec![ids[2], ids[0], ids[3], ids[1]]
+        );
+
+        backend
+            .insert_edge(sample_edge(ids[1], ids[3], "DEP"))
+            .unwrap();
+        assert!(matches!(
+            backend.topological_sort(),
+            Err(SqliteGraphError::CycleDetected(_))
+        ));
+    }
+}
+
+#[test]
+fn test_both_direction_deduplicates_reciprocal_edges() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_both_direction_deduplicates(&sqlite);
+
+    let native = sqlitegraph::backend::NativeGraphBackend::in_memory().expect("backend");
+    assert_both_direction_deduplicates(&native);
+}
+
+fn assert_both_direction_deduplicates(backend: &dyn GraphBackend) {
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    let c = backend.insert_node(sample_node("C")).unwrap();
+    // a <-> b is reciprocal, b -> c is one-directional.
+    backend.insert_edge(sample_edge(a, b, "KNOWS")).unwrap();
+    backend.insert_edge(sample_edge(b, a, "KNOWS")).unwrap();
+    backend.insert_edge(sample_edge(b, c, "KNOWS")).unwrap();
+
+    let both = NeighborQuery {
+        direction: BackendDirection::Both,
+        edge_type: None,
+    };
+    assert_eq!(backend.neighbors(a, both.clone()).unwrap(), vec![b]);
+    assert_eq!(backend.neighbors(b, both.clone()).unwrap(), vec![a, c]);
+    assert_eq!(backend.neighbors(c, both).unwrap(), vec![b]);
+    let typed = |edge_type: &str| NeighborQuery {
+        direction: BackendDirection::Both,
+        edge_type: Some(edge_type.into()),
+    };
+    assert_eq!(backend.neighbors(b, typed("KNOWS")).unwrap(), vec![a, c]);
+    assert!(backend.neighbors(b, typed("LIKES")).unwrap().is_empty());
+    assert_eq!(
+        backend.k_hop(c, 2, BackendDirection::Both).unwrap(),
+        vec![b, a]
+    );
+    assert_eq!(
+        backend.bfs_directed(c, 2, BackendDirection::Both).unwrap(),
+        vec![c, b, a]
+    );
+}
+
+fn assert_edge_lookup_and_enumeration(backend: &dyn GraphBackend) {
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    let c = backend.insert_node(sample_node("C")).unwrap();
+    let ab = backend.insert_edge(sample_edge(a, b, "KNOWS")).unwrap();
+    let bc = backend.insert_edge(sample_edge(b, c, "LIKES")).unwrap();
+    let ca = backend.insert_edge(sample_edge(c, a, "KNOWS")).unwrap();
+
+    let edge = backend.get_edge(bc).unwrap();
+    assert_eq!(edge.id, bc);
+    assert_eq!((edge.from_id, edge.to_id), (b, c));
+    assert_eq!(edge.edge_type, "LIKES");
+    assert_eq!(backend.list_edge_ids().unwrap(), vec![ab, bc, ca]);
+
+    backend.delete_edge(bc).unwrap();
+    assert!(matches!(
+        backend.get_edge(bc),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+    assert!(matches!(
+        backend.get_edge(ca + 100),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+    assert_eq!(backend.list_edge_ids().unwrap(), vec![ab, ca]);
+}
+
+#[test]
+fn test_get_edge_and_list_edge_ids() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_edge_lookup_and_enumeration(&sqlite);
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_edge_lookup_and_enumeration(&native);
+}
+
+#[test]
+fn test_get_edge_hides_edges_of_soft_deleted_nodes() {
+    let backend = SqliteGraphBackend::in_memory().expect("backend");
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    let c = backend.insert_node(sample_node("C")).unwrap();
+    let ab = backend.insert_edge(sample_edge(a, b, "KNOWS")).unwrap();
+    let bc = backend.insert_edge(sample_edge(b, c, "KNOWS")).unwrap();
+
+    backend.graph().soft_delete_node(c).unwrap();
+    assert!(matches!(
+        backend.get_edge(bc),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+    assert_eq!(backend.list_edge_ids().unwrap(), vec![ab]);
+}
+
+/// Nodes as `(name, lang, labels)`; native reads these from `data`, SQLite from its index tables.
+const PROPERTY_FIXTURE: [(&str, &str, &[&str]); 5] = [
+    ("parse", "rust", &["Function"]),
+    ("Parser", "rust", &["Struct"]),
+    ("main", "python", &["Function", "Entry"]),
+    ("helper", "rust", &["Function"]),
+    ("README", "markdown", &[]),
+];
+
+fn insert_property_fixture(backend: &dyn GraphBackend) -> Vec<i64> {
+    PROPERTY_FIXTURE
+        .iter()
+        .map(|&(name, lang, labels)| {
+            backend
+                .insert_node(NodeSpec {
+                    kind: "Symbol".into(),
+                    name: name.into(),
+                    file_path: None,
+                    data: json!({ "lang": lang, "labels": labels }),
+                })
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_find_nodes_by_property_and_label_agree_across_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    let ids = insert_property_fixture(&sqlite);
+    for (&id, &(_, lang, labels)) in ids.iter().zip(PROPERTY_FIXTURE.iter()) {
+        sqlitegraph::add_property(sqlite.graph(), id, "lang", lang).unwrap();
+        for label in labels {
+            sqlitegraph::add_label(sqlite.graph(), id, label).unwrap();
+        }
+    }
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_eq!(insert_property_fixture(&native), ids);
+
+    assert_eq!(
+        sqlite.find_nodes_by_property("lang", "rust").unwrap(),
+        vec![ids[0], ids[1], ids[3]]
+    );
+    assert_eq!(
+        sqlite.find_nodes_by_label("Function").unwrap(),
+        vec![ids[0], ids[2], ids[3]]
+    );
+    for (key, value) in [
+        ("lang", "rust"),
+        ("lang", "python"),
+        ("lang", "go"),
+        ("size", "1"),
+    ] {
+        assert_eq!(
+            sqlite.find_nodes_by_property(key, value).unwrap(),
+            native.find_nodes_by_property(key, value).unwrap(),
+            "{key}={value}"
+        );
+    }
+    for label in ["Function", "Struct", "Entry", "Missing"] {
+        assert_eq!(
+            sqlite.find_nodes_by_label(label).unwrap(),
+            native.find_nodes_by_label(label).unwrap(),
+            "{label}"
+        );
+    }
+
+    sqlite.delete_node(ids[3]).unwrap();
+    native.delete_node(ids[3]).unwrap();
+    assert_eq!(
+        sqlite.find_nodes_by_property("lang", "rust").unwrap(),
+        vec![ids[0], ids[1]]
+    );
+    assert_eq!(
+        native.find_nodes_by_property("lang", "rust").unwrap(),
+        vec![ids[0], ids[1]]
+    );
+}
+
+fn assert_upsert_edge_dedupes_by_triple(backend: &dyn GraphBackend) {
+    let a = backend.insert_node(sample_node("a")).unwrap();
+    let b = backend.insert_node(sample_node("b")).unwrap();
+    let upsert = |edge_type: &str, weight: i64| {
+        backend.upsert_edge(EdgeSpec {
+            data: json!({ "weight": weight }),
+            ..sample_edge(a, b, edge_type)
+        })
+    };
+
+    let first = upsert("calls", 1).unwrap();
+    let second = upsert("calls", 2).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(backend.list_edge_ids().unwrap(), vec![first]);
+    assert_eq!(
+        backend.get_edge(first).unwrap().data,
+        json!({ "weight": 2 })
+    );
+
+    // A different type, or the reverse direction, is a different edge
+    let other_type = upsert("imports", 3).unwrap();
+    let reverse = backend.upsert_edge(sample_edge(b, a, "calls")).unwrap();
+    assert_eq!(
+        backend.list_edge_ids().unwrap(),
+        vec![first, other_type, reverse]
+    );
+
+    // Deleted edges are not reused
+    backend.delete_edge(first).unwrap();
+    let replacement = upsert("calls", 4).unwrap();
+    assert_ne!(replacement, first);
+    assert_eq!(
+        backend.get_edge(replacement).unwrap().data,
+        json!({ "weight": 4 })
+    );
+}
+
+#[test]
+fn test_upsert_edge_updates_existing_triple_on_both_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_upsert_edge_dedupes_by_triple(&sqlite);
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_upsert_edge_dedupes_by_triple(&native);
+}
+
+#[test]
+fn test_cache_stats_through_trait_objects() {
+    let dir = tempfile::tempdir().unwrap();
+    let backends = [
+        (
+            "sqlite",
+            sqlitegraph::open_graph(dir.path().join("s.db"), &sqlitegraph::GraphConfig::sqlite())
+                .unwrap(),
+        ),
+        (
+            "native",
+            sqlitegraph::open_graph(dir.path().join("n.db"), &sqlitegraph::GraphConfig::native())
+                .unwrap(),
+        ),
+    ];
+    for (name, backend) in &backends {
+        let a = backend.insert_node(sample_node("a")).unwrap();
+        let b = backend.insert_node(sample_node("b")).unwrap();
+        backend.insert_edge(sample_edge(a, b, "LINK")).unwrap();
+        backend.reset_cache_stats();
+        for _ in 0..3 {
+            backend.neighbors(a, NeighborQuery::default()).unwrap();
+        }
+
+        let stats = backend.cache_stats();
+        if *name == "sqlite" {
+            assert_eq!((stats.hits, stats.misses), (2, 1), "{name}");
+            assert!(stats.entries >= 1, "{name}");
+        } else {
+            assert_eq!(stats, sqlitegraph::CacheStats::default(), "{name}");
+        }
+
+        backend.reset_cache_stats();
+        let reset = backend.cache_stats();
+        assert_eq!((reset.hits, reset.misses), (0, 0), "{name}");
+        assert_eq!(reset.entries, stats.entries, "{name}");
+    }
+}
+
+const GRID: i64 = 5;
+
+/// Right and down edges over a `GRID` x `GRID` lattice with uneven weights.
+/// Returns the node id of each cell, row-major, and the cheapest cost from the
+/// top-left corner to every cell.
+fn insert_weighted_grid(backend: &dyn GraphBackend) -> (Vec<i64>, Vec<f64>) {
+    let cells: Vec<i64> = (0..GRID * GRID)
+        .map(|i| {
+            backend
+                .insert_node(sample_node(&format!("cell{i}")))
+                .unwrap()
+        })
+        .collect();
+    let weight = |r: i64, c: i64, down: bool| 1.0 + ((r * 3 + c * 5 + i64::from(down)) % 4) as f64;
+    let mut cost = vec![f64::INFINITY; cells.len()];
+    cost[0] = 0.0;
+    for r in 0..GRID {
+        for c in 0..GRID {
+            let here = (r * GRID + c) as usize;
+            for (down, nr, nc) in [(false, r, c + 1), (true, r + 1, c)] {
+                if nr == GRID || nc == GRID {
+                    continue;
+                }
+                let there = (nr * GRID + nc) as usize;
+                let w = weight(r, c, down);
+                backend
+                    .insert_edge(EdgeSpec {
+                        data: json!({ "weight": w }),
+                        ..sample_edge(cells[here], cells[there], "road")
+                    })
+                    .unwrap();
+                cost[there] = cost[there].min(cost[here] + w);
+            }
+        }
+    }
+    (cells, cost)
+}
+
+fn assert_astar_matches_dijkstra(backend: &dyn GraphBackend) {
+    let (cells, cost) = insert_weighted_grid(backend);
+    let position: std::collections::HashMap<i64, (i64, i64)> = cells
+        .iter()
+        .enumerate()
+        .map(|(i, &id)| (id, (i as i64 / GRID, i as i64 % GRID)))
+        .collect();
+    // Every edge costs at least 1, so Manhattan distance never overestimates
+    let target = *cells.last().unwrap();
+    let manhattan = |node: i64| {
+        let (r, c) = position[&node];
+        ((GRID - 1 - r) + (GRID - 1 - c)) as f64
+    };
+
+    let (path, total) = backend
+        .astar(cells[0], target, "weight", &manhattan)
+        .unwrap()
+        .unwrap();
+    let dijkstra = backend
+        .astar(cells[0], target, "weight", &|_| 0.0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(total, *cost.last().unwrap());
+    assert_eq!(dijkstra, (path.clone(), total));
+    assert_eq!((path[0], *path.last().unwrap()), (cells[0], target));
+    assert_eq!(path.len() as i64, 2 * GRID - 1);
+
+    // Without the weight key every edge costs 1
+    let (_, hops) = backend
+        .astar(cells[0], target, "missing", &manhattan)
+        .unwrap()
+        .unwrap();
+    assert_eq!(hops, (2 * (GRID - 1)) as f64);
+
+    assert_eq!(
+        backend.astar(target, cells[0], "weight", &|_| 0.0).unwrap(),
+        None
+    );
+    assert_eq!(
+        backend
+            .astar(cells[0], cells[0], "weight", &manhattan)
+            .unwrap(),
+        Some((vec![cells[0]], 0.0))
+    );
+    assert!(matches!(
+        backend.astar(cells[0], target, "weight", &|_| -1.0),
+        Err(SqliteGraphError::InvalidInput { .. })
+    ));
+}
+
+#[test]
+fn test_astar_matches_dijkstra_on_grid_for_both_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_astar_matches_dijkstra(&sqlite);
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_astar_matches_dijkstra(&native);
+}
+
+fn assert_all_pairs_distances(backend: &dyn GraphBackend) {
+    let [a, b, c, d] =
+        ["a", "b", "c", "d"].map(|name| backend.insert_node(sample_node(name)).unwrap());
+    for (from, to) in [(a, b), (b, c), (c, d), (a, c), (d, b)] {
+        backend.insert_edge(sample_edge(from, to, "LINK")).unwrap();
+    }
+
+    let distances = backend.all_pairs_shortest_paths(4).unwrap();
+    let expected: std::collections::HashMap<(i64, i64), u32> = [
+        ((a, b), 1),
+        ((a, c), 1),
+        ((a, d), 2),
+        ((b, c), 1),
+        ((b, d), 2),
+        ((c, d), 1),
+        ((c, b), 2),
+        ((d, b), 1),
+        ((d, c), 2),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(distances, expected);
+    assert_eq!(backend.all_pairs_shortest_paths(4).unwrap(), distances);
+
+    assert!(matches!(
+        backend.all_pairs_shortest_paths(3),
+        Err(SqliteGraphError::InvalidInput { .. })
+    ));
+}
+
+#[test]
+fn test_all_pairs_shortest_paths_on_both_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_all_pairs_distances(&sqlite);
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_all_pairs_distances(&native);
+}
+
+fn assert_betweenness_on_star_and_path(backend: &dyn GraphBackend) {
+    let node = |name: String| backend.insert_node(sample_node(&name)).unwrap();
+    let hub = node("hub".into());
+    let leaves: Vec<i64> = (0..4).map(|i| node(format!("leaf{i}"))).collect();
+    let path: Vec<i64> = (0..4).map(|i| node(format!("p{i}"))).collect();
+    // Star: the hub linked both ways to four leaves
+    for &leaf in &leaves {
+        backend.insert_edge(sample_edge(hub, leaf, "LINK")).unwrap();
+        backend.insert_edge(sample_edge(leaf, hub, "LINK")).unwrap();
+    }
+    // Path: p0 -> p1 -> p2 -> p3, disconnected from the star
+    for pair in path.windows(2) {
+        backend
+            .insert_edge(sample_edge(pair[0], pair[1], "LINK"))
+            .unwrap();
+    }
+
+    // Every ordered pair of leaves routes through the hub: 4 * 3 = 12.
+    // p1 lies on p0->p2 and p0->p3; p2 on p0->p3 and p1->p3.
+    let scores = backend.betweenness_centrality(false, 16).unwrap();
+    assert_eq!(scores.len(), 9);
+    assert_eq!(scores[&hub], 12.0);
+    for &leaf in &leaves {
+        assert_eq!(scores[&leaf], 0.0);
+    }
+    assert_eq!(
+        path.iter().map(|id| scores[id]).collect::<Vec<_>>(),
+        vec![0.0, 2.0, 2.0, 0.0]
+    );
+
+    // Normalized over the (9 - 1)(9 - 2) = 56 ordered pairs
+    let normalized = backend.betweenness_centrality(true, 16).unwrap();
+    assert_eq!(normalized[&hub], 12.0 / 56.0);
+    assert_eq!(normalized[&path[1]], 2.0 / 56.0);
+
+    assert!(matches!(
+        backend.betweenness_centrality(false, 8),
+        Err(SqliteGraphError::InvalidInput { .. })
+    ));
+}
+
+#[test]
+fn test_betweenness_centrality_on_both_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_betweenness_on_star_and_path(&sqlite);
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_betweenness_on_star_and_path(&native);
+}
+
+/// Four nodes of two kinds: a and b share two calls, c is imported by b and
+/// called by a, and d has no edges.
+fn assert_statistics_on_fixture(backend: &dyn GraphBackend) {
+    let node = |kind: &str, name: &str| NodeSpec {
+        kind: kind.into(),
+        ..sample_node(name)
+    };
+    let a = backend.insert_node(node("Fn", "a")).unwrap();
+    let b = backend.insert_node(node("Fn", "b")).unwrap();
+    let c = backend.insert_node(node("Mod", "c")).unwrap();
+    backend.insert_node(node("Mod", "d")).unwrap();
+    for (from, to, edge_type) in [
+        (a, b, "CALLS"),
+        (a, b, "CALLS"),
+        (b, c, "IMPORTS"),
+        (a, c, "CALLS"),
+    ] {
+        backend
+            .insert_edge(sample_edge(from, to, edge_type))
+            .unwrap();
+    }
+
+    let stats = backend.statistics().unwrap();
+    assert_eq!((stats.node_count, stats.edge_count), (4, 4));
+    assert_eq!(
+        stats.nodes_by_kind.into_iter().collect::<Vec<_>>(),
+        vec![("Fn".to_string(), 2), ("Mod".to_string(), 2)]
+    );
+    assert_eq!(
+        stats.edges_by_type.into_iter().collect::<Vec<_>>(),
+        vec![("CALLS".to_string(), 3), ("IMPORTS".to_string(), 1)]
+    );
+    assert_eq!((stats.min_degree, stats.max_degree), (0, 3));
+    assert_eq!(stats.avg_degree, 2.0);
+    assert_eq!(stats.isolated_nodes, 1);
+}
+
+#[test]
+fn test_statistics_on_both_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_statistics_on_fixture(&sqlite);
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_statistics_on_fixture(&native);
+
+    // Soft-deleted nodes and their edges drop out of every count
+    sqlite.graph().soft_delete_node(3).unwrap();
+    let stats = sqlite.statistics().unwrap();
+    assert_eq!((stats.node_count, stats.edge_count), (3, 2));
+    assert_eq!(
+        (stats.min_degree, stats.max_degree, stats.isolated_nodes),
+        (0, 2, 1)
+    );
+    assert_eq!(stats.edges_by_type.get("IMPORTS"), None);
+
+    let empty = SqliteGraphBackend::in_memory().expect("backend");
+    assert_eq!(empty.statistics().unwrap(), Default::default());
+}
+
+fn assert_top_nodes_on_star(backend: &dyn GraphBackend) {
+    let center = backend.insert_node(sample_node("center")).unwrap();
+    let leaves: Vec<i64> = (1..=5)
+        .map(|i| {
+            backend
+                .insert_node(sample_node(&format!("leaf{i}")))
+                .unwrap()
+        })
+        .collect();
+    let isolated = backend.insert_node(sample_node("isolated")).unwrap();
+    for &leaf in &leaves[..4] {
+        backend
+            .insert_edge(sample_edge(center, leaf, "LINK"))
+            .unwrap();
+    }
+    backend
+        .insert_edge(sample_edge(leaves[4], center, "LINK"))
+        .unwrap();
+    backend
+        .insert_edge(sample_edge(leaves[0], leaves[1], "LINK"))
+        .unwrap();
+
+    assert_eq!(
+        backend
+            .top_nodes_by_degree(3, BackendDirection::Both)
+            .unwrap(),
+        vec![(center, 5), (leaves[0], 2), (leaves[1], 2)]
+    );
+    assert_eq!(
+        backend
+            .top_nodes_by_degree(3, BackendDirection::Outgoing)
+            .unwrap(),
+        vec![(center, 4), (leaves[0], 1), (leaves[4], 1)]
+    );
+    assert_eq!(
+        backend
+            .top_nodes_by_degree(2, BackendDirection::Incoming)
+            .unwrap(),
+        vec![(leaves[1], 2), (center, 1)]
+    );
+    let all = backend
+        .top_nodes_by_degree(10, BackendDirection::Both)
+        .unwrap();
+    assert_eq!(all.len(), 7);
+    assert_eq!(all.last(), Some(&(isolated, 0)));
+    assert!(
+        backend
+            .top_nodes_by_degree(0, BackendDirection::Both)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_top_nodes_by_degree_ranks_star_center_first_on_both_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_top_nodes_on_star(&sqlite);
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_top_nodes_on_star(&native);
+}
+
+fn assert_not_found_reports_kind_and_id(backend: &dyn GraphBackend) {
+    let a = backend.insert_node(sample_node("a")).unwrap();
+    let b = backend.insert_node(sample_node("b")).unwrap();
+    let edge = backend.insert_edge(sample_edge(a, b, "CALLS")).unwrap();
+
+    let err = backend.get_node(b + 100).unwrap_err();
+    assert!(matches!(
+        err,
+        SqliteGraphError::NotFound { kind: EntityKind::Node, id } if id == b + 100
+    ));
+    assert_eq!(
+        err.to_string(),
+        format!("entity not found: entity {}", b + 100)
+    );
+
+    let err = backend.get_edge(edge + 100).unwrap_err();
+    assert!(matches!(
+        err,
+        SqliteGraphError::NotFound { kind: EntityKind::Edge, id } if id == edge + 100
+    ));
+    assert_eq!(
+        err.to_string(),
+        format!("entity not found: edge {}", edge + 100)
+    );
+}
+
+#[test]
+fn test_not_found_is_structured_on_both_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    assert_not_found_reports_kind_and_id(&sqlite);
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    assert_not_found_reports_kind_and_id(&native);
+}
+
+fn build_neighbor_count_graph(backend: &dyn GraphBackend) -> i64 {
+    let hub = backend.insert_node(sample_node("hub")).unwrap();
+    let a = backend.insert_node(sample_node("a")).unwrap();
+    let b = backend.insert_node(sample_node("b")).unwrap();
+    let c = backend.insert_node(sample_node("c")).unwrap();
+    for (from, to, edge_type) in [
+        (hub, a, "CALLS"),
+        (hub, b, "CALLS"),
+        (hub, c, "USES"),
+        (a, hub, "CALLS"),
+        (c, hub, "USES"),
+        (hub, hub, "USES"),
+    ] {
+        backend
+            .insert_edge(sample_edge(from, to, edge_type))
+            .unwrap();
+    }
+    hub
+}
+
+fn assert_neighbor_count_matches_neighbors(backend: &dyn GraphBackend, hub: i64) {
+    let directions = [
+        BackendDirection::Outgoing,
+        BackendDirection::Incoming,
+        BackendDirection::Both,
+    ];
+    let mut counts = Vec::new();
+    for direction in directions {
+        for edge_type in [None, Some("CALLS"), Some("USES"), Some("MISSING")] {
+            let query = NeighborQuery {
+                direction,
+                edge_type: edge_type.map(String::from),
+            };
+            let count = backend.neighbor_count(hub, query.clone()).unwrap();
+            assert_eq!(
+                count,
+                backend.neighbors(hub, query).unwrap().len(),
+                "{direction:?} {edge_type:?}"
+            );
+            counts.push(count);
+        }
+    }
+    assert_eq!(counts, vec![4, 2, 2, 0, 3, 1, 2, 0, 4, 2, 2, 0]);
+}
+
+#[test]
+fn test_neighbor_count_matches_neighbors_on_both_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    let hub = build_neighbor_count_graph(&sqlite);
+    assert_neighbor_count_matches_neighbors(&sqlite, hub);
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    let hub = build_neighbor_count_graph(&native);
+    // Native adjacency is laid out by compaction
+    native.compact().unwrap();
+    assert_neighbor_count_matches_neighbors(&native, hub);
+}
+
+fn build_mixed_type_graph(backend: &dyn GraphBackend) -> [i64; 5] {
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    let c = backend.insert_node(sample_node("C")).unwrap();
+    let d = backend.insert_node(sample_node("D")).unwrap();
+    let e = backend.insert_node(sample_node("E")).unwrap();
+    for (from, to, edge_type) in [
+        (a, b, "CALLS"),
+        (a, c, "USES"),
+        (b, d, "CALLS"),
+        (c, e, "CALLS"),
+        (d, e, "USES"),
+    ] {
+        backend
+            .insert_edge(sample_edge(from, to, edge_type))
+            .unwrap();
+    }
+    [a, b, c, d, e]
+}
+
+fn assert_bfs_filtered_follows_allowed_types(backend: &dyn GraphBackend, ids: [i64; 5]) {
+    let [a, b, c, d, e] = ids;
+    assert_eq!(
+        backend.bfs_filtered(a, 3, &["CALLS"]).unwrap(),
+        vec![a, b, d]
+    );
+    assert_eq!(backend.bfs_filtered(a, 3, &["USES"]).unwrap(), vec![a, c]);
+    assert_eq!(
+        backend.bfs_filtered(a, 1, &["CALLS", "USES"]).unwrap(),
+        vec![a, b, c]
+    );
+    assert_eq!(
+        backend.bfs_filtered(a, 3, &["CALLS", "USES"]).unwrap(),
+        vec![a, b, c, d, e]
+    );
+    assert_eq!(backend.bfs_filtered(a, 3, &[]).unwrap(), vec![a]);
+    assert!(backend.bfs_filtered(e + 100, 3, &["CALLS"]).is_err());
+}
+
+#[test]
+fn test_bfs_filtered_on_both_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    let ids = build_mixed_type_graph(&sqlite);
+    assert_bfs_filtered_follows_allowed_types(&sqlite, ids);
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    let ids = build_mixed_type_graph(&native);
+    native.compact().unwrap();
+    assert_bfs_filtered_follows_allowed_types(&native, ids);
+}
+
+/// A -> C -> E over a USES then a CALLS edge, and a longer call-only route
+/// A -> B -> F -> E.
+fn build_call_detour_graph(backend: &dyn GraphBackend) -> [i64; 5] {
+    let a = backend.insert_node(sample_node("A")).unwrap();
+    let b = backend.insert_node(sample_node("B")).unwrap();
+    let c = backend.insert_node(sample_node("C")).unwrap();
+    let e = backend.insert_node(sample_node("E")).unwrap();
+    let f = backend.insert_node(sample_node("F")).unwrap();
+    for (from, to, edge_type) in [
+        (a, b, "CALLS"),
+        (a, c, "USES"),
+        (c, e, "CALLS"),
+        (b, f, "CALLS"),
+        (f, e, "CALLS"),
+    ] {
+        backend
+            .insert_edge(sample_edge(from, to, edge_type))
+            .unwrap();
+    }
+    [a, b, c, e, f]
+}
+
+fn assert_shortest_path_filtered_respects_types(backend: &dyn GraphBackend, ids: [i64; 5]) {
+    let [a, b, c, e, f] = ids;
+    assert_eq!(backend.shortest_path(a, e).unwrap(), Some(vec![a, c, e]));
+    // The call-only path is longer than the unconstrained one
+    assert_eq!(
+        backend.shortest_path_filtered(a, e, &["CALLS"]).unwrap(),
+        Some(vec![a, b, f, e])
+    );
+    // No USES-only path reaches E, although mixed paths do
+    assert_eq!(
+        backend.shortest_path_filtered(a, e, &["USES"]).unwrap(),
+        None
+    );
+    assert_eq!(
+        backend
+            .shortest_path_filtered(a, e, &["CALLS", "USES"])
+            .unwrap(),
+        Some(vec![a, c, e])
+    );
+    assert_eq!(backend.shortest_path_filtered(a, e, &[]).unwrap(), None);
+    assert_eq!(
+        backend.shortest_path_filtered(a, a, &[]).unwrap(),
+        Some(vec![a])
+    );
+}
+
+#[test]
+fn test_shortest_path_filtered_on_both_backends() {
+    let sqlite = SqliteGraphBackend::in_memory().expect("backend");
+    let ids = build_call_detour_graph(&sqlite);
+    assert_shortest_path_filtered_respects_types(&sqlite, ids);
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = sqlitegraph::backend::NativeGraphBackend::new(temp_file.path()).unwrap();
+    let ids = build_call_detour_graph(&native);
+    native.compact().unwrap();
+    assert_shortest_path_filtered_respects_types(&native, ids);
+}
+
+#[test]
+fn test_in_memory_native_backend_passes_shared_suites() {
+    use sqlitegraph::backend::NativeGraphBackend;
+
+    let fresh = || NativeGraphBackend::in_memory().expect("backend");
+    assert_node_edges(&fresh());
+    assert_delete_middle_of_chain(&fresh());
+    assert_update_data_keeps_ids_and_edges(&fresh());
+    assert_batch_matches_single_lookups(&fresh());
+    assert_two_clusters_and_an_isolated_node(&fresh());
+    assert_edge_lookup_and_enumeration(&fresh());
+    assert_upsert_edge_dedupes_by_triple(&fresh());
+    assert_astar_matches_dijkstra(&fresh());
+    assert_all_pairs_distances(&fresh());
+    assert_betweenness_on_star_and_path(&fresh());
+    assert_statistics_on_fixture(&fresh());
+    assert_top_nodes_on_star(&fresh());
+    assert_not_found_reports_kind_and_id(&fresh());
+
+    // Compaction rebuilds the in-memory buffer in place
+    let native = fresh();
+    let hub = build_neighbor_count_graph(&native);
+    native.compact().unwrap();
+    assert_neighbor_count_matches_neighbors(&native, hub);
+
+    let native = fresh();
+    let ids = build_mixed_type_graph(&native);
+    native.compact().unwrap();
+    assert_bfs_filtered_follows_allowed_types(&native, ids);
+
+    let native = fresh();
+    let ids = build_call_detour_graph(&native);
+    native.compact().unwrap();
+    assert_shortest_path_filtered_respects_types(&native, ids);
+}
+
+#[test]
+fn test_in_memory_factory_runs_shared_suites_on_both_backends() {
+    for cfg in [GraphConfig::sqlite(), GraphConfig::native()] {
+        let fresh = || sqlitegraph::open_graph_in_memory(&cfg).expect("backend");
+        assert_node_edges(fresh().as_ref());
+        assert_delete_middle_of_chain(fresh().as_ref());
+        assert_update_data_keeps_ids_and_edges(fresh().as_ref());
+        assert_batch_matches_single_lookups(fresh().as_ref());
+        assert_two_clusters_and_an_isolated_node(fresh().as_ref());
+        assert_edge_lookup_and_enumeration(fresh().as_ref());
+        assert_upsert_edge_dedupes_by_triple(fresh().as_ref());
+        assert_astar_matches_dijkstra(fresh().as_ref());
+        assert_all_pairs_distances(fresh().as_ref());
+        assert_betweenness_on_star_and_path(fresh().as_ref());
+        assert_statistics_on_fixture(fresh().as_ref());
+        assert_top_nodes_on_star(fresh().as_ref());
+        assert_not_found_reports_kind_and_id(fresh().as_ref());
+    }
+}
+
+#[test]
+fn test_in_memory_factory_applies_config() {
+    let mut cfg = GraphConfig::new(BackendKind::Native);
+    cfg.reject_self_loops = true;
+    let graph = sqlitegraph::open_graph_in_memory(&cfg).unwrap();
+    let node = graph.insert_node(sample_node("a")).unwrap();
+    assert!(graph.insert_edge(sample_edge(node, node, "SELF")).is_err());
+
+    let mut cfg = GraphConfig::sqlite();
+    cfg.sqlite.read_only = true;
+    assert!(matches!(
+        sqlitegraph::open_graph_in_memory(&cfg),
+        Err(SqliteGraphError::InvalidInput { .. })
+    ));
+}
@@ -1,7 +1,9 @@
 use serde_json::json;
 use sqlitegraph::{
-    GraphEdge, GraphEntity, SqliteGraph,
-    bfs::{bfs_neighbors, shortest_path},
+    BackendDirection, GraphEdge, GraphEntity, SqliteGraph, SqliteGraphError,
+    algo::bidirectional_shortest_path,
+    bfs::{bfs_iter, bfs_neighbors, shortest_path, shortest_path_directed},
+    graph_opt::cache_stats,
 };
 
 fn build_graph(edges: &[(i64, i64)]) -> SqliteGraph {
@@ -64,6 +66,22 @@ fn test_shortest_path_not_exists() {
     assert_eq!(path, None);
 }
 
+#[test]
+fn test_shortest_path_missing_endpoint_matches_directed() {
+    let graph = build_graph(&[(1, 2)]);
+    for (start, end) in [(1, 999), (999, 1), (999, 999)] {
+        let directed = shortest_path_directed(&graph, start, end, BackendDirection::Outgoing);
+        assert!(matches!(
+            directed,
+            Err(SqliteGraphError::NotFound { id: 999, .. })
+        ));
+        assert!(matches!(
+            shortest_path(&graph, start, end),
+            Err(SqliteGraphError::NotFound { id: 999, .. })
+        ));
+    }
+}
+
 #[test]
 fn test_bfs_deterministic_with_same_insert_order() {
     let edges = vec![(1, 3), (1, 2), (2, 4), (2, 5)];
@@ -98,3 +116,147 @@ fn test_shortest_path_prefers_lexicographic_neighbors() {
     let path = shortest_path(&graph, 1, 4).expect("shortest");
     assert_eq!(path, Some(vec![1, 2, 4]));
 }
+
+#[test]
+fn test_bidirectional_shortest_path_matches_forward_bfs() {
+    // Small pseudo-random graphs with many equal-length alternatives.
+    let mut state: u64 = 0x2545_f491;
+    for _ in 0..40 {
+        let mut edges = Vec::new();
+        for _ in 0..18 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let from = (state >> 33) % 10 + 1;
+            let to = (state >> 17) % 10 + 1;
+            edges.push((from as i64, to as i64));
+        }
+        let graph = build_graph(&edges);
+        for start in 1..=10 {
+            for end in 1..=10 {
+                let expected =
+                    shortest_path_directed(&graph, start, end, BackendDirection::Outgoing).unwrap();
+                assert_eq!(
+                    shortest_path(&graph, start, end).unwrap(),
+                    expected,
+                    "{start}->{end} over {edges:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bidirectional_shortest_path_visits_far_fewer_nodes_on_long_chain() {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    let node = |name: String| GraphEntity {
+        id: 0,
+        kind: "Node".to_string(),
+        name,
+        file_path: None,
+        data: json!({}),
+    };
+    let link = |from_id, to_id| GraphEdge {
+        id: 0,
+        from_id,
+        to_id,
+        edge_type: "NEXT".to_string(),
+        data: json!({}),
+    };
+    let chain: Vec<i64> = (0..10_000)
+        .map(|i| graph.insert_entity(&node(format!("n{i}"))).unwrap())
+        .collect();
+    for pair in chain.windows(2) {
+        graph.insert_edge(&link(pair[0], pair[1])).unwrap();
+    }
+    // `root` only points into the chain, so nothing in the chain reaches it.
+    let root = graph.insert_entity(&node("root".into())).unwrap();
+    graph.insert_edge(&link(root, chain[0])).unwrap();
+
+    // Every visited node costs one adjacency lookup (a cache hit or miss).
+    let lookups = |graph: &SqliteGraph| {
+        let stats = cache_stats(graph);
+        stats.hits + stats.misses
+    };
+    let reset = |graph: &SqliteGraph| {
+        graph.outgoing_cache_ref().clear();
+        graph.incoming_cache_ref().clear();
+    };
+
+    reset(&graph);
+    assert_eq!(
+        bidirectional_shortest_path(&graph, chain[0], root).unwrap(),
+        None
+    );
+    let bidirectional = lookups(&graph);
+
+    reset(&graph);
+    assert_eq!(
+        shortest_path_directed(&graph, chain[0], root, BackendDirection::Outgoing).unwrap(),
+        None
+    );
+    let forward = lookups(&graph);
+    assert!(forward >= 10_000);
+    assert!(
+        bidirectional * 100 < forward,
+        "bidirectional {bidirectional} vs forward {forward} lookups"
+    );
+
+    // Reaching across the whole chain still finds the single path.
+    let path = shortest_path(&graph, root, chain[9_999]).unwrap().unwrap();
+    assert_eq!(path.len(), 10_001);
+    assert_eq!(path[0], root);
+    assert_eq!(&path[1..], &chain[..]);
+}
+
+#[test]
+fn test_bfs_iter_matches_bfs_and_stops_early() {
+    let edges = vec![
+        (1, 2),
+        (1, 3),
+        (2, 4),
+        (2, 5),
+        (3, 6),
+        (3, 7),
+        (4, 8),
+        (5, 9),
+    ];
+    let graph = build_graph(&edges);
+    let lookups = |graph: &SqliteGraph| {
+        let stats = cache_stats(graph);
+        stats.hits + stats.misses
+    };
+    let reset = |graph: &SqliteGraph| {
+        graph.outgoing_cache_ref().clear();
+        graph.incoming_cache_ref().clear();
+    };
+
+    reset(&graph);
+    let lazy: Vec<i64> = bfs_iter(&graph, 1, 10)
+        .expect("bfs iter")
+        .collect::<Result<_, _>>()
+        .expect("nodes");
+    let full_lookups = lookups(&graph);
+    assert_eq!(lazy, bfs_neighbors(&graph, 1, 10).expect("bfs"));
+
+    let limited: Vec<i64> = bfs_iter(&graph, 1, 1)
+        .expect("bfs iter")
+        .map(|node| node.unwrap())
+        .collect();
+    assert_eq!(limited, vec![1, 2, 3]);
+
+    reset(&graph);
+    let prefix: Vec<i64> = bfs_iter(&graph, 1, 10)
+        .expect("bfs iter")
+        .map(|node| node.unwrap())
+        .take_while(|&node| node != 3)
+        .collect();
+    assert_eq!(prefix, vec![1, 2]);
+    let early_lookups = lookups(&graph);
+    assert!(
+        early_lookups < full_lookups,
+        "early {early_lookups} vs full {full_lookups} lookups"
+    );
+
+    assert!(bfs_iter(&graph, 999, 3).is_err());
+}
@@ -54,3 +54,159 @@ fn test_cache_invalidated_when_edges_change() {
     let third = graph.query().outgoing(a).expect("outgoing");
     assert_eq!(third, vec![b]);
 }
+
+#[test]
+fn test_cache_stays_warm_across_single_edge_insert() {
+    let graph = SqliteGraph::open_in_memory().unwrap();
+    let a = insert_entity(&graph, "A");
+    let b = insert_entity(&graph, "B");
+    let c = insert_entity(&graph, "C");
+    let d = insert_entity(&graph, "D");
+
+    insert_edge(&graph, a, b);
+    insert_edge(&graph, a, d);
+    assert_eq!(graph.query().outgoing(a).expect("outgoing"), vec![b, d]);
+    assert_eq!(
+        graph.query().incoming(c).expect("incoming"),
+        Vec::<i64>::new()
+    );
+    let warm_out = graph.outgoing_cache_ref().stats();
+    let warm_in = graph.incoming_cache_ref().stats();
+
+    insert_edge(&graph, a, c);
+    assert_eq!(graph.query().outgoing(a).expect("outgoing"), vec![b, c, d]);
+    assert_eq!(graph.query().incoming(c).expect("incoming"), vec![a]);
+
+    let after_out = graph.outgoing_cache_ref().stats();
+    let after_in = graph.incoming_cache_ref().stats();
+    assert_eq!(after_out.misses, warm_out.misses);
+    assert_eq!(after_out.hits, warm_out.hits + 1);
+    assert_eq!(after_in.misses, warm_in.misses);
+    assert_eq!(after_in.hits, warm_in.hits + 1);
+}
+
+#[test]
+fn test_invalidate_node_cache_drops_only_affected_entries() {
+    let graph = SqliteGraph::open_in_memory().unwrap();
+    let a = insert_entity(&graph, "A");
+    let b = insert_entity(&graph, "B");
+    let c = insert_entity(&graph, "C");
+    let d = insert_entity(&graph, "D");
+    insert_edge(&graph, a, b);
+    insert_edge(&graph, c, a);
+    insert_edge(&graph, c, d);
+
+    for id in [a, b, c, d] {
+        graph.query().outgoing(id).expect("outgoing");
+        graph.query().incoming(id).expect("incoming");
+    }
+
+    graph.invalidate_node_cache(a);
+    let outgoing = graph.outgoing_cache_ref().inner();
+    let incoming = graph.incoming_cache_ref().inner();
+    // a's own entries, b's incoming (old target) and c's outgoing (old source).
+    assert!(!outgoing.contains_key(&a) && !incoming.contains_key(&a));
+    assert!(!incoming.contains_key(&b));
+    assert!(!outgoing.contains_key(&c));
+    assert!(outgoing.contains_key(&b) && outgoing.contains_key(&d));
+    assert!(incoming.contains_key(&c) && incoming.contains_key(&d));
+
+    assert_eq!(graph.query().outgoing(c).expect("outgoing"), vec![a, d]);
+}
+
+#[test]
+fn test_warm_adjacency_cache_turns_first_lookups_into_hits() {
+    let graph = SqliteGraph::open_in_memory().unwrap();
+    let ids: Vec<i64> = ["A", "B", "C", "D"]
+        .iter()
+        .map(|name| insert_entity(&graph, name))
+        .collect();
+    let (a, b, c, d) = (ids[0], ids[1], ids[2], ids[3]);
+    for (from, to) in [(a, c), (a, b), (b, c), (a, b), (d, a)] {
+        insert_edge(&graph, from, to);
+    }
+    let clear = || {
+        graph.outgoing_cache_ref().clear();
+        graph.incoming_cache_ref().clear();
+    };
+    let lookup_all = || -> Vec<(Vec<i64>, Vec<i64>)> {
+        ids.iter()
+            .map(|&id| {
+                (
+                    graph.query().outgoing(id).expect("outgoing"),
+                    graph.query().incoming(id).expect("incoming"),
+                )
+            })
+            .collect()
+    };
+
+    clear();
+    let cold = lookup_all();
+    assert_eq!(graph.outgoing_cache_ref().stats().hits, 0);
+
+    clear();
+    graph.warm_adjacency_cache(&ids).expect("warm");
+    assert_eq!(graph.outgoing_cache_ref().stats().entries, ids.len());
+    assert_eq!(graph.incoming_cache_ref().stats().entries, ids.len());
+    assert_eq!(lookup_all(), cold);
+    for stats in [
+        graph.outgoing_cache_ref().stats(),
+        graph.incoming_cache_ref().stats(),
+    ] {
+        assert_eq!(stats.hits, ids.len() as u64);
+        assert_eq!(stats.misses, 0);
+    }
+
+    clear();
+    assert_eq!(graph.warm_all_adjacency(Some(2)).expect("warm"), 2);
+    assert_eq!(graph.outgoing_cache_ref().stats().entries, 2);
+    assert_eq!(graph.warm_all_adjacency(None).expect("warm"), ids.len());
+    assert_eq!(lookup_all(), cold);
+    assert_eq!(graph.outgoing_cache_ref().stats().misses, 0);
+}
+
+#[test]
+fn test_neighbors_uncached_sees_changes_made_behind_the_cache() {
+    use sqlitegraph::{GraphBackend, NeighborQuery, SqliteGraphBackend};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+    let backend = SqliteGraphBackend::from_graph(SqliteGraph::open(&path).unwrap());
+    let graph = backend.graph();
+    let a = insert_entity(graph, "A");
+    let b = insert_entity(graph, "B");
+    let c = insert_entity(graph, "C");
+    insert_edge(graph, a, b);
+    assert_eq!(
+        backend.neighbors(a, NeighborQuery::default()).unwrap(),
+        vec![b]
+    );
+
+    // Another connection adds an edge the cache knows nothing about
+    let external = rusqlite::Connection::open(&path).unwrap();
+    external
+        .execute(
+            "INSERT INTO graph_edges(from_id, to_id, edge_type, data) VALUES(?1, ?2, 'LINK', '{}')",
+            rusqlite::params![a, c],
+        )
+        .unwrap();
+
+    let entries = graph.outgoing_cache_ref().stats().entries;
+    assert_eq!(
+        backend
+            .neighbors_uncached(a, NeighborQuery::default())
+            .unwrap(),
+        vec![b, c]
+    );
+    assert_eq!(graph.outgoing_cache_ref().stats().entries, entries);
+    assert_eq!(
+        backend.neighbors(a, NeighborQuery::default()).unwrap(),
+        vec![b]
+    );
+
+    graph.invalidate_node_cache(a);
+    assert_eq!(
+        backend.neighbors(a, NeighborQuery::default()).unwrap(),
+        vec![b, c]
+    );
+}
@@ -0,0 +1,312 @@
+use rand::{Rng, rngs::StdRng};
+use serde_json::{Map, Value, json};
+use sqlitegraph::{
+    DataFormat, GraphBackend, GraphEdge, GraphEntity, NativeGraphBackend, NodeSpec, SqliteGraph,
+    SqliteGraphError,
+    data_format::{decode_msgpack, encode_msgpack},
+};
+use tempfile::NamedTempFile;
+
+#[path = "fuzz_common.rs"]
+mod fuzz_common;
+
+fn entity(name: &str, data: serde_json::Value) -> GraphEntity {
+    GraphEntity {
+        id: 0,
+        kind: "Doc".into(),
+        name: name.into(),
+        file_path: None,
+        data,
+    }
+}
+
+#[test]
+fn test_msgpack_roundtrip_covers_all_widths() {
+    let value = json!({
+        "null": null,
+        "flags": [true, false],
+        "ints": [0, 127, 128, 255, 256, 65535, 65536, 4294967296u64, u64::MAX,
+                 -1, -32, -33, -128, -129, -32768, -32769, -2147483649i64, i64::MIN],
+        "float": 1.5,
+        "short": "hi",
+        "medium": "m".repeat(40),
+        "long": "l".repeat(70_000),
+        "wide": (0..20).collect::<Vec<_>>(),
+        "nested": { "deeper": { "list": [[], {}] } },
+    });
+    let bytes = encode_msgpack(&value).unwrap();
+    assert_eq!(decode_msgpack(&bytes).unwrap(), value);
+    assert!(bytes.len() < value.to_string().len());
+}
+
+#[test]
+fn test_msgpack_decode_rejects_malformed_payloads() {
+    // Truncated fixstr, trailing byte, bin8, and a map with an integer key.
+    for bytes in [
+        &[0xa3, b'a'][..],
+        &[0xc0, 0xc0],
+        &[0xc4, 0x01, 0x00],
+        &[0x81, 0x01, 0xc0],
+    ] {
+        assert!(decode_msgpack(bytes).is_err(), "accepted {bytes:?}");
+    }
+    let deep = [0x91; 200];
+    assert!(decode_msgpack(&deep).is_err());
+}
+
+#[test]
+fn test_data_too_deep_to_decode_is_refused_on_write() {
+    let nest = |levels: usize| (0..levels).fold(json!(1), |inner, _| json!([inner]));
+    for format in [DataFormat::Json, DataFormat::MessagePack] {
+        let deepest = nest(127);
+        assert_eq!(
+            format.decode(&format.encode(&deepest).unwrap()).unwrap(),
+            deepest
+        );
+        assert!(matches!(
+            format.encode(&nest(128)),
+            Err(SqliteGraphError::InvalidInput { .. })
+        ));
+    }
+    let deep_map = (0..128).fold(json!(null), |inner, _| json!({ "k": inner }));
+    assert!(encode_msgpack(&deep_map).is_err());
+
+    let mut graph = SqliteGraph::open_in_memory().unwrap();
+    graph.set_data_format(DataFormat::MessagePack).unwrap();
+    assert!(matches!(
+        graph.insert_entity(&entity("deep", nest(128))),
+        Err(SqliteGraphError::InvalidInput { .. })
+    ));
+    let native = NativeGraphBackend::in_memory().unwrap();
+    native.set_data_format(DataFormat::MessagePack).unwrap();
+    let spec = NodeSpec {
+        kind: "Doc".into(),
+        name: "deep".into(),
+        file_path: None,
+        data: nest(128),
+    };
+    assert!(matches!(
+        native.insert_node(spec),
+        Err(SqliteGraphError::InvalidInput { .. })
+    ));
+}
+
+#[test]
+fn test_set_data_format_needs_the_settings_table() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut graph = SqliteGraph::open_without_migrations(temp_file.path()).unwrap();
+    assert!(matches!(
+        graph.set_data_format(DataFormat::MessagePack),
+        Err(SqliteGraphError::SchemaError { .. })
+    ));
+    assert_eq!(graph.data_format(), DataFormat::Json);
+}
+
+fn random_string(rng: &mut StdRng) -> String {
+    // Lengths straddle the fixstr/str8/str16 boundaries; some chars are multi-byte.
+    let len = match rng.gen_range(0..4) {
+        0 => rng.gen_range(0..32),
+        1 => rng.gen_range(28..36),
+        2 => rng.gen_range(250..262),
+        _ => rng.gen_range(0..8),
+    };
+    (0..len)
+        .map(|_| match rng.gen_range(0..4) {
+            0 => rng.gen_range('\u{80}'..'\u{800}'),
+            1 => rng.gen_range('\u{10000}'..'\u{10ffff}'),
+            _ => rng.gen_range('a'..='z'),
+        })
+        .collect()
+}
+
+fn random_number(rng: &mut StdRng) -> Value {
+    let bits = [4, 7, 8, 15, 16, 31, 32, 63, 64][rng.gen_range(0..9)];
+    let magnitude = rng.r#gen::<u64>() >> (64 - bits);
+    match rng.gen_range(0..3) {
+        0 => json!(magnitude),
+        1 => json!(-(magnitude.min(i64::MAX as u64) as i64) - 1),
+        // Eighths below 2^37 print with few enough digits that serde_json parses them exactly.
+        _ => json!(rng.gen_range(-(1i64 << 40)..1i64 << 40) as f64 / 8.0),
+    }
+}
+
+fn random_value(rng: &mut StdRng, depth: usize) -> Value {
+    let leaf = depth == 0 || rng.gen_bool(0.6);
+    match rng.gen_range(0..if leaf { 4 } else { 6 }) {
+        0 => Value::Null,
+        1 => Value::Bool(rng.r#gen()),
+        2 => random_number(rng),
+        3 => Value::String(random_string(rng)),
+        4 => {
+            let len = rng.gen_range(0..20);
+            Value::Array((0..len).map(|_| random_value(rng, depth - 1)).collect())
+        }
+        _ => {
+            let len = rng.gen_range(0..20);
+            let map: Map<String, Value> = (0..len)
+                .map(|_| (random_string(rng), random_value(rng, depth - 1)))
+                .collect();
+            Value::Object(map)
+        }
+    }
+}
+
+/// Encoded size per the MessagePack spec, always choosing the narrowest format.
+fn spec_encoded_len(value: &Value) -> usize {
+    fn len_header(len: usize, fix_limit: usize) -> usize {
+        if len < fix_limit {
+            1
+        } else if len <= u16::MAX as usize {
+            3
+        } else {
+            5
+        }
+    }
+    fn str_len(text: &str) -> usize {
+        let header = match text.len() {
+            0..=31 => 1,
+            32..=255 => 2,
+            256..=65535 => 3,
+            _ => 5,
+        };
+        header + text.len()
+    }
+    match value {
+        Value::Null | Value::Bool(_) => 1,
+        Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+            (Some(v), _) if v < 0x80 => 1,
+            (Some(v), _) if v <= u8::MAX as u64 => 2,
+            (Some(v), _) if v <= u16::MAX as u64 => 3,
+            (Some(v), _) if v <= u32::MAX as u64 => 5,
+            (Some(_), _) => 9,
+            (None, Some(v)) if v >= -32 => 1,
+            (None, Some(v)) if v >= i8::MIN as i64 => 2,
+            (None, Some(v)) if v >= i16::MIN as i64 => 3,
+            (None, Some(v)) if v >= i32::MIN as i64 => 5,
+            _ => 9,
+        },
+        Value::String(text) => str_len(text),
+        Value::Array(items) => {
+            len_header(items.len(), 16) + items.iter().map(spec_encoded_len).sum::<usize>()
+        }
+        Value::Object(map) => {
+            len_header(map.len(), 16)
+                + map
+                    .iter()
+                    .map(|(key, item)| str_len(key) + spec_encoded_len(item))
+                    .sum::<usize>()
+        }
+    }
+}
+
+#[test]
+fn test_msgpack_random_values_match_json_roundtrip() {
+    let mut rng = fuzz_common::labeled_rng("msgpack_roundtrip");
+    for _ in 0..fuzz_common::fuzz_iterations() {
+        let value = random_value(&mut rng, 4);
+        let bytes = encode_msgpack(&value).unwrap();
+        let decoded = decode_msgpack(&bytes).unwrap();
+        // serde_json is the reference: both encodings must restore the same value.
+        let via_json: Value = serde_json::from_str(&value.to_string()).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(decoded, via_json);
+        assert_eq!(bytes.len(), spec_encoded_len(&value), "{value}");
+        assert_eq!(encode_msgpack(&decoded).unwrap(), bytes);
+    }
+}
+
+#[test]
+fn test_msgpack_damaged_payloads_error_without_panicking() {
+    let mut rng = fuzz_common::labeled_rng("msgpack_damage");
+    for _ in 0..fuzz_common::fuzz_iterations() {
+        let value = random_value(&mut rng, 3);
+        let bytes = encode_msgpack(&value).unwrap();
+        let cut = rng.gen_range(0..bytes.len());
+        assert!(
+            decode_msgpack(&bytes[..cut]).is_err(),
+            "accepted prefix of {value}"
+        );
+
+        let mut flipped = bytes.clone();
+        let at = rng.gen_range(0..flipped.len());
+        flipped[at] ^= 1 << rng.gen_range(0..8);
+        if let Ok(decoded) = decode_msgpack(&flipped) {
+            assert_eq!(
+                decode_msgpack(&encode_msgpack(&decoded).unwrap()).unwrap(),
+                decoded
+            );
+        }
+    }
+}
+
+#[test]
+fn test_sqlite_mixed_formats_decode_and_persist() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let (json_id, msgpack_id) = {
+        let mut graph = SqliteGraph::open(temp_file.path()).unwrap();
+        assert_eq!(graph.data_format(), DataFormat::Json);
+        let json_id = graph
+            .insert_entity(&entity("a", json!({ "v": 1 })))
+            .unwrap();
+        graph.set_data_format(DataFormat::MessagePack).unwrap();
+        let msgpack_id = graph
+            .insert_entity(&entity("b", json!({ "v": [2, "x"] })))
+            .unwrap();
+        graph
+            .insert_edge(&GraphEdge {
+                id: 0,
+                from_id: json_id,
+                to_id: msgpack_id,
+                edge_type: "LINK".into(),
+                data: json!({ "w": 0.25 }),
+            })
+            .unwrap();
+        (json_id, msgpack_id)
+    };
+
+    let graph = SqliteGraph::open(temp_file.path()).unwrap();
+    assert_eq!(graph.data_format(), DataFormat::MessagePack);
+    let conn = rusqlite::Connection::open(temp_file.path()).unwrap();
+    let storage: Vec<String> = [json_id, msgpack_id]
+        .iter()
+        .map(|id| {
+            conn.query_row(
+                "SELECT typeof(data) FROM graph_entities WHERE id=?1",
+                [id],
+                |row| row.get(0),
+            )
+            .unwrap()
+        })
+        .collect();
+    assert_eq!(storage, vec!["text", "blob"]);
+    assert_eq!(graph.get_entity(json_id).unwrap().data, json!({ "v": 1 }));
+    assert_eq!(
+        graph.get_entity(msgpack_id).unwrap().data,
+        json!({ "v": [2, "x"] })
+    );
+    assert_eq!(graph.query().outgoing(json_id).unwrap(), vec![msgpack_id]);
+}
+
+#[test]
+fn test_native_data_format_lives_in_header() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let data = json!({ "tags": ["a", "b"], "score": -7 });
+    let id = {
+        let native = NativeGraphBackend::new(temp_file.path()).unwrap();
+        native.set_data_format(DataFormat::MessagePack).unwrap();
+        let id = native
+            .insert_node(NodeSpec {
+                kind: "Doc".into(),
+                name: "n".into(),
+                file_path: None,
+                data: data.clone(),
+            })
+            .unwrap();
+        assert!(native.set_data_format(DataFormat::Json).is_err());
+        id
+    };
+
+    let native = NativeGraphBackend::open(temp_file.path()).unwrap();
+    assert_eq!(native.data_format(), DataFormat::MessagePack);
+    assert_eq!(native.get_node(id).unwrap().data, data);
+}
@@ -0,0 +1,216 @@
+use std::error::Error;
+
+use serde_json::json;
+use sqlitegraph::SqliteGraphError;
+use sqlitegraph::backend::{
+    BackendDirection, EdgeSpec, GraphBackend, NodeSpec, SqliteGraphBackend,
+};
+use sqlitegraph::dsl::{DslError, DslResult, execute, parse_dsl};
+use sqlitegraph::index::{add_label, add_property};
+use sqlitegraph::pattern::{PatternLeg, PatternMatch, PatternQuery};
+
+fn parse_error(input: &str) -> DslError {
+    match parse_dsl(input) {
+        DslResult::Error(err) => err,
+        other => panic!("expected a parse error for {input:?}, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_filtered_path_compiles_to_triple() {
+    let DslResult::Triple(triple) = parse_dsl(
+        r#"(n:Function {language:"rust", visibility: pub})-[:CALLS]->(m:Function {lines: 10})"#,
+    ) else {
+        panic!("expected a triple");
+    };
+    assert_eq!(triple.edge_type, "CALLS");
+    assert_eq!(triple.direction, BackendDirection::Outgoing);
+    assert_eq!(triple.start_label.as_deref(), Some("Function"));
+    assert_eq!(triple.end_label.as_deref(), Some("Function"));
+    assert_eq!(triple.start_props.len(), 2);
+    assert_eq!(triple.start_props["language"], "rust");
+    assert_eq!(triple.start_props["visibility"], "pub");
+    assert_eq!(triple.end_props["lines"], "10");
+    assert!(triple.hops.is_none());
+
+    let DslResult::Triple(triple) = parse_dsl("(a)<-[:USES*1..3]-(b:Module)") else {
+        panic!("expected a triple");
+    };
+    assert_eq!(triple.direction, BackendDirection::Incoming);
+    assert_eq!(triple.start_label, None);
+    assert_eq!(triple.end_label.as_deref(), Some("Module"));
+    assert_eq!(triple.hops, Some(1..3));
+}
+
+#[test]
+fn test_unfiltered_path_compiles_to_pattern_query() {
+    let DslResult::Pattern(query) = parse_dsl("(a)-[:CALLS]->(b)<-[:USES*2]-(c)-[]-(d)") else {
+        panic!("expected a pattern query");
+    };
+    let legs: Vec<_> = query
+        .legs
+        .iter()
+        .map(|leg| (leg.direction, leg.edge_type.as_deref()))
+        .collect();
+    assert_eq!(
+        legs,
+        vec![
+            (BackendDirection::Outgoing, Some("CALLS")),
+            (BackendDirection::Incoming, Some("USES")),
+            (BackendDirection::Incoming, Some("USES")),
+            (BackendDirection::Both, None),
+        ]
+    );
+    assert!(query.legs.iter().all(|leg| leg.constraint.is_none()));
+
+    // The edge-type chain form still works
+    let DslResult::Pattern(query) = parse_dsl("CALLS->USES*2") else {
+        panic!("expected a pattern query");
+    };
+    assert_eq!(query.legs.len(), 3);
+}
+
+#[test]
+fn test_parse_errors_report_columns() {
+    let err = parse_error(r#"(n:Function {language:"rust")-[:CALLS]->(m)"#);
+    assert_eq!(err.column, 29);
+    assert_eq!(err.message, "expected '}', found ')'");
+
+    let err = parse_error("(n)-[:CALLS]=>(m)");
+    assert_eq!(
+        (err.column, err.message.as_str()),
+        (13, "expected '-', found '='")
+    );
+
+    let err = parse_error(r#"(n {name: "open)-[:CALLS]->(m)"#);
+    assert_eq!(
+        (err.column, err.message.as_str()),
+        (11, "unterminated string")
+    );
+
+    let err = parse_error("(a)-[:CALLS]->(b:Module)-[:USES]->(c)");
+    assert_eq!(err.column, 15);
+    assert_eq!(err.to_string(), format!("column 15: {}", err.message));
+
+    let err = parse_error("(a:Module)<-[:USES]->(b)");
+    assert_eq!(
+        (err.column, err.message.as_str()),
+        (11, "a relationship cannot point both ways")
+    );
+
+    assert_eq!(
+        parse_error("(a)").message,
+        "expected a relationship after the node"
+    );
+}
+
+/// `f0 -CALLS-> f1 -CALLS-> f2`, `f0 -CALLS-> f2`, `f1 -USES-> m` and
+/// `f2 -USES-> m`; f0 and f1 are labelled `Function` with language rust
+fn seed() -> (SqliteGraphBackend, Vec<i64>) {
+    let graph = SqliteGraphBackend::in_memory().unwrap();
+    let ids: Vec<i64> = ["f0", "f1", "f2", "m"]
+        .iter()
+        .map(|name| {
+            graph
+                .insert_node(NodeSpec {
+                    kind: "Symbol".into(),
+                    name: name.to_string(),
+                    file_path: None,
+                    data: json!({}),
+                })
+                .unwrap()
+        })
+        .collect();
+    for (from, to, edge_type) in [
+        (0, 1, "CALLS"),
+        (1, 2, "CALLS"),
+        (0, 2, "CALLS"),
+        (1, 3, "USES"),
+        (2, 3, "USES"),
+    ] {
+        graph
+            .insert_edge(EdgeSpec {
+                from: ids[from],
+                to: ids[to],
+                edge_type: edge_type.into(),
+                data: json!({}),
+            })
+            .unwrap();
+    }
+    for &id in &ids[..2] {
+        add_label(graph.graph(), id, "Function").unwrap();
+        add_property(graph.graph(), id, "language", "rust").unwrap();
+    }
+    (graph, ids)
+}
+
+fn leg(direction: BackendDirection, edge_type: &str) -> PatternLeg {
+    PatternLeg {
+        direction,
+        edge_type: Some(edge_type.into()),
+        constraint: None,
+    }
+}
+
+#[test]
+fn test_execute_matches_programmatic_pattern_search() {
+    let (graph, ids) = seed();
+    let pattern = PatternQuery {
+        legs: vec![
+            leg(BackendDirection::Outgoing, "CALLS"),
+            leg(BackendDirection::Outgoing, "USES"),
+        ],
+        ..PatternQuery::default()
+    };
+    let mut expected = Vec::new();
+    for &id in &ids {
+        expected.extend(graph.pattern_search(id, &pattern).unwrap());
+    }
+    let found = execute(&graph, "(a)-[:CALLS]->(b)-[:USES]->(c)").unwrap();
+    assert_eq!(found, expected);
+    assert_eq!(found.len(), 3);
+    assert_eq!(execute(&graph, "CALLS->USES").unwrap(), expected);
+
+    let single = PatternQuery {
+        legs: vec![leg(BackendDirection::Incoming, "USES")],
+        ..PatternQuery::default()
+    };
+    assert_eq!(
+        execute(&graph, "(m)<-[:USES]-(f)").unwrap(),
+        graph.pattern_search(ids[3], &single).unwrap()
+    );
+}
+
+#[test]
+fn test_execute_applies_filters_and_hop_ranges() {
+    let (graph, ids) = seed();
+    let path = |nodes: &[usize]| PatternMatch {
+        nodes: nodes.iter().map(|&idx| ids[idx]).collect(),
+    };
+    assert_eq!(
+        execute(
+            &graph,
+            r#"(a:Function {language: "rust"})-[:CALLS]->(b:Function)"#
+        )
+        .unwrap(),
+        vec![path(&[0, 1])]
+    );
+    assert_eq!(
+        execute(&graph, "(a:Function)-[:CALLS*1..2]->(b)").unwrap(),
+        vec![
+            path(&[0, 1]),
+            path(&[0, 1, 2]),
+            path(&[0, 2]),
+            path(&[1, 2])
+        ]
+    );
+}
+
+#[test]
+fn test_execute_reports_parse_errors_as_invalid_input() {
+    let (graph, _) = seed();
+    let err = execute(&graph, "(a)-[:CALLS->(b)").unwrap_err();
+    assert!(matches!(err, SqliteGraphError::InvalidInput { .. }));
+    let source = err.source().unwrap().downcast_ref::<DslError>().unwrap();
+    assert_eq!(source.column, 12);
+}
@@ -1,9 +1,14 @@
 use serde_json::json;
 use sqlitegraph::backend::{
-    BackendDirection, EdgeSpec, GraphBackend, NeighborQuery, NodeSpec, SqliteGraphBackend,
+    BackendDirection, EdgeSpec, GraphBackend, NativeGraphBackend, NeighborQuery, NodeSpec,
+    SqliteGraphBackend,
 };
 use sqlitegraph::backend_selector::{BackendKind, GraphBackendFactory};
-use sqlitegraph::dual_runner::{DualRunConfig, DualRunResult, run_dual_check};
+use sqlitegraph::dual_runner::{
+    DualDumpResult, DualRunConfig, DualRunResult, GoldenGraph, Operation, build_golden,
+    compare_backends, dump_normalized, run_dual_check, run_dual_dump_check,
+};
+use sqlitegraph::pattern::{PatternLeg, PatternQuery};
 
 fn seed(factory: &GraphBackendFactory, edges: &[(usize, usize)]) -> (SqliteGraphBackend, Vec<i64>) {
     let backend = factory.new_sqlite().unwrap();
@@ -65,3 +70,173 @@ fn test_dual_run_reports_mismatch() {
     let result = run_dual_check(base, other, config).unwrap();
     assert!(matches!(result, DualRunResult::Mismatch { .. }));
 }
+
+#[test]
+fn test_dual_dump_matches_for_identical_graphs() {
+    let factory = GraphBackendFactory::new(BackendKind::Sqlite);
+    let golden = GoldenGraph::standard();
+    let result = run_dual_dump_check(
+        factory.new_sqlite().unwrap(),
+        factory.new_sqlite().unwrap(),
+        &golden,
+    )
+    .unwrap();
+    assert_eq!(result, DualDumpResult::Match);
+}
+
+#[test]
+fn test_dual_dump_normalizes_ids_and_reports_divergence() {
+    let factory = GraphBackendFactory::new(BackendKind::Sqlite);
+    let golden = GoldenGraph::standard();
+
+    // Shift the ids of one backend; normalization must hide the offset.
+    let shifted = factory.new_sqlite().unwrap();
+    for _ in 0..3 {
+        shifted
+            .insert_node(NodeSpec {
+                kind: "Padding".into(),
+                name: "pad".into(),
+                file_path: None,
+                data: json!({}),
+            })
+            .unwrap();
+    }
+    let base = factory.new_sqlite().unwrap();
+    let base_ids = build_golden(&base, &golden).unwrap();
+    let shifted_ids = build_golden(&shifted, &golden).unwrap();
+    assert_ne!(base_ids, shifted_ids);
+    assert_eq!(
+        dump_normalized(&base, &golden, &base_ids).unwrap(),
+        dump_normalized(&shifted, &golden, &shifted_ids).unwrap()
+    );
+
+    let mut dropped = golden.clone();
+    dropped.edges.pop();
+    let base = factory.new_sqlite().unwrap();
+    let other = factory.new_sqlite().unwrap();
+    let base_ids = build_golden(&base, &golden).unwrap();
+    let other_ids = build_golden(&other, &dropped).unwrap();
+    assert_ne!(
+        dump_normalized(&base, &golden, &base_ids).unwrap(),
+        dump_normalized(&other, &golden, &other_ids).unwrap()
+    );
+}
+
+#[test]
+fn test_dual_dump_sqlite_matches_native() {
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = NativeGraphBackend::new(temp_file.path()).unwrap();
+    let sqlite = SqliteGraphBackend::in_memory().unwrap();
+    let golden = GoldenGraph::standard();
+    let sqlite_ids = build_golden(&sqlite, &golden).unwrap();
+    let native_ids = build_golden(&native, &golden).unwrap();
+    let sqlite_dump = dump_normalized(&sqlite, &golden, &sqlite_ids).unwrap();
+    let native_dump = dump_normalized(&native, &golden, &native_ids).unwrap();
+
+    // Known native limitation: node file_paths are not stored and dump as null
+    let without_file_path = |line: &str, stored: bool| {
+        let mut value: serde_json::Value = serde_json::from_str(line).unwrap();
+        if let Some(file_path) = value.as_object_mut().unwrap().remove("file_path") {
+            assert_eq!(file_path.is_string(), stored, "{line}");
+        }
+        value
+    };
+    assert_eq!(sqlite_dump.len(), native_dump.len());
+    for (sqlite_line, native_line) in sqlite_dump.iter().zip(&native_dump) {
+        assert_eq!(
+            without_file_path(sqlite_line, true),
+            without_file_path(native_line, false)
+        );
+    }
+}
+
+/// Chain 0 -> 1 -> 2 plus 0 -> 3, followed by one read of each kind.
+fn comparison_script() -> Vec<Operation> {
+    let mut ops: Vec<Operation> = (0..4)
+        .map(|idx| {
+            Operation::InsertNode(NodeSpec {
+                kind: "Node".into(),
+                name: format!("n{idx}"),
+                file_path: None,
+                data: json!({ "idx": idx }),
+            })
+        })
+        .collect();
+    for (from, to, edge_type) in [(0, 1, "CALLS"), (1, 2, "CALLS"), (0, 3, "USES")] {
+        ops.push(Operation::InsertEdge {
+            from,
+            to,
+            edge_type: edge_type.into(),
+            data: json!({}),
+        });
+    }
+    ops.push(Operation::Neighbors {
+        node: 0,
+        query: NeighborQuery {
+            direction: BackendDirection::Outgoing,
+            edge_type: None,
+        },
+    });
+    ops.push(Operation::Bfs { start: 0, depth: 2 });
+    ops.push(Operation::Pattern {
+        start: 0,
+        pattern: PatternQuery {
+            legs: vec![PatternLeg {
+                direction: BackendDirection::Outgoing,
+                edge_type: Some("CALLS".into()),
+                constraint: None,
+            }],
+            ..PatternQuery::default()
+        },
+    });
+    ops
+}
+
+#[test]
+fn test_compare_backends_agrees_on_matching_pair() {
+    let factory = GraphBackendFactory::new(BackendKind::Sqlite);
+    let script = comparison_script();
+    let report = compare_backends(
+        &factory.new_sqlite().unwrap(),
+        &factory.new_sqlite().unwrap(),
+        &script,
+    )
+    .unwrap();
+    assert!(report.is_match(), "{report:?}");
+    assert_eq!(report.steps_run, script.len());
+}
+
+#[test]
+fn test_compare_backends_agrees_on_sqlite_and_native() {
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let native = NativeGraphBackend::new(temp_file.path()).unwrap();
+    let sqlite = SqliteGraphBackend::in_memory().unwrap();
+    let report = compare_backends(&sqlite, &native, &comparison_script()).unwrap();
+    assert!(report.is_match(), "{report:?}");
+}
+
+#[test]
+fn test_compare_backends_flags_divergent_backend() {
+    // A stray node left in one backend shifts its state from the first write on
+    let stub = SqliteGraphBackend::in_memory().unwrap();
+    stub.insert_node(NodeSpec {
+        kind: "Stray".into(),
+        name: "stray".into(),
+        file_path: None,
+        data: json!({}),
+    })
+    .unwrap();
+    let sqlite = SqliteGraphBackend::in_memory().unwrap();
+    let report = compare_backends(&sqlite, &stub, &comparison_script()).unwrap();
+    let divergence = report.divergence.expect("divergence");
+    assert_eq!((divergence.step, report.steps_run), (0, 1));
+    assert!(divergence.native.contains("Stray"), "{}", divergence.native);
+
+    // Referring to a node the script never inserted is a script error
+    let err =
+        compare_backends(&sqlite, &stub, &[Operation::Bfs { start: 9, depth: 1 }]).unwrap_err();
+    assert!(matches!(
+        err,
+        sqlitegraph::SqliteGraphError::InvalidInput { .. }
+    ));
+}
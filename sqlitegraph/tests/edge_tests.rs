@@ -1,5 +1,7 @@
 use serde_json::json;
-use sqlitegraph::{GraphEdge, GraphEntity, SqliteGraph, SqliteGraphError};
+use sqlitegraph::{
+    GraphEdge, GraphEdgeCreate, GraphEntity, SqliteGraph, SqliteGraphError, bulk_insert_edges,
+};
 
 fn sample_entity(name: &str) -> GraphEntity {
     GraphEntity {
@@ -73,9 +75,10 @@ fn test_delete_edge_removes_record() {
     let id = graph
         .insert_edge(&sample_edge(1, 2, "DECLARES"))
         .expect("edge");
-    graph.delete_edge(id).expect("delete");
+    let outcome = graph.delete_edge(id).expect("delete");
+    assert_eq!(outcome.affected, 1);
     let err = graph.get_edge(id).expect_err("missing");
-    assert!(matches!(err, SqliteGraphError::NotFound(_)));
+    assert!(matches!(err, SqliteGraphError::NotFound { .. }));
 }
 
 #[test]
@@ -94,7 +97,7 @@ fn test_insert_edge_requires_type() {
     let mut edge = sample_edge(1, 2, "");
     edge.edge_type.clear();
     let err = graph.insert_edge(&edge).expect_err("invalid");
-    assert!(matches!(err, SqliteGraphError::InvalidInput(_)));
+    assert!(matches!(err, SqliteGraphError::InvalidInput { .. }));
 }
 
 #[test]
@@ -107,9 +110,138 @@ fn test_insert_edge_allows_self_loops() {
     assert_eq!(stored.to_id, 1);
 }
 
+#[test]
+fn test_bulk_insert_edges_honors_reject_self_loops() {
+    let mut graph = prepared_graph();
+    graph.set_reject_self_loops(true);
+    let entry = |from_id, to_id| GraphEdgeCreate {
+        from_id,
+        to_id,
+        edge_type: "CALLS".into(),
+        data: json!({}),
+    };
+    let err = bulk_insert_edges(&graph, &[entry(1, 2), entry(3, 3)]).expect_err("self loop");
+    assert!(matches!(err, SqliteGraphError::InvalidInput { .. }));
+    // The chunk is rolled back as a whole
+    assert!(graph.query().outgoing(1).unwrap().is_empty());
+}
+
 #[test]
 fn test_get_edge_not_found_returns_error() {
     let graph = prepared_graph();
     let err = graph.get_edge(44).expect_err("missing");
-    assert!(matches!(err, SqliteGraphError::NotFound(_)));
+    assert!(matches!(err, SqliteGraphError::NotFound { .. }));
+}
+
+#[test]
+fn test_deferred_integrity_allows_edges_before_nodes() {
+    let mut graph = SqliteGraph::open_in_memory().expect("graph");
+    assert!(graph.insert_edge(&sample_edge(1, 2, "CALLS")).is_err());
+
+    graph.set_deferred_integrity(true);
+    let early = graph
+        .insert_edge(&sample_edge(1, 2, "CALLS"))
+        .expect("deferred edge");
+    let orphan = graph
+        .insert_edge(&sample_edge(2, 7, "CALLS"))
+        .expect("deferred edge");
+    for name in ["a", "b"] {
+        graph.insert_entity(&sample_entity(name)).expect("entity");
+    }
+
+    let report = graph.check_integrity().expect("integrity");
+    assert!(!report.is_clean());
+    assert_eq!(report.dangling_edges.len(), 1);
+    let dangling = &report.dangling_edges[0];
+    assert_eq!(dangling.edge_id, orphan);
+    assert!(!dangling.missing_from);
+    assert!(dangling.missing_to);
+    assert_eq!(graph.query().outgoing(1).expect("outgoing"), vec![2]);
+    assert_ne!(early, orphan);
+}
+
+#[test]
+fn test_induced_subgraph_keeps_only_internal_edges() {
+    let graph = prepared_graph();
+    let mut internal = Vec::new();
+    for &(from, to) in &[(3, 1), (1, 2), (2, 4), (4, 3), (1, 3), (3, 3)] {
+        let id = graph
+            .insert_edge(&sample_edge(from, to, "USES"))
+            .expect("edge");
+        if from != 2 && to != 2 {
+            internal.push(id);
+        }
+    }
+
+    let subgraph = graph.induced_subgraph(&[4, 1, 3, 1]).expect("subgraph");
+    let node_ids: Vec<i64> = subgraph.nodes.iter().map(|node| node.id).collect();
+    let edge_ids: Vec<i64> = subgraph.edges.iter().map(|edge| edge.id).collect();
+    assert_eq!(node_ids, vec![1, 3, 4]);
+    assert_eq!(edge_ids, internal);
+    assert_eq!(subgraph.nodes[0], graph.get_entity(1).unwrap());
+    assert!(
+        subgraph
+            .edges
+            .iter()
+            .all(|edge| edge.from_id != 2 && edge.to_id != 2)
+    );
+
+    assert!(graph.induced_subgraph(&[]).expect("empty").nodes.is_empty());
+    assert!(matches!(
+        graph.induced_subgraph(&[1, 99]),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+    graph.soft_delete_node(4).unwrap();
+    assert!(matches!(
+        graph.induced_subgraph(&[1, 4]),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn test_ego_subgraph_by_radius_and_direction() {
+    use sqlitegraph::BackendDirection;
+
+    let graph = prepared_graph();
+    let e = graph.insert_entity(&sample_entity("e")).expect("entity");
+    // 1 -> 2 -> 3 -> 4 and e -> 1, plus a shortcut 3 -> 1 back to the center
+    for &(from, to) in &[(1, 2), (2, 3), (3, 4), (e, 1), (3, 1)] {
+        graph
+            .insert_edge(&sample_edge(from, to, "NEXT"))
+            .expect("edge");
+    }
+    let members = |radius, direction| {
+        let subgraph = graph.ego_subgraph(1, radius, direction).expect("ego");
+        let nodes: Vec<i64> = subgraph.nodes.iter().map(|node| node.id).collect();
+        let edges: Vec<(i64, i64)> = subgraph
+            .edges
+            .iter()
+            .map(|edge| (edge.from_id, edge.to_id))
+            .collect();
+        (nodes, edges)
+    };
+
+    assert_eq!(members(0, BackendDirection::Outgoing), (vec![1], vec![]));
+    assert_eq!(
+        members(1, BackendDirection::Outgoing),
+        (vec![1, 2], vec![(1, 2)])
+    );
+    assert_eq!(
+        members(2, BackendDirection::Outgoing),
+        (vec![1, 2, 3], vec![(1, 2), (2, 3), (3, 1)])
+    );
+    assert_eq!(
+        members(1, BackendDirection::Incoming),
+        (vec![1, 3, e], vec![(e, 1), (3, 1)])
+    );
+    assert_eq!(
+        members(1, BackendDirection::Both),
+        (vec![1, 2, 3, e], vec![(1, 2), (2, 3), (e, 1), (3, 1)])
+    );
+    assert_eq!(members(2, BackendDirection::Both).0, vec![1, 2, 3, 4, e]);
+
+    assert!(matches!(
+        graph.ego_subgraph(99, 1, BackendDirection::Outgoing),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
 }
@@ -52,7 +52,8 @@ fn test_entity_update_persists_changes() {
     entity.id = id;
     entity.name = "BetaRenamed".to_string();
     entity.file_path = Some("src/lib.rs".to_string());
-    graph.update_entity(&entity).expect("update");
+    let outcome = graph.update_entity(&entity).expect("update");
+    assert_eq!(outcome.affected, 1);
     let stored = graph.get_entity(id).expect("get");
     assert_eq!(stored.name, "BetaRenamed");
     assert_eq!(stored.file_path.as_deref(), Some("src/lib.rs"));
@@ -67,7 +68,7 @@ fn test_entity_delete_removes_record() {
     graph.delete_entity(id).expect("delete");
     let err = graph.get_entity(id).expect_err("missing");
     match err {
-        SqliteGraphError::NotFound(_) => {}
+        SqliteGraphError::NotFound { .. } => {}
         other => panic!("expected NotFound, got {other:?}"),
     }
 }
@@ -93,7 +94,7 @@ fn test_insert_entity_requires_name() {
     entity.name.clear();
     let err = graph.insert_entity(&entity).expect_err("invalid");
     match err {
-        SqliteGraphError::InvalidInput(_) => {}
+        SqliteGraphError::InvalidInput { .. } => {}
         other => panic!("expected InvalidInput, got {other:?}"),
     }
 }
@@ -115,5 +116,308 @@ fn test_insert_entity_assigns_incrementing_ids() {
 fn test_get_entity_not_found_returns_error() {
     let graph = SqliteGraph::open_in_memory().expect("graph");
     let err = graph.get_entity(999).expect_err("missing");
-    assert!(matches!(err, SqliteGraphError::NotFound(_)));
+    assert!(matches!(err, SqliteGraphError::NotFound { .. }));
+}
+
+#[test]
+fn test_max_data_bytes_rejects_oversized_insert_and_update() {
+    let mut graph = SqliteGraph::open_in_memory().expect("graph");
+    graph.set_max_data_bytes(Some(64));
+    let id = graph
+        .insert_entity(&sample_entity("Node", "small"))
+        .expect("within limit");
+
+    let mut oversized = sample_entity("Node", "big");
+    oversized.data = json!({ "blob": "x".repeat(256) });
+    let err = graph.insert_entity(&oversized).expect_err("too large");
+    assert!(matches!(err, SqliteGraphError::InvalidInput { .. }));
+
+    oversized.id = id;
+    let err = graph.update_entity(&oversized).expect_err("too large");
+    assert!(matches!(err, SqliteGraphError::InvalidInput { .. }));
+    assert_eq!(graph.get_entity(id).unwrap().name, "small");
+}
+
+#[test]
+fn test_entity_delete_reports_cascaded_edges() {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    let ids: Vec<i64> = ["a", "b", "c"]
+        .iter()
+        .map(|name| graph.insert_entity(&sample_entity("Fn", name)).unwrap())
+        .collect();
+    for &(from, to) in &[(0, 1), (2, 0), (1, 2)] {
+        graph
+            .insert_edge(&sqlitegraph::GraphEdge {
+                id: 0,
+                from_id: ids[from],
+                to_id: ids[to],
+                edge_type: "CALLS".into(),
+                data: json!({}),
+            })
+            .unwrap();
+    }
+    let outcome = graph.delete_entity(ids[0]).expect("delete");
+    assert_eq!(outcome.affected, 3);
+}
+
+#[test]
+fn test_soft_delete_hides_node_until_undeleted() {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    let ids: Vec<i64> = ["a", "b", "c"]
+        .iter()
+        .map(|name| graph.insert_entity(&sample_entity("Fn", name)).unwrap())
+        .collect();
+    for &(from, to) in &[(0, 1), (1, 2)] {
+        graph
+            .insert_edge(&sqlitegraph::GraphEdge {
+                id: 0,
+                from_id: ids[from],
+                to_id: ids[to],
+                edge_type: "CALLS".into(),
+                data: json!({}),
+            })
+            .unwrap();
+    }
+    assert_eq!(
+        sqlitegraph::bfs::bfs_neighbors(&graph, ids[0], 2).unwrap(),
+        ids
+    );
+
+    graph.soft_delete_node(ids[1]).expect("soft delete");
+    let err = graph.get_entity(ids[1]).expect_err("hidden");
+    assert!(matches!(err, SqliteGraphError::NotFound { .. }));
+    assert_eq!(graph.get_node_including_deleted(ids[1]).unwrap().name, "b");
+    assert!(graph.deleted_at(ids[1]).unwrap().is_some());
+    assert_eq!(graph.list_entity_ids().unwrap(), vec![ids[0], ids[2]]);
+    assert_eq!(
+        sqlitegraph::bfs::bfs_neighbors(&graph, ids[0], 2).unwrap(),
+        vec![ids[0]]
+    );
+    assert!(graph.query().incoming(ids[2]).unwrap().is_empty());
+
+    graph.undelete_node(ids[1]).expect("undelete");
+    assert!(graph.deleted_at(ids[1]).unwrap().is_none());
+    assert_eq!(graph.get_entity(ids[1]).unwrap().name, "b");
+    assert_eq!(
+        sqlitegraph::bfs::bfs_neighbors(&graph, ids[0], 2).unwrap(),
+        ids
+    );
+
+    let err = graph.soft_delete_node(9_999).expect_err("missing");
+    assert!(matches!(err, SqliteGraphError::NotFound { .. }));
+}
+
+/// `a -CALLS-> b -CALLS-> c` with `b` soft-deleted.
+fn chain_with_deleted_middle() -> (SqliteGraph, [i64; 3]) {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    let ids: Vec<i64> = ["a", "b", "c"]
+        .iter()
+        .map(|name| graph.insert_entity(&sample_entity("Fn", name)).unwrap())
+        .collect();
+    for (from, to) in [(ids[0], ids[1]), (ids[1], ids[2])] {
+        graph
+            .insert_edge(&sqlitegraph::GraphEdge {
+                id: 0,
+                from_id: from,
+                to_id: to,
+                edge_type: "CALLS".into(),
+                data: json!({}),
+            })
+            .unwrap();
+    }
+    graph.soft_delete_node(ids[1]).expect("soft delete");
+    (graph, [ids[0], ids[1], ids[2]])
+}
+
+#[test]
+fn test_edges_of_type_skips_soft_deleted_nodes() {
+    let (graph, [a, _, c]) = chain_with_deleted_middle();
+    assert!(graph.query().edges_of_type(a, "CALLS").unwrap().is_empty());
+    assert!(graph.query().edges_of_type(c, "CALLS").unwrap().is_empty());
+}
+
+#[test]
+fn test_neighbor_filter_skips_soft_deleted_nodes() {
+    let (graph, [a, _, c]) = chain_with_deleted_middle();
+    let query = graph.query();
+    assert!(query.from(a).edge_type("CALLS").nodes().unwrap().is_empty());
+    assert!(
+        query
+            .from(c)
+            .direction(sqlitegraph::BackendDirection::Both)
+            .nodes()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_typed_multi_hop_skips_soft_deleted_nodes() {
+    let (graph, [a, _, _]) = chain_with_deleted_middle();
+    let reached = sqlitegraph::multi_hop::k_hop_filtered(
+        &graph,
+        a,
+        2,
+        sqlitegraph::BackendDirection::Outgoing,
+        &["CALLS"],
+    )
+    .unwrap();
+    assert!(reached.is_empty());
+    assert!(
+        graph
+            .query()
+            .traverse(a)
+            .out("CALLS")
+            .out("CALLS")
+            .nodes()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_match_triples_skips_soft_deleted_nodes() {
+    use sqlitegraph::{PatternTriple, count_triples, match_triples};
+
+    let (graph, [a, _, _]) = chain_with_deleted_middle();
+    let pattern = PatternTriple::new("CALLS");
+    assert!(match_triples(&graph, &pattern).unwrap().is_empty());
+    assert_eq!(count_triples(&graph, &pattern).unwrap(), 0);
+    let labeled = PatternTriple::new("CALLS")
+        .start_label("Fn")
+        .end_label("Fn");
+    sqlitegraph::add_label(&graph, a, "Fn").unwrap();
+    assert!(match_triples(&graph, &labeled).unwrap().is_empty());
+    let ranged = PatternTriple::new("CALLS").hops(1..2);
+    assert!(match_triples(&graph, &ranged).unwrap().is_empty());
+}
+
+#[test]
+fn test_backend_typed_neighbors_skip_soft_deleted_nodes() {
+    use sqlitegraph::backend::{GraphBackend, NeighborQuery, SqliteGraphBackend};
+
+    let (graph, [a, _, c]) = chain_with_deleted_middle();
+    let backend = SqliteGraphBackend::from_graph(graph);
+    let outgoing = NeighborQuery {
+        direction: sqlitegraph::BackendDirection::Outgoing,
+        edge_type: Some("CALLS".into()),
+    };
+    let incoming = NeighborQuery {
+        direction: sqlitegraph::BackendDirection::Incoming,
+        edge_type: Some("CALLS".into()),
+    };
+    assert!(backend.neighbors(a, outgoing).unwrap().is_empty());
+    assert!(backend.neighbors(c, incoming).unwrap().is_empty());
+}
+
+#[test]
+fn test_open_read_only_reads_alongside_writer() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = dir.path().join("shared.db");
+    let writer = SqliteGraph::open(&path).expect("writer");
+    let id = writer
+        .insert_entity(&sample_entity("Function", "alpha"))
+        .expect("insert");
+
+    let reader = SqliteGraph::open_read_only(&path).expect("reader");
+    assert!(reader.is_read_only());
+    assert_eq!(reader.get_entity(id).expect("get").name, "alpha");
+
+    // Writes from the other connection stay visible to the reader
+    let beta = writer
+        .insert_entity(&sample_entity("Function", "beta"))
+        .expect("insert");
+    assert_eq!(reader.list_entity_ids().expect("list"), vec![id, beta]);
+
+    let err = reader
+        .insert_entity(&sample_entity("Function", "gamma"))
+        .unwrap_err();
+    assert!(matches!(err, SqliteGraphError::ReadOnly(_)), "{err:?}");
+    assert!(matches!(
+        reader.delete_entity(id),
+        Err(SqliteGraphError::ReadOnly(_))
+    ));
+    assert!(!writer.is_read_only());
+}
+
+#[test]
+fn test_read_only_graph_refuses_index_and_bulk_writes() {
+    use sqlitegraph::{GraphEdgeCreate, GraphEntityCreate, graph_opt, index, recovery};
+
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = dir.path().join("shared.db");
+    let writer = SqliteGraph::open(&path).expect("writer");
+    let a = writer
+        .insert_entity(&sample_entity("Function", "a"))
+        .expect("insert");
+    let b = writer
+        .insert_entity(&sample_entity("Function", "b"))
+        .expect("insert");
+    let reader = SqliteGraph::open_read_only(&path).expect("reader");
+
+    let entity = GraphEntityCreate {
+        kind: "Function".into(),
+        name: "c".into(),
+        file_path: None,
+        data: json!({}),
+    };
+    let edge = GraphEdgeCreate {
+        from_id: a,
+        to_id: b,
+        edge_type: "CALLS".into(),
+        data: json!({}),
+    };
+    let jsonl = format!(
+        "{}\n",
+        json!({"t": "node", "id": 9, "kind": "Function", "name": "d", "file_path": null, "data": {}})
+    );
+    let results = [
+        index::add_label(&reader, a, "entry"),
+        index::add_labels_bulk(&reader, &[(a, "entry")]),
+        index::remove_label(&reader, a, "entry"),
+        index::add_property(&reader, a, "k", "v"),
+        index::add_properties_bulk(&reader, &[(a, "k", "v")]),
+        index::update_property(&reader, a, "k", "v"),
+        index::remove_property(&reader, a, "k"),
+        graph_opt::bulk_insert_entities(&reader, std::slice::from_ref(&entity)).map(drop),
+        graph_opt::bulk_insert_entities_iter(&reader, [entity], 8).map(drop),
+        graph_opt::bulk_insert_edges(&reader, &[edge]).map(drop),
+        recovery::stream_import_jsonl(&reader, jsonl.as_bytes(), 8).map(drop),
+    ];
+    for (i, result) in results.into_iter().enumerate() {
+        assert!(
+            matches!(result, Err(SqliteGraphError::ReadOnly(_))),
+            "write {i}: {result:?}"
+        );
+    }
+    assert_eq!(writer.list_entity_ids().expect("list"), vec![a, b]);
+}
+
+#[test]
+fn test_sqlite_failure_is_kept_as_error_source() {
+    use std::error::Error;
+
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = dir.path().join("garbage.db");
+    std::fs::write(&path, vec![0x5a; 4096]).expect("write");
+    let Err(err) = SqliteGraph::open(&path) else {
+        panic!("opened a file that is not a database");
+    };
+    assert!(
+        matches!(err, SqliteGraphError::SchemaError { .. }),
+        "{err:?}"
+    );
+    let source = err
+        .source()
+        .and_then(|source| source.downcast_ref::<rusqlite::Error>())
+        .expect("rusqlite source");
+    assert_eq!(
+        source.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::NotADatabase)
+    );
+    // The message still matches the wrapped error
+    assert_eq!(err.to_string(), format!("schema error: {source}"));
+
+    let err = SqliteGraphError::query("plain message");
+    assert!(err.source().is_none());
 }
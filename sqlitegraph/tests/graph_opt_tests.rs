@@ -2,8 +2,10 @@ use serde_json::json;
 use sqlitegraph::{
     graph::SqliteGraph,
     graph_opt::{
-        GraphEdgeCreate, GraphEntityCreate, adjacency_fetch_outgoing_batch, bulk_insert_edges,
-        bulk_insert_entities, cache_clear_ranges, cache_stats,
+        BatchConfig, BulkResult, GraphEdgeCreate, GraphEntityCreate, UpsertOutcome,
+        adjacency_fetch_outgoing_batch, bulk_insert_edges, bulk_insert_entities,
+        bulk_insert_entities_iter, bulk_insert_entities_with_config, bulk_upsert_edges,
+        cache_clear_ranges, cache_stats,
     },
 };
 
@@ -39,6 +41,36 @@ fn test_bulk_insert_vs_single_insert_equivalence() {
     assert!(manual.id > expected);
 }
 
+#[test]
+fn test_bulk_insert_entities_ids_align_with_input() {
+    let graph = graph();
+    let specs: Vec<_> = (0..25)
+        .map(|i| GraphEntityCreate {
+            kind: if i % 2 == 0 { "Fn" } else { "Struct" }.into(),
+            name: format!("entity_{i}"),
+            file_path: None,
+            data: json!({ "index": i }),
+        })
+        .collect();
+    let config = BatchConfig {
+        max_batch_size: 7,
+        enable_chunking: true,
+    };
+    let ids = bulk_insert_entities_with_config(&graph, &specs, &config).unwrap();
+    assert_eq!(ids.len(), specs.len());
+    for (spec, id) in specs.iter().zip(&ids) {
+        let entity = graph.get_entity(*id).unwrap();
+        assert_eq!(entity.name, spec.name);
+        assert_eq!(entity.kind, spec.kind);
+        assert_eq!(entity.data, spec.data);
+    }
+
+    let more = bulk_insert_entities(&graph, &specs[..3]).unwrap();
+    for (spec, id) in specs.iter().zip(&more) {
+        assert_eq!(graph.get_entity(*id).unwrap().name, spec.name);
+    }
+}
+
 #[test]
 fn test_batch_outgoing_matches_scalar_fetch() {
     let graph = graph();
@@ -165,3 +197,152 @@ fn test_bulk_insert_edges_skips_duplicates() {
     let neighbors = graph.query().neighbors(from).unwrap();
     assert_eq!(neighbors, vec![to]);
 }
+
+fn edge_create(
+    from_id: i64,
+    to_id: i64,
+    edge_type: &str,
+    data: serde_json::Value,
+) -> GraphEdgeCreate {
+    GraphEdgeCreate {
+        from_id,
+        to_id,
+        edge_type: edge_type.into(),
+        data,
+    }
+}
+
+#[test]
+fn test_bulk_upsert_edges_reconciles_without_duplicates() {
+    let graph = graph();
+    let specs: Vec<_> = ["a", "b", "c"]
+        .iter()
+        .map(|name| GraphEntityCreate {
+            kind: "Fn".into(),
+            name: (*name).into(),
+            file_path: None,
+            data: json!({}),
+        })
+        .collect();
+    let ids = bulk_insert_entities(&graph, &specs).unwrap();
+    let (a, b, c) = (ids[0], ids[1], ids[2]);
+
+    let first = bulk_upsert_edges(
+        &graph,
+        &[
+            edge_create(a, b, "CALLS", json!({ "sync": 1 })),
+            edge_create(b, c, "CALLS", json!({ "sync": 1 })),
+            edge_create(a, b, "USES", json!({ "sync": 1 })),
+        ],
+    )
+    .unwrap();
+    assert!(
+        first
+            .iter()
+            .all(|outcome| matches!(outcome, UpsertOutcome::Inserted(_)))
+    );
+
+    let second = bulk_upsert_edges(
+        &graph,
+        &[
+            edge_create(a, b, "CALLS", json!({ "sync": 2 })),
+            edge_create(b, c, "CALLS", json!({ "sync": 2 })),
+            edge_create(a, c, "CALLS", json!({ "sync": 2 })),
+            edge_create(a, b, "USES", json!({ "sync": 2 })),
+        ],
+    )
+    .unwrap();
+    assert_eq!(second[0], UpsertOutcome::Updated(first[0].id()));
+    assert_eq!(second[1], UpsertOutcome::Updated(first[1].id()));
+    assert!(matches!(second[2], UpsertOutcome::Inserted(_)));
+    assert_eq!(second[3], UpsertOutcome::Updated(first[2].id()));
+
+    assert_eq!(graph.statistics().unwrap().edge_count, 4);
+    for outcome in &second {
+        let edge = graph.get_edge(outcome.id()).unwrap();
+        assert_eq!(edge.data, json!({ "sync": 2 }));
+    }
+}
+
+#[test]
+fn test_bulk_upsert_edges_repeated_key_and_rollback() {
+    let graph = graph();
+    let ids = bulk_insert_entities(
+        &graph,
+        &["a", "b"].map(|name| GraphEntityCreate {
+            kind: "Fn".into(),
+            name: name.into(),
+            file_path: None,
+            data: json!({}),
+        }),
+    )
+    .unwrap();
+    let (a, b) = (ids[0], ids[1]);
+
+    let outcomes = bulk_upsert_edges(
+        &graph,
+        &[
+            edge_create(a, b, "CALLS", json!({ "v": 1 })),
+            edge_create(a, b, "CALLS", json!({ "v": 2 })),
+        ],
+    )
+    .unwrap();
+    assert_eq!(outcomes[1], UpsertOutcome::Updated(outcomes[0].id()));
+    assert_eq!(
+        graph.get_edge(outcomes[0].id()).unwrap().data,
+        json!({ "v": 2 })
+    );
+
+    // A missing endpoint fails the whole batch, including the earlier update
+    let err = bulk_upsert_edges(
+        &graph,
+        &[
+            edge_create(a, b, "CALLS", json!({ "v": 3 })),
+            edge_create(a, 999, "CALLS", json!({})),
+        ],
+    );
+    assert!(err.is_err());
+    assert_eq!(graph.statistics().unwrap().edge_count, 1);
+    assert_eq!(
+        graph.get_edge(outcomes[0].id()).unwrap().data,
+        json!({ "v": 2 })
+    );
+}
+
+#[test]
+fn test_bulk_insert_entities_iter_streams_in_batches() {
+    let graph = graph();
+    let entries = (0..25).map(|idx| GraphEntityCreate {
+        kind: "Fn".into(),
+        name: format!("fn_{idx}"),
+        file_path: None,
+        data: json!({ "idx": idx }),
+    });
+    let result = bulk_insert_entities_iter(&graph, entries, 10).expect("stream");
+    assert_eq!(result.inserted, 25);
+    assert_eq!(result.batches, 3);
+    let ids = graph.list_entity_ids().unwrap();
+    assert_eq!(result.first_id, ids.first().copied());
+    assert_eq!(result.last_id, ids.last().copied());
+
+    let empty = bulk_insert_entities_iter(&graph, std::iter::empty(), 10).unwrap();
+    assert_eq!(empty, BulkResult::default());
+    assert!(bulk_insert_entities_iter(&graph, std::iter::empty(), 0).is_err());
+}
+
+#[test]
+fn test_bulk_insert_entities_iter_keeps_committed_batches_on_error() {
+    let graph = graph();
+    let entries = (0..5).map(|idx| GraphEntityCreate {
+        kind: "Fn".into(),
+        name: if idx == 3 {
+            String::new()
+        } else {
+            format!("fn_{idx}")
+        },
+        file_path: None,
+        data: json!({}),
+    });
+    assert!(bulk_insert_entities_iter(&graph, entries, 2).is_err());
+    assert_eq!(graph.list_entity_ids().unwrap().len(), 2);
+}
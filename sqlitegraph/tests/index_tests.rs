@@ -1,7 +1,16 @@
+use std::collections::BTreeSet;
+
 use serde_json::json;
 use sqlitegraph::{
+    PropertyValue,
     graph::{GraphEntity, SqliteGraph},
-    index::{add_label, add_property, get_entities_by_label, get_entities_by_property},
+    index::{
+        OrderBy, PropertyFilter, PropertyQuery, add_label, add_labels_bulk, add_properties_bulk,
+        add_property, add_typed_property, get_entities_by_label, get_entities_by_label_ordered,
+        get_entities_by_property, get_entities_by_property_filter, get_labels, get_properties,
+        get_properties_bulk, get_typed_property, has_label, remove_label, remove_property,
+        update_property,
+    },
 };
 
 fn graph() -> SqliteGraph {
@@ -30,6 +39,32 @@ fn test_label_roundtrip() {
     assert_eq!(entities[0].id, id);
 }
 
+#[test]
+fn test_label_lifecycle() {
+    let g = graph();
+    let id = insert_node(&g, "A");
+    let other = insert_node(&g, "B");
+    add_label(&g, id, "Service").unwrap();
+    add_label(&g, id, "Module").unwrap();
+    add_label(&g, other, "Module").unwrap();
+    assert_eq!(get_labels(&g, id).unwrap(), vec!["Module", "Service"]);
+
+    remove_label(&g, id, "Module").unwrap();
+    assert_eq!(get_labels(&g, id).unwrap(), vec!["Service"]);
+    assert!(!has_label(&g, id, "Module").unwrap());
+    assert!(has_label(&g, id, "Service").unwrap());
+    // Other entities keep the label, and removing an absent label is a no-op
+    assert!(has_label(&g, other, "Module").unwrap());
+    remove_label(&g, id, "Module").unwrap();
+    let module: Vec<i64> = get_entities_by_label(&g, "Module")
+        .unwrap()
+        .iter()
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(module, vec![other]);
+    assert!(get_labels(&g, 999).unwrap().is_empty());
+}
+
 #[test]
 fn test_property_roundtrip() {
     let g = graph();
@@ -39,6 +74,156 @@ fn test_property_roundtrip() {
     assert_eq!(entities[0].id, id);
 }
 
+#[test]
+fn test_property_lifecycle() {
+    let g = graph();
+    let id = insert_node(&g, "A");
+    add_property(&g, id, "role", "leaf").unwrap();
+    add_property(&g, id, "tier", "1").unwrap();
+    update_property(&g, id, "role", "root").unwrap();
+    update_property(&g, id, "owner", "core").unwrap();
+    let props = get_properties(&g, id).unwrap();
+    assert_eq!(props.len(), 3);
+    assert_eq!(props["role"], "root");
+    assert_eq!(props["tier"], "1");
+    assert_eq!(props["owner"], "core");
+    // The replaced value no longer matches lookups
+    assert!(
+        get_entities_by_property(&g, "role", "leaf")
+            .unwrap()
+            .is_empty()
+    );
+
+    // Appended values stay queryable, but the listing keeps the last one
+    add_property(&g, id, "tier", "2").unwrap();
+    assert_eq!(get_properties(&g, id).unwrap()["tier"], "2");
+    assert_eq!(get_entities_by_property(&g, "tier", "1").unwrap().len(), 1);
+
+    remove_property(&g, id, "tier").unwrap();
+    remove_property(&g, id, "missing").unwrap();
+    let props = get_properties(&g, id).unwrap();
+    assert_eq!(props.len(), 2);
+    assert!(!props.contains_key("tier"));
+}
+
+#[test]
+fn test_bulk_labels_and_properties_land() {
+    let g = graph();
+    let ids: Vec<i64> = (0..50).map(|i| insert_node(&g, &format!("n{i}"))).collect();
+    let tiers: Vec<String> = (0..ids.len()).map(|i| (i % 3).to_string()).collect();
+    let pairs: Vec<(i64, &str)> = ids
+        .iter()
+        .flat_map(|&id| {
+            [
+                (id, "Service"),
+                (id, if id % 2 == 0 { "Even" } else { "Odd" }),
+            ]
+        })
+        .collect();
+    let triples: Vec<(i64, &str, &str)> = ids
+        .iter()
+        .zip(&tiers)
+        .map(|(&id, tier)| (id, "tier", tier.as_str()))
+        .collect();
+    add_labels_bulk(&g, &pairs).unwrap();
+    add_properties_bulk(&g, &triples).unwrap();
+
+    assert_eq!(
+        get_entities_by_label(&g, "Service").unwrap().len(),
+        ids.len()
+    );
+    for (&id, tier) in ids.iter().zip(&tiers) {
+        let parity = if id % 2 == 0 { "Even" } else { "Odd" };
+        let mut expected = vec![parity, "Service"];
+        expected.sort();
+        assert_eq!(get_labels(&g, id).unwrap(), expected);
+        assert_eq!(&get_properties(&g, id).unwrap()["tier"], tier);
+    }
+
+    // A missing entity rejects the whole batch
+    let err = add_labels_bulk(&g, &[(ids[0], "New"), (9_999, "New")]).unwrap_err();
+    assert!(matches!(
+        err,
+        sqlitegraph::SqliteGraphError::NotFound { .. }
+    ));
+    assert!(!has_label(&g, ids[0], "New").unwrap());
+    assert!(add_properties_bulk(&g, &[(ids[0], "k", "v"), (9_999, "k", "v")]).is_err());
+    assert!(!get_properties(&g, ids[0]).unwrap().contains_key("k"));
+}
+
+#[test]
+fn test_typed_properties_round_trip() {
+    let g = graph();
+    let id = insert_node(&g, "A");
+    let values = [
+        ("count", PropertyValue::Integer(-42)),
+        ("ratio", PropertyValue::Float(2.5)),
+        ("whole", PropertyValue::Float(3.0)),
+        ("active", PropertyValue::Bool(true)),
+        ("label", PropertyValue::String("42".into())),
+    ];
+    for (key, value) in &values {
+        add_typed_property(&g, id, key, value.clone()).unwrap();
+    }
+    for (key, value) in &values {
+        assert_eq!(
+            get_typed_property(&g, id, key).unwrap().as_ref(),
+            Some(value)
+        );
+    }
+    // The string API reads back as strings, and typed values remain visible to it
+    add_property(&g, id, "plain", "7").unwrap();
+    assert_eq!(
+        get_typed_property(&g, id, "plain").unwrap(),
+        Some(PropertyValue::String("7".into()))
+    );
+    assert_eq!(get_properties(&g, id).unwrap()["active"], "true");
+    assert_eq!(get_typed_property(&g, id, "missing").unwrap(), None);
+
+    // Types survive a dump and reload
+    let mut dump = Vec::new();
+    sqlitegraph::recovery::dump_graph_to_writer(&g, &mut dump).unwrap();
+    let restored = graph();
+    sqlitegraph::recovery::load_graph_from_reader(&restored, dump.as_slice()).unwrap();
+    for (key, value) in &values {
+        assert_eq!(
+            get_typed_property(&restored, id, key).unwrap().as_ref(),
+            Some(value)
+        );
+    }
+}
+
+#[test]
+fn test_typed_numeric_properties_compare_numerically() {
+    let g = graph();
+    let ids: Vec<i64> = [9i64, 10, 100]
+        .iter()
+        .map(|&size| {
+            let id = insert_node(&g, &format!("n{size}"));
+            add_typed_property(&g, id, "size", size.into()).unwrap();
+            id
+        })
+        .collect();
+    let fractional = insert_node(&g, "fractional");
+    add_typed_property(&g, fractional, "size", PropertyValue::Float(9.5)).unwrap();
+
+    let ids_matching = |filter| -> Vec<i64> {
+        get_entities_by_property_filter(&g, "size", &filter)
+            .unwrap()
+            .iter()
+            .map(|e| e.id)
+            .collect()
+    };
+    assert_eq!(
+        ids_matching(PropertyFilter::Gt("9".into())),
+        vec![ids[1], ids[2], fractional]
+    );
+    assert_eq!(
+        ids_matching(PropertyFilter::Between("9".into(), "10".into())),
+        vec![ids[0], ids[1], fractional]
+    );
+}
+
 #[test]
 fn test_multi_label_entries() {
     let g = graph();
@@ -73,3 +258,230 @@ fn test_selective_property_match() {
     let empty = get_entities_by_property(&g, "kind", "type").unwrap();
     assert!(empty.is_empty());
 }
+
+#[test]
+fn test_get_properties_bulk_fetches_requested_keys() {
+    let g = graph();
+    let a = insert_node(&g, "A");
+    let b = insert_node(&g, "B");
+    let c = insert_node(&g, "C");
+    add_property(&g, a, "language", "rust").unwrap();
+    add_property(&g, a, "owner", "core").unwrap();
+    add_property(&g, a, "secret", "hidden").unwrap();
+    add_property(&g, b, "language", "python").unwrap();
+    add_property(&g, c, "owner", "infra").unwrap();
+
+    let props = get_properties_bulk(&g, &[a, b, c, 99], &["language", "owner"]).unwrap();
+    assert_eq!(props.len(), 3);
+    assert_eq!(props[&a].len(), 2);
+    assert_eq!(props[&a]["language"], "rust");
+    assert_eq!(props[&a]["owner"], "core");
+    assert_eq!(props[&b]["language"], "python");
+    assert!(!props[&c].contains_key("language"));
+    assert!(
+        get_properties_bulk(&g, &[], &["language"])
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_get_entities_by_label_ordered_sorts_and_limits() {
+    let g = graph();
+    let c = insert_node(&g, "charlie");
+    let a = insert_node(&g, "alpha");
+    let b = insert_node(&g, "bravo");
+    let other = insert_node(&g, "aardvark");
+    for id in [c, a, b] {
+        add_label(&g, id, "Module").unwrap();
+    }
+    add_label(&g, a, "Module").unwrap();
+    add_label(&g, other, "Other").unwrap();
+
+    let by_id = get_entities_by_label_ordered(&g, "Module", OrderBy::Id, None).unwrap();
+    assert_eq!(
+        by_id.iter().map(|e| e.id).collect::<Vec<_>>(),
+        vec![c, a, b]
+    );
+
+    let by_name = get_entities_by_label_ordered(&g, "Module", OrderBy::Name, Some(2)).unwrap();
+    let names: Vec<_> = by_name.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["alpha", "bravo"]);
+}
+
+fn filter_ids(g: &SqliteGraph, key: &str, filter: PropertyFilter) -> Vec<i64> {
+    get_entities_by_property_filter(g, key, &filter)
+        .unwrap()
+        .into_iter()
+        .map(|entity| entity.id)
+        .collect()
+}
+
+#[test]
+fn test_property_filter_numeric_range() {
+    let g = graph();
+    let ids: Vec<i64> = ["9", "10", "100", "5.5", "n/a"]
+        .iter()
+        .map(|line| {
+            let id = insert_node(&g, line);
+            add_property(&g, id, "line", line).unwrap();
+            id
+        })
+        .collect();
+
+    // Numeric text compares as numbers, so "100" > "9" even though it sorts first as text.
+    assert_eq!(
+        filter_ids(&g, "line", PropertyFilter::Gt("9".into())),
+        vec![ids[1], ids[2], ids[4]]
+    );
+    assert_eq!(
+        filter_ids(&g, "line", PropertyFilter::Between("5".into(), "10".into())),
+        vec![ids[0], ids[1], ids[3]]
+    );
+    assert_eq!(
+        filter_ids(&g, "line", PropertyFilter::Le("5.5".into())),
+        vec![ids[3]]
+    );
+    assert_eq!(
+        filter_ids(&g, "line", PropertyFilter::Eq("10.0".into())),
+        vec![ids[1]]
+    );
+}
+
+#[test]
+fn test_property_filter_string_inequality() {
+    let g = graph();
+    let a = insert_node(&g, "A");
+    let b = insert_node(&g, "B");
+    let c = insert_node(&g, "C");
+    add_property(&g, a, "lang", "rust").unwrap();
+    add_property(&g, b, "lang", "python").unwrap();
+    add_property(&g, c, "lang", "go").unwrap();
+
+    assert_eq!(
+        filter_ids(&g, "lang", PropertyFilter::Ne("rust".into())),
+        vec![b, c]
+    );
+    assert_eq!(
+        filter_ids(&g, "lang", PropertyFilter::Lt("python".into())),
+        vec![c]
+    );
+    assert_eq!(
+        filter_ids(&g, "lang", PropertyFilter::Ge("python".into())),
+        vec![a, b]
+    );
+    assert!(filter_ids(&g, "missing", PropertyFilter::Ne("rust".into())).is_empty());
+}
+
+#[test]
+fn test_property_filter_in_set() {
+    let g = graph();
+    let a = insert_node(&g, "A");
+    let b = insert_node(&g, "B");
+    let c = insert_node(&g, "C");
+    add_property(&g, a, "kind", "fn").unwrap();
+    add_property(&g, b, "kind", "struct").unwrap();
+    add_property(&g, c, "kind", "trait").unwrap();
+    add_property(&g, c, "kind", "fn").unwrap();
+
+    assert_eq!(
+        filter_ids(
+            &g,
+            "kind",
+            PropertyFilter::In(vec!["trait".into(), "fn".into()])
+        ),
+        vec![a, c]
+    );
+    assert!(filter_ids(&g, "kind", PropertyFilter::In(Vec::new())).is_empty());
+}
+
+/// Symbols tagged with `kind` and `language`; returns their ids in insertion order.
+fn tagged_graph() -> (SqliteGraph, Vec<i64>) {
+    let g = graph();
+    let ids = [
+        ("Function", "rust"),
+        ("Function", "go"),
+        ("Struct", "rust"),
+        ("Function", "python"),
+        ("Struct", "go"),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(idx, (kind, language))| {
+        let id = insert_node(&g, &format!("sym{idx}"));
+        add_property(&g, id, "kind", kind).unwrap();
+        add_property(&g, id, "language", language).unwrap();
+        id
+    })
+    .collect();
+    (g, ids)
+}
+
+fn property_ids(g: &SqliteGraph, key: &str, value: &str) -> BTreeSet<i64> {
+    get_entities_by_property(g, key, value)
+        .unwrap()
+        .into_iter()
+        .map(|entity| entity.id)
+        .collect()
+}
+
+#[test]
+fn test_property_query_and_matches_intersection() {
+    let (g, _) = tagged_graph();
+    let query = PropertyQuery::new()
+        .and_eq("kind", "Function")
+        .and_eq("language", "rust");
+    let expected: Vec<i64> = property_ids(&g, "kind", "Function")
+        .intersection(&property_ids(&g, "language", "rust"))
+        .copied()
+        .collect();
+    assert_eq!(query.execute(&g).unwrap(), expected);
+    assert_eq!(expected.len(), 1);
+}
+
+#[test]
+fn test_property_query_or_matches_union() {
+    let (g, _) = tagged_graph();
+    let query = PropertyQuery::new()
+        .or_eq("language", "python")
+        .or_eq("kind", "Struct");
+    let expected: Vec<i64> = property_ids(&g, "language", "python")
+        .union(&property_ids(&g, "kind", "Struct"))
+        .copied()
+        .collect();
+    assert_eq!(query.execute(&g).unwrap(), expected);
+    assert_eq!(expected.len(), 3);
+}
+
+#[test]
+fn test_property_query_mixed_groups() {
+    let (g, ids) = tagged_graph();
+    // kind = 'Function' AND (language = 'rust' OR language = 'go')
+    let grouped = PropertyQuery::new().and_eq("kind", "Function").and_group(
+        PropertyQuery::new()
+            .or_eq("language", "rust")
+            .or_eq("language", "go"),
+    );
+    let languages: BTreeSet<i64> = property_ids(&g, "language", "rust")
+        .union(&property_ids(&g, "language", "go"))
+        .copied()
+        .collect();
+    let expected: Vec<i64> = property_ids(&g, "kind", "Function")
+        .intersection(&languages)
+        .copied()
+        .collect();
+    assert_eq!(grouped.execute(&g).unwrap(), expected);
+    assert_eq!(expected, vec![ids[0], ids[1]]);
+
+    // Without the group AND binds tighter: (Function AND rust) OR go.
+    let flat = PropertyQuery::new()
+        .and_eq("kind", "Function")
+        .and_eq("language", "rust")
+        .or_eq("language", "go");
+    assert_eq!(flat.execute(&g).unwrap(), vec![ids[0], ids[1], ids[4]]);
+
+    // Values are bound as parameters, never spliced into the SQL.
+    let hostile = PropertyQuery::new().and_eq("kind", "x' OR 1=1 --");
+    assert!(hostile.execute(&g).unwrap().is_empty());
+    assert_eq!(PropertyQuery::new().execute(&g).unwrap(), ids);
+}
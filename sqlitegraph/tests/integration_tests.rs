@@ -1,6 +1,6 @@
 use serde_json::json;
 use sqlitegraph::{
-    GraphEdge, GraphEntity, SqliteGraph,
+    GraphEdge, GraphEntity, SqliteGraph, SqliteGraphError,
     bfs::{bfs_neighbors, shortest_path},
     query::GraphQuery,
 };
@@ -152,3 +152,38 @@ fn test_integration_multi_hop_bfs_ordering() {
         vec![ids[0], ids[1], ids[2], ids[3], ids[4], ids[5]]
     );
 }
+
+#[test]
+fn test_with_connection_runs_custom_aggregate() {
+    let graph = complex_graph();
+    let per_type: Vec<(String, i64)> = graph
+        .with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT edge_type, COUNT(*) FROM graph_edges \
+                 GROUP BY edge_type ORDER BY COUNT(*) DESC, edge_type",
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        })
+        .unwrap();
+    assert_eq!(
+        per_type,
+        vec![
+            ("CALLS".to_string(), 8),
+            ("USES_TYPE".to_string(), 4),
+            ("INCLUDES".to_string(), 3),
+            ("MEMBER_OF".to_string(), 3),
+        ]
+    );
+
+    let err = graph
+        .with_connection(|conn| {
+            conn.query_row("SELECT * FROM no_such_table", [], |row| {
+                row.get::<_, i64>(0)
+            })
+        })
+        .unwrap_err();
+    assert!(matches!(err, SqliteGraphError::QueryError { .. }));
+    let source = std::error::Error::source(&err).expect("source");
+    assert!(source.downcast_ref::<rusqlite::Error>().is_some());
+}
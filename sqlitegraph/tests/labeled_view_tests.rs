@@ -0,0 +1,188 @@
+use serde_json::json;
+use sqlitegraph::{
+    GraphEdge, GraphEntity, SqliteGraph, SqliteGraphError, add_label,
+    backend::{BackendDirection, GraphBackend, NeighborQuery, NodeSpec},
+};
+
+fn insert_entity(graph: &SqliteGraph, name: &str, tenant: &str) -> i64 {
+    let id = graph
+        .insert_entity(&GraphEntity {
+            id: 0,
+            kind: "Node".into(),
+            name: name.into(),
+            file_path: None,
+            data: json!({ "name": name }),
+        })
+        .expect("entity");
+    add_label(graph, id, tenant).expect("label");
+    id
+}
+
+fn insert_edge(graph: &SqliteGraph, from: i64, to: i64, edge_type: &str) {
+    graph
+        .insert_edge(&GraphEdge {
+            id: 0,
+            from_id: from,
+            to_id: to,
+            edge_type: edge_type.into(),
+            data: json!({}),
+        })
+        .expect("edge");
+}
+
+/// Tenant `acme`: a1 -> a2 -> a4 -> a3. Tenant `globex`: b1, with a shortcut a1 -> b1 -> a3.
+fn tenant_graph() -> (SqliteGraph, [i64; 5]) {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    let a1 = insert_entity(&graph, "a1", "acme");
+    let a2 = insert_entity(&graph, "a2", "acme");
+    let a3 = insert_entity(&graph, "a3", "acme");
+    let a4 = insert_entity(&graph, "a4", "acme");
+    let b1 = insert_entity(&graph, "b1", "globex");
+    insert_edge(&graph, a1, a2, "LINK");
+    insert_edge(&graph, a2, a4, "LINK");
+    insert_edge(&graph, a4, a3, "CALL");
+    insert_edge(&graph, a1, b1, "LINK");
+    insert_edge(&graph, b1, a3, "LINK");
+    (graph, [a1, a2, a3, a4, b1])
+}
+
+#[test]
+fn test_labeled_view_neighbors_exclude_other_labels() {
+    let (graph, [a1, a2, a3, a4, b1]) = tenant_graph();
+    let view = graph.labeled_view("acme");
+
+    let outgoing = view.neighbors(a1, NeighborQuery::default()).unwrap();
+    assert_eq!(outgoing, vec![a2]);
+    let incoming = view
+        .neighbors(
+            a3,
+            NeighborQuery {
+                direction: BackendDirection::Incoming,
+                edge_type: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(incoming.len(), 1);
+    assert!(
+        view.neighbors(b1, NeighborQuery::default())
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(view.node_degree(a1).unwrap(), (1, 0));
+    assert_eq!(view.node_edges(a1).unwrap().degree(), (1, 0));
+    assert_eq!(view.node_edges(a3).unwrap().incoming[0].from_id, a4);
+}
+
+#[test]
+fn test_labeled_view_traversals_stay_inside_label() {
+    let (graph, [a1, a2, a3, a4, b1]) = tenant_graph();
+    let view = graph.labeled_view("acme");
+
+    assert_eq!(view.bfs(a1, 3).unwrap(), vec![a1, a2, a4, a3]);
+    assert_eq!(
+        view.shortest_path(a1, a3).unwrap(),
+        Some(vec![a1, a2, a4, a3])
+    );
+    assert_eq!(
+        view.k_hop(a1, 2, BackendDirection::Outgoing).unwrap(),
+        vec![a2, a4]
+    );
+    assert_eq!(
+        view.k_hop_filtered(a1, 3, BackendDirection::Outgoing, &["LINK"])
+            .unwrap(),
+        vec![a2, a4]
+    );
+    assert!(matches!(
+        view.shortest_path(a1, b1),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn test_labeled_view_hides_foreign_nodes_and_rejects_writes() {
+    let (graph, [a1, _a2, _a3, _a4, b1]) = tenant_graph();
+    let view = graph.labeled_view("acme");
+
+    assert_eq!(view.get_node(a1).unwrap().name, "a1");
+    assert!(matches!(
+        view.get_node(b1),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+    let err = view
+        .insert_node(NodeSpec {
+            kind: "Node".into(),
+            name: "intruder".into(),
+            file_path: None,
+            data: json!({}),
+        })
+        .expect_err("read-only");
+    assert!(matches!(err, SqliteGraphError::InvalidInput { .. }));
+}
+
+#[test]
+fn test_labeled_view_edges_require_both_endpoints_visible() {
+    let (graph, [_, a2, _, a4, _]) = tenant_graph();
+    let view = graph.labeled_view("acme");
+
+    assert_eq!(view.list_edge_ids().unwrap(), vec![1, 2, 3]);
+    let edge = view.get_edge(2).unwrap();
+    assert_eq!((edge.from_id, edge.to_id), (a2, a4));
+    assert!(matches!(
+        view.get_edge(4),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn test_labeled_view_contains_skips_deleted_and_missing_nodes() {
+    let (graph, [a1, a2, _, _, b1]) = tenant_graph();
+    let view = graph.labeled_view("acme");
+    assert!(view.contains(a1).unwrap());
+    assert!(!view.contains(b1).unwrap());
+
+    graph.soft_delete_node(a2).unwrap();
+    assert!(!view.contains(a2).unwrap());
+    assert!(matches!(
+        view.get_node(a2),
+        Err(SqliteGraphError::NotFound { .. })
+    ));
+
+    // A label row left behind by a hard delete does not make the id visible
+    graph
+        .with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO graph_labels(entity_id, label) VALUES(999, 'acme')",
+                [],
+            )
+        })
+        .unwrap();
+    assert!(!view.contains(999).unwrap());
+}
+
+#[test]
+fn test_labeled_view_validate_integrity_only_reports_view_edges() {
+    let (graph, [a1, a2, a3, _, b1]) = tenant_graph();
+    let b2 = insert_entity(&graph, "b2", "globex");
+    insert_edge(&graph, b1, b2, "LINK");
+    insert_edge(&graph, a3, a1, "LINK");
+    graph.soft_delete_node(b2).unwrap();
+    graph.soft_delete_node(a1).unwrap();
+
+    let acme = graph.labeled_view("acme").validate_integrity().unwrap();
+    let mut endpoints: Vec<_> = acme
+        .dangling_edges
+        .iter()
+        .map(|edge| (edge.from_id, edge.to_id))
+        .collect();
+    endpoints.sort();
+    assert_eq!(endpoints, vec![(a1, a2), (a3, a1)]);
+
+    let globex = graph.labeled_view("globex").validate_integrity().unwrap();
+    let endpoints: Vec<_> = globex
+        .dangling_edges
+        .iter()
+        .map(|edge| (edge.from_id, edge.to_id))
+        .collect();
+    // a1 -> b1 crosses tenants, so it dangles off b1 inside globex
+    assert_eq!(endpoints, vec![(a1, b1), (b1, b2)]);
+}
@@ -185,7 +185,7 @@ fn test_error_types_through_lib_api() {
     let result = graph.get_entity(999);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::NotFound(_) => {} // Expected
+        SqliteGraphError::NotFound { .. } => {} // Expected
         other => panic!("Expected NotFound error, got: {:?}", other),
     }
 
@@ -200,7 +200,7 @@ fn test_error_types_through_lib_api() {
     let result = graph.insert_entity(&invalid_entity);
     assert!(result.is_err());
     match result.unwrap_err() {
-        SqliteGraphError::InvalidInput(_) => {} // Expected
+        SqliteGraphError::InvalidInput { .. } => {} // Expected
         other => panic!("Expected InvalidInput error, got: {:?}", other),
     }
 }
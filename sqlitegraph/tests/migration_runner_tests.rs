@@ -1,8 +1,10 @@
 use rand::Rng;
 use rusqlite::Connection;
+use sqlitegraph::backend::{GraphBackend, NativeGraphBackend, SqliteGraphBackend};
 use sqlitegraph::schema::{
     BASE_SCHEMA_VERSION, SCHEMA_VERSION, ensure_schema, read_schema_version, run_pending_migrations,
 };
+use sqlitegraph::{SqliteGraph, SqliteGraphError};
 
 #[path = "fuzz_common.rs"]
 mod fuzz_common;
@@ -53,6 +55,134 @@ fn apply_migrations_updates_version() {
     assert!(exists);
 }
 
+#[test]
+fn ensure_schema_adds_property_type_column_to_older_databases() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute(
+        "CREATE TABLE graph_properties(entity_id INTEGER NOT NULL, key TEXT NOT NULL, value TEXT NOT NULL)",
+        [],
+    )
+    .unwrap();
+    conn.execute("INSERT INTO graph_properties VALUES(1, 'role', 'leaf')", [])
+        .unwrap();
+    ensure_schema(&conn).unwrap();
+    let value_type: String = conn
+        .query_row("SELECT value_type FROM graph_properties", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(value_type, "string");
+    // Running it again leaves the column alone
+    ensure_schema(&conn).unwrap();
+}
+
+#[test]
+fn property_types_need_migration_but_old_databases_stay_readable() {
+    use sqlitegraph::{PropertyValue, add_property, index::add_typed_property};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("untyped.db");
+    let old = SqliteGraph::open_without_migrations(&path).unwrap();
+    let id = old
+        .insert_entity(&sqlitegraph::GraphEntity {
+            id: 0,
+            kind: "Fn".into(),
+            name: "leaf".into(),
+            file_path: None,
+            data: serde_json::json!({}),
+        })
+        .unwrap();
+    add_property(&old, id, "role", "leaf").unwrap();
+    assert!(matches!(
+        add_typed_property(&old, id, "size", PropertyValue::Integer(3)),
+        Err(SqliteGraphError::SchemaError { .. })
+    ));
+    drop(old);
+
+    let reader = SqliteGraph::open_read_only(&path).unwrap();
+    assert_eq!(
+        sqlitegraph::index::get_typed_property(&reader, id, "role").unwrap(),
+        Some(PropertyValue::String("leaf".into()))
+    );
+    drop(reader);
+
+    let migrated = SqliteGraph::open(&path).unwrap();
+    assert_eq!(migrated.schema_version().unwrap(), SCHEMA_VERSION);
+    add_typed_property(&migrated, id, "size", PropertyValue::Integer(3)).unwrap();
+    assert_eq!(
+        sqlitegraph::index::get_typed_property(&migrated, id, "size").unwrap(),
+        Some(PropertyValue::Integer(3))
+    );
+    assert_eq!(
+        sqlitegraph::index::get_typed_property(&migrated, id, "role").unwrap(),
+        Some(PropertyValue::String("leaf".into()))
+    );
+}
+
+#[test]
+fn older_database_reports_version_and_pending_migrations() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("old.db");
+    drop(SqliteGraph::open_without_migrations(&path).unwrap());
+
+    let old = SqliteGraphBackend::from_graph(SqliteGraph::open_read_only(&path).unwrap());
+    assert_eq!(old.schema_version().unwrap(), BASE_SCHEMA_VERSION as u32);
+    match old.check_compatibility() {
+        Err(SqliteGraphError::SchemaError { message, .. }) => {
+            assert!(
+                message.ends_with("needs migrations to version 2, 3, 4"),
+                "{message}"
+            );
+        }
+        other => panic!("expected a schema error, got {other:?}"),
+    }
+    drop(old);
+
+    let migrated = SqliteGraph::open(&path).unwrap();
+    assert_eq!(migrated.schema_version().unwrap(), SCHEMA_VERSION);
+    migrated.check_compatibility().unwrap();
+}
+
+#[test]
+fn newer_database_is_reported_incompatible() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("new.db");
+    drop(SqliteGraph::open(&path).unwrap());
+    Connection::open(&path)
+        .unwrap()
+        .execute(
+            "UPDATE graph_meta SET schema_version=?1 WHERE id=1",
+            [SCHEMA_VERSION + 1],
+        )
+        .unwrap();
+
+    let graph = SqliteGraph::open_read_only(&path).unwrap();
+    assert_eq!(graph.schema_version().unwrap(), SCHEMA_VERSION + 1);
+    let err = graph.check_compatibility().unwrap_err();
+    assert!(err.to_string().contains("newer than supported"), "{err}");
+}
+
+#[test]
+fn native_schema_version_and_compatibility() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.native");
+    let backend = NativeGraphBackend::new(&path).unwrap();
+    assert_eq!(backend.schema_version().unwrap(), 2);
+    backend.check_compatibility().unwrap();
+    drop(backend);
+
+    for (version, compatible) in [(1, true), (3, false)] {
+        let mut file = sqlitegraph::backend::native::GraphFile::open(&path).unwrap();
+        file.header_mut().schema_version = version;
+        file.persist_header().unwrap();
+        drop(file);
+
+        let backend = NativeGraphBackend::open(&path).unwrap();
+        assert_eq!(backend.schema_version().unwrap(), version as u32);
+        assert_eq!(backend.check_compatibility().is_ok(), compatible);
+    }
+}
+
 #[test]
 fn random_migration_sequences_stabilize() {
     let iterations = fuzz_common::fuzz_iterations();
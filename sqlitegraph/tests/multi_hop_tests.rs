@@ -50,6 +50,32 @@ fn test_k_hop_outgoing_depth_two() {
     assert_eq!(hops, vec![ids[1], ids[4], ids[2], ids[3]]);
 }
 
+#[test]
+fn test_k_hop_with_distance_labels_minimum_hops() {
+    let (graph, ids) = build_sample_graph();
+    let hops = multi_hop::k_hop_with_distance(&graph, ids[0], 3, BackendDirection::Outgoing)
+        .expect("k-hop with distance");
+    // D is three hops away via B and C but two via E.
+    assert_eq!(
+        hops,
+        vec![(ids[1], 1), (ids[4], 1), (ids[2], 2), (ids[3], 2)]
+    );
+    let plain = multi_hop::k_hop(&graph, ids[0], 3, BackendDirection::Outgoing).unwrap();
+    assert_eq!(hops.iter().map(|&(id, _)| id).collect::<Vec<_>>(), plain);
+
+    let incoming = multi_hop::k_hop_with_distance(&graph, ids[3], 2, BackendDirection::Incoming)
+        .expect("incoming");
+    assert_eq!(
+        incoming,
+        vec![(ids[2], 1), (ids[4], 1), (ids[0], 2), (ids[1], 2)]
+    );
+    assert!(
+        multi_hop::k_hop_with_distance(&graph, ids[0], 0, BackendDirection::Outgoing)
+            .unwrap()
+            .is_empty()
+    );
+}
+
 #[test]
 fn test_k_hop_filtered_by_type() {
     let (graph, ids) = build_sample_graph();
@@ -673,3 +673,589 @@ fn test_file_size_validation() {
     // File should still be valid
     assert!(graph_file.validate_file_size().is_ok());
 }
+
+/// Insert nodes with a few overlapping properties through the backend
+fn insert_property_nodes(backend: &NativeGraphBackend, count: i64) {
+    use sqlitegraph::backend::{GraphBackend, NodeSpec};
+    for i in 1..=count {
+        backend
+            .insert_node(NodeSpec {
+                kind: "Function".to_string(),
+                name: format!("func{i}"),
+                file_path: None,
+                data: serde_json::json!({
+                    "lang": if i % 3 == 0 { "go" } else { "rust" },
+                    "loc": i % 4,
+                    "public": i % 2 == 0,
+                    "tags": ["ignored"],
+                }),
+            })
+            .expect("Failed to insert node");
+    }
+}
+
+/// Compare indexed property lookups against a scan of every node record
+fn assert_index_matches_scan(path: &std::path::Path, backend: &NativeGraphBackend) {
+    use sqlitegraph::backend::GraphBackend;
+    use sqlitegraph::backend::native::graph_ops::{data_property_matches, native_scan_nodes};
+    let queries = [
+        ("lang", "rust"),
+        ("lang", "go"),
+        ("loc", "0"),
+        ("loc", "3"),
+        ("public", "true"),
+        ("tags", "ignored"),
+        ("missing", "x"),
+    ];
+    let mut graph_file = GraphFile::open(path).expect("Failed to open graph file");
+    for (key, value) in queries {
+        let scanned = native_scan_nodes(&mut graph_file, |data| {
+            data_property_matches(data, key, value)
+        })
+        .expect("Failed to scan nodes");
+        assert_eq!(
+            backend.find_nodes_by_property(key, value).unwrap(),
+            scanned,
+            "{key}={value}"
+        );
+    }
+}
+
+#[test]
+fn test_property_index_survives_reopen() {
+    use sqlitegraph::backend::GraphBackend;
+    let temp_file = NamedTempFile::new().expect("Failed to create temp file");
+    {
+        let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+        insert_property_nodes(&backend, 20);
+        // Records written after a flush must land before the re-persisted section
+        backend.flush().unwrap();
+        insert_property_nodes(&backend, 5);
+        backend
+            .update_node_data(4, serde_json::json!({"lang": "zig", "loc": 9}))
+            .unwrap();
+        backend.delete_node(6).unwrap();
+        assert_eq!(
+            backend.find_nodes_by_property("lang", "zig").unwrap(),
+            vec![4]
+        );
+    }
+
+    let graph_file = GraphFile::open(temp_file.path()).unwrap();
+    assert!(graph_file.property_index().is_some());
+    assert_eq!(graph_file.header().node_count, 25);
+    drop(graph_file);
+
+    let backend = NativeGraphBackend::open(temp_file.path()).unwrap();
+    assert_eq!(backend.get_node(25).unwrap().name, "func5");
+    assert_eq!(
+        backend.find_nodes_by_property("lang", "zig").unwrap(),
+        vec![4]
+    );
+    assert!(
+        !backend
+            .find_nodes_by_property("lang", "go")
+            .unwrap()
+            .contains(&6)
+    );
+    assert_index_matches_scan(temp_file.path(), &backend);
+}
+
+#[test]
+fn test_property_index_rebuilt_for_files_without_section() {
+    use std::io::{Seek, SeekFrom, Write};
+    let temp_file = NamedTempFile::new().expect("Failed to create temp file");
+    {
+        let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+        insert_property_nodes(&backend, 12);
+    }
+
+    // Strip the section and downgrade the header, as an older version would write it
+    let section_offset = {
+        let bytes = std::fs::read(temp_file.path()).unwrap();
+        let footer = &bytes[bytes.len() - constants::property_index::FOOTER_SIZE as usize..];
+        property_index::decode_footer(footer).unwrap().0
+    };
+    let mut header = GraphFile::open(temp_file.path()).unwrap().header().clone();
+    header.schema_version = 1;
+    header.update_checksum();
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .open(temp_file.path())
+        .unwrap();
+    file.set_len(section_offset).unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.write_all(&encode_header(&header).unwrap()).unwrap();
+    drop(file);
+
+    let graph_file = GraphFile::open(temp_file.path()).unwrap();
+    assert!(graph_file.property_index().is_none());
+    drop(graph_file);
+
+    {
+        let backend = NativeGraphBackend::open(temp_file.path()).unwrap();
+        assert_index_matches_scan(temp_file.path(), &backend);
+    }
+
+    let graph_file = GraphFile::open(temp_file.path()).unwrap();
+    assert!(graph_file.property_index().is_some());
+    assert_eq!(
+        graph_file.header().schema_version,
+        constants::PROPERTY_INDEX_SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn test_compact_drops_deleted_records_and_preserves_ids() {
+    use sqlitegraph::backend::{EdgeSpec, GraphBackend, NeighborQuery};
+    let temp_file = NamedTempFile::new().expect("Failed to create temp file");
+    let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+    insert_property_nodes(&backend, 40);
+    let edge = |from_id, to_id| EdgeSpec {
+        from: from_id,
+        to: to_id,
+        edge_type: "calls".to_string(),
+        data: serde_json::json!({"weight": from_id}),
+    };
+    for i in 1..40 {
+        backend.insert_edge(edge(i, i + 1)).unwrap();
+    }
+    for i in (1..39).step_by(2) {
+        backend.insert_edge(edge(i, i + 2)).unwrap();
+    }
+    for i in (2..=40).step_by(2) {
+        backend.delete_node(i).unwrap();
+    }
+    let live_edges = backend.list_edge_ids().unwrap();
+
+    let stats = backend.compact().unwrap();
+    assert_eq!(stats.tombstoned_nodes, 20);
+    // Tombstones shrink the node records, but the edge section keeps its offset
+    assert!(stats.bytes_after <= stats.bytes_before, "{stats:?}");
+    assert_eq!(
+        stats.bytes_reclaimed,
+        stats.bytes_before - stats.bytes_after
+    );
+    assert_eq!(
+        std::fs::metadata(temp_file.path()).unwrap().len(),
+        stats.bytes_after
+    );
+
+    let check = |backend: &NativeGraphBackend| {
+        for i in 1..=40 {
+            if i % 2 == 0 {
+                assert!(backend.get_node(i).is_err(), "node {i} should stay deleted");
+                continue;
+            }
+            let node = backend.get_node(i).unwrap();
+            assert_eq!(node.id, i);
+            assert_eq!(node.name, format!("func{i}"));
+            assert_eq!(node.data["loc"], i % 4);
+        }
+        assert_eq!(backend.list_edge_ids().unwrap(), live_edges);
+        for &id in &live_edges {
+            let edge = backend.get_edge(id).unwrap();
+            assert_eq!(edge.to_id, edge.from_id + 2);
+            assert_eq!(edge.data["weight"], edge.from_id);
+        }
+        // Adjacency ranges are rebuilt from the live edges
+        assert_eq!(
+            backend.neighbors(1, NeighborQuery::default()).unwrap(),
+            vec![3]
+        );
+        assert_eq!(
+            backend.find_nodes_by_property("lang", "go").unwrap(),
+            vec![3, 9, 15, 21, 27, 33, 39]
+        );
+    };
+    check(&backend);
+    drop(backend);
+    check(&NativeGraphBackend::open(temp_file.path()).unwrap());
+}
+
+fn bulk_node_specs(count: i64) -> Vec<sqlitegraph::backend::NodeSpec> {
+    (1..=count)
+        .map(|i| sqlitegraph::backend::NodeSpec {
+            kind: "Function".to_string(),
+            name: format!("func{i}"),
+            file_path: None,
+            data: serde_json::json!({"lang": if i % 2 == 0 { "go" } else { "rust" }}),
+        })
+        .collect()
+}
+
+fn bulk_edge_specs(count: i64) -> Vec<sqlitegraph::backend::EdgeSpec> {
+    (1..=count)
+        .flat_map(|i| [(i, i % count + 1), (i, (i + 2) % count + 1)])
+        .map(|(from, to)| sqlitegraph::backend::EdgeSpec {
+            from,
+            to,
+            edge_type: "calls".to_string(),
+            data: serde_json::json!({"from": from}),
+        })
+        .collect()
+}
+
+#[test]
+fn test_bulk_insert_matches_per_record_insertion() {
+    use sqlitegraph::backend::GraphBackend;
+    let per_record_file = NamedTempFile::new().unwrap();
+    let bulk_file = NamedTempFile::new().unwrap();
+    let per_record = NativeGraphBackend::new(per_record_file.path()).unwrap();
+    let bulk = NativeGraphBackend::new(bulk_file.path()).unwrap();
+
+    let nodes = bulk_node_specs(30);
+    let edges = bulk_edge_specs(30);
+    let mut expected_nodes = Vec::new();
+    for spec in &nodes {
+        expected_nodes.push(per_record.insert_node(spec.clone()).unwrap());
+    }
+    let mut expected_edges = Vec::new();
+    for spec in &edges {
+        expected_edges.push(per_record.insert_edge(spec.clone()).unwrap());
+    }
+    assert_eq!(bulk.bulk_insert_nodes(&nodes).unwrap(), expected_nodes);
+    assert_eq!(bulk.bulk_insert_edges(&edges).unwrap(), expected_edges);
+    assert!(bulk.bulk_insert_edges(&[]).unwrap().is_empty());
+
+    let snapshot = |backend: &NativeGraphBackend| {
+        let nodes: Vec<_> = (1..=30)
+            .map(|id| {
+                let node = backend.get_node(id).unwrap();
+                let edges = backend.node_edges(id).unwrap();
+                (
+                    node.name,
+                    node.data,
+                    edges.outgoing.len(),
+                    edges.incoming.len(),
+                )
+            })
+            .collect();
+        let edges: Vec<_> = backend
+            .list_edge_ids()
+            .unwrap()
+            .into_iter()
+            .map(|id| {
+                let edge = backend.get_edge(id).unwrap();
+                (edge.id, edge.from_id, edge.to_id, edge.data)
+            })
+            .collect();
+        let go = backend.find_nodes_by_property("lang", "go").unwrap();
+        (nodes, edges, go)
+    };
+    let expected = snapshot(&per_record);
+    assert_eq!(snapshot(&bulk), expected);
+
+    // The batch header was persisted without relying on drop
+    let header = GraphFile::open(bulk_file.path()).unwrap().header().clone();
+    assert_eq!((header.node_count, header.edge_count), (30, 60));
+    drop(bulk);
+    assert_eq!(
+        snapshot(&NativeGraphBackend::open(bulk_file.path()).unwrap()),
+        expected
+    );
+}
+
+#[test]
+fn test_bulk_insert_edges_rolls_back_on_failure() {
+    use sqlitegraph::backend::GraphBackend;
+    let temp_file = NamedTempFile::new().unwrap();
+    let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+    backend.bulk_insert_nodes(&bulk_node_specs(4)).unwrap();
+    backend.bulk_insert_edges(&bulk_edge_specs(4)[..2]).unwrap();
+    let size_before = std::fs::metadata(temp_file.path()).unwrap().len();
+
+    let mut edges = bulk_edge_specs(4);
+    edges[5].to = 99;
+    assert!(backend.bulk_insert_edges(&edges).is_err());
+    assert_eq!(backend.list_edge_ids().unwrap(), vec![1, 2]);
+    assert_eq!(
+        GraphFile::open(temp_file.path())
+            .unwrap()
+            .header()
+            .edge_count,
+        2
+    );
+    assert!(std::fs::metadata(temp_file.path()).unwrap().len() <= size_before);
+
+    let mut nodes = bulk_node_specs(3);
+    nodes[2].kind = "k".repeat(70_000);
+    assert!(backend.bulk_insert_nodes(&nodes).is_err());
+    assert!(backend.get_node(5).is_err());
+    assert_eq!(
+        backend.find_nodes_by_property("lang", "rust").unwrap(),
+        vec![1, 3]
+    );
+
+    // Ids continue from the restored counts
+    assert_eq!(backend.bulk_insert_nodes(&nodes[..1]).unwrap(), vec![5]);
+    assert_eq!(backend.bulk_insert_edges(&edges[..1]).unwrap(), vec![3]);
+}
+
+#[test]
+fn test_reserved_file_reads_and_writes_like_unreserved() {
+    use sqlitegraph::backend::GraphBackend;
+    let plain_file = NamedTempFile::new().unwrap();
+    let reserved_file = NamedTempFile::new().unwrap();
+    let plain = NativeGraphBackend::new(plain_file.path()).unwrap();
+    let reserved = NativeGraphBackend::new(reserved_file.path()).unwrap();
+    reserved.reserve(30, 60).unwrap();
+    assert_eq!(reserved.file_growth_count(), 1);
+
+    for backend in [&plain, &reserved] {
+        for spec in bulk_node_specs(30) {
+            backend.insert_node(spec).unwrap();
+        }
+        for spec in bulk_edge_specs(30) {
+            backend.insert_edge(spec).unwrap();
+        }
+    }
+    assert!(reserved.file_growth_count() < plain.file_growth_count() / 10);
+
+    let check = |backend: &NativeGraphBackend| {
+        for id in 1..=30 {
+            assert_eq!(backend.get_node(id).unwrap().name, format!("func{id}"));
+            let edges = backend.node_edges(id).unwrap();
+            assert_eq!((edges.outgoing.len(), edges.incoming.len()), (2, 2));
+        }
+        assert_eq!(backend.list_edge_ids().unwrap().len(), 60);
+        assert_eq!(
+            backend.find_nodes_by_property("lang", "go").unwrap().len(),
+            15
+        );
+    };
+    check(&reserved);
+
+    // Unused space is released on close, leaving the same bytes on disk
+    drop(plain);
+    drop(reserved);
+    assert_eq!(
+        std::fs::read(reserved_file.path()).unwrap(),
+        std::fs::read(plain_file.path()).unwrap()
+    );
+    check(&NativeGraphBackend::open(reserved_file.path()).unwrap());
+
+    // A flush hands the reservation back and later appends still line up
+    let temp_file = NamedTempFile::new().unwrap();
+    let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+    backend.reserve(100, 0).unwrap();
+    let specs = bulk_node_specs(4);
+    backend.insert_node(specs[0].clone()).unwrap();
+    backend.flush().unwrap();
+    for spec in &specs[1..] {
+        backend.insert_node(spec.clone()).unwrap();
+    }
+    drop(backend);
+    let reopened = NativeGraphBackend::open(temp_file.path()).unwrap();
+    for id in 1..=4 {
+        assert_eq!(reopened.get_node(id).unwrap().name, format!("func{id}"));
+    }
+}
+
+#[test]
+fn test_reservation_left_by_a_crash_is_trimmed_on_open() {
+    use sqlitegraph::backend::GraphBackend;
+    let temp_file = NamedTempFile::new().unwrap();
+    let specs = bulk_node_specs(8);
+    let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+    backend.reserve(100, 0).unwrap();
+    for spec in &specs[..5] {
+        backend.insert_node(spec.clone()).unwrap();
+    }
+    // Skipping the drop leaves the reserved zeros in the file, as a crash would
+    std::mem::forget(backend);
+    let crashed_len = std::fs::metadata(temp_file.path()).unwrap().len();
+
+    let reopened = NativeGraphBackend::open(temp_file.path()).unwrap();
+    assert!(std::fs::metadata(temp_file.path()).unwrap().len() < crashed_len);
+    for spec in &specs[5..] {
+        reopened.insert_node(spec.clone()).unwrap();
+    }
+    for spec in bulk_edge_specs(8) {
+        reopened.insert_edge(spec).unwrap();
+    }
+    drop(reopened);
+
+    let reopened = NativeGraphBackend::open(temp_file.path()).unwrap();
+    for id in 1..=8 {
+        assert_eq!(reopened.get_node(id).unwrap().name, format!("func{id}"));
+        let edges = reopened.node_edges(id).unwrap();
+        assert_eq!((edges.outgoing.len(), edges.incoming.len()), (2, 2));
+    }
+    assert_eq!(
+        reopened.find_nodes_by_property("lang", "go").unwrap().len(),
+        4
+    );
+
+    // A reservation running past the edge slots is cut back to the last slot
+    let temp_file = NamedTempFile::new().unwrap();
+    let edge_specs = bulk_edge_specs(8);
+    let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+    backend.reserve(8, 100).unwrap();
+    for spec in specs.clone() {
+        backend.insert_node(spec).unwrap();
+    }
+    for spec in &edge_specs[..10] {
+        backend.insert_edge(spec.clone()).unwrap();
+    }
+    std::mem::forget(backend);
+
+    let reopened = NativeGraphBackend::open(temp_file.path()).unwrap();
+    for spec in &edge_specs[10..] {
+        reopened.insert_edge(spec.clone()).unwrap();
+    }
+    drop(reopened);
+    let reopened = NativeGraphBackend::open(temp_file.path()).unwrap();
+    assert_eq!(reopened.list_edge_ids().unwrap().len(), 16);
+    for id in 1..=8 {
+        let edges = reopened.node_edges(id).unwrap();
+        assert_eq!((edges.outgoing.len(), edges.incoming.len()), (2, 2));
+    }
+}
+
+#[test]
+fn test_verify_pinpoints_corrupt_records_and_repair_drops_them() {
+    use sqlitegraph::backend::GraphBackend;
+    let temp_file = NamedTempFile::new().unwrap();
+    let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+    for spec in bulk_node_specs(4) {
+        backend.insert_node(spec).unwrap();
+    }
+    for spec in bulk_edge_specs(4) {
+        backend.insert_edge(spec).unwrap();
+    }
+    backend.compact().unwrap();
+    let report = backend.verify().unwrap();
+    assert!(report.is_clean());
+    assert_eq!((report.nodes_checked, report.edges_checked), (4, 8));
+    drop(backend);
+
+    // Break the UTF-8 of node 2's name and the version byte of edge 7's slot
+    let mut bytes = std::fs::read(temp_file.path()).unwrap();
+    let header = decode_header(&bytes).unwrap();
+    let name_at = bytes.windows(5).position(|w| w == b"func2").unwrap();
+    bytes[name_at] = 0xFF;
+    bytes[header.edge_data_offset as usize + 6 * 256] = 0;
+    std::fs::write(temp_file.path(), &bytes).unwrap();
+
+    let backend = NativeGraphBackend::open(temp_file.path()).unwrap();
+    let report = backend.verify().unwrap();
+    assert!(!report.is_clean());
+    assert_eq!(report.header_error, None);
+    assert_eq!(report.corrupt_nodes, vec![2]);
+    assert_eq!(report.corrupt_edges, vec![7]);
+
+    assert_eq!(backend.repair().unwrap(), report);
+    assert!(backend.verify().unwrap().is_clean());
+    assert!(backend.get_node(2).is_err());
+    assert_eq!(backend.get_node(3).unwrap().name, "func3");
+    // Edges touching node 2 go with it
+    assert_eq!(backend.list_edge_ids().unwrap(), vec![2, 5, 8]);
+    assert_eq!(backend.neighbors(3, Default::default()).unwrap(), vec![4]);
+    drop(backend);
+    assert!(
+        NativeGraphBackend::open(temp_file.path())
+            .unwrap()
+            .verify()
+            .unwrap()
+            .is_clean()
+    );
+}
+
+#[test]
+fn test_node_edges_read_only_the_node_adjacency_range() {
+    use sqlitegraph::backend::GraphBackend;
+    let temp_file = NamedTempFile::new().unwrap();
+    let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+    for spec in bulk_node_specs(4) {
+        backend.insert_node(spec).unwrap();
+    }
+    // Node 1 owns edges 1 and 3; edges 2 and 4 belong to nodes 3 and 4 only
+    for (from, to) in [(1, 2), (3, 4), (1, 3), (4, 3)] {
+        backend
+            .insert_edge(sqlitegraph::backend::EdgeSpec {
+                from,
+                to,
+                edge_type: "calls".to_string(),
+                data: serde_json::json!({}),
+            })
+            .unwrap();
+    }
+    drop(backend);
+
+    // Edge 4 lies outside every range of node 1, so breaking it must not matter
+    let mut bytes = std::fs::read(temp_file.path()).unwrap();
+    let header = decode_header(&bytes).unwrap();
+    bytes[header.edge_data_offset as usize + 3 * 256] = 0;
+    std::fs::write(temp_file.path(), &bytes).unwrap();
+
+    let backend = NativeGraphBackend::open(temp_file.path()).unwrap();
+    let edges = backend.node_edges(1).unwrap();
+    let outgoing: Vec<i64> = edges.outgoing.iter().map(|edge| edge.to_id).collect();
+    assert_eq!(outgoing, vec![2, 3]);
+    assert!(edges.incoming.is_empty());
+    assert_eq!(backend.node_degree(2).unwrap(), (0, 1));
+
+    backend.delete_node(1).unwrap();
+    assert_eq!(backend.node_degree(2).unwrap(), (0, 0));
+    assert!(backend.get_edge(3).is_err());
+    assert_eq!(backend.get_edge(2).unwrap().from_id, 3);
+}
+
+#[test]
+fn test_grown_node_data_is_moved_and_survives_reopen() {
+    use sqlitegraph::DataFormat;
+    use sqlitegraph::backend::{GraphBackend, NeighborQuery};
+    let big = |i: i64| serde_json::json!({"lang": "rust", "notes": "x".repeat(40 * i as usize)});
+    for format in [DataFormat::Json, DataFormat::MessagePack] {
+        for with_edges in [false, true] {
+            let temp_file = NamedTempFile::new().unwrap();
+            let backend = NativeGraphBackend::new(temp_file.path()).unwrap();
+            backend.set_data_format(format).unwrap();
+            for spec in bulk_node_specs(3) {
+                backend.insert_node(spec).unwrap();
+            }
+            if with_edges {
+                for spec in bulk_edge_specs(3) {
+                    backend.insert_edge(spec).unwrap();
+                }
+            }
+            // Node 2 moves twice; without edges node 4 lands behind its copies
+            backend.update_node_data(2, big(1)).unwrap();
+            backend.update_node_data(2, big(2)).unwrap();
+            backend.update_node_data(3, big(3)).unwrap();
+            if !with_edges {
+                backend
+                    .insert_node(bulk_node_specs(4).pop().unwrap())
+                    .unwrap();
+            }
+            drop(backend);
+
+            let check = |backend: &NativeGraphBackend| {
+                assert_eq!(backend.get_node(1).unwrap().name, "func1");
+                assert_eq!(backend.get_node(2).unwrap().data, big(2));
+                assert_eq!(backend.get_node(3).unwrap().data, big(3));
+                assert_eq!(backend.get_node(3).unwrap().name, "func3");
+                if with_edges {
+                    let mut neighbors = backend.neighbors(2, NeighborQuery::default()).unwrap();
+                    neighbors.sort_unstable();
+                    assert_eq!(neighbors, vec![2, 3]);
+                    assert_eq!(backend.node_degree(3).unwrap(), (2, 2));
+                } else {
+                    assert_eq!(backend.get_node(4).unwrap().name, "func4");
+                }
+                assert!(backend.verify().unwrap().is_clean());
+            };
+            let backend = NativeGraphBackend::open(temp_file.path()).unwrap();
+            check(&backend);
+            assert_eq!(
+                backend.find_nodes_by_property("lang", "rust").unwrap(),
+                vec![1, 2, 3]
+            );
+            backend.compact().unwrap();
+            check(&backend);
+            drop(backend);
+            check(&NativeGraphBackend::open(temp_file.path()).unwrap());
+        }
+    }
+}
@@ -460,6 +460,51 @@ fn test_patterns_with_property_filters() {
     assert_eq!(sql_results, fast_results);
 }
 
+#[test]
+fn test_patterns_with_exclusion_and_key_filters() {
+    let graph = create_test_graph();
+
+    let f1 = get_entity_by_name(&graph, "process_data");
+    let f2 = get_entity_by_name(&graph, "validate_input");
+    let f3 = get_entity_by_name(&graph, "handle_error");
+    let f4 = get_entity_by_name(&graph, "log_result");
+
+    let end_points = |pattern: &PatternTriple| -> Vec<(i64, i64)> {
+        let sql_results = graph.match_triples(pattern).expect("SQL path failed");
+        let fast_results = graph.match_triples_fast(pattern).expect("Fast path failed");
+        assert_eq!(sql_results, fast_results);
+        sql_results.iter().map(|m| (m.start_id, m.end_id)).collect()
+    };
+
+    // `not` excludes matching values only
+    let pattern = PatternTriple::new("CALLS").end_property_not("async", "true");
+    assert_eq!(end_points(&pattern), vec![(f1, f2), (f1, f3)]);
+
+    // `not` combines with equality filters on the same pattern
+    let pattern = PatternTriple::new("CALLS")
+        .start_property("language", "rust")
+        .end_property_not("async", "false");
+    assert_eq!(end_points(&pattern), vec![(f1, f4)]);
+
+    // Entities lacking the key are not excluded by `not`
+    let pattern = PatternTriple::new("USES").end_property_not("async", "true");
+    assert_eq!(end_points(&pattern).len(), 3);
+
+    // `has_key` matches any value
+    let pattern = PatternTriple::new("CALLS").start_has_key("async");
+    assert_eq!(end_points(&pattern), vec![(f1, f2), (f1, f3), (f1, f4)]);
+    let pattern = PatternTriple::new("USES").end_has_key("thread_safe");
+    assert_eq!(end_points(&pattern).len(), 3);
+    let pattern = PatternTriple::new("CALLS").end_has_key("thread_safe");
+    assert!(end_points(&pattern).is_empty());
+
+    let pattern = PatternTriple::new("CALLS")
+        .direction(BackendDirection::Incoming)
+        .start_has_key("language")
+        .end_property_not("async", "false");
+    assert_eq!(end_points(&pattern), vec![(f2, f1), (f3, f1), (f4, f1)]);
+}
+
 #[test]
 fn test_patterns_with_different_directions() {
     let graph = create_test_graph();
@@ -61,6 +61,7 @@ fn test_pattern_query_matches_kind_chain() {
                 constraint: Some(NodeConstraint::kind("Struct")),
             },
         ],
+        ..PatternQuery::default()
     };
     let matches = graph
         .query()
@@ -83,6 +84,7 @@ fn test_pattern_query_blocks_root_constraint() {
             edge_type: Some("CALLS".into()),
             constraint: Some(NodeConstraint::kind("Function")),
         }],
+        ..PatternQuery::default()
     };
     let matches = graph
         .query()
@@ -124,6 +126,7 @@ fn test_pattern_query_with_name_prefix_filter() {
                 constraint: Some(NodeConstraint::name_prefix("S_a")),
             },
         ],
+        ..PatternQuery::default()
     };
     let matches = graph
         .query()
@@ -132,3 +135,78 @@ fn test_pattern_query_with_name_prefix_filter() {
     let sequences: Vec<Vec<i64>> = matches.into_iter().map(|m| m.nodes).collect();
     assert_eq!(sequences, vec![vec![ids[0], ids[1], ids[3]]]);
 }
+
+#[test]
+fn test_pattern_query_skips_soft_deleted_nodes() {
+    let (graph, ids) = build_graph();
+    graph.soft_delete_node(ids[1]).expect("soft delete");
+    let pattern = PatternQuery {
+        root: Some(NodeConstraint::kind("Function")),
+        legs: vec![PatternLeg {
+            direction: BackendDirection::Outgoing,
+            edge_type: Some("CALLS".into()),
+            constraint: Some(NodeConstraint::kind("Function")),
+        }],
+        ..PatternQuery::default()
+    };
+    let matches = graph
+        .query()
+        .pattern_matches(ids[0], &pattern)
+        .expect("pattern");
+    let sequences: Vec<Vec<i64>> = matches.into_iter().map(|m| m.nodes).collect();
+    assert_eq!(sequences, vec![vec![ids[0], ids[2]]]);
+    assert_eq!(
+        pattern::entity_ids_with_constraint(&graph, &NodeConstraint::kind("Function")).unwrap(),
+        vec![ids[0], ids[2]]
+    );
+}
+
+#[test]
+fn test_pattern_query_limit_and_offset_page_sorted_matches() {
+    let (graph, ids) = build_graph();
+    for (from, to) in [(ids[0], ids[3]), (ids[0], ids[4]), (ids[4], ids[0])] {
+        insert_edge(&graph, from, to, "CALLS");
+    }
+    let two_legs = PatternQuery {
+        root: None,
+        legs: vec![
+            PatternLeg {
+                direction: BackendDirection::Both,
+                edge_type: None,
+                constraint: None,
+            },
+            PatternLeg {
+                direction: BackendDirection::Outgoing,
+                edge_type: None,
+                constraint: None,
+            },
+        ],
+        ..PatternQuery::default()
+    };
+    let one_leg = PatternQuery {
+        root: None,
+        legs: vec![PatternLeg {
+            direction: BackendDirection::Both,
+            edge_type: Some("CALLS".into()),
+            constraint: Some(NodeConstraint::kind("Struct")),
+        }],
+        ..PatternQuery::default()
+    };
+    for pattern in [two_legs, one_leg] {
+        let all = graph.query().pattern_matches(ids[0], &pattern).unwrap();
+        assert!(all.windows(2).all(|w| w[0].nodes < w[1].nodes));
+        for offset in 0..=all.len() {
+            for limit in 0..=2 {
+                let page = graph
+                    .query()
+                    .pattern_matches(
+                        ids[0],
+                        &pattern.clone().with_offset(offset).with_limit(limit),
+                    )
+                    .unwrap();
+                let expected: Vec<_> = all.iter().skip(offset).take(limit).cloned().collect();
+                assert_eq!(page, expected, "offset {offset} limit {limit}");
+            }
+        }
+    }
+}
@@ -1,5 +1,10 @@
 use serde_json::json;
-use sqlitegraph::{GraphEdge, GraphEntity, SqliteGraph, query::GraphQuery};
+use sqlitegraph::{
+    GraphEdge, GraphEntity, SqliteGraph, add_label, add_property,
+    backend::BackendDirection,
+    multi_hop::{ChainStep, chain_query},
+    query::GraphQuery,
+};
 
 fn setup_query_graph() -> SqliteGraph {
     let graph = SqliteGraph::open_in_memory().expect("graph");
@@ -95,3 +100,180 @@ fn test_has_path_respects_max_depth() {
     assert!(!q.has_path(1, 5, 2).unwrap());
     assert!(q.has_path(1, 5, 4).unwrap());
 }
+
+#[test]
+fn test_neighbor_filter_combines_type_label_and_property() {
+    let graph = setup_query_graph();
+    add_label(&graph, 3, "public").unwrap();
+    add_label(&graph, 4, "public").unwrap();
+    add_property(&graph, 4, "async", "true").unwrap();
+    add_property(&graph, 3, "async", "false").unwrap();
+
+    let calls = graph.query().from(2).edge_type("CALLS");
+    assert_eq!(calls.nodes().unwrap(), vec![4]);
+
+    let public = graph.query().from(2).target_label("public");
+    assert_eq!(public.nodes().unwrap(), vec![3, 4]);
+
+    let filtered = graph
+        .query()
+        .from(2)
+        .direction(BackendDirection::Outgoing)
+        .target_label("public")
+        .target_property("async", "true")
+        .nodes()
+        .unwrap();
+    assert_eq!(filtered, vec![4]);
+
+    let incoming = graph
+        .query()
+        .from(4)
+        .direction(BackendDirection::Incoming)
+        .edge_type("CALLS")
+        .nodes()
+        .unwrap();
+    assert_eq!(incoming, vec![2, 3]);
+
+    let both = graph
+        .query()
+        .from(2)
+        .direction(BackendDirection::Both)
+        .nodes()
+        .unwrap();
+    assert_eq!(both, vec![1, 3, 4]);
+
+    assert!(graph.query().from(2).edge_type(" ").nodes().is_err());
+}
+
+#[test]
+fn test_traversal_matches_chain_query() {
+    let graph = setup_query_graph();
+    let chain = [
+        ChainStep {
+            direction: BackendDirection::Outgoing,
+            edge_type: Some("CALLS".into()),
+        },
+        ChainStep {
+            direction: BackendDirection::Outgoing,
+            edge_type: Some("USES".into()),
+        },
+    ];
+    let expected = chain_query(&graph, 1, &chain).unwrap();
+    assert_eq!(expected, vec![3]);
+
+    let traversal = graph.query().traverse(1).out("CALLS").out("USES");
+    assert_eq!(traversal.nodes().unwrap(), expected);
+    let names: Vec<String> = traversal
+        .entities()
+        .unwrap()
+        .into_iter()
+        .map(|entity| entity.name)
+        .collect();
+    assert_eq!(names, vec!["C"]);
+
+    let back = graph.query().traverse(4).in_("CALLS").in_("USES");
+    assert_eq!(back.nodes().unwrap(), vec![2]);
+}
+
+#[test]
+fn test_traversal_filters_between_hops() {
+    let graph = setup_query_graph();
+    add_label(&graph, 3, "public").unwrap();
+    add_property(&graph, 4, "async", "true").unwrap();
+
+    let all = graph.query().traverse(2).out("CALLS").out("IMPLEMENTS");
+    assert_eq!(all.nodes().unwrap(), vec![5]);
+
+    // 2 -[USES]-> 3 -[CALLS]-> 4 also passes through 3 carrying "public"
+    let labeled = graph
+        .query()
+        .traverse(2)
+        .step(ChainStep {
+            direction: BackendDirection::Outgoing,
+            edge_type: None,
+        })
+        .filter_label("public")
+        .out("CALLS");
+    assert_eq!(labeled.nodes().unwrap(), vec![4]);
+
+    let unmatched = graph
+        .query()
+        .traverse(1)
+        .out("CALLS")
+        .out("CALLS")
+        .filter_property("async", "false");
+    assert!(unmatched.nodes().unwrap().is_empty());
+
+    let matched = graph
+        .query()
+        .traverse(1)
+        .out("CALLS")
+        .out("CALLS")
+        .filter_property("async", "true")
+        .out("IMPLEMENTS");
+    assert_eq!(matched.nodes().unwrap(), vec![5]);
+
+    assert!(graph.query().traverse(1).out(" ").nodes().is_err());
+}
+
+#[test]
+fn test_paged_neighbors_cover_supernode() {
+    let graph = setup_query_graph();
+    let hub = 5;
+    let mut spokes = Vec::new();
+    for i in 0..10 {
+        let id = graph
+            .insert_entity(&GraphEntity {
+                id: 0,
+                kind: "Node".into(),
+                name: format!("spoke{i}"),
+                file_path: None,
+                data: json!({}),
+            })
+            .unwrap();
+        for (from, to) in [(hub, id), (id, hub)] {
+            graph
+                .insert_edge(&GraphEdge {
+                    id: 0,
+                    from_id: from,
+                    to_id: to,
+                    edge_type: "LINK".into(),
+                    data: json!({}),
+                })
+                .unwrap();
+        }
+        spokes.push(id);
+    }
+    let q = graph.query();
+
+    let mut seen: Vec<i64> = Vec::new();
+    let mut offset = 0;
+    let mut sizes = Vec::new();
+    loop {
+        let page = q.outgoing_paged(hub, offset, 4).unwrap();
+        assert_eq!(page.total, 10);
+        sizes.push(page.neighbors.len());
+        seen.extend(&page.neighbors);
+        if !page.has_more() {
+            break;
+        }
+        offset = page.next_offset();
+    }
+    assert_eq!(sizes, vec![4, 4, 2]);
+    assert_eq!(seen, spokes);
+    assert_eq!(seen, q.outgoing(hub).unwrap());
+
+    // The hub also has an incoming IMPLEMENTS edge from node 4
+    let mut incoming = q.incoming_paged(hub, 0, 4).unwrap();
+    let mut all_incoming = incoming.neighbors.clone();
+    while incoming.has_more() {
+        incoming = q.incoming_paged(hub, incoming.next_offset(), 4).unwrap();
+        all_incoming.extend(&incoming.neighbors);
+    }
+    assert_eq!(incoming.total, 11);
+    assert_eq!(all_incoming, q.incoming(hub).unwrap());
+
+    let past_end = q.outgoing_paged(hub, 20, 4).unwrap();
+    assert!(past_end.neighbors.is_empty());
+    assert!(!past_end.has_more());
+}
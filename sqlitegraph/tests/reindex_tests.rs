@@ -0,0 +1,165 @@
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+};
+
+use rusqlite::Connection;
+use serde_json::json;
+use sqlitegraph::{
+    GraphEdgeCreate, GraphEntityCreate, ReindexConfig, ReindexStage, SqliteGraph, SqliteGraphError,
+    bulk_insert_edges, bulk_insert_entities,
+};
+
+fn edge_lookup_plan(conn: &Connection) -> String {
+    let mut stmt = conn
+        .prepare("EXPLAIN QUERY PLAN SELECT id FROM graph_edges WHERE from_id = 1")
+        .unwrap();
+    stmt.query_map([], |row| row.get::<_, String>(3))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("; ")
+}
+
+fn index_names(conn: &Connection) -> Vec<String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'index' AND name LIKE 'idx_%' ORDER BY name")
+        .unwrap();
+    stmt.query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+#[test]
+fn test_reindex_restores_dropped_index() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+    let graph = SqliteGraph::open(&path).unwrap();
+    let entities: Vec<GraphEntityCreate> = (0..200)
+        .map(|i| GraphEntityCreate {
+            kind: "Node".into(),
+            name: format!("n{i}"),
+            file_path: None,
+            data: json!({}),
+        })
+        .collect();
+    let ids = bulk_insert_entities(&graph, &entities).unwrap();
+    let edges: Vec<GraphEdgeCreate> = (0..1200)
+        .map(|i| GraphEdgeCreate {
+            from_id: ids[i % ids.len()],
+            to_id: ids[(i + 1 + i / 200) % ids.len()],
+            edge_type: "LINK".into(),
+            data: json!({}),
+        })
+        .collect();
+    bulk_insert_edges(&graph, &edges).unwrap();
+
+    let external = Connection::open(&path).unwrap();
+    external.execute("DROP INDEX idx_edges_from", []).unwrap();
+    assert!(edge_lookup_plan(&external).contains("SCAN graph_edges"));
+
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&progress);
+    let result = graph
+        .reindex_with_config(ReindexConfig {
+            batch_size: 500,
+            progress_callback: Some(Box::new(move |p| seen.lock().unwrap().push(p))),
+            ..ReindexConfig::default()
+        })
+        .unwrap();
+
+    assert!(result.success, "{:?}", result.validation_errors);
+    assert_eq!(result.entities_processed, 200);
+    assert_eq!(result.edges_processed, 1200);
+    assert_eq!(result.indexes_rebuilt.len(), 8);
+    assert!(
+        result
+            .indexes_rebuilt
+            .contains(&"idx_edges_from".to_string())
+    );
+    let edge_progress: Vec<u64> = progress
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|p| p.stage == ReindexStage::Edges)
+        .map(|p| p.current)
+        .collect();
+    assert_eq!(edge_progress, vec![500, 1000, 1200]);
+
+    // A connection that already loaded the schema keeps planning against it
+    let plan = edge_lookup_plan(&Connection::open(&path).unwrap());
+    assert!(plan.contains("INDEX idx_edges_from"), "{plan}");
+    let mut expected: Vec<i64> = edges
+        .iter()
+        .filter(|edge| edge.from_id == ids[0])
+        .map(|edge| edge.to_id)
+        .collect();
+    expected.sort_unstable();
+    expected.dedup();
+    let mut outgoing = graph.query().outgoing(ids[0]).unwrap();
+    outgoing.sort_unstable();
+    outgoing.dedup();
+    assert_eq!(outgoing, expected);
+}
+
+#[test]
+fn test_reindex_cancelled_after_first_batch_rolls_back_stage() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.db");
+    let graph = SqliteGraph::open(&path).unwrap();
+    let entities: Vec<GraphEntityCreate> = (0..200)
+        .map(|i| GraphEntityCreate {
+            kind: format!("Kind{}", i % 4),
+            name: format!("n{i}"),
+            file_path: None,
+            data: json!({}),
+        })
+        .collect();
+    bulk_insert_entities(&graph, &entities).unwrap();
+    // Rolling the stage back must also undo recreating a missing index
+    let external = Connection::open(&path).unwrap();
+    external
+        .execute("DROP INDEX idx_entities_kind_id", [])
+        .unwrap();
+    let indexes_before = index_names(&external);
+    drop(external);
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&progress);
+    let err = graph
+        .reindex_with_config(ReindexConfig {
+            batch_size: 50,
+            progress_callback: Some(Box::new(move |p| {
+                seen.lock().unwrap().push((p.stage, p.current));
+                flag.store(true, Ordering::Relaxed);
+            })),
+            cancel: Some(Arc::clone(&cancel)),
+            ..ReindexConfig::default()
+        })
+        .unwrap_err();
+
+    assert!(matches!(err, SqliteGraphError::Cancelled(_)), "{err:?}");
+    assert_eq!(
+        *progress.lock().unwrap(),
+        vec![(ReindexStage::Entities, 50)]
+    );
+    let external = Connection::open(&path).unwrap();
+    assert_eq!(index_names(&external), indexes_before);
+    let check: String = external
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(check, "ok");
+    assert_eq!(graph.list_entity_ids().unwrap().len(), 200);
+
+    // The graph is usable afterwards, and a run without cancellation finishes
+    cancel.store(false, Ordering::Relaxed);
+    let result = graph.reindex().unwrap();
+    assert!(result.success);
+    assert_eq!(result.entities_processed, 200);
+    assert!(
+        index_names(&Connection::open(&path).unwrap()).contains(&"idx_entities_kind_id".into())
+    );
+}
@@ -0,0 +1,162 @@
+use serde_json::json;
+use sqlitegraph::{
+    GraphEdge, GraphEntity, GraphEntityCreate, SqliteGraph, SqliteGraphError, bulk_insert_entities,
+};
+
+fn insert_entity(graph: &SqliteGraph, name: &str) -> i64 {
+    graph
+        .insert_entity(&GraphEntity {
+            id: 0,
+            kind: "Node".into(),
+            name: name.into(),
+            file_path: None,
+            data: json!({}),
+        })
+        .expect("entity")
+}
+
+fn insert_edge(graph: &SqliteGraph, from: i64, to: i64) -> i64 {
+    graph
+        .insert_edge(&GraphEdge {
+            id: 0,
+            from_id: from,
+            to_id: to,
+            edge_type: "link".into(),
+            data: json!({}),
+        })
+        .expect("edge")
+}
+
+#[test]
+fn test_commit_keeps_writes() {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    let guard = graph.transaction_guard().expect("guard");
+    assert!(graph.in_transaction());
+    let a = insert_entity(&graph, "a");
+    let b = insert_entity(&graph, "b");
+    insert_edge(&graph, a, b);
+    guard.commit().expect("commit");
+
+    assert!(!graph.in_transaction());
+    assert_eq!(graph.list_entity_ids().unwrap(), vec![a, b]);
+    assert_eq!(graph.query().outgoing(a).unwrap(), vec![b]);
+}
+
+#[test]
+fn test_drop_rolls_back_writes() {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    let a = insert_entity(&graph, "a");
+    {
+        let _guard = graph.transaction_guard().expect("guard");
+        let b = insert_entity(&graph, "b");
+        insert_edge(&graph, a, b);
+    }
+
+    assert!(!graph.in_transaction());
+    assert_eq!(graph.list_entity_ids().unwrap(), vec![a]);
+}
+
+#[test]
+fn test_rollback_leaves_cache_consistent() {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    let a = insert_entity(&graph, "a");
+    let b = insert_entity(&graph, "b");
+    // Warm the adjacency cache before the transaction
+    assert!(graph.query().outgoing(a).unwrap().is_empty());
+
+    let guard = graph.transaction_guard().expect("guard");
+    insert_edge(&graph, a, b);
+    assert_eq!(graph.query().outgoing(a).unwrap(), vec![b]);
+    assert_eq!(graph.query().incoming(b).unwrap(), vec![a]);
+    guard.rollback();
+
+    assert!(graph.query().outgoing(a).unwrap().is_empty());
+    assert!(graph.query().incoming(b).unwrap().is_empty());
+}
+
+#[test]
+fn test_bulk_insert_joins_open_transaction() {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    {
+        let _guard = graph.transaction_guard().expect("guard");
+        let entries = vec![
+            GraphEntityCreate {
+                kind: "Node".into(),
+                name: "x".into(),
+                file_path: None,
+                data: json!({}),
+            };
+            3
+        ];
+        let ids = bulk_insert_entities(&graph, &entries).expect("bulk");
+        assert_eq!(ids.len(), 3);
+        assert_eq!(graph.list_entity_ids().unwrap().len(), 3);
+    }
+    assert!(graph.list_entity_ids().unwrap().is_empty());
+}
+
+#[test]
+fn test_second_guard_is_rejected() {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    let guard = graph.transaction_guard().expect("guard");
+    let err = graph.transaction_guard().err().expect("second guard");
+    assert!(matches!(err, SqliteGraphError::TransactionError { .. }));
+    guard.commit().expect("commit");
+
+    // A new guard can be opened once the first is finished
+    graph.transaction_guard().expect("guard after commit");
+}
+
+fn sqlite_failure(code: std::os::raw::c_int) -> SqliteGraphError {
+    SqliteGraphError::query(rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(code),
+        None,
+    ))
+}
+
+#[test]
+fn test_with_retry_recovers_from_busy_errors() {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    assert!(sqlite_failure(rusqlite::ffi::SQLITE_BUSY).is_retryable());
+    assert!(sqlite_failure(rusqlite::ffi::SQLITE_LOCKED).is_retryable());
+    assert!(!sqlite_failure(rusqlite::ffi::SQLITE_CONSTRAINT).is_retryable());
+    assert!(!SqliteGraphError::query("database is locked").is_retryable());
+
+    let mut calls = 0;
+    let id = graph
+        .with_retry(5, || {
+            calls += 1;
+            if calls <= 2 {
+                return Err(sqlite_failure(rusqlite::ffi::SQLITE_BUSY));
+            }
+            Ok(insert_entity(&graph, "a"))
+        })
+        .expect("retried");
+    assert_eq!(calls, 3);
+    assert!(graph.get_entity(id).is_ok());
+
+    // Busy on every attempt: the last busy error is returned
+    let mut calls = 0;
+    let err = graph
+        .with_retry(3, || -> Result<(), _> {
+            calls += 1;
+            Err(sqlite_failure(rusqlite::ffi::SQLITE_BUSY))
+        })
+        .unwrap_err();
+    assert_eq!(calls, 3);
+    assert!(err.is_retryable());
+}
+
+#[test]
+fn test_with_retry_fails_fast_on_other_errors() {
+    let graph = SqliteGraph::open_in_memory().expect("graph");
+    let mut calls = 0;
+    let err = graph
+        .with_retry(5, || -> Result<(), _> {
+            calls += 1;
+            Err(sqlite_failure(rusqlite::ffi::SQLITE_CONSTRAINT))
+        })
+        .unwrap_err();
+    assert_eq!(calls, 1);
+    assert!(matches!(err, SqliteGraphError::QueryError { .. }));
+}
@@ -330,3 +330,41 @@ fn test_concurrent_read_isolation() {
     let final_entity = graph.get_entity(entity_id).unwrap();
     assert_eq!(final_entity.name, "modified");
 }
+
+#[test]
+fn test_busy_timeout_waits_for_competing_writer() {
+    use sqlitegraph::{GraphConfig, NodeSpec, open_graph};
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("contended.db");
+    let node = || NodeSpec {
+        kind: "Node".to_string(),
+        name: "n".to_string(),
+        file_path: None,
+        data: serde_json::json!({}),
+    };
+    let mut cfg = GraphConfig::sqlite();
+    cfg.sqlite.busy_timeout_ms = Some(5000);
+    let graph = open_graph(&path, &cfg).unwrap();
+    graph.insert_node(node()).unwrap();
+
+    // A second connection takes the write lock and releases it shortly after
+    let competitor = Connection::open(&path).unwrap();
+    competitor.execute_batch("BEGIN IMMEDIATE").unwrap();
+    let holder = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(200));
+        competitor.execute_batch("COMMIT").unwrap();
+    });
+    assert!(graph.insert_node(node()).is_ok());
+    holder.join().unwrap();
+
+    // Without a timeout the same write fails straight away
+    cfg.sqlite.busy_timeout_ms = Some(0);
+    let impatient = open_graph(&path, &cfg).unwrap();
+    let competitor = Connection::open(&path).unwrap();
+    competitor.execute_batch("BEGIN IMMEDIATE").unwrap();
+    assert!(impatient.insert_node(node()).is_err());
+    competitor.execute_batch("COMMIT").unwrap();
+    assert!(impatient.insert_node(node()).is_ok());
+}